@@ -0,0 +1,126 @@
+// Historical balances for vote weighting.
+//
+// A holder's checkpoints are a ring of {slot, balance}, oldest first from
+// `head`. Slots only go up, so the balance at a slot is that of the last
+// checkpoint at or before it, found by binary search. Once the ring is full
+// the oldest checkpoint is overwritten, and slots before the oldest one left
+// can no longer be answered.
+
+use anchor_lang::prelude::*;
+
+use crate::{Checkpoint, CustomError};
+
+// The `i`th oldest checkpoint.
+fn nth(ring: &[Checkpoint], head: u8, i: usize) -> Checkpoint {
+    ring[(head as usize + i) % ring.len()]
+}
+
+// Record `balance` at `slot`. A second checkpoint in the same slot replaces
+// the first, so each slot ends up with its final balance.
+pub fn push(ring: &mut [Checkpoint], head: &mut u8, len: &mut u8, slot: u64, balance: u64) {
+    let cap = ring.len();
+    let count = *len as usize;
+    if count > 0 {
+        let last = (*head as usize + count - 1) % cap;
+        if ring[last].slot == slot {
+            ring[last].balance = balance;
+            return;
+        }
+    }
+    let checkpoint = Checkpoint { slot, balance };
+    if count < cap {
+        ring[(*head as usize + count) % cap] = checkpoint;
+        *len += 1;
+    } else {
+        ring[*head as usize] = checkpoint;
+        *head = ((*head as usize + 1) % cap) as u8;
+    }
+}
+
+// The balance as of the end of `slot`. Zero before the first checkpoint,
+// unless the ring is full: older checkpoints may have been overwritten then.
+pub fn balance_at(ring: &[Checkpoint], head: u8, len: u8, slot: u64) -> Result<u64> {
+    let count = len as usize;
+    if count == 0 || slot < nth(ring, head, 0).slot {
+        require!(count < ring.len(), CustomError::CheckpointExpired);
+        return Ok(0);
+    }
+    // The last checkpoint at or before `slot`; the first one qualifies.
+    let (mut lo, mut hi) = (0, count - 1);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if nth(ring, head, mid).slot <= slot {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Ok(nth(ring, head, lo).balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Ring {
+        ring: [Checkpoint; 4],
+        head: u8,
+        len: u8,
+    }
+
+    impl Ring {
+        fn new() -> Self {
+            Ring { ring: [Checkpoint::default(); 4], head: 0, len: 0 }
+        }
+        fn push(&mut self, slot: u64, balance: u64) {
+            push(&mut self.ring, &mut self.head, &mut self.len, slot, balance);
+        }
+        fn at(&self, slot: u64) -> Result<u64> {
+            balance_at(&self.ring, self.head, self.len, slot)
+        }
+    }
+
+    #[test]
+    fn has_nothing_before_the_first_checkpoint() {
+        let mut ring = Ring::new();
+        assert_eq!(ring.at(100).unwrap(), 0);
+        ring.push(10, 5);
+        assert_eq!(ring.at(9).unwrap(), 0);
+        assert_eq!(ring.at(10).unwrap(), 5);
+    }
+
+    #[test]
+    fn finds_the_last_checkpoint_at_or_before_a_slot() {
+        let mut ring = Ring::new();
+        ring.push(10, 1);
+        ring.push(20, 2);
+        ring.push(30, 3);
+        assert_eq!(ring.at(19).unwrap(), 1);
+        assert_eq!(ring.at(20).unwrap(), 2);
+        assert_eq!(ring.at(29).unwrap(), 2);
+        assert_eq!(ring.at(u64::MAX).unwrap(), 3);
+    }
+
+    #[test]
+    fn keeps_the_last_balance_of_a_slot() {
+        let mut ring = Ring::new();
+        ring.push(10, 1);
+        ring.push(10, 7);
+        assert_eq!(ring.len, 1);
+        assert_eq!(ring.at(10).unwrap(), 7);
+    }
+
+    #[test]
+    fn overwrites_the_oldest_once_full() {
+        let mut ring = Ring::new();
+        for (slot, balance) in [(10, 1), (20, 2), (30, 3), (40, 4), (50, 5), (60, 6)] {
+            ring.push(slot, balance);
+        }
+        assert_eq!(ring.len, 4);
+        assert!(ring.at(29).is_err());
+        assert_eq!(ring.at(30).unwrap(), 3);
+        assert_eq!(ring.at(45).unwrap(), 4);
+        assert_eq!(ring.at(59).unwrap(), 5);
+        assert_eq!(ring.at(60).unwrap(), 6);
+    }
+}
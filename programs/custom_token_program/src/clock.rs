@@ -0,0 +1,123 @@
+// The program's view of the current time.
+//
+// Every handler reads the unix timestamp through `now`. Built with the
+// `test-clock` feature, a MockClock account passed as the last remaining
+// account overrides the sysvar, so tests can move time without waiting or
+// warping the validator. Without the feature the override, the MockClock
+// account and set_mock_time are compiled out and `now` is the sysvar.
+//
+// set_mock_time is not in the IDL. Clients build it by hand: the usual
+// sighash of "global:set_mock_time", then the timestamp as an i64, with
+// accounts [payer (signer, writable), mock clock PDA (writable), system program].
+
+use anchor_lang::prelude::*;
+
+#[cfg(not(feature = "test-clock"))]
+pub fn now(_remaining_accounts: &[AccountInfo]) -> Result<i64> {
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+#[cfg(feature = "test-clock")]
+pub fn now(remaining_accounts: &[AccountInfo]) -> Result<i64> {
+    match remaining_accounts.last().filter(|info| is_mock_clock(info)) {
+        Some(info) => Ok(MockClock::try_deserialize(&mut &info.try_borrow_data()?[..])?.unix_timestamp),
+        None => Ok(Clock::get()?.unix_timestamp),
+    }
+}
+
+// `remaining_accounts` without a trailing MockClock, for handlers that also
+// treat them as a list of their own.
+#[cfg(not(feature = "test-clock"))]
+pub fn without_mock_clock<'a, 'info>(remaining_accounts: &'a [AccountInfo<'info>]) -> &'a [AccountInfo<'info>] {
+    remaining_accounts
+}
+
+#[cfg(feature = "test-clock")]
+pub fn without_mock_clock<'a, 'info>(remaining_accounts: &'a [AccountInfo<'info>]) -> &'a [AccountInfo<'info>] {
+    match remaining_accounts.split_last() {
+        Some((last, rest)) if is_mock_clock(last) => rest,
+        _ => remaining_accounts,
+    }
+}
+
+#[cfg(feature = "test-clock")]
+pub const MOCK_CLOCK_SEED: &[u8] = b"mock_clock";
+
+#[cfg(feature = "test-clock")]
+fn is_mock_clock(info: &AccountInfo) -> bool {
+    let (address, _) = Pubkey::find_program_address(&[MOCK_CLOCK_SEED], &crate::ID);
+    info.key() == address && *info.owner == crate::ID && !info.data_is_empty()
+}
+
+// The overriding timestamp. Only exists in test-clock builds.
+#[cfg(feature = "test-clock")]
+#[account]
+#[derive(InitSpace)]
+pub struct MockClock {
+    pub unix_timestamp: i64,
+    pub bump: u8,
+}
+
+// set_mock_time(unix_timestamp: i64), reached through the program's fallback.
+// Anyone may call it; test-clock builds are never deployed.
+#[cfg(feature = "test-clock")]
+pub fn dispatch<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+    let discriminator = anchor_lang::solana_program::hash::hash(b"global:set_mock_time").to_bytes();
+    require!(
+        data.len() >= 8 && data[..8] == discriminator[..8],
+        ErrorCode::InstructionFallbackNotFound
+    );
+    let ix_data = &data[8..];
+    let unix_timestamp =
+        i64::try_from_slice(ix_data).map_err(|_| error!(ErrorCode::InstructionDidNotDeserialize))?;
+
+    let mut remaining = accounts;
+    let mut bumps = SetMockTimeBumps::default();
+    let mut reallocs = std::collections::BTreeSet::new();
+    let mut ctx = SetMockTime::try_accounts(program_id, &mut remaining, ix_data, &mut bumps, &mut reallocs)?;
+    ctx.mock_clock.unix_timestamp = unix_timestamp;
+    ctx.mock_clock.bump = bumps.mock_clock;
+    ctx.exit(program_id)
+}
+
+#[cfg(not(feature = "test-clock"))]
+pub fn dispatch<'info>(_program_id: &Pubkey, _accounts: &'info [AccountInfo<'info>], _data: &[u8]) -> Result<()> {
+    err!(ErrorCode::InstructionFallbackNotFound)
+}
+
+#[cfg(feature = "test-clock")]
+#[derive(Accounts)]
+pub struct SetMockTime<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MockClock::INIT_SPACE,
+        seeds = [MOCK_CLOCK_SEED],
+        bump
+    )]
+    pub mock_clock: Account<'info, MockClock>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Without the feature the discriminator is unknown and falls straight
+    // through. With it, set_mock_time is found and fails later, on the missing
+    // accounts instead.
+    #[test]
+    fn set_mock_time_only_exists_with_the_feature() {
+        let discriminator = anchor_lang::solana_program::hash::hash(b"global:set_mock_time").to_bytes();
+        let mut data = discriminator[..8].to_vec();
+        data.extend_from_slice(&0i64.to_le_bytes());
+
+        let err = crate::entry(&crate::ID, &[], &data).unwrap_err();
+        let unknown: ProgramError = error!(ErrorCode::InstructionFallbackNotFound).into();
+        assert_eq!(err == unknown, !cfg!(feature = "test-clock"));
+    }
+}
@@ -0,0 +1,76 @@
+// Reading ed25519 signature checks done by the native ed25519 program.
+//
+// The program can't verify signatures itself within its compute budget, so
+// the client puts an ed25519 program instruction in front of ours. If that
+// instruction fails the whole transaction fails; if it's present, all we need
+// to do is check it verified the key and message we expect.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    get_instruction_relative, load_current_index_checked,
+};
+
+use crate::CustomError;
+
+// num_signatures (u8) + padding (u8).
+const HEADER_LEN: usize = 2;
+// Seven u16 offsets per signature.
+const OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+// Offsets pointing at `u16::MAX` refer to the ed25519 instruction's own data.
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16> {
+    let bytes = data
+        .get(at..at + 2)
+        .ok_or(CustomError::InvalidApprovalInstruction)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+// Return the message that `expected_signer` signed, as verified by the ed25519
+// instruction immediately before the current one.
+pub fn verified_message(instructions: &AccountInfo, expected_signer: &Pubkey) -> Result<Vec<u8>> {
+    // Reading the current index first makes sure this really is the Instructions sysvar.
+    load_current_index_checked(instructions)?;
+    let ix = get_instruction_relative(-1, instructions)
+        .map_err(|_| CustomError::MissingApprovalSignature)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, CustomError::MissingApprovalSignature);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1,
+        CustomError::InvalidApprovalInstruction
+    );
+
+    let signature_offset = read_u16(data, 2)? as usize;
+    let signature_ix = read_u16(data, 4)?;
+    let pubkey_offset = read_u16(data, 6)? as usize;
+    let pubkey_ix = read_u16(data, 8)?;
+    let message_offset = read_u16(data, 10)? as usize;
+    let message_len = read_u16(data, 12)? as usize;
+    let message_ix = read_u16(data, 14)?;
+
+    // Everything must live in the ed25519 instruction itself; otherwise the
+    // offsets could point the check at data we never look at.
+    require!(
+        signature_ix == THIS_INSTRUCTION && pubkey_ix == THIS_INSTRUCTION && message_ix == THIS_INSTRUCTION,
+        CustomError::InvalidApprovalInstruction
+    );
+    require!(
+        data.len() >= signature_offset + SIGNATURE_LEN,
+        CustomError::InvalidApprovalInstruction
+    );
+
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + PUBKEY_LEN)
+        .ok_or(CustomError::InvalidApprovalInstruction)?;
+    require!(pubkey == expected_signer.as_ref(), CustomError::WrongApprovalSigner);
+
+    let message = data
+        .get(message_offset..message_offset + message_len)
+        .ok_or(CustomError::InvalidApprovalInstruction)?;
+    Ok(message.to_vec())
+}
@@ -0,0 +1,169 @@
+// Byte offsets for getProgramAccounts memcmp filters.
+//
+// FreezeRecord and DelegationRecord start with the same four keys, right
+// after the 8-byte discriminator and before anything of variable length:
+//
+//     offset   8  mint
+//     offset  40  token_account
+//     offset  72  owner
+//     offset 104  actor (FreezeRecord::actor, DelegationRecord::delegate)
+//
+// so "all freeze records of mint X" is two filters: the discriminator at 0
+// and the mint at MINT_OFFSET. Client code builds them with the helpers
+// below, e.g. `[freeze_records(), by_mint(&mint)]`, and maps each
+// MemcmpFilter onto its RPC client's memcmp type.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::{DelegationRecord, FreezeRecord};
+
+pub const MINT_OFFSET: usize = 8;
+pub const TOKEN_ACCOUNT_OFFSET: usize = MINT_OFFSET + 32;
+pub const OWNER_OFFSET: usize = TOKEN_ACCOUNT_OFFSET + 32;
+pub const ACTOR_OFFSET: usize = OWNER_OFFSET + 32;
+
+// Account data at `offset` must equal `bytes`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemcmpFilter {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+pub fn freeze_records() -> MemcmpFilter {
+    MemcmpFilter { offset: 0, bytes: FreezeRecord::DISCRIMINATOR.to_vec() }
+}
+
+pub fn delegation_records() -> MemcmpFilter {
+    MemcmpFilter { offset: 0, bytes: DelegationRecord::DISCRIMINATOR.to_vec() }
+}
+
+pub fn by_mint(mint: &Pubkey) -> MemcmpFilter {
+    MemcmpFilter { offset: MINT_OFFSET, bytes: mint.to_bytes().to_vec() }
+}
+
+pub fn by_token_account(token_account: &Pubkey) -> MemcmpFilter {
+    MemcmpFilter { offset: TOKEN_ACCOUNT_OFFSET, bytes: token_account.to_bytes().to_vec() }
+}
+
+pub fn by_owner(owner: &Pubkey) -> MemcmpFilter {
+    MemcmpFilter { offset: OWNER_OFFSET, bytes: owner.to_bytes().to_vec() }
+}
+
+pub fn by_actor(actor: &Pubkey) -> MemcmpFilter {
+    MemcmpFilter { offset: ACTOR_OFFSET, bytes: actor.to_bytes().to_vec() }
+}
+
+pub fn matches(filter: &MemcmpFilter, data: &[u8]) -> bool {
+    data.get(filter.offset..filter.offset + filter.bytes.len()) == Some(&filter.bytes[..])
+}
+
+// DelegationRecords written before this layout had token_account first and
+// mint second. The record's address is derived from its token account, so a
+// record whose first key derives its own address is in the old layout.
+// Returns whether `data` was rewritten.
+pub fn upgrade_delegation_record(address: &Pubkey, data: &mut [u8]) -> bool {
+    let Some(first) = data.get(MINT_OFFSET..TOKEN_ACCOUNT_OFFSET) else {
+        return false;
+    };
+    let first = Pubkey::try_from(first).unwrap();
+    let (expected, _) = Pubkey::find_program_address(&[b"delegation", first.as_ref()], &crate::ID);
+    if expected != *address {
+        return false;
+    }
+    let (mint, rest) = data[MINT_OFFSET..OWNER_OFFSET].split_at_mut(32);
+    mint.swap_with_slice(rest);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DelegationMode, DelegationTerms};
+
+    fn delegation_record(token_account: Pubkey, mint: Pubkey, owner: Pubkey, delegate: Pubkey) -> DelegationRecord {
+        DelegationRecord {
+            mint,
+            token_account,
+            owner,
+            delegate,
+            terms: DelegationTerms {
+                allowance: 1,
+                mode: DelegationMode::Advisory,
+                expires_at: Some(7),
+                period_seconds: 0,
+                period_limit: 0,
+            },
+            allowed_destinations: vec![Pubkey::new_unique()],
+            spent_total: 0,
+            spent_this_period: 0,
+            period_start_ts: 0,
+            bump: 255,
+            delegate_program: Some(Pubkey::new_unique()),
+        }
+    }
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn freeze_record_keys_sit_at_the_documented_offsets() {
+        let (mint, token_account, owner, actor) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let data = serialize(&FreezeRecord {
+            mint,
+            token_account,
+            owner,
+            actor,
+            amount: 5,
+            frozen_at: 6,
+            bump: 255,
+            rent_payer: None,
+            bond_bump: None,
+        });
+        assert!(matches(&freeze_records(), &data));
+        assert!(!matches(&delegation_records(), &data));
+        assert!(matches(&by_mint(&mint), &data));
+        assert!(matches(&by_token_account(&token_account), &data));
+        assert!(matches(&by_owner(&owner), &data));
+        assert!(matches(&by_actor(&actor), &data));
+        assert!(!matches(&by_mint(&owner), &data));
+    }
+
+    #[test]
+    fn delegation_record_keys_sit_at_the_documented_offsets() {
+        let (mint, token_account, owner, delegate) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let data = serialize(&delegation_record(token_account, mint, owner, delegate));
+        assert!(matches(&delegation_records(), &data));
+        assert!(matches(&by_mint(&mint), &data));
+        assert!(matches(&by_token_account(&token_account), &data));
+        assert!(matches(&by_owner(&owner), &data));
+        assert!(matches(&by_actor(&delegate), &data));
+    }
+
+    #[test]
+    fn short_data_never_matches() {
+        assert!(!matches(&by_actor(&Pubkey::new_unique()), &[0; 100]));
+    }
+
+    #[test]
+    fn upgrades_only_old_layout_delegation_records() {
+        let (mint, token_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (address, _) = Pubkey::find_program_address(&[b"delegation", token_account.as_ref()], &crate::ID);
+        let current = serialize(&delegation_record(token_account, mint, Pubkey::new_unique(), Pubkey::new_unique()));
+
+        let mut old = current.clone();
+        let (first, second) = old[MINT_OFFSET..OWNER_OFFSET].split_at_mut(32);
+        first.swap_with_slice(second);
+
+        assert!(upgrade_delegation_record(&address, &mut old));
+        assert_eq!(old, current);
+        // Already upgraded, so left alone.
+        assert!(!upgrade_delegation_record(&address, &mut old));
+        assert_eq!(old, current);
+    }
+}
@@ -1,6 +1,33 @@
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, FreezeAccount, ThawAccount, MintTo, Approve};
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::{get_return_data, invoke, invoke_signed};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, FreezeAccount, ThawAccount, SetAuthority, MintTo, TransferChecked, CloseAccount, Approve, Revoke, Transfer, Burn};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::token_interface::{self, TokenInterface};
+use anchor_spl::token_2022::spl_token_2022::extension::{metadata_pointer, ExtensionType};
+use anchor_spl::token_2022::Token2022;
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use clock::now;
+
+pub mod checkpoints;
+pub mod clock;
+pub mod ed25519;
+pub mod filters;
+pub mod merkle;
+pub mod pda;
+pub mod rebase;
+pub mod shares;
+pub mod staking;
+pub mod stream;
+pub mod validation;
 
 // This is the program's on-chain ID. Anchor automatically populates this.
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
@@ -16,19 +43,51 @@ pub mod custom_token_program {
         decimals: u8,
         mint_authority: Pubkey,
     ) -> Result<()> {
-        // We don't need to do anything here.
+        validation::require_valid_decimals(decimals)?;
+        validation::require_pubkey_not_default(&mint_authority)?;
+
         // Anchor's framework, combined with the account constraints below,
         // handles the creation and initialization of the mint account.
         // The `#[account(...)` macros are doing the heavy lifting.
         // Specifically, the `mint` account is being created and initialized
         // by the token program, with the `freeze_authority` set to our PDA.
+        //
+        // All that's left is to cache the canonical bumps, so later
+        // instructions don't have to re-derive the PDAs on every call.
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts
+            .mint_config
+            .init(mint_key, ctx.bumps.program_authority, ctx.bumps.mint_config);
+
         Ok(())
     }
 
     // Instruction 2: Delegate spending authority to another account.
-    // This is a direct wrapper around the SPL Token program's `approve` instruction.
-    pub fn delegate_tokens(ctx: Context<DelegateTokens>, amount: u64) -> Result<()> {
-        let cpi_accounts = Approve {
+    // This is a direct wrapper around the `approve` instruction of whichever
+    // token program (SPL Token or Token-2022) owns the token account.
+    // A `referrer` is credited with the delegation in its ReferralStats.
+    pub fn delegate_tokens(ctx: Context<DelegateTokens>, amount: u64, referrer: Option<Pubkey>) -> Result<()> {
+        // A zero approve, or one for more than the account holds, is almost
+        // always a client mistake.
+        validation::require_nonzero_amount(amount)?;
+        validation::require_within_balance(amount, ctx.accounts.token_account.amount)?;
+        validation::require_pubkey_not_default(&ctx.accounts.delegate.key())?;
+        require_delegate_approved(&ctx.accounts.mint_config, ctx.accounts.approved_delegate.as_ref())?;
+        charge_delegation_fee(
+            &ctx.accounts.mint_config,
+            &ctx.accounts.owner,
+            ctx.accounts.fee_recipient.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let owner = ctx.accounts.owner.key();
+        if let Some(stats) = referral_stats(referrer, &owner, ctx.accounts.referral_stats.as_mut(), ctx.bumps.referral_stats)? {
+            stats.totals.delegations = stats.totals.delegations.checked_add(1).ok_or(CustomError::MathOverflow)?;
+            stats.totals.delegated_volume =
+                stats.totals.delegated_volume.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        }
+
+        let cpi_accounts = token_interface::Approve {
             to: ctx.accounts.token_account.to_account_info(),
             delegate: ctx.accounts.delegate.to_account_info(),
             authority: ctx.accounts.owner.to_account_info(),
@@ -36,16 +95,76 @@ pub mod custom_token_program {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         
-        token::approve(cpi_ctx, amount)?;
+        token_interface::approve(cpi_ctx, amount)?;
+
+        emit!(TokensDelegated {
+            token_account: ctx.accounts.token_account.key(),
+            delegate: ctx.accounts.delegate.key(),
+            amount,
+            referrer,
+            sequence: update_if_registered(&ctx.accounts.mint_config, |config| {
+                config.record_delegation(false)?;
+                config.next_sequence()
+            })?,
+        });
         
         Ok(())
     }
 
     // Instruction 3: Freeze a user's token account.
-    pub fn freeze_token_account(ctx: Context<FreezeOrThawAccount>) -> Result<()> {
-        // Security Check: Ensure the signer is the original mint authority.
+    pub fn freeze_token_account<'info>(ctx: Context<'_, '_, 'info, 'info, FreezeTokenAccount<'info>>) -> Result<()> {
+        // Security Check: Ensure the signer is the original mint authority, or
+        // holds the freezer role, in which case it is held to the daily quota
+        // unless the super_admin co-signs. The mint authority grants the role
+        // and sets the quota, so there is nothing to bound it by.
         // This prevents unauthorized accounts from freezing tokens.
-        require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.mint.mint_authority.unwrap(), CustomError::Unauthorized);
+        let by_mint_authority = ctx.accounts.mint.mint_authority == COption::Some(ctx.accounts.admin.key());
+        if !by_mint_authority {
+            let quota = ctx.accounts.mint_config.freeze_quota_per_day;
+            let by_super_admin = ctx.accounts.super_admin.is_some();
+            let Some(stats) = ctx.accounts.freezer_stats.as_mut() else {
+                msg!("admin: {} is neither the mint authority nor a freezer", ctx.accounts.admin.key());
+                return err!(CustomError::Unauthorized);
+            };
+            if !by_super_admin {
+                stats.consume_quota(1, quota, now(ctx.remaining_accounts)?)?;
+            }
+
+            let bond_bump = stats.bond_bump;
+            require_keys_eq!(
+                ctx.accounts.freezer_bond.key(),
+                freezer_bond_address(&ctx.accounts.mint.key(), &ctx.accounts.admin.key(), bond_bump)?,
+                ErrorCode::ConstraintSeeds
+            );
+            let min_bond = ctx.accounts.mint_config.min_freezer_bond;
+            if min_bond > 0 {
+                let bonded = posted_bond(&ctx.accounts.freezer_bond)?.map_or(0, |bond| bond.bonded);
+                require!(bonded >= min_bond, CustomError::BondTooLow);
+            }
+            if update_bond_if_posted(&ctx.accounts.freezer_bond, FreezerBond::open_freeze)?.is_some() {
+                ctx.accounts.freeze_record.bond_bump = Some(bond_bump);
+            }
+        }
+        let now = now(ctx.remaining_accounts)?;
+        let token_account_key = ctx.accounts.token_account_to_process.key();
+        let mut history = load_account_history(
+            &ctx.accounts.account_history,
+            &token_account_key,
+            &ctx.accounts.admin,
+            &ctx.accounts.system_program,
+        )?;
+        // Infrastructure accounts (DEX pools, bridge custody) must never be frozen.
+        require_keys_eq!(
+            ctx.accounts.freeze_exempt.key(),
+            history.freeze_exempt_address()?,
+            ErrorCode::ConstraintSeeds
+        );
+        require!(ctx.accounts.freeze_exempt.data_is_empty(), CustomError::AccountExempt);
+        history.require_cooldown_elapsed(
+            ctx.accounts.mint_config.toggle_cooldown_seconds,
+            now,
+            ctx.accounts.super_admin.is_some(),
+        )?;
 
         let cpi_accounts = FreezeAccount {
             account: ctx.accounts.token_account_to_process.to_account_info(),
@@ -55,19 +174,91 @@ pub mod custom_token_program {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         
         // We need to provide the PDA seeds for the program to "sign" the transaction.
-        let seeds = &["authority".as_bytes(), &[ctx.bumps.program_authority]];
+        // Which PDA that is depends on whether this mint has been rotated to v2.
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
         let signer = &[&seeds[..]];
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
         token::freeze_account(cpi_ctx)?;
+
+        // Remember how much was frozen, so thaw takes the same figure back off the tally.
+        let amount = ctx.accounts.token_account_to_process.amount;
+        let record = &mut ctx.accounts.freeze_record;
+        record.mint = mint_key;
+        record.token_account = ctx.accounts.token_account_to_process.key();
+        record.owner = ctx.accounts.token_account_to_process.owner;
+        record.actor = ctx.accounts.admin.key();
+        record.amount = amount;
+        record.frozen_at = now;
+        record.bump = ctx.bumps.freeze_record;
+
+        reimburse_record_rent(
+            ctx.accounts.rent_payer.as_deref_mut(),
+            &ctx.accounts.admin.to_account_info(),
+            &mut ctx.accounts.freeze_record,
+        )?;
+
+        ctx.accounts.mint_config.record_freeze(amount)?;
+
+        emit!(AccountFrozen {
+            mint: ctx.accounts.mint.key(),
+            token_account: ctx.accounts.token_account_to_process.key(),
+            actor: ctx.accounts.admin.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        let reason = if by_mint_authority { HistoryReason::MintAuthority } else { HistoryReason::Freezer };
+        history.append(token_account_key, history.bump, HistoryAction::Freeze, ctx.accounts.admin.key(), reason, now)?;
+        store_account_history(&ctx.accounts.account_history, &history)?;
+
+        notify_hook(
+            ctx.accounts.mint_config.notification_hook,
+            ctx.accounts.notification_hook.as_ref(),
+            ctx.accounts.notifier.as_ref().map(|notifier| (notifier, ctx.accounts.mint_config.notifier_bump())),
+            &ctx.accounts.token_account_to_process.key(),
+            &mint_key,
+            true,
+            clock::without_mock_clock(ctx.remaining_accounts),
+        )?;
         
         Ok(())
     }
 
     // Instruction 4: Thaw (unfreeze) a user's token account.
-    pub fn thaw_token_account(ctx: Context<FreezeOrThawAccount>) -> Result<()> {
-        // Security Check: Ensure the signer is the original mint authority.
-        require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.mint.mint_authority.unwrap(), CustomError::Unauthorized);
+    pub fn thaw_token_account<'info>(ctx: Context<'_, '_, 'info, 'info, ThawTokenAccount<'info>>) -> Result<()> {
+        ctx.accounts.mint_config.require_not_permissioned()?;
+        // Security Check: the signer must be the original mint authority. This is
+        // checked on the accounts, see ThawTokenAccount.
+        let now = now(ctx.remaining_accounts)?;
+        let token_account_key = ctx.accounts.token_account_to_process.key();
+        let mut history = load_account_history(
+            &ctx.accounts.account_history,
+            &token_account_key,
+            &ctx.accounts.admin,
+            &ctx.accounts.system_program,
+        )?;
+        history.require_cooldown_elapsed(
+            ctx.accounts.mint_config.toggle_cooldown_seconds,
+            now,
+            ctx.accounts.super_admin.is_some(),
+        )?;
+
+        // Some mints also need the back office's off-chain approval key to sign off.
+        if ctx.accounts.mint_config.require_thaw_approval {
+            let instructions = ctx
+                .accounts
+                .instructions
+                .as_ref()
+                .ok_or(CustomError::MissingApprovalSignature)?;
+            let message = ed25519::verified_message(instructions, &ctx.accounts.mint_config.approval_signer)?;
+            check_thaw_approval(
+                &message,
+                &ctx.accounts.token_account_to_process.key(),
+                &ctx.accounts.mint.key(),
+                ctx.accounts.mint_config.max_approval_age_slots,
+            )?;
+        }
 
         let cpi_accounts = ThawAccount {
             account: ctx.accounts.token_account_to_process.to_account_info(),
@@ -77,80 +268,11281 @@ pub mod custom_token_program {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         
         // We use the same PDA seeds to sign for the thaw operation.
-        let seeds = &["authority".as_bytes(), &[ctx.bumps.program_authority]];
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
         let signer = &[&seeds[..]];
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
         token::thaw_account(cpi_ctx)?;
+
+        let amount = ctx.accounts.freeze_record.amount;
+        close_freeze_record(
+            &ctx.accounts.freeze_record,
+            ctx.accounts.rent_payer.as_deref(),
+            &ctx.accounts.admin.to_account_info(),
+        )?;
+        ctx.accounts.mint_config.record_thaw(amount)?;
+        if let Some(bond) = record_bond(&ctx.accounts.freezer_bond, &ctx.accounts.freeze_record)? {
+            update_bond_if_posted(bond, FreezerBond::close_freeze)?;
+        }
+
+        emit!(AccountThawed {
+            mint: ctx.accounts.mint.key(),
+            token_account: ctx.accounts.token_account_to_process.key(),
+            actor: ctx.accounts.admin.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        history.append(
+            token_account_key,
+            history.bump,
+            HistoryAction::Thaw,
+            ctx.accounts.admin.key(),
+            HistoryReason::MintAuthority,
+            now,
+        )?;
+        store_account_history(&ctx.accounts.account_history, &history)?;
+
+        notify_hook(
+            ctx.accounts.mint_config.notification_hook,
+            ctx.accounts.notification_hook.as_ref(),
+            ctx.accounts.notifier.as_ref().map(|notifier| (notifier, ctx.accounts.mint_config.notifier_bump())),
+            &ctx.accounts.token_account_to_process.key(),
+            &mint_key,
+            false,
+            clock::without_mock_clock(ctx.remaining_accounts),
+        )?;
         
         Ok(())
     }
-}
 
-// ====== Account Structs ======
+    // Instruction 5: Create the global program config, exactly once.
+    // Only the program's upgrade authority may call this, so nobody can
+    // front-run the deployer and claim super_admin right after deploy.
+    pub fn initialize_program_config(ctx: Context<InitializeProgramConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.program_config;
+        config.super_admin = ctx.accounts.authority.key();
+        config.bump = ctx.bumps.program_config;
+        // No delay until the super_admin opts in via set_timelock_delay.
+        config.timelock_delay = 0;
 
-#[derive(Accounts)]
-#[instruction(decimals: u8, mint_authority: Pubkey)]
-pub struct CreateTokenMint<'info> {
-    #[account(
-        init,
-        payer = payer,
-        mint::decimals = decimals,
-        mint::authority = mint_authority,
-        // CRITICAL: This sets our program's PDA as the freeze authority.
-        mint::freeze_authority = program_authority.key()
-    )]
-    pub mint: Account<'info, Mint>,
+        Ok(())
+    }
 
-    /// CHECK: This is our program's authority, a PDA. It doesn't need to be checked because we are defining it here.
-    #[account(
-        seeds = [b"authority"],
-        bump
-    )]
-    pub program_authority: UncheckedAccount<'info>,
+    // Instruction 6: Create the MintConfig for a mint that predates it.
+    // Mints created before configs existed still have our PDA as freeze
+    // authority, but no cached bumps. The mint authority can backfill them here.
+    pub fn migrate_mint_config(ctx: Context<MigrateMintConfig>) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts
+            .mint_config
+            .init(mint_key, ctx.bumps.program_authority, ctx.bumps.mint_config);
+        resume_sequence(&mut ctx.accounts.mint_config, &ctx.accounts.retired_mint)?;
 
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct DelegateTokens<'info> {
-    #[account(mut)]
-    pub token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: The account being delegated to. It can be any account.
-    pub delegate: UncheckedAccount<'info>,
-    
-    pub owner: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+    // Instruction 7: Queue a privileged action behind the timelock.
+    // `action_id` commits to the instruction and its arguments (see `admin_action_id`),
+    // so the queued action can only ever be executed exactly as announced.
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action_id: [u8; 32]) -> Result<()> {
+        let action = &mut ctx.accounts.admin_action;
+        action.action_id = action_id;
+        action.eta = now(ctx.remaining_accounts)?
+            .checked_add(ctx.accounts.program_config.timelock_delay)
+            .ok_or(CustomError::MathOverflow)?;
+        action.bump = ctx.bumps.admin_action;
 
-#[derive(Accounts)]
-pub struct FreezeOrThawAccount<'info> {
-    // The authority allowed to freeze/thaw (e.g., the original creator of the token).
-    pub admin: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub token_account_to_process: Account<'info, TokenAccount>,
-    
-    // We need the mint to verify that the admin is the mint_authority.
-    #[account(
-        constraint = mint.key() == token_account_to_process.mint
-    )]
-    pub mint: Account<'info, Mint>,
+    // Instruction 8: Drop a queued action before it is executed.
+    pub fn cancel_admin_action(_ctx: Context<CancelAdminAction>) -> Result<()> {
+        // The `close` constraint does the work.
+        Ok(())
+    }
 
-    /// CHECK: This is the same PDA from our CreateTokenMint instruction.
-    #[account(
-        seeds = [b"authority"],
-        bump
-    )]
-    pub program_authority: UncheckedAccount<'info>,
-    
-    pub token_program: Program<'info, Token>,
+    // Instruction 9: Change the timelock delay.
+    // Making the delay longer is always safe and applies immediately.
+    // Shortening it must itself wait out the current delay.
+    pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, delay: i64) -> Result<()> {
+        require!(delay >= 0, CustomError::InvalidTimelockDelay);
+
+        let config = &mut ctx.accounts.program_config;
+        if delay < config.timelock_delay {
+            let action = ctx
+                .accounts
+                .admin_action
+                .as_ref()
+                .ok_or(CustomError::TimelockRequired)?;
+            action.require_ready(admin_action_id(b"set_timelock_delay", &[&delay.to_le_bytes()]), now(ctx.remaining_accounts)?)?;
+            action.close(ctx.accounts.super_admin.to_account_info())?;
+        }
+        config.timelock_delay = delay;
+
+        Ok(())
+    }
+
+    // Instruction 10: Move a mint's freeze authority from the v1 `[b"authority"]`
+    // PDA to its per-mint `[b"authority_v2", mint]` PDA.
+    // Mints that are never rotated keep working with v1 seeds.
+    pub fn rotate_program_authority(ctx: Context<RotateProgramAuthority>) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts
+            .admin_action
+            .require_ready(admin_action_id(b"rotate_program_authority", &[mint_key.as_ref()]), now(ctx.remaining_accounts)?)?;
+
+        // The old PDA signs to hand its freeze authority over.
+        let cpi_accounts = SetAuthority {
+            current_authority: ctx.accounts.program_authority.to_account_info(),
+            account_or_mint: ctx.accounts.mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::set_authority(
+            cpi_ctx,
+            AuthorityType::FreezeAccount,
+            Some(ctx.accounts.new_program_authority.key()),
+        )?;
+
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.authority_v2_bump = ctx.bumps.new_program_authority;
+        mint_config.authority_version = AUTHORITY_VERSION_V2;
+
+        emit!(ProgramAuthorityRotated {
+            mint: mint_key,
+            program_authority: ctx.accounts.new_program_authority.key(),
+            sequence: mint_config.next_sequence()?,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 11: Create the missing FreezeRecord for an account that is
+    // already frozen (e.g. frozen before records existed) and count it in the tallies.
+    pub fn reconcile_frozen(ctx: Context<ReconcileFrozen>) -> Result<()> {
+        let amount = ctx.accounts.token_account.amount;
+        let record = &mut ctx.accounts.freeze_record;
+        record.mint = ctx.accounts.mint.key();
+        record.token_account = ctx.accounts.token_account.key();
+        record.owner = ctx.accounts.token_account.owner;
+        record.actor = ctx.accounts.admin.key();
+        record.amount = amount;
+        record.frozen_at = now(ctx.remaining_accounts)?;
+        record.bump = ctx.bumps.freeze_record;
+
+        ctx.accounts.mint_config.add_frozen(amount)?;
+
+        Ok(())
+    }
+
+    // Instruction 12: Protect a token account from ever being frozen by this program.
+    pub fn add_freeze_exempt(ctx: Context<AddFreezeExempt>, token_account: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&token_account)?;
+
+        let exempt = &mut ctx.accounts.freeze_exempt;
+        exempt.mint = ctx.accounts.mint.key();
+        exempt.token_account = token_account;
+        exempt.bump = ctx.bumps.freeze_exempt;
+
+        Ok(())
+    }
+
+    // Instruction 13: Lift a freeze exemption. The `close` constraint returns the rent.
+    pub fn remove_freeze_exempt(_ctx: Context<RemoveFreezeExempt>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 14: Mint tokens to a single destination, signed by the mint authority.
+    // Minting into a frozen account would trap the tokens, so it's refused.
+    // A `referrer` is credited with the mint in its ReferralStats.
+    pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64, referrer: Option<Pubkey>) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        // Nothing earlier in this instruction touches the destination, so its state is current.
+        require!(!ctx.accounts.destination.is_frozen(), CustomError::DestinationFrozen);
+
+        let owner = ctx.accounts.destination.owner;
+        if let Some(stats) = referral_stats(referrer, &owner, ctx.accounts.referral_stats.as_mut(), ctx.bumps.referral_stats)? {
+            stats.totals.mints = stats.totals.mints.checked_add(1).ok_or(CustomError::MathOverflow)?;
+            stats.totals.minted_volume =
+                stats.totals.minted_volume.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        }
+
+        mint_to_account(
+            &ctx.accounts.mint,
+            &ctx.accounts.destination,
+            &ctx.accounts.mint_authority,
+            &mut ctx.accounts.mint_config,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        checkpoint_balance(ctx.accounts.voting_checkpoints.as_mut(), &mut ctx.accounts.destination)?;
+
+        emit!(TokensMinted {
+            mint: ctx.accounts.mint.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            referrer,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 15: Mint to many destinations at once.
+    // remaining_accounts holds one destination token account per entry in `amounts`.
+    // Frozen destinations abort the whole batch, or are skipped and reported
+    // in the summary event when `allow_skip` is set.
+    pub fn distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+        amounts: Vec<u64>,
+        allow_skip: bool,
+    ) -> Result<()> {
+        require!(
+            amounts.len() == ctx.remaining_accounts.len(),
+            CustomError::InvalidRemainingAccounts
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let mut skipped = Vec::new();
+        let mut total_minted: u64 = 0;
+
+        for (destination_info, &amount) in ctx.remaining_accounts.iter().zip(amounts.iter()) {
+            validation::require_nonzero_amount(amount)?;
+
+            // Deserialized right before use, so an earlier CPI in this loop
+            // (e.g. the same account listed twice) can't leave us with stale state.
+            let destination = Account::<TokenAccount>::try_from(destination_info)?;
+            require_keys_eq!(destination.mint, mint_key, CustomError::MintMismatch);
+            if destination.is_frozen() {
+                require!(allow_skip, CustomError::DestinationFrozen);
+                skipped.push(destination_info.key());
+                continue;
+            }
+            mint_to_account(
+                &ctx.accounts.mint,
+                &destination,
+                &ctx.accounts.mint_authority,
+                &mut ctx.accounts.mint_config,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            total_minted = total_minted
+                .checked_add(amount)
+                .ok_or(CustomError::MathOverflow)?;
+        }
+
+        emit!(DistributionCompleted {
+            mint: mint_key,
+            recipients: (amounts.len() - skipped.len()) as u32,
+            total_minted,
+            skipped,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 16: Add to an existing allowance without a client-side read-modify-write.
+    // If no delegate is set yet this simply approves `additional`.
+    pub fn increase_delegation(ctx: Context<AdjustDelegation>, additional: u64) -> Result<()> {
+        validation::require_nonzero_amount(additional)?;
+        require_delegate_approved(&ctx.accounts.mint_config, ctx.accounts.approved_delegate.as_ref())?;
+        charge_delegation_fee(
+            &ctx.accounts.mint_config,
+            &ctx.accounts.owner,
+            ctx.accounts.fee_recipient.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let current = current_allowance(&ctx.accounts.token_account, &ctx.accounts.delegate.key())?;
+        let new_amount = current
+            .checked_add(additional)
+            .ok_or(CustomError::MathOverflow)?;
+        validation::require_within_balance(new_amount, ctx.accounts.token_account.amount)?;
+
+        let cpi_accounts = token_interface::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.delegate.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token_interface::approve(cpi_ctx, new_amount)?;
+
+        emit!(TokensDelegated {
+            token_account: ctx.accounts.token_account.key(),
+            delegate: ctx.accounts.delegate.key(),
+            amount: new_amount,
+            referrer: None,
+            sequence: update_if_registered(&ctx.accounts.mint_config, |config| config.next_sequence())?,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 17: Take away from an existing allowance.
+    // Reducing past zero clamps to a full revoke.
+    pub fn decrease_delegation(ctx: Context<AdjustDelegation>, reduction: u64) -> Result<()> {
+        validation::require_nonzero_amount(reduction)?;
+        require!(
+            ctx.accounts.token_account.delegate == COption::Some(ctx.accounts.delegate.key()),
+            CustomError::DelegateMismatch
+        );
+
+        let new_amount = ctx.accounts.token_account.delegated_amount.saturating_sub(reduction);
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        if new_amount == 0 {
+            let cpi_accounts = token_interface::Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            token_interface::revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+            emit!(DelegateRevoked {
+                token_account: ctx.accounts.token_account.key(),
+                delegate: ctx.accounts.delegate.key(),
+                sequence: update_if_registered(&ctx.accounts.mint_config, |config| {
+                    config.record_revocation()?;
+                    config.next_sequence()
+                })?,
+            });
+        } else {
+            let cpi_accounts = token_interface::Approve {
+                to: ctx.accounts.token_account.to_account_info(),
+                delegate: ctx.accounts.delegate.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            token_interface::approve(CpiContext::new(cpi_program, cpi_accounts), new_amount)?;
+            emit!(TokensDelegated {
+                token_account: ctx.accounts.token_account.key(),
+                delegate: ctx.accounts.delegate.key(),
+                amount: new_amount,
+                referrer: None,
+                sequence: update_if_registered(&ctx.accounts.mint_config, |config| config.next_sequence())?,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Instruction 18: Set the share of reclaimed rent paid to whoever cranks gc_records.
+    pub fn set_gc_reward_bps(ctx: Context<SetGcRewardBps>, gc_reward_bps: u16) -> Result<()> {
+        require!(gc_reward_bps <= BPS_DENOMINATOR, CustomError::InvalidBps);
+        ctx.accounts.program_config.gc_reward_bps = gc_reward_bps;
+        Ok(())
+    }
+
+    // Instruction 19: Permissionless crank closing records that no longer describe anything live.
+    // remaining_accounts holds up to MAX_GC_RECORDS triples of
+    // (record, account the record refers to, original rent payer). That is:
+    // - FreezeRecord: its token account, collectable once that is thawed or closed.
+    // - DelegationRecord: its token account, collectable once the terms have
+    //   expired or the token account is closed. The rent goes to the owner.
+    // - DelegationOffer: its token account, collectable once expired, as
+    //   close_expired_offer does. The rent goes to the owner.
+    // - ThawRequest: the token account's FreezeRecord, collectable once that
+    //   freeze is over. The rent goes to the owner.
+    // Every record is validated for its type; a single live record fails the whole call.
+    pub fn gc_records<'info>(ctx: Context<'_, '_, 'info, 'info, GcRecords<'info>>) -> Result<()> {
+        let now = now(ctx.remaining_accounts)?;
+        let triples = clock::without_mock_clock(ctx.remaining_accounts).chunks_exact(3);
+        require!(
+            triples.len() > 0 && triples.remainder().is_empty(),
+            CustomError::InvalidRemainingAccounts
+        );
+        require!(triples.len() <= MAX_GC_RECORDS, CustomError::BatchTooLarge);
+
+        let reward_bps = ctx.accounts.program_config.gc_reward_bps as u64;
+        let caller = ctx.accounts.caller.to_account_info();
+
+        for triple in triples {
+            let (record_info, target_info, payer_info) = (&triple[0], &triple[1], &triple[2]);
+            require_keys_eq!(*record_info.owner, crate::ID, CustomError::InvalidRecordAccount);
+
+            let data = record_info.try_borrow_data()?;
+            require!(data.len() >= 8, CustomError::InvalidRecordAccount);
+            let discriminator: [u8; 8] = data[..8].try_into().unwrap();
+            drop(data);
+
+            if discriminator == FreezeRecord::DISCRIMINATOR {
+                let record = Account::<FreezeRecord>::try_from(record_info)?;
+                require_keys_eq!(record.mint, ctx.accounts.mint_config.mint, CustomError::MintMismatch);
+                require_keys_eq!(target_info.key(), record.token_account, CustomError::InvalidRecordAccount);
+                require_keys_eq!(
+                    payer_info.key(),
+                    record.rent_payer.unwrap_or(record.actor),
+                    CustomError::InvalidRecordAccount
+                );
+
+                // Live while the token account still exists and is frozen.
+                if !target_info.data_is_empty() {
+                    let token_account = Account::<TokenAccount>::try_from(target_info)?;
+                    require!(!token_account.is_frozen(), CustomError::RecordStillLive);
+                }
+
+                ctx.accounts.mint_config.remove_frozen(record.amount)?;
+            } else if discriminator == DelegationRecord::DISCRIMINATOR {
+                let record = Account::<DelegationRecord>::try_from(record_info)?;
+                require_keys_eq!(record.mint, ctx.accounts.mint_config.mint, CustomError::MintMismatch);
+                require_keys_eq!(target_info.key(), record.token_account, CustomError::InvalidRecordAccount);
+                require_keys_eq!(payer_info.key(), record.owner, CustomError::InvalidRecordAccount);
+
+                // Live until its terms expire, for as long as the token account exists.
+                let expired = record.terms.expires_at.is_some_and(|expires_at| now >= expires_at);
+                require!(expired || target_info.data_is_empty(), CustomError::RecordStillLive);
+
+                ctx.accounts.mint_config.record_delegation_closed();
+            } else if discriminator == DelegationOffer::DISCRIMINATOR {
+                let offer = Account::<DelegationOffer>::try_from(record_info)?;
+                require_keys_eq!(offer.mint, ctx.accounts.mint_config.mint, CustomError::MintMismatch);
+                require_keys_eq!(target_info.key(), offer.token_account, CustomError::InvalidRecordAccount);
+                require_keys_eq!(payer_info.key(), offer.owner, CustomError::InvalidRecordAccount);
+
+                // Live until it expires, as in close_expired_offer.
+                require!(now >= offer.expires_at, CustomError::RecordStillLive);
+            } else if discriminator == ThawRequest::DISCRIMINATOR {
+                let request = Account::<ThawRequest>::try_from(record_info)?;
+                require_keys_eq!(request.mint, ctx.accounts.mint_config.mint, CustomError::MintMismatch);
+                let (freeze_record, _) = Pubkey::find_program_address(
+                    &[b"freeze_record", request.token_account.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(target_info.key(), freeze_record, CustomError::InvalidRecordAccount);
+                require_keys_eq!(payer_info.key(), request.owner, CustomError::InvalidRecordAccount);
+
+                // Live while the freeze it appeals against is.
+                require!(target_info.data_is_empty(), CustomError::RecordStillLive);
+            } else {
+                return err!(CustomError::InvalidRecordAccount);
+            }
+
+            close_with_reward(record_info, &caller, payer_info, reward_bps)?;
+        }
+
+        Ok(())
+    }
+
+    // Instruction 20: Set how long a recovery request stays open to dispute.
+    pub fn set_recovery_window(ctx: Context<SetRecoveryWindow>, recovery_window: i64) -> Result<()> {
+        require!(recovery_window >= 0, CustomError::InvalidDuration);
+        ctx.accounts.mint_config.recovery_window = recovery_window;
+        Ok(())
+    }
+
+    // Instruction 21: Start moving a token account to a new wallet.
+    // The owner hands delegate and close authority to the RecoveryRequest PDA,
+    // then the account is frozen so nobody (including an attacker holding the
+    // same key) can spend from it while the request is pending.
+    //
+    // An attacker can initiate a recovery to their own wallet just as easily.
+    // Nothing moves until the admin calls execute_recovery, so that step is
+    // where requests are vetted; a bogus one is cancelled by the admin and the
+    // real owner starts again.
+    pub fn initiate_recovery(ctx: Context<InitiateRecovery>, new_owner: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&new_owner)?;
+        require_keys_neq!(new_owner, ctx.accounts.owner.key(), CustomError::InvalidRecoveryTarget);
+
+        let token_account_key = ctx.accounts.token_account.key();
+        let balance = ctx.accounts.token_account.amount;
+        let request_key = ctx.accounts.recovery_request.key();
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        // Has to happen before the freeze: frozen accounts reject both.
+        let cpi_accounts = Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.recovery_request.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token::approve(CpiContext::new(cpi_program.clone(), cpi_accounts), balance)?;
+
+        let cpi_accounts = SetAuthority {
+            current_authority: ctx.accounts.owner.to_account_info(),
+            account_or_mint: ctx.accounts.token_account.to_account_info(),
+        };
+        token::set_authority(
+            CpiContext::new(cpi_program.clone(), cpi_accounts),
+            AuthorityType::CloseAccount,
+            Some(request_key),
+        )?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let now = now(ctx.remaining_accounts)?;
+        let record = &mut ctx.accounts.freeze_record;
+        record.mint = mint_key;
+        record.token_account = token_account_key;
+        record.owner = ctx.accounts.owner.key();
+        record.actor = ctx.accounts.owner.key();
+        record.amount = balance;
+        record.frozen_at = now;
+        record.bump = ctx.bumps.freeze_record;
+        ctx.accounts.mint_config.record_freeze(balance)?;
+
+        emit!(TokensDelegated {
+            token_account: token_account_key,
+            delegate: request_key,
+            amount: balance,
+            referrer: None,
+            sequence: Some(ctx.accounts.mint_config.next_sequence()?),
+        });
+        emit!(AccountFrozen {
+            mint: mint_key,
+            token_account: token_account_key,
+            actor: ctx.accounts.owner.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        let request = &mut ctx.accounts.recovery_request;
+        request.mint = mint_key;
+        request.token_account = token_account_key;
+        request.owner = ctx.accounts.owner.key();
+        request.new_owner = new_owner;
+        request.initiated_at = now;
+        request.bump = ctx.bumps.recovery_request;
+
+        Ok(())
+    }
+
+    // Instruction 22: After the dispute window, move the whole balance to the
+    // new owner's ATA and close the old account. All reclaimed rent goes to the
+    // new owner, since the old wallet is presumed compromised.
+    pub fn execute_recovery(ctx: Context<ExecuteRecovery>) -> Result<()> {
+        let request = &ctx.accounts.recovery_request;
+        let opens_at = request
+            .initiated_at
+            .checked_add(ctx.accounts.mint_config.recovery_window)
+            .ok_or(CustomError::MathOverflow)?;
+        require!(now(ctx.remaining_accounts)? >= opens_at, CustomError::RecoveryWindowActive);
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+
+        // Thaw with the freeze authority PDA...
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer))?;
+
+        // ...then the request PDA, as delegate and close authority, empties and closes it.
+        let token_account_key = ctx.accounts.token_account.key();
+        let request_seeds = &[
+            b"recovery".as_ref(),
+            token_account_key.as_ref(),
+            std::slice::from_ref(&request.bump),
+        ];
+        let request_signer = &[&request_seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.new_owner_token_account.to_account_info(),
+            authority: ctx.accounts.recovery_request.to_account_info(),
+        };
+        token::transfer_checked(
+            CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, request_signer),
+            ctx.accounts.token_account.amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            destination: ctx.accounts.new_owner.to_account_info(),
+            authority: ctx.accounts.recovery_request.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, request_signer))?;
+
+        // The request and freeze record are closed by their `close` constraints.
+        let amount = ctx.accounts.freeze_record.amount;
+        ctx.accounts.mint_config.record_thaw(amount)?;
+
+        emit!(AccountThawed {
+            mint: mint_key,
+            token_account: token_account_key,
+            actor: ctx.accounts.admin.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 23: Abandon a pending recovery and thaw the account.
+    // Only the admin can cancel; the owner may co-sign to clear the delegate
+    // too. If the owner alone could cancel, whoever holds a leaked key could
+    // cancel every request the real owner opens and open their own instead.
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        ctx.accounts.mint_config.require_not_permissioned()?;
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer))?;
+        let token_account_key = ctx.accounts.token_account.key();
+        emit!(AccountThawed {
+            mint: mint_key,
+            token_account: token_account_key,
+            actor: ctx.accounts.admin.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        // Give the close authority back. The request PDA can do that itself.
+        let request_seeds = &[
+            b"recovery".as_ref(),
+            token_account_key.as_ref(),
+            std::slice::from_ref(&ctx.accounts.recovery_request.bump),
+        ];
+        let request_signer = &[&request_seeds[..]];
+        let cpi_accounts = SetAuthority {
+            current_authority: ctx.accounts.recovery_request.to_account_info(),
+            account_or_mint: ctx.accounts.token_account.to_account_info(),
+        };
+        token::set_authority(
+            CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, request_signer),
+            AuthorityType::CloseAccount,
+            None,
+        )?;
+
+        // Revoking needs the owner's signature. Without it the request PDA stays
+        // delegate. That's harmless: only execute_recovery signs for it, and only
+        // once initiate_recovery has opened a request at the same address again,
+        // which approves it afresh for the balance at that time.
+        if ctx.accounts.owner.is_signer {
+            let cpi_accounts = Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            token::revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+            emit!(DelegateRevoked {
+                token_account: token_account_key,
+                delegate: ctx.accounts.recovery_request.key(),
+                sequence: Some(ctx.accounts.mint_config.next_sequence()?),
+            });
+        }
+
+        let amount = ctx.accounts.freeze_record.amount;
+        ctx.accounts.mint_config.record_thaw(amount)?;
+
+        Ok(())
+    }
+
+    // Instruction 24: Fund a claim-based airdrop.
+    // `merkle_root` commits to (claimant, amount) leaves; see `airdrop_leaf`.
+    pub fn create_airdrop(
+        ctx: Context<CreateAirdrop>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        claim_deadline: i64,
+    ) -> Result<()> {
+        validation::require_nonzero_amount(total_amount)?;
+        require!(claim_deadline > now(ctx.remaining_accounts)?, CustomError::InvalidDeadline);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), total_amount)?;
+
+        let airdrop = &mut ctx.accounts.airdrop;
+        airdrop.mint = ctx.accounts.mint.key();
+        airdrop.funder = ctx.accounts.funder.key();
+        airdrop.merkle_root = merkle_root;
+        airdrop.total_amount = total_amount;
+        airdrop.claimed_amount = 0;
+        airdrop.claim_deadline = claim_deadline;
+        airdrop.bump = ctx.bumps.airdrop;
+        airdrop.vault_bump = ctx.bumps.vault;
+
+        Ok(())
+    }
+
+    // Instruction 25: Claim an airdrop allocation with a merkle proof.
+    // The ClaimStatus PDA makes a second claim by the same wallet fail on `init`.
+    pub fn claim_airdrop(ctx: Context<ClaimAirdrop>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let airdrop = &ctx.accounts.airdrop;
+        require!(now(ctx.remaining_accounts)? <= airdrop.claim_deadline, CustomError::ClaimWindowClosed);
+
+        let leaf = airdrop_leaf(&ctx.accounts.claimant.key(), amount);
+        require!(merkle::verify(&proof, &airdrop.merkle_root, leaf), CustomError::InvalidProof);
+
+        let mint_key = airdrop.mint;
+        let funder_key = airdrop.funder;
+        let seeds = &[
+            b"airdrop".as_ref(),
+            mint_key.as_ref(),
+            funder_key.as_ref(),
+            airdrop.merkle_root.as_ref(),
+            std::slice::from_ref(&airdrop.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.claimant_token_account.to_account_info(),
+            authority: ctx.accounts.airdrop.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        let airdrop = &mut ctx.accounts.airdrop;
+        airdrop.claimed_amount = airdrop
+            .claimed_amount
+            .checked_add(amount)
+            .ok_or(CustomError::MathOverflow)?;
+
+        let status = &mut ctx.accounts.claim_status;
+        status.airdrop = airdrop.key();
+        status.claimant = ctx.accounts.claimant.key();
+        status.amount = amount;
+        status.bump = ctx.bumps.claim_status;
+
+        Ok(())
+    }
+
+    // Instruction 26: After the deadline, return whatever is left to the funder
+    // and close the vault.
+    pub fn reclaim_unclaimed(ctx: Context<ReclaimUnclaimed>) -> Result<()> {
+        let airdrop = &ctx.accounts.airdrop;
+        require!(now(ctx.remaining_accounts)? > airdrop.claim_deadline, CustomError::ClaimWindowOpen);
+
+        let mint_key = airdrop.mint;
+        let funder_key = airdrop.funder;
+        let seeds = &[
+            b"airdrop".as_ref(),
+            mint_key.as_ref(),
+            funder_key.as_ref(),
+            airdrop.merkle_root.as_ref(),
+            std::slice::from_ref(&airdrop.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.funder_token_account.to_account_info(),
+            authority: ctx.accounts.airdrop.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer),
+            ctx.accounts.vault.amount,
+        )?;
+
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.funder.to_account_info(),
+            authority: ctx.accounts.airdrop.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        Ok(())
+    }
+
+    // Instruction 27: Set (or clear) the merkle root of owners allowed to self-thaw.
+    // Proofs are always checked against the current root, so rotating it
+    // invalidates every old proof immediately.
+    pub fn set_whitelist_root(ctx: Context<SetWhitelistRoot>, whitelist_root: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.mint_config.whitelist_root = whitelist_root;
+        Ok(())
+    }
+
+    // Instruction 28: Thaw an account whose owner proves membership in the whitelist tree.
+    pub fn thaw_with_proof(ctx: Context<ThawWithProof>, proof: Vec<[u8; 32]>) -> Result<()> {
+        ctx.accounts.mint_config.require_not_permissioned()?;
+        let root = ctx
+            .accounts
+            .mint_config
+            .whitelist_root
+            .ok_or(CustomError::WhitelistNotSet)?;
+        let leaf = whitelist_leaf(&ctx.accounts.token_account_to_process.owner);
+        require!(merkle::verify(&proof, &root, leaf), CustomError::InvalidProof);
+
+        let mint_config = &ctx.accounts.mint_config;
+        charge_lamport_fee(
+            &ctx.accounts.caller.to_account_info(),
+            ctx.accounts.fee_recipient.as_ref(),
+            &mint_config.fee_recipient,
+            mint_config.fee_tier.thaw_fee,
+            &ctx.accounts.system_program,
+        )?;
+
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.token_account_to_process.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token::thaw_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let amount = ctx.accounts.freeze_record.amount;
+        close_freeze_record(
+            &ctx.accounts.freeze_record,
+            ctx.accounts.rent_payer.as_deref(),
+            &ctx.accounts.freeze_actor.to_account_info(),
+        )?;
+        ctx.accounts.mint_config.record_thaw(amount)?;
+        if let Some(bond) = record_bond(&ctx.accounts.freezer_bond, &ctx.accounts.freeze_record)? {
+            update_bond_if_posted(bond, FreezerBond::close_freeze)?;
+        }
+
+        emit!(AccountThawed {
+            mint: ctx.accounts.mint.key(),
+            token_account: ctx.accounts.token_account_to_process.key(),
+            actor: ctx.accounts.caller.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        ctx.accounts.account_history.append(
+            ctx.accounts.token_account_to_process.key(),
+            ctx.bumps.account_history,
+            HistoryAction::Thaw,
+            ctx.accounts.caller.key(),
+            HistoryReason::WhitelistProof,
+            now(ctx.remaining_accounts)?,
+        )?;
+
+        Ok(())
+    }
+
+    // Instruction 29: Configure the off-chain co-signature required by thaw_token_account.
+    pub fn set_thaw_approval(
+        ctx: Context<SetThawApproval>,
+        require_thaw_approval: bool,
+        approval_signer: Pubkey,
+        max_approval_age_slots: u64,
+    ) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.require_thaw_approval = require_thaw_approval;
+        mint_config.approval_signer = approval_signer;
+        mint_config.max_approval_age_slots = max_approval_age_slots;
+        Ok(())
+    }
+
+    // Instruction 30: Set how many accounts each freezer may freeze per UTC day.
+    pub fn set_freeze_quota(ctx: Context<SetFreezeQuota>, freeze_quota_per_day: u16) -> Result<()> {
+        ctx.accounts.mint_config.freeze_quota_per_day = freeze_quota_per_day;
+        Ok(())
+    }
+
+    // Instruction 31: Let another key freeze accounts of this mint, within the daily quota.
+    pub fn add_freezer(ctx: Context<AddFreezer>, freezer: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&freezer)?;
+
+        let stats = &mut ctx.accounts.freezer_stats;
+        stats.mint = ctx.accounts.mint.key();
+        stats.freezer = freezer;
+        stats.bump = ctx.bumps.freezer_stats;
+        // Found here once, so freezes don't search for it.
+        stats.bond_bump = Pubkey::find_program_address(
+            &[b"freezer_bond", stats.mint.as_ref(), freezer.as_ref()],
+            &crate::ID,
+        )
+        .1;
+        Ok(())
+    }
+
+    // Instruction 32: Take the freezer role away. The `close` constraint returns the rent.
+    pub fn remove_freezer(_ctx: Context<RemoveFreezer>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 33: Move tokens into an escrow only `delegate` can spend from.
+    // Unlike `approve`, the owner can't spend them out from under the delegate.
+    // Calling it again for the same delegate tops the escrow up.
+    pub fn escrow_delegate(ctx: Context<EscrowDelegate>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.mint = ctx.accounts.mint.key();
+        escrow.owner = ctx.accounts.owner.key();
+        escrow.delegate = ctx.accounts.delegate.key();
+        escrow.remaining = escrow
+            .remaining
+            .checked_add(amount)
+            .ok_or(CustomError::MathOverflow)?;
+        escrow.bump = ctx.bumps.escrow;
+        escrow.vault_bump = ctx.bumps.vault;
+
+        Ok(())
+    }
+
+    // Instruction 34: The delegate moves escrowed tokens to `destination`.
+    pub fn escrow_spend(ctx: Context<EscrowSpend>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let escrow = &ctx.accounts.escrow;
+        let remaining = escrow
+            .remaining
+            .checked_sub(amount)
+            .ok_or(CustomError::InsufficientEscrow)?;
+
+        let seeds = &[
+            b"escrow".as_ref(),
+            escrow.mint.as_ref(),
+            escrow.owner.as_ref(),
+            escrow.delegate.as_ref(),
+            std::slice::from_ref(&escrow.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        ctx.accounts.escrow.remaining = remaining;
+        Ok(())
+    }
+
+    // Instruction 35: The owner takes back whatever is left and closes the escrow.
+    pub fn escrow_reclaim(ctx: Context<EscrowReclaim>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        let seeds = &[
+            b"escrow".as_ref(),
+            escrow.mint.as_ref(),
+            escrow.owner.as_ref(),
+            escrow.delegate.as_ref(),
+            std::slice::from_ref(&escrow.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer),
+            ctx.accounts.vault.amount,
+        )?;
+
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        Ok(())
+    }
+
+    // Instruction 36: Open the caller's program-owned vault for this mint.
+    pub fn open_vault(ctx: Context<OpenVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.mint = ctx.accounts.mint.key();
+        vault.owner = ctx.accounts.owner.key();
+        vault.bump = ctx.bumps.vault;
+        vault.token_account_bump = ctx.bumps.vault_token_account;
+        Ok(())
+    }
+
+    // Instruction 37: Move tokens from the owner's account into their vault.
+    pub fn deposit(ctx: Context<VaultDeposit>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+        Ok(())
+    }
+
+    // Instruction 38: Move tokens from the vault back to the owner.
+    pub fn withdraw(ctx: Context<VaultWithdraw>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        // The token program would refuse too; this gives the caller a clearer error.
+        require!(!ctx.accounts.vault_token_account.is_frozen(), CustomError::VaultFrozen);
+
+        let vault = &ctx.accounts.vault;
+        let seeds = vault.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+        Ok(())
+    }
+
+    // Instruction 39: Close an empty vault and return its rent to the owner.
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        require!(ctx.accounts.vault_token_account.amount == 0, CustomError::VaultNotEmpty);
+
+        let vault = &ctx.accounts.vault;
+        let seeds = vault.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+        Ok(())
+    }
+
+    // Instruction 40: Pay many recipients out of the owner's vault in one go.
+    // remaining_accounts holds one recipient token account per entry in `amounts`.
+    pub fn run_payroll<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RunPayroll<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        pay_from_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            &amounts,
+        )
+    }
+
+    // Instruction 41: Pay the same recipients from the vault every `period_seconds`.
+    // remaining_accounts holds one recipient token account per entry in `amounts`.
+    pub fn create_payroll_schedule<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreatePayrollSchedule<'info>>,
+        period_seconds: i64,
+        amounts: Vec<u64>,
+        first_run_ts: i64,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.vault = ctx.accounts.vault.key();
+        schedule.next_run_ts = first_run_ts;
+        schedule.bump = ctx.bumps.schedule;
+        schedule.set_payments(&ctx.accounts.vault, period_seconds, ctx.remaining_accounts, amounts)
+    }
+
+    // Instruction 42: Permissionless crank paying out one period of a schedule.
+    // Each call pays exactly once and moves `next_run_ts` on by exactly one period.
+    // If several periods were missed, they are not paid together: the next call
+    // pays one and advances one, and the schedule stays due until it catches up.
+    pub fn execute_payroll<'info>(ctx: Context<'_, '_, 'info, 'info, ExecutePayroll<'info>>) -> Result<()> {
+        let schedule = &ctx.accounts.schedule;
+        require!(
+            now(ctx.remaining_accounts)? >= schedule.next_run_ts,
+            CustomError::PayrollNotDue
+        );
+        let recipients = clock::without_mock_clock(ctx.remaining_accounts);
+        require!(
+            recipients.len() == schedule.recipients.len()
+                && recipients
+                    .iter()
+                    .zip(&schedule.recipients)
+                    .all(|(info, recipient)| info.key() == *recipient),
+            CustomError::InvalidRemainingAccounts
+        );
+
+        pay_from_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.token_program,
+            recipients,
+            &schedule.amounts,
+        )?;
+
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.next_run_ts = schedule
+            .next_run_ts
+            .checked_add(schedule.period_seconds)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 43: Replace a schedule's period, recipients and amounts.
+    // The next run stays where it was.
+    pub fn update_schedule<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdatePayrollSchedule<'info>>,
+        period_seconds: i64,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .schedule
+            .set_payments(&ctx.accounts.vault, period_seconds, ctx.remaining_accounts, amounts)
+    }
+
+    // Instruction 44: Stop a schedule. The `close` constraint returns the rent.
+    pub fn cancel_schedule(_ctx: Context<CancelPayrollSchedule>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 45: Set a mint's staking inflation, behind the timelock.
+    // The first call also creates the mint's RewardPool and its vault.
+    // The mint authority must already be the `[b"mint_authority", mint]` PDA.
+    pub fn set_inflation(ctx: Context<SetInflation>, inflation_rate_bps: u16, interval: i64) -> Result<()> {
+        require!(inflation_rate_bps <= MAX_INFLATION_RATE_BPS, CustomError::InvalidInflationRate);
+        require!(interval > 0, CustomError::InvalidDuration);
+
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts.admin_action.require_ready(admin_action_id(
+            b"set_inflation",
+            &[mint_key.as_ref(), &inflation_rate_bps.to_le_bytes(), &interval.to_le_bytes()],
+        ), now(ctx.remaining_accounts)?)?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        if pool.mint == Pubkey::default() {
+            pool.mint = mint_key;
+            pool.bump = ctx.bumps.reward_pool;
+            pool.vault_bump = ctx.bumps.reward_vault;
+            pool.mint_authority_bump = ctx.bumps.mint_authority;
+        }
+        // Issuance accrues from now on at the new rate; crank first to settle
+        // anything owed at the old one.
+        pool.inflation_rate_bps = inflation_rate_bps;
+        pool.inflation_interval = interval;
+        pool.last_inflation_ts = now(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Instruction 46: Permissionless crank minting staking rewards into the reward vault.
+    // Mints `inflation_rate_bps` of the supply per year, pro-rated over the time
+    // since the last mint. Within an interval of the last mint it does nothing,
+    // so bots can call it blindly. While nothing is staked it mints nothing
+    // and moves the time on: inflation built up over an empty pool would all
+    // go to whoever staked first. While what is due would round to nothing it
+    // does nothing, and leaves the time to count towards the next mint.
+    pub fn crank_inflation(ctx: Context<CrankInflation>) -> Result<()> {
+        let now = now(ctx.remaining_accounts)?;
+        let pool = &ctx.accounts.reward_pool;
+        let elapsed = now.saturating_sub(pool.last_inflation_ts);
+        if elapsed < pool.inflation_interval {
+            return Ok(());
+        }
+        if pool.total_staked == 0 {
+            ctx.accounts.reward_pool.last_inflation_ts = now;
+            return Ok(());
+        }
+
+        let amount = (ctx.accounts.mint.supply as u128)
+            .checked_mul(pool.inflation_rate_bps as u128)
+            .and_then(|v| v.checked_mul(elapsed as u128))
+            .map(|v| v / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(CustomError::MathOverflow)?;
+        let increase = staking::reward_per_token_increase(amount, pool.total_staked);
+        if increase == 0 {
+            return Ok(());
+        }
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"mint_authority".as_ref(),
+            mint_key.as_ref(),
+            std::slice::from_ref(&pool.mint_authority_bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::mint_to(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        // Shared out over whatever is staked right now.
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.reward_per_token = pool
+            .reward_per_token
+            .checked_add(increase)
+            .ok_or(CustomError::MathOverflow)?;
+        pool.last_inflation_ts = now;
+        Ok(())
+    }
+
+    // Instruction 47: Burn tokens against an off-chain order.
+    // The receipt PDA is keyed by `order_id`, so each order can be redeemed once.
+    pub fn redeem(ctx: Context<Redeem>, amount: u64, order_id: [u8; 32]) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.redeemer_token_account.to_account_info(),
+            authority: ctx.accounts.redeemer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::burn(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+        let slot = Clock::get()?.slot;
+        let created_at = now(ctx.remaining_accounts)?;
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.mint = ctx.accounts.mint.key();
+        receipt.redeemer = ctx.accounts.redeemer.key();
+        receipt.amount = amount;
+        receipt.order_id = order_id;
+        receipt.slot = slot;
+        receipt.created_at = created_at;
+        receipt.status = RedemptionStatus::Pending;
+        receipt.bump = ctx.bumps.receipt;
+
+        Ok(())
+    }
+
+    // Instruction 48: Mark a redemption as shipped.
+    pub fn fulfill_redemption(ctx: Context<FulfillRedemption>) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        require!(receipt.status == RedemptionStatus::Pending, CustomError::RedemptionNotPending);
+        receipt.status = RedemptionStatus::Fulfilled;
+        Ok(())
+    }
+
+    // Instruction 49: Mint the tokens back for a redemption never fulfilled in time.
+    // The receipt stays, marked refunded, so the order id can't be used again.
+    pub fn refund_redemption(ctx: Context<RefundRedemption>) -> Result<()> {
+        let receipt = &ctx.accounts.receipt;
+        require!(receipt.status == RedemptionStatus::Pending, CustomError::RedemptionNotPending);
+        let expires_at = receipt
+            .created_at
+            .checked_add(REDEMPTION_EXPIRY)
+            .ok_or(CustomError::MathOverflow)?;
+        require!(now(ctx.remaining_accounts)? >= expires_at, CustomError::RedemptionNotExpired);
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"mint_authority".as_ref(),
+            mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.redeemer_token_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::mint_to(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), receipt.amount)?;
+
+        ctx.accounts.receipt.status = RedemptionStatus::Refunded;
+        Ok(())
+    }
+
+    // Instruction 50: Set up bridge custody for a mint.
+    // `bridge_operator` is the only key that may release locked tokens.
+    pub fn initialize_bridge(ctx: Context<InitializeBridge>, bridge_operator: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&bridge_operator)?;
+
+        let bridge = &mut ctx.accounts.bridge;
+        bridge.mint = ctx.accounts.mint.key();
+        bridge.bridge_operator = bridge_operator;
+        bridge.next_nonce = 0;
+        bridge.bump = ctx.bumps.bridge;
+        bridge.vault_bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // Instruction 51: Lock tokens in the bridge vault to be minted on another chain.
+    // Every lock gets the next nonce, which the other side uses to dedupe.
+    pub fn bridge_lock(
+        ctx: Context<BridgeLock>,
+        amount: u64,
+        destination_chain: u16,
+        recipient: [u8; 32],
+    ) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.sender_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.sender.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+        let bridge = &mut ctx.accounts.bridge;
+        let nonce = bridge.next_nonce;
+        bridge.next_nonce = nonce.checked_add(1).ok_or(CustomError::MathOverflow)?;
+
+        emit!(BridgeLocked {
+            mint: bridge.mint,
+            sender: ctx.accounts.sender.key(),
+            amount,
+            destination_chain,
+            recipient,
+            nonce,
+        });
+        Ok(())
+    }
+
+    // Instruction 52: Release tokens for a message from the other chain.
+    // The ProcessedNonce PDA makes replaying the same `source_nonce` fail on `init`.
+    pub fn bridge_unlock(ctx: Context<BridgeUnlock>, amount: u64, source_nonce: u64, recipient: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&recipient)?;
+        validation::require_nonzero_amount(amount)?;
+
+        let bridge = &ctx.accounts.bridge;
+        let seeds = &[
+            b"bridge".as_ref(),
+            bridge.mint.as_ref(),
+            std::slice::from_ref(&bridge.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: ctx.accounts.bridge.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        let processed = &mut ctx.accounts.processed_nonce;
+        processed.bridge = ctx.accounts.bridge.key();
+        processed.nonce = source_nonce;
+        processed.recipient = recipient;
+        processed.amount = amount;
+        processed.bump = ctx.bumps.processed_nonce;
+
+        emit!(BridgeUnlocked {
+            mint: ctx.accounts.bridge.mint,
+            recipient,
+            amount,
+            source_nonce,
+        });
+        Ok(())
+    }
+
+    // Instruction 53: Create a program-minted wrapper for `underlying_mint`,
+    // backed 1:1 by underlying tokens held in custody.
+    pub fn initialize_wrapper(ctx: Context<InitializeWrapper>) -> Result<()> {
+        let wrapper = &mut ctx.accounts.wrapper;
+        wrapper.underlying_mint = ctx.accounts.underlying_mint.key();
+        wrapper.wrapped_mint = ctx.accounts.wrapped_mint.key();
+        wrapper.bump = ctx.bumps.wrapper;
+        wrapper.wrapped_mint_bump = ctx.bumps.wrapped_mint;
+        wrapper.vault_bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // Instruction 54: Deposit underlying tokens and receive as many wrapped tokens.
+    pub fn wrap(ctx: Context<Wrap>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_underlying.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(CpiContext::new(cpi_program.clone(), cpi_accounts), amount)?;
+
+        let wrapper = &ctx.accounts.wrapper;
+        let seeds = &[
+            b"wrapper".as_ref(),
+            wrapper.underlying_mint.as_ref(),
+            std::slice::from_ref(&wrapper.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.wrapped_mint.to_account_info(),
+            to: ctx.accounts.user_wrapped.to_account_info(),
+            authority: ctx.accounts.wrapper.to_account_info(),
+        };
+        token::mint_to(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        ctx.accounts.wrapped_mint.reload()?;
+        ctx.accounts.vault.reload()?;
+        require_backed(&ctx.accounts.wrapped_mint, &ctx.accounts.vault)
+    }
+
+    // Instruction 55: Burn wrapped tokens and get the underlying back.
+    pub fn unwrap(ctx: Context<Wrap>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.wrapped_mint.to_account_info(),
+            from: ctx.accounts.user_wrapped.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::burn(CpiContext::new(cpi_program.clone(), cpi_accounts), amount)?;
+
+        let wrapper = &ctx.accounts.wrapper;
+        let seeds = &[
+            b"wrapper".as_ref(),
+            wrapper.underlying_mint.as_ref(),
+            std::slice::from_ref(&wrapper.bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_underlying.to_account_info(),
+            authority: ctx.accounts.wrapper.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        ctx.accounts.wrapped_mint.reload()?;
+        ctx.accounts.vault.reload()?;
+        require_backed(&ctx.accounts.wrapped_mint, &ctx.accounts.vault)
+    }
+
+    // Instruction 56: View of the wrapper's backing, for monitoring.
+    pub fn wrapper_backing(ctx: Context<WrapperBacking>) -> Result<Backing> {
+        Ok(Backing {
+            wrapped_supply: ctx.accounts.wrapped_mint.supply,
+            vault_balance: ctx.accounts.vault.amount,
+        })
+    }
+
+    // Instruction 57: Let holders swap `old_mint` for `new_mint` at numerator/denominator.
+    // The new mint's authority goes to the `[b"mint_authority", new_mint]` PDA
+    // here if the admin still holds it.
+    pub fn initialize_migration(
+        ctx: Context<InitializeMigration>,
+        numerator: u64,
+        denominator: u64,
+        deadline: i64,
+        vault_old_tokens: bool,
+    ) -> Result<()> {
+        require!(numerator > 0 && denominator > 0, CustomError::InvalidRatio);
+        require!(deadline > now(ctx.remaining_accounts)?, CustomError::InvalidDeadline);
+
+        let pda_authority = ctx.accounts.new_mint_authority.key();
+        match ctx.accounts.new_mint.mint_authority {
+            COption::Some(current) if current == pda_authority => {}
+            COption::Some(current) if current == ctx.accounts.admin.key() => {
+                let cpi_accounts = SetAuthority {
+                    current_authority: ctx.accounts.admin.to_account_info(),
+                    account_or_mint: ctx.accounts.new_mint.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                token::set_authority(
+                    CpiContext::new(cpi_program, cpi_accounts),
+                    AuthorityType::MintTokens,
+                    Some(pda_authority),
+                )?;
+            }
+            _ => return err!(CustomError::WrongMintAuthority),
+        }
+
+        let migration = &mut ctx.accounts.migration;
+        migration.admin = ctx.accounts.admin.key();
+        migration.old_mint = ctx.accounts.old_mint.key();
+        migration.new_mint = ctx.accounts.new_mint.key();
+        migration.numerator = numerator;
+        migration.denominator = denominator;
+        migration.deadline = deadline;
+        migration.vault_old_tokens = vault_old_tokens;
+        migration.bump = ctx.bumps.migration;
+        migration.vault_bump = ctx.bumps.vault;
+        migration.new_mint_authority_bump = ctx.bumps.new_mint_authority;
+        Ok(())
+    }
+
+    // Instruction 58: Swap old tokens for new ones. Rounds down.
+    // The old tokens are burned, or kept in the migration vault if configured.
+    pub fn migrate_tokens(ctx: Context<MigrateTokens>, amount: u64) -> Result<()> {
+        let migration = &ctx.accounts.migration;
+        require!(!migration.finalized, CustomError::MigrationFinalized);
+        validation::require_nonzero_amount(amount)?;
+
+        let new_amount = (amount as u128)
+            .checked_mul(migration.numerator as u128)
+            .map(|v| v / migration.denominator as u128)
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(CustomError::MathOverflow)?;
+        // Don't take tokens for nothing when the amount rounds down to zero.
+        validation::require_nonzero_amount(new_amount)?;
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        if migration.vault_old_tokens {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.holder_old.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            };
+            token::transfer(CpiContext::new(cpi_program.clone(), cpi_accounts), amount)?;
+        } else {
+            let cpi_accounts = Burn {
+                mint: ctx.accounts.old_mint.to_account_info(),
+                from: ctx.accounts.holder_old.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            };
+            token::burn(CpiContext::new(cpi_program.clone(), cpi_accounts), amount)?;
+        }
+
+        let new_mint_key = migration.new_mint;
+        let seeds = &[
+            b"mint_authority".as_ref(),
+            new_mint_key.as_ref(),
+            std::slice::from_ref(&migration.new_mint_authority_bump),
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.new_mint.to_account_info(),
+            to: ctx.accounts.holder_new.to_account_info(),
+            authority: ctx.accounts.new_mint_authority.to_account_info(),
+        };
+        token::mint_to(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), new_amount)?;
+
+        let migration = &mut ctx.accounts.migration;
+        migration.total_old_migrated = migration
+            .total_old_migrated
+            .checked_add(amount)
+            .ok_or(CustomError::MathOverflow)?;
+        migration.total_new_minted = migration
+            .total_new_minted
+            .checked_add(new_amount)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 59: After the deadline, stop any further swaps.
+    pub fn finalize_migration(ctx: Context<FinalizeMigration>) -> Result<()> {
+        let migration = &mut ctx.accounts.migration;
+        require!(now(ctx.remaining_accounts)? > migration.deadline, CustomError::MigrationStillOpen);
+        migration.finalized = true;
+        Ok(())
+    }
+
+    // Instruction 60: Change a mint's rebase factor (scaled by `rebase::REBASE_SCALE`),
+    // behind the timelock.
+    pub fn set_rebase_factor(ctx: Context<SetRebaseFactor>, rebase_factor: u64) -> Result<()> {
+        rebase::validate_factor(rebase_factor)?;
+
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts.admin_action.require_ready(admin_action_id(
+            b"set_rebase_factor",
+            &[mint_key.as_ref(), &rebase_factor.to_le_bytes()],
+        ), now(ctx.remaining_accounts)?)?;
+
+        ctx.accounts.mint_config.rebase_factor = rebase_factor;
+        Ok(())
+    }
+
+    // Instruction 61: View of a token account's balance in effective units.
+    pub fn get_effective_balance(ctx: Context<GetEffectiveBalance>) -> Result<u64> {
+        rebase::to_effective(ctx.accounts.token_account.amount, ctx.accounts.mint_config.rebase_factor)
+    }
+
+    // Instruction 62: Transfer between two accounts of a program mint.
+    // With `effective_units` set, `amount` is converted to raw first (see `rebase::to_raw`).
+    pub fn transfer_tokens(ctx: Context<TransferTokens>, amount: u64, effective_units: bool) -> Result<()> {
+        let raw_amount = if effective_units {
+            rebase::to_raw(amount, ctx.accounts.mint_config.rebase_factor)?
+        } else {
+            amount
+        };
+        validation::require_nonzero_amount(raw_amount)?;
+
+        let accounts = &mut *ctx.accounts;
+        transfer_with_tax(
+            &accounts.owner,
+            &accounts.mint,
+            &mut accounts.mint_config,
+            &mut accounts.from,
+            &mut accounts.to,
+            accounts.fee_token_account.as_mut(),
+            &accounts.token_program,
+            raw_amount,
+        )?;
+        checkpoint_balance(ctx.accounts.from_checkpoints.as_mut(), &mut ctx.accounts.from)?;
+        checkpoint_balance(ctx.accounts.to_checkpoints.as_mut(), &mut ctx.accounts.to)?;
+        Ok(())
+    }
+
+    // Instruction 63: Set the fees this mint charges. Fees go to the caller.
+    pub fn set_fee_tier(ctx: Context<SetFeeTier>, tier: FeeTier) -> Result<()> {
+        require!(
+            tier.delegation_fee <= MAX_DELEGATION_FEE
+                && tier.thaw_fee <= MAX_THAW_FEE
+                && tier.transfer_tax_bps <= MAX_TRANSFER_TAX_BPS,
+            CustomError::FeeAboveCap
+        );
+
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.fee_tier = tier;
+        mint_config.fee_recipient = ctx.accounts.admin.key();
+        Ok(())
+    }
+
+    // Instruction 64: View a referrer's running totals.
+    pub fn get_referral_stats(ctx: Context<GetReferralStats>) -> Result<ReferralTotals> {
+        Ok(ctx.accounts.referral_stats.totals.clone())
+    }
+
+    // Instruction 65: Adopt a mint created outside this program, e.g. with the
+    // spl-token CLI. Its freeze authority signs the freeze authority over to our
+    // PDA, and the mint gets its MintConfig in the same transaction.
+    pub fn register_existing_mint(ctx: Context<RegisterExistingMint>) -> Result<()> {
+        let cpi_accounts = SetAuthority {
+            current_authority: ctx.accounts.admin.to_account_info(),
+            account_or_mint: ctx.accounts.mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::set_authority(
+            CpiContext::new(cpi_program, cpi_accounts),
+            AuthorityType::FreezeAccount,
+            Some(ctx.accounts.program_authority.key()),
+        )?;
+
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts
+            .mint_config
+            .init(mint_key, ctx.bumps.program_authority, ctx.bumps.mint_config);
+        resume_sequence(&mut ctx.accounts.mint_config, &ctx.accounts.retired_mint)?;
+
+        Ok(())
+    }
+
+    // Instruction 66: Hand a mint's freeze authority back and drop its MintConfig.
+    // Timelocked, and refused while any account of the mint is still frozen, so
+    // nobody is left frozen with no one able to thaw them.
+    pub fn deregister_mint(ctx: Context<DeregisterMint>, new_freeze_authority: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&new_freeze_authority)?;
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts.admin_action.require_ready(
+            admin_action_id(b"deregister_mint", &[mint_key.as_ref(), new_freeze_authority.as_ref()]),
+            now(ctx.remaining_accounts)?,
+        )?;
+        require!(
+            ctx.accounts.mint_config.frozen_account_count == 0,
+            CustomError::FrozenAccountsOutstanding
+        );
+
+        let cpi_accounts = SetAuthority {
+            current_authority: ctx.accounts.program_authority.to_account_info(),
+            account_or_mint: ctx.accounts.mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token::set_authority(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            AuthorityType::FreezeAccount,
+            Some(new_freeze_authority),
+        )?;
+
+        emit!(MintDeregistered {
+            mint: mint_key,
+            new_freeze_authority,
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        let retired = &mut ctx.accounts.retired_mint;
+        retired.mint = mint_key;
+        retired.sequence = ctx.accounts.mint_config.sequence;
+        retired.bump = ctx.bumps.retired_mint;
+
+        Ok(())
+    }
+
+    // Instruction 67: View a token account's freeze/thaw history, oldest first.
+    pub fn get_account_history(ctx: Context<GetAccountHistory>) -> Result<Vec<HistoryEntry>> {
+        Ok(ctx.accounts.account_history.entries.clone())
+    }
+
+    // Instruction 68: Revoke the delegate of a frozen account. SPL refuses to
+    // revoke on a frozen account, so the program thaws it, revokes with the
+    // owner's signature and freezes it again, all in this one instruction.
+    // The account is never spendable in between, and its FreezeRecord and the
+    // mint's frozen tally stay as they are. Unfrozen accounts use a plain revoke.
+    // Refused while a recovery is pending: its request PDA is the delegate
+    // execute_recovery moves the balance with, and a leaked key could
+    // otherwise revoke it out from under every request.
+    pub fn revoke_while_frozen(ctx: Context<RevokeWhileFrozen>) -> Result<()> {
+        require!(ctx.accounts.recovery_request.data_is_empty(), CustomError::RecoveryPending);
+        let delegate = ctx.accounts.token_account.delegate;
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer))?;
+
+        let cpi_accounts = Revoke {
+            source: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token::revoke(CpiContext::new(cpi_program.clone(), cpi_accounts))?;
+
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+        ctx.accounts.mint_config.record_revocation()?;
+
+        emit!(FrozenDelegateRevoked {
+            token_account: ctx.accounts.token_account.key(),
+            delegate: delegate.into(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 69: Delegate with a DelegationRecord, so spends made through
+    // spend_delegated are limited by `terms` and accounted for. In
+    // ProgramEnforced mode the SPL delegate is the record PDA, which only
+    // spend_delegated signs for; in Advisory mode it is `delegate` itself.
+    // A non-empty `allowed_destinations` is the only token accounts it may pay.
+    pub fn create_delegation(
+        ctx: Context<CreateDelegation>,
+        terms: DelegationTerms,
+        allowed_destinations: Vec<Pubkey>,
+    ) -> Result<()> {
+        validation::require_nonzero_amount(terms.allowance)?;
+        validation::require_within_balance(terms.allowance, ctx.accounts.token_account.amount)?;
+        validation::require_pubkey_not_default(&ctx.accounts.delegate.key())?;
+        let now = now(ctx.remaining_accounts)?;
+        if let Some(expires_at) = terms.expires_at {
+            require!(expires_at > now, CustomError::InvalidDuration);
+        }
+        require!(terms.period_seconds >= 0, CustomError::InvalidDuration);
+        if terms.period_seconds > 0 {
+            validation::require_nonzero_amount(terms.period_limit)?;
+        }
+        require_delegate_approved(&ctx.accounts.mint_config, ctx.accounts.approved_delegate.as_ref())?;
+        charge_delegation_fee(
+            &ctx.accounts.mint_config,
+            &ctx.accounts.owner,
+            ctx.accounts.fee_recipient.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let record = &mut ctx.accounts.delegation_record;
+        record.token_account = ctx.accounts.token_account.key();
+        record.mint = ctx.accounts.token_account.mint;
+        record.owner = ctx.accounts.owner.key();
+        record.delegate = ctx.accounts.delegate.key();
+        record.terms = terms;
+        record.set_allowed_destinations(allowed_destinations)?;
+        record.spent_total = 0;
+        record.spent_this_period = 0;
+        record.period_start_ts = now;
+        record.bump = ctx.bumps.delegation_record;
+
+        let spl_delegate = match terms.mode {
+            DelegationMode::Advisory => ctx.accounts.delegate.to_account_info(),
+            DelegationMode::ProgramEnforced => ctx.accounts.delegation_record.to_account_info(),
+        };
+        let cpi_accounts = token_interface::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: spl_delegate,
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::approve(CpiContext::new(cpi_program, cpi_accounts), terms.allowance)?;
+
+        emit!(TokensDelegated {
+            token_account: ctx.accounts.token_account.key(),
+            delegate: ctx.accounts.delegate.key(),
+            amount: terms.allowance,
+            referrer: None,
+            sequence: update_if_registered(&ctx.accounts.mint_config, |config| {
+                config.record_delegation(true)?;
+                config.next_sequence()
+            })?,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 70: The delegate of a DelegationRecord spends from the owner's account.
+    pub fn spend_delegated(ctx: Context<SpendDelegated>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let now = now(ctx.remaining_accounts)?;
+        ctx.accounts
+            .delegation_record
+            .require_destination_allowed(&ctx.accounts.destination.key())?;
+        ctx.accounts.delegation_record.record_spend(amount, now)?;
+
+        let record = &ctx.accounts.delegation_record;
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: match record.terms.mode {
+                DelegationMode::Advisory => ctx.accounts.delegate.to_account_info(),
+                DelegationMode::ProgramEnforced => record.to_account_info(),
+            },
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let seeds = &[
+            b"delegation".as_ref(),
+            record.token_account.as_ref(),
+            std::slice::from_ref(&record.bump),
+        ];
+        let signer = &[&seeds[..]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        update_if_registered(&ctx.accounts.mint_config, |config| config.record_transfer(amount))?;
+
+        emit!(DelegatedSpend {
+            token_account: record.token_account,
+            delegate: record.delegate,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            spent_total: record.spent_total,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 71: End a DelegationRecord. The SPL allowance is revoked too,
+    // unless the owner has since approved someone else directly.
+    pub fn close_delegation(ctx: Context<CloseDelegation>) -> Result<()> {
+        let record = &ctx.accounts.delegation_record;
+        let spl_delegate = match record.terms.mode {
+            DelegationMode::Advisory => record.delegate,
+            DelegationMode::ProgramEnforced => record.key(),
+        };
+        if ctx.accounts.token_account.delegate == COption::Some(spl_delegate) {
+            let cpi_accounts = token_interface::Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token_interface::revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+            emit!(DelegateRevoked {
+                token_account: ctx.accounts.token_account.key(),
+                delegate: spl_delegate,
+                sequence: update_if_registered(&ctx.accounts.mint_config, |config| {
+                    config.record_revocation()?;
+                    config.next_sequence()
+                })?,
+            });
+        }
+        update_if_registered(&ctx.accounts.mint_config, |config| {
+            config.record_delegation_closed();
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    // Instruction 72: Replace the token accounts a live delegation may pay.
+    // An empty list lifts the restriction.
+    pub fn update_delegation_destinations(
+        ctx: Context<UpdateDelegationDestinations>,
+        allowed_destinations: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.delegation_record.set_allowed_destinations(allowed_destinations)
+    }
+
+    // Instruction 73: Set how long freeze_token_account and thaw_token_account
+    // must wait after an account last changed state. Zero turns the cooldown off.
+    pub fn set_toggle_cooldown(ctx: Context<SetToggleCooldown>, toggle_cooldown_seconds: i64) -> Result<()> {
+        require!(toggle_cooldown_seconds >= 0, CustomError::InvalidDuration);
+        ctx.accounts.mint_config.toggle_cooldown_seconds = toggle_cooldown_seconds;
+        Ok(())
+    }
+
+    // Instruction 74: Write one page of an export of the mint's frozen accounts.
+    // remaining_accounts holds (FreezeRecord, token account) pairs, sorted by
+    // token account across the whole export, so no account can appear twice.
+    // Pages are written in order, each chaining its hash onto the header's.
+    pub fn export_frozen_page<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExportFrozenPage<'info>>,
+        export_id: u64,
+        page: u16,
+    ) -> Result<()> {
+        let header = &mut ctx.accounts.export_header;
+        require!(page == header.total_pages, CustomError::ExportPageOutOfOrder);
+        if page == 0 {
+            header.mint = ctx.accounts.mint.key();
+            header.export_id = export_id;
+            header.frozen_account_count = ctx.accounts.mint_config.frozen_account_count;
+            header.bump = ctx.bumps.export_header;
+        }
+
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            pairs.len() > 0 && pairs.remainder().is_empty(),
+            CustomError::InvalidRemainingAccounts
+        );
+        require!(pairs.len() <= MAX_EXPORT_PAGE_ENTRIES, CustomError::BatchTooLarge);
+
+        let mut entries = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            let (record_info, target_info) = (&pair[0], &pair[1]);
+            require_keys_eq!(*record_info.owner, crate::ID, CustomError::InvalidRecordAccount);
+            let record = Account::<FreezeRecord>::try_from(record_info)?;
+            require_keys_eq!(record.mint, header.mint, CustomError::MintMismatch);
+            require_keys_eq!(target_info.key(), record.token_account, CustomError::InvalidRecordAccount);
+
+            // A record whose account has been thawed (or closed) would pad the export.
+            require!(!target_info.data_is_empty(), CustomError::StaleFreezeRecord);
+            let token_account = Account::<TokenAccount>::try_from(target_info)?;
+            require!(token_account.is_frozen(), CustomError::StaleFreezeRecord);
+
+            if let Some(last) = header.last_token_account {
+                require!(record.token_account > last, CustomError::ExportOutOfOrder);
+            }
+            header.last_token_account = Some(record.token_account);
+            entries.push(FrozenExportEntry {
+                token_account: record.token_account,
+                amount: record.amount,
+            });
+        }
+
+        let page_hash = FrozenExportPage::content_hash(&entries);
+        header.chain_hash = hashv(&[&header.chain_hash, &page_hash]).to_bytes();
+        header.total_pages = header.total_pages.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        header.total_accounts = header
+            .total_accounts
+            .checked_add(entries.len() as u64)
+            .ok_or(CustomError::MathOverflow)?;
+
+        let export_page = &mut ctx.accounts.export_page;
+        export_page.export = header.key();
+        export_page.page = page;
+        export_page.entries = entries;
+        export_page.page_hash = page_hash;
+        export_page.chain_hash = header.chain_hash;
+        export_page.bump = ctx.bumps.export_page;
+
+        Ok(())
+    }
+
+    // Instruction 75: Cap how many accounts may hold the mint, or lift the cap
+    // with None. A cap below the current count only keeps new holders out.
+    pub fn set_max_holders(ctx: Context<SetMaxHolders>, max_holders: Option<u16>) -> Result<()> {
+        ctx.accounts.mint_config.max_holders = max_holders;
+        Ok(())
+    }
+
+    // Instruction 76: Set how long tokens issued by mint_locked stay frozen.
+    pub fn set_holding_period(ctx: Context<SetHoldingPeriod>, holding_period_seconds: i64) -> Result<()> {
+        require!(holding_period_seconds >= 0, CustomError::InvalidDuration);
+        ctx.accounts.mint_config.holding_period_seconds = holding_period_seconds;
+        Ok(())
+    }
+
+    // Instruction 77: Mint tokens that can't be moved for the mint's holding
+    // period. The destination is frozen and a HoldingLock records when
+    // unlock_holding may thaw it. Only an empty account, or one still held by
+    // its lock (which is then pushed back), can receive: freezing an existing
+    // holder would also lock up the tokens it already had.
+    // Holding locks are not compliance freezes: there is no FreezeRecord and
+    // the mint's frozen tally doesn't count them.
+    pub fn mint_locked(ctx: Context<MintLocked>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let holding_period = ctx.accounts.mint_config.holding_period_seconds;
+        require!(holding_period > 0, CustomError::HoldingPeriodNotSet);
+        require!(ctx.accounts.freeze_exempt.data_is_empty(), CustomError::AccountExempt);
+        // Frozen by freeze_token_account: never thawed here.
+        require!(ctx.accounts.freeze_record.data_is_empty(), CustomError::DestinationFrozen);
+
+        let destination = &ctx.accounts.destination;
+        let held = ctx.accounts.holding_lock.unlock_ts != 0;
+        if destination.is_frozen() {
+            require!(held, CustomError::DestinationFrozen);
+        } else {
+            require!(destination.amount == 0, CustomError::NotNewHolder);
+        }
+
+        let balance = destination.amount;
+        let was_frozen = destination.is_frozen();
+        ctx.accounts
+            .mint_config
+            .track_holder(balance, balance.checked_add(amount).ok_or(CustomError::MathOverflow)?)?;
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+
+        if ctx.accounts.destination.is_frozen() {
+            let cpi_accounts = ThawAccount {
+                account: ctx.accounts.destination.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            };
+            token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer))?;
+        }
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token::mint_to(CpiContext::new(cpi_program.clone(), cpi_accounts), amount)?;
+
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.destination.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let unlock_ts = now(ctx.remaining_accounts)?
+            .checked_add(holding_period)
+            .ok_or(CustomError::MathOverflow)?;
+        let lock = &mut ctx.accounts.holding_lock;
+        lock.token_account = ctx.accounts.destination.key();
+        lock.rent_payer = ctx.accounts.mint_authority.key();
+        lock.unlock_ts = lock.unlock_ts.max(unlock_ts);
+        lock.bump = ctx.bumps.holding_lock;
+
+        emit!(TokensMinted {
+            mint: mint_key,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            referrer: None,
+        });
+        // An account already held was only thawed for the mint, so it ends as
+        // it started.
+        if !was_frozen {
+            emit!(AccountFrozen {
+                mint: mint_key,
+                token_account: ctx.accounts.destination.key(),
+                actor: ctx.accounts.mint_authority.key(),
+                sequence: ctx.accounts.mint_config.next_sequence()?,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Instruction 78: Permissionless: once a holding period is over, thaw the
+    // account and close its HoldingLock, returning the rent to whoever paid it.
+    // An account frozen by freeze_token_account in the meantime stays frozen.
+    pub fn unlock_holding(ctx: Context<UnlockHolding>) -> Result<()> {
+        ctx.accounts.mint_config.require_not_permissioned()?;
+        require!(
+            now(ctx.remaining_accounts)? >= ctx.accounts.holding_lock.unlock_ts,
+            CustomError::HoldingPeriodActive
+        );
+
+        if ctx.accounts.token_account.is_frozen() && ctx.accounts.freeze_record.data_is_empty() {
+            let cpi_accounts = ThawAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let mint_key = ctx.accounts.mint.key();
+            let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+            let signer = &[&seeds[..]];
+            token::thaw_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+            // Anyone may unlock, so the program's authority stands as the actor.
+            emit!(AccountThawed {
+                mint: mint_key,
+                token_account: ctx.accounts.token_account.key(),
+                actor: ctx.accounts.program_authority.key(),
+                sequence: ctx.accounts.mint_config.next_sequence()?,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Instruction 79: Set (or clear) the program notified of this mint's
+    // freezes and thaws, behind the timelock. See `notify_hook`.
+    pub fn set_notification_hook(ctx: Context<SetNotificationHook>, hook_program: Option<Pubkey>) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts.admin_action.require_ready(
+            admin_action_id(
+                b"set_notification_hook",
+                &[mint_key.as_ref(), hook_program.unwrap_or_default().as_ref()],
+            ),
+            now(ctx.remaining_accounts)?,
+        )?;
+        let (_, notifier_bump) = Pubkey::find_program_address(&[b"notifier", mint_key.as_ref()], ctx.program_id);
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.notification_hook = hook_program;
+        mint_config.notifier_bump = Some(notifier_bump);
+        Ok(())
+    }
+
+    // Instruction 80: Set the program thaw_with_validator consults.
+    // Pubkey::default() clears it.
+    pub fn set_thaw_validator(ctx: Context<SetThawValidator>, program: Pubkey) -> Result<()> {
+        ctx.accounts.mint_config.thaw_validator = (program != Pubkey::default()).then_some(program);
+        Ok(())
+    }
+
+    // Instruction 81: Thaw an account the mint's thaw validator approves of.
+    // Anyone may call it; remaining accounts are passed on to the validator.
+    pub fn thaw_with_validator<'info>(ctx: Context<'_, '_, 'info, 'info, ThawWithValidator<'info>>) -> Result<()> {
+        ctx.accounts.mint_config.require_not_permissioned()?;
+        let validator = ctx
+            .accounts
+            .mint_config
+            .thaw_validator
+            .ok_or(CustomError::ThawValidatorNotSet)?;
+        require_keys_eq!(ctx.accounts.validator_program.key(), validator, CustomError::ThawValidatorMismatch);
+        validate_thaw(
+            &ctx.accounts.validator_program,
+            &ctx.accounts.token_account_to_process.owner,
+            &ctx.accounts.token_account_to_process.key(),
+            clock::without_mock_clock(ctx.remaining_accounts),
+        )?;
+
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.token_account_to_process.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token::thaw_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let amount = ctx.accounts.freeze_record.amount;
+        close_freeze_record(
+            &ctx.accounts.freeze_record,
+            ctx.accounts.rent_payer.as_deref(),
+            &ctx.accounts.freeze_actor.to_account_info(),
+        )?;
+        ctx.accounts.mint_config.record_thaw(amount)?;
+        if let Some(bond) = record_bond(&ctx.accounts.freezer_bond, &ctx.accounts.freeze_record)? {
+            update_bond_if_posted(bond, FreezerBond::close_freeze)?;
+        }
+
+        emit!(AccountThawed {
+            mint: ctx.accounts.mint.key(),
+            token_account: ctx.accounts.token_account_to_process.key(),
+            actor: ctx.accounts.caller.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        ctx.accounts.account_history.append(
+            ctx.accounts.token_account_to_process.key(),
+            ctx.bumps.account_history,
+            HistoryAction::Thaw,
+            ctx.accounts.caller.key(),
+            HistoryReason::Validator,
+            now(ctx.remaining_accounts)?,
+        )?;
+
+        Ok(())
+    }
+
+    // Instruction 82: Set how long burn receipts must be kept before their
+    // burner may close them. Applies to receipts created afterwards.
+    pub fn set_burn_receipt_retention(ctx: Context<SetBurnReceiptRetention>, retention_seconds: i64) -> Result<()> {
+        require!(retention_seconds >= 0, CustomError::InvalidDuration);
+        ctx.accounts.mint_config.burn_receipt_retention_seconds = retention_seconds;
+        Ok(())
+    }
+
+    // Instruction 83: Burn tokens and leave a BurnReceipt other programs can check.
+    // The receipt is keyed by `(burner, tag)`, so a tag can only be used once
+    // per burner and one burn can't be split into several receipts for it.
+    pub fn burn_with_receipt(ctx: Context<BurnWithReceipt>, amount: u64, tag: [u8; 32]) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.burner_token_account.to_account_info(),
+            authority: ctx.accounts.burner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::burn(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+        let created_at = now(ctx.remaining_accounts)?;
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.burner = ctx.accounts.burner.key();
+        receipt.mint = ctx.accounts.mint.key();
+        receipt.amount = amount;
+        receipt.slot = Clock::get()?.slot;
+        receipt.tag = tag;
+        receipt.closable_at = created_at
+            .checked_add(ctx.accounts.mint_config.burn_receipt_retention_seconds)
+            .ok_or(CustomError::MathOverflow)?;
+        receipt.bump = ctx.bumps.receipt;
+
+        Ok(())
+    }
+
+    // Instruction 84: Close a burn receipt once its retention period is over.
+    // The rent goes back to the burner.
+    pub fn close_burn_receipt(ctx: Context<CloseBurnReceipt>) -> Result<()> {
+        require!(
+            now(ctx.remaining_accounts)? >= ctx.accounts.receipt.closable_at,
+            CustomError::BurnReceiptRetained
+        );
+        Ok(())
+    }
+
+    // Instruction 85: Move tokens someone sent to the `[b"authority"]` PDA out
+    // again. With `rescue_all` the whole balance is moved, `amount` is
+    // ignored and the stray account is closed, its rent going to the super_admin.
+    // Program vaults are never owned by that PDA, and those derived from the
+    // mint alone are refused by address as well, see `pda::is_program_vault`.
+    pub fn rescue_tokens(ctx: Context<RescueTokens>, amount: u64, rescue_all: bool) -> Result<()> {
+        let amount = if rescue_all { ctx.accounts.stray_account.amount } else { amount };
+        if !rescue_all {
+            validation::require_nonzero_amount(amount)?;
+        }
+
+        let seeds = &[b"authority".as_ref(), std::slice::from_ref(&ctx.bumps.program_authority)];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        if amount > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.stray_account.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), amount)?;
+        }
+
+        if rescue_all {
+            let cpi_accounts = CloseAccount {
+                account: ctx.accounts.stray_account.to_account_info(),
+                destination: ctx.accounts.super_admin.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            };
+            token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+        }
+
+        emit!(TokensRescued {
+            stray_account: ctx.accounts.stray_account.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            closed: rescue_all,
+        });
+        Ok(())
+    }
+
+    // Instruction 86: Send the `[b"authority"]` PDA's lamports above its
+    // rent-exempt minimum to `treasury`, behind the timelock.
+    // The PDA is a system account, so only the system program can debit it:
+    // the move is a system transfer the PDA signs for, not lamport arithmetic.
+    pub fn rescue_lamports(ctx: Context<RescueLamports>) -> Result<()> {
+        let treasury_key = ctx.accounts.treasury.key();
+        ctx.accounts.admin_action.require_ready(
+            admin_action_id(b"rescue_lamports", &[treasury_key.as_ref()]),
+            now(ctx.remaining_accounts)?,
+        )?;
+
+        let authority = ctx.accounts.program_authority.to_account_info();
+        let minimum = Rent::get()?.minimum_balance(authority.data_len());
+        let surplus = authority.lamports().saturating_sub(minimum);
+        if surplus > 0 {
+            let seeds = &[b"authority".as_ref(), std::slice::from_ref(&ctx.bumps.program_authority)];
+            let cpi_accounts = system_program::Transfer {
+                from: authority,
+                to: ctx.accounts.treasury.to_account_info(),
+            };
+            system_program::transfer(
+                CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, &[&seeds[..]]),
+                surplus,
+            )?;
+        }
+
+        emit!(LamportsRescued {
+            treasury: treasury_key,
+            amount: surplus,
+        });
+        Ok(())
+    }
+
+    // Instruction 87: Flag a delegate known from wallet-drainer campaigns.
+    // Flags are program-wide: a drainer doesn't care which mint it empties.
+    pub fn flag_delegate(ctx: Context<FlagDelegate>, delegate: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&delegate)?;
+        let flagged = &mut ctx.accounts.flagged_delegate;
+        flagged.delegate = delegate;
+        flagged.flagged_at = now(ctx.remaining_accounts)?;
+        flagged.bump = ctx.bumps.flagged_delegate;
+        Ok(())
+    }
+
+    // Instruction 88: Lift a flag. The `close` constraint returns the rent.
+    // Accounts already frozen for it stay frozen until thawed the usual way.
+    pub fn unflag_delegate(_ctx: Context<UnflagDelegate>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 89: Anyone may freeze a token account whose delegate is
+    // flagged, to stop the drainer before it moves the tokens. The caller pays
+    // for the freeze record and is recorded as its actor.
+    pub fn freeze_if_flagged_delegate(ctx: Context<FreezeIfFlaggedDelegate>) -> Result<()> {
+        let flagged = &ctx.accounts.flagged_delegate;
+        require!(
+            ctx.accounts.token_account_to_process.delegate.is_some()
+                && *flagged.owner == crate::ID
+                && !flagged.data_is_empty(),
+            CustomError::DelegateNotFlagged
+        );
+        require!(ctx.accounts.freeze_exempt.data_is_empty(), CustomError::AccountExempt);
+        let now = now(ctx.remaining_accounts)?;
+        ctx.accounts.account_history.require_cooldown_elapsed(
+            ctx.accounts.mint_config.toggle_cooldown_seconds,
+            now,
+            false,
+        )?;
+
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.token_account_to_process.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let amount = ctx.accounts.token_account_to_process.amount;
+        let record = &mut ctx.accounts.freeze_record;
+        record.mint = mint_key;
+        record.token_account = ctx.accounts.token_account_to_process.key();
+        record.owner = ctx.accounts.token_account_to_process.owner;
+        record.actor = ctx.accounts.caller.key();
+        record.amount = amount;
+        record.frozen_at = now;
+        record.bump = ctx.bumps.freeze_record;
+
+        reimburse_record_rent(
+            ctx.accounts.rent_payer.as_deref_mut(),
+            &ctx.accounts.caller.to_account_info(),
+            &mut ctx.accounts.freeze_record,
+        )?;
+
+        ctx.accounts.mint_config.record_freeze(amount)?;
+
+        emit!(AccountFrozen {
+            mint: ctx.accounts.mint.key(),
+            token_account: ctx.accounts.token_account_to_process.key(),
+            actor: ctx.accounts.caller.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        ctx.accounts.account_history.append(
+            ctx.accounts.token_account_to_process.key(),
+            ctx.bumps.account_history,
+            HistoryAction::Freeze,
+            ctx.accounts.caller.key(),
+            HistoryReason::DrainerProtection,
+            now,
+        )?;
+
+        Ok(())
+    }
+
+    // Instruction 90: Set the mint's display settings for wallets and our
+    // frontend. The DisplayConfig is created on first use, sized to the
+    // strings given, and grown whenever longer ones come in; it never shrinks.
+    pub fn set_display_config(
+        ctx: Context<SetDisplayConfig>,
+        ui_decimals: Option<u8>,
+        logo_uri: String,
+        project_url: String,
+        color: [u8; 3],
+    ) -> Result<()> {
+        if let Some(decimals) = ui_decimals {
+            validation::require_valid_decimals(decimals)?;
+        }
+        validation::require_logo_uri_len(&logo_uri)?;
+        validation::require_project_url_len(&project_url)?;
+
+        grow_account(
+            &ctx.accounts.display_config.to_account_info(),
+            DisplayConfig::space(&logo_uri, &project_url),
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let display = &mut ctx.accounts.display_config;
+        display.mint = ctx.accounts.mint.key();
+        display.ui_decimals = ui_decimals;
+        display.logo_uri = logo_uri;
+        display.project_url = project_url;
+        display.color = color;
+        display.bump = ctx.bumps.display_config;
+        Ok(())
+    }
+
+    // Instruction 91: View a mint's display settings.
+    pub fn get_display_config(ctx: Context<GetDisplayConfig>) -> Result<DisplayConfig> {
+        Ok((*ctx.accounts.display_config).clone())
+    }
+
+    // Instruction 92: mint_tokens to a wallet rather than a token account. The
+    // wallet's associated token account is created first if it doesn't exist,
+    // paid for by `payer`.
+    pub fn mint_to_owner(ctx: Context<MintToOwner>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        require!(!ctx.accounts.destination.is_frozen(), CustomError::DestinationFrozen);
+
+        mint_to_account(
+            &ctx.accounts.mint,
+            &ctx.accounts.destination,
+            &ctx.accounts.mint_authority,
+            &mut ctx.accounts.mint_config,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        emit!(TokensMinted {
+            mint: ctx.accounts.mint.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            referrer: None,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 93: distribute to wallets rather than token accounts.
+    // remaining_accounts holds one (owner, associated token account) pair per
+    // entry in `amounts`. Each address is checked against the derivation and
+    // the account created, paid for by `payer`, if it doesn't exist yet.
+    pub fn distribute_to_owners<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeToOwners<'info>>,
+        amounts: Vec<u64>,
+        allow_skip: bool,
+    ) -> Result<()> {
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            pairs.len() == amounts.len() && pairs.remainder().is_empty(),
+            CustomError::InvalidRemainingAccounts
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let mut skipped = Vec::new();
+        let mut total_minted: u64 = 0;
+
+        for (pair, &amount) in pairs.zip(amounts.iter()) {
+            validation::require_nonzero_amount(amount)?;
+            let (owner_info, destination_info) = (&pair[0], &pair[1]);
+            require_keys_eq!(
+                destination_info.key(),
+                associated_token::get_associated_token_address(&owner_info.key(), &mint_key),
+                CustomError::InvalidAssociatedTokenAccount
+            );
+
+            if destination_info.data_is_empty() {
+                let cpi_accounts = associated_token::Create {
+                    payer: ctx.accounts.payer.to_account_info(),
+                    associated_token: destination_info.clone(),
+                    authority: owner_info.clone(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                };
+                associated_token::create(CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    cpi_accounts,
+                ))?;
+            }
+
+            // Deserialized after the create, and right before use, as in distribute.
+            let destination = Account::<TokenAccount>::try_from(destination_info)?;
+            if destination.is_frozen() {
+                require!(allow_skip, CustomError::DestinationFrozen);
+                skipped.push(destination_info.key());
+                continue;
+            }
+            mint_to_account(
+                &ctx.accounts.mint,
+                &destination,
+                &ctx.accounts.mint_authority,
+                &mut ctx.accounts.mint_config,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            total_minted = total_minted
+                .checked_add(amount)
+                .ok_or(CustomError::MathOverflow)?;
+        }
+
+        emit!(DistributionCompleted {
+            mint: mint_key,
+            recipients: (amounts.len() - skipped.len()) as u32,
+            total_minted,
+            skipped,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 94: Add lamports to the `[b"rent_payer"]` PDA, which pays
+    // for freeze records in place of the caller. Anyone may fund it.
+    pub fn fund_authority(ctx: Context<FundAuthority>, lamports: u64) -> Result<()> {
+        validation::require_nonzero_amount(lamports)?;
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.rent_payer.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            lamports,
+        )?;
+
+        let rent_payer = &mut ctx.accounts.rent_payer;
+        rent_payer.total_funded = rent_payer.total_funded.checked_add(lamports).ok_or(CustomError::MathOverflow)?;
+        rent_payer.bump = ctx.bumps.rent_payer;
+        Ok(())
+    }
+
+    // Instruction 95: Donate tokens and/or SOL to the project treasury.
+    // Tokens go to a token account owned by the `[b"treasury"]` PDA, lamports
+    // to the PDA itself. Each donation leaves a DonationReceipt, numbered per donor.
+    pub fn donate(ctx: Context<Donate>, token_amount: u64, lamports: u64, memo_hash: [u8; 32]) -> Result<()> {
+        require!(token_amount > 0 || lamports > 0, CustomError::EmptyDonation);
+
+        let mut mint = None;
+        if token_amount > 0 {
+            let (Some(from), Some(to)) = (
+                ctx.accounts.donor_token_account.as_ref(),
+                ctx.accounts.treasury_token_account.as_ref(),
+            ) else {
+                return err!(CustomError::InvalidTreasuryTokenAccount);
+            };
+            require_keys_eq!(to.owner, ctx.accounts.treasury.key(), CustomError::InvalidTreasuryTokenAccount);
+            require_keys_eq!(to.mint, from.mint, CustomError::MintMismatch);
+            validation::require_within_balance(token_amount, from.amount)?;
+
+            let cpi_accounts = Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: ctx.accounts.donor.to_account_info(),
+            };
+            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), token_amount)?;
+            mint = Some(from.mint);
+        }
+
+        if lamports > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.donor.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            };
+            system_program::transfer(
+                CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+                lamports,
+            )?;
+        }
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.bump = ctx.bumps.treasury;
+        treasury.donations = treasury.donations.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        treasury.lamports_donated = treasury.lamports_donated.checked_add(lamports).ok_or(CustomError::MathOverflow)?;
+
+        let donor_stats = &mut ctx.accounts.donor_stats;
+        let index = donor_stats.donation_count;
+        donor_stats.donor = ctx.accounts.donor.key();
+        donor_stats.donation_count = index.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        donor_stats.bump = ctx.bumps.donor_stats;
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.donor = ctx.accounts.donor.key();
+        receipt.index = index;
+        receipt.mint = mint;
+        receipt.token_amount = token_amount;
+        receipt.lamports = lamports;
+        receipt.memo_hash = memo_hash;
+        receipt.created_at = now(ctx.remaining_accounts)?;
+        receipt.bump = ctx.bumps.receipt;
+
+        emit!(DonationReceived {
+            donor: receipt.donor,
+            index,
+            mint,
+            token_amount,
+            lamports,
+            memo_hash,
+        });
+        Ok(())
+    }
+
+    // Instruction 96: Set the mint's transfer policy. With `permissioned_mode`
+    // on, holders' accounts are kept frozen and permissioned_transfer is the
+    // only way to move tokens; the ordinary thaw instructions are refused.
+    // `transfers_paused` and `max_transfer_amount` apply to permissioned_transfer.
+    pub fn set_transfer_policy(
+        ctx: Context<SetTransferPolicy>,
+        permissioned_mode: bool,
+        transfers_paused: bool,
+        max_transfer_amount: Option<u64>,
+    ) -> Result<()> {
+        if let Some(max) = max_transfer_amount {
+            validation::require_nonzero_amount(max)?;
+        }
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.permissioned_mode = permissioned_mode;
+        mint_config.transfers_paused = transfers_paused;
+        mint_config.max_transfer_amount = max_transfer_amount;
+
+        emit!(TransferPolicySet {
+            mint: ctx.accounts.mint.key(),
+            permissioned_mode,
+            transfers_paused,
+            max_transfer_amount,
+            sequence: mint_config.next_sequence()?,
+        });
+        Ok(())
+    }
+
+    // Instruction 97: Allow `owner` to send and receive through permissioned_transfer.
+    pub fn add_approved_holder(ctx: Context<AddApprovedHolder>, owner: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&owner)?;
+
+        let holder = &mut ctx.accounts.approved_holder;
+        holder.mint = ctx.accounts.mint.key();
+        holder.owner = owner;
+        holder.bump = ctx.bumps.approved_holder;
+
+        Ok(())
+    }
+
+    // Instruction 98: Withdraw a holder's approval. Its accounts stay frozen,
+    // and the `close` constraint returns the rent.
+    pub fn remove_approved_holder(_ctx: Context<RemoveApprovedHolder>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 99: Move tokens between two frozen accounts of a permissioned
+    // mint. The policy is checked first; then the program thaws both accounts,
+    // transfers and freezes them again. Anything failing on the way aborts the
+    // whole transaction, so neither account is ever left thawed.
+    pub fn permissioned_transfer(ctx: Context<PermissionedTransfer>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let mint_config = &ctx.accounts.mint_config;
+        require!(mint_config.permissioned_mode, CustomError::NotPermissioned);
+        require!(!mint_config.transfers_paused, CustomError::TransfersPaused);
+        if let Some(max) = mint_config.max_transfer_amount {
+            require!(amount <= max, CustomError::TransferCapExceeded);
+        }
+        for holder in [&ctx.accounts.from_holder, &ctx.accounts.to_holder] {
+            require!(
+                *holder.owner == crate::ID && !holder.data_is_empty(),
+                CustomError::HolderNotApproved
+            );
+        }
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        let accounts = [ctx.accounts.from.to_account_info(), ctx.accounts.to.to_account_info()];
+
+        for account in &accounts {
+            let cpi_accounts = ThawAccount {
+                account: account.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            };
+            token::thaw_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer))?;
+        }
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token::transfer_checked(
+            CpiContext::new(cpi_program.clone(), cpi_accounts),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        for account in &accounts {
+            let cpi_accounts = FreezeAccount {
+                account: account.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            };
+            token::freeze_account(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer))?;
+        }
+
+        // The frozen supply is unchanged; only its split between the two records moves.
+        let from_before = ctx.accounts.from.amount;
+        let to_before = ctx.accounts.to.amount;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.from_record.amount = ctx.accounts.from.amount;
+        ctx.accounts.to_record.amount = ctx.accounts.to.amount;
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.track_holder(from_before, ctx.accounts.from.amount)?;
+        mint_config.track_holder(to_before, ctx.accounts.to.amount)?;
+        mint_config.record_transfer(amount)?;
+
+        emit!(PermissionedTransferred {
+            mint: mint_key,
+            from: ctx.accounts.from.key(),
+            to: ctx.accounts.to.key(),
+            amount,
+            sequence: mint_config.next_sequence()?,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 100: Only allow delegating this mint's tokens to approved
+    // delegates, or lift the restriction. Existing approvals are untouched.
+    pub fn set_restrict_delegates(ctx: Context<SetRestrictDelegates>, restrict: bool) -> Result<()> {
+        ctx.accounts.mint_config.restrict_delegates = restrict;
+        Ok(())
+    }
+
+    // Instruction 101: Vet `delegate` (an escrow, a DEX program...) for this mint.
+    pub fn add_approved_delegate(ctx: Context<AddApprovedDelegate>, delegate: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&delegate)?;
+
+        let entry = &mut ctx.accounts.approved_delegate;
+        entry.mint = ctx.accounts.mint.key();
+        entry.delegate = delegate;
+        entry.bump = ctx.bumps.approved_delegate;
+
+        Ok(())
+    }
+
+    // Instruction 102: Take a delegate off the list. Allowances already
+    // granted to it stay until their owners revoke them.
+    pub fn remove_approved_delegate(_ctx: Context<RemoveApprovedDelegate>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 103: Sanction a wallet. Program-wide: every token account it
+    // holds in any mint registered here can then be frozen by enforce_sanction.
+    pub fn sanction_owner(ctx: Context<SanctionOwner>, owner: Pubkey) -> Result<()> {
+        validation::require_pubkey_not_default(&owner)?;
+        let sanction = &mut ctx.accounts.sanctioned_owner;
+        sanction.owner = owner;
+        sanction.sanctioned_at = now(ctx.remaining_accounts)?;
+        sanction.bump = ctx.bumps.sanctioned_owner;
+        Ok(())
+    }
+
+    // Instruction 104: Lift a sanction. The `close` constraint returns the rent.
+    // The owner's frozen accounts stay frozen until each is thawed explicitly.
+    pub fn lift_sanction(_ctx: Context<LiftSanction>) -> Result<()> {
+        Ok(())
+    }
+
+    // Instruction 105: Anyone may freeze a token account of a sanctioned owner,
+    // in any registered mint, with that mint's freeze authority. The caller
+    // pays for the freeze record and is recorded as its actor.
+    pub fn enforce_sanction(ctx: Context<EnforceSanction>) -> Result<()> {
+        let sanction = &ctx.accounts.sanctioned_owner;
+        require!(
+            *sanction.owner == crate::ID && !sanction.data_is_empty(),
+            CustomError::OwnerNotSanctioned
+        );
+        let sanctioned_at = SanctionedOwner::try_deserialize(&mut &sanction.try_borrow_data()?[..])?.sanctioned_at;
+        require!(ctx.accounts.freeze_exempt.data_is_empty(), CustomError::AccountExempt);
+        let now = now(ctx.remaining_accounts)?;
+        ctx.accounts.account_history.require_cooldown_elapsed(
+            ctx.accounts.mint_config.toggle_cooldown_seconds,
+            now,
+            false,
+        )?;
+
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.token_account_to_process.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let amount = ctx.accounts.token_account_to_process.amount;
+        let record = &mut ctx.accounts.freeze_record;
+        record.mint = mint_key;
+        record.token_account = ctx.accounts.token_account_to_process.key();
+        record.owner = ctx.accounts.token_account_to_process.owner;
+        record.actor = ctx.accounts.caller.key();
+        record.amount = amount;
+        record.frozen_at = now;
+        record.bump = ctx.bumps.freeze_record;
+
+        reimburse_record_rent(
+            ctx.accounts.rent_payer.as_deref_mut(),
+            &ctx.accounts.caller.to_account_info(),
+            &mut ctx.accounts.freeze_record,
+        )?;
+
+        ctx.accounts.mint_config.record_freeze(amount)?;
+
+        emit!(SanctionEnforced {
+            mint: mint_key,
+            token_account: ctx.accounts.token_account_to_process.key(),
+            owner: ctx.accounts.token_account_to_process.owner,
+            sanction: ctx.accounts.sanctioned_owner.key(),
+            sanctioned_at,
+            actor: ctx.accounts.caller.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        ctx.accounts.account_history.append(
+            ctx.accounts.token_account_to_process.key(),
+            ctx.bumps.account_history,
+            HistoryAction::Freeze,
+            ctx.accounts.caller.key(),
+            HistoryReason::Sanction,
+            now,
+        )?;
+
+        Ok(())
+    }
+
+    // Instruction 106: Stream `deposit_amount` to `recipient` between `start_ts`
+    // and `end_ts`. The deposit is locked in the stream's vault and accrues
+    // per second, see `stream::accrued`. One open stream per sender, recipient
+    // and mint.
+    pub fn open_stream(
+        ctx: Context<OpenStream>,
+        recipient: Pubkey,
+        deposit_amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        validation::require_nonzero_amount(deposit_amount)?;
+        validation::require_pubkey_not_default(&recipient)?;
+        stream::validate_window(start_ts, end_ts)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.sender_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.sender.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), deposit_amount)?;
+
+        let stream = &mut ctx.accounts.stream;
+        stream.mint = ctx.accounts.mint.key();
+        stream.sender = ctx.accounts.sender.key();
+        stream.recipient = recipient;
+        stream.deposit = deposit_amount;
+        stream.withdrawn = 0;
+        stream.start_ts = start_ts;
+        stream.end_ts = end_ts;
+        stream.bump = ctx.bumps.stream;
+        stream.vault_bump = ctx.bumps.vault;
+
+        emit!(StreamOpened {
+            stream: stream.key(),
+            sender: stream.sender,
+            recipient,
+            deposit: deposit_amount,
+            rate_per_second: stream::rate_per_second(deposit_amount, start_ts, end_ts)?,
+            start_ts,
+            end_ts,
+        });
+
+        Ok(())
+    }
+
+    // Instruction 107: The recipient takes everything accrued since their last withdrawal.
+    pub fn withdraw_from_stream(ctx: Context<WithdrawFromStream>) -> Result<()> {
+        let stream = &ctx.accounts.stream;
+        let amount = stream
+            .accrued(now(ctx.remaining_accounts)?)?
+            .checked_sub(stream.withdrawn)
+            .ok_or(CustomError::MathOverflow)?;
+        require!(amount > 0, CustomError::NothingToWithdraw);
+
+        let seeds = stream.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: ctx.accounts.stream.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+
+        let stream = &mut ctx.accounts.stream;
+        stream.withdrawn = stream.withdrawn.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 108: The sender ends the stream. What has accrued so far goes
+    // to the recipient, the rest back to the sender, and the stream and its
+    // vault are closed.
+    pub fn close_stream(ctx: Context<CloseStream>) -> Result<()> {
+        let stream = &ctx.accounts.stream;
+        let owed = stream
+            .accrued(now(ctx.remaining_accounts)?)?
+            .checked_sub(stream.withdrawn)
+            .ok_or(CustomError::MathOverflow)?;
+        let refund = ctx.accounts.vault.amount.checked_sub(owed).ok_or(CustomError::MathOverflow)?;
+
+        let seeds = stream.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        for (to, amount) in [
+            (ctx.accounts.recipient_token_account.to_account_info(), owed),
+            (ctx.accounts.sender_token_account.to_account_info(), refund),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to,
+                authority: ctx.accounts.stream.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), amount)?;
+        }
+
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.sender.to_account_info(),
+            authority: ctx.accounts.stream.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        // The stream is closed by its `close` constraint.
+        Ok(())
+    }
+
+    // Instruction 109: Configure staking for a mint whose RewardPool exists,
+    // behind the timelock. The first call also creates the stake vault.
+    // Unstaking waits `cooldown_seconds`; skipping the wait costs `penalty_bps`
+    // of the amount, burned or paid to the treasury.
+    pub fn set_stake_config(
+        ctx: Context<SetStakeConfig>,
+        cooldown_seconds: i64,
+        penalty_bps: u16,
+        burn_penalty: bool,
+    ) -> Result<()> {
+        require!(cooldown_seconds >= 0, CustomError::InvalidDuration);
+        require!(penalty_bps <= BPS_DENOMINATOR, CustomError::InvalidPenalty);
+
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts.admin_action.require_ready(
+            admin_action_id(
+                b"set_stake_config",
+                &[
+                    mint_key.as_ref(),
+                    &cooldown_seconds.to_le_bytes(),
+                    &penalty_bps.to_le_bytes(),
+                    &[burn_penalty as u8],
+                ],
+            ),
+            now(ctx.remaining_accounts)?,
+        )?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.stake_vault_bump = ctx.bumps.stake_vault;
+        // Applies to requests made from now on.
+        pool.cooldown_seconds = cooldown_seconds;
+        pool.penalty_bps = penalty_bps;
+        pool.burn_penalty = burn_penalty;
+        Ok(())
+    }
+
+    // Instruction 110: Stake tokens. They earn a share of every inflation mint
+    // from now until they're requested for unstaking.
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.staker_token_account.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.staker.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let position = &mut ctx.accounts.position;
+        if position.mint == Pubkey::default() {
+            position.mint = pool.mint;
+            position.staker = ctx.accounts.staker.key();
+            position.bump = ctx.bumps.position;
+        }
+        position.settle(pool.reward_per_token)?;
+        position.amount = position.amount.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        pool.total_staked = pool.total_staked.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        checkpoint_balance(ctx.accounts.voting_checkpoints.as_mut(), &mut ctx.accounts.staker_token_account)?;
+        Ok(())
+    }
+
+    // Instruction 111: Pay out a position's rewards from the reward vault.
+    pub fn claim_stake_rewards(ctx: Context<ClaimStakeRewards>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.settle(ctx.accounts.reward_pool.reward_per_token)?;
+        let rewards = std::mem::take(&mut position.pending_rewards);
+        if rewards == 0 {
+            return Ok(());
+        }
+
+        let pool = &ctx.accounts.reward_pool;
+        let seeds = pool.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.staker_token_account.to_account_info(),
+            authority: ctx.accounts.reward_pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), rewards)?;
+        Ok(())
+    }
+
+    // Instruction 112: Start the cooldown on `amount` of a position. It stops
+    // earning right away and can be taken out with complete_unstake once the
+    // cooldown is over. A staker may have several requests cooling at once.
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        require!(ctx.accounts.slash_hold.data_is_empty(), CustomError::SlashPending);
+        let now = now(ctx.remaining_accounts)?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let position = &mut ctx.accounts.position;
+        position.settle(pool.reward_per_token)?;
+        position.amount = position.amount.checked_sub(amount).ok_or(CustomError::InsufficientStake)?;
+        pool.total_staked = pool.total_staked.checked_sub(amount).ok_or(CustomError::MathOverflow)?;
+
+        let request = &mut ctx.accounts.unstake_request;
+        request.position = position.key();
+        request.staker = position.staker;
+        request.mint = position.mint;
+        request.index = position.request_count;
+        request.amount = amount;
+        request.unlocks_at = now.checked_add(pool.cooldown_seconds).ok_or(CustomError::MathOverflow)?;
+        request.bump = ctx.bumps.unstake_request;
+        position.request_count = position.request_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        position.open_requests = position.open_requests.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 113: Take out an unstake request whose cooldown is over.
+    pub fn complete_unstake(ctx: Context<CompleteUnstake>) -> Result<()> {
+        let request = &ctx.accounts.unstake_request;
+        require!(now(ctx.remaining_accounts)? >= request.unlocks_at, CustomError::UnstakeCooldownActive);
+
+        let pool = &ctx.accounts.reward_pool;
+        let seeds = pool.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            to: ctx.accounts.staker_token_account.to_account_info(),
+            authority: ctx.accounts.reward_pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), request.amount)?;
+        checkpoint_balance(ctx.accounts.voting_checkpoints.as_mut(), &mut ctx.accounts.staker_token_account)?;
+
+        // The request is closed by its `close` constraint.
+        ctx.accounts.position.open_requests = ctx.accounts.position.open_requests.checked_sub(1).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 114: Take out an unstake request before its cooldown is
+    // over. `penalty_bps` of it is burned or paid to the treasury, as the
+    // pool is configured; the staker gets the rest.
+    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+        let amount = ctx.accounts.unstake_request.amount;
+        let pool = &ctx.accounts.reward_pool;
+        let penalty = staking::bps_of(amount, pool.penalty_bps);
+        let payout = amount - penalty;
+
+        let seeds = pool.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        if penalty > 0 {
+            if pool.burn_penalty {
+                let cpi_accounts = Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                token::burn(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), penalty)?;
+            } else {
+                let treasury_token_account = ctx
+                    .accounts
+                    .treasury_token_account
+                    .as_ref()
+                    .ok_or(CustomError::InvalidTreasuryTokenAccount)?;
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: treasury_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), penalty)?;
+            }
+        }
+        if payout > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.staker_token_account.to_account_info(),
+                authority: ctx.accounts.reward_pool.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), payout)?;
+        }
+
+        emit!(EmergencyUnstaked {
+            mint: ctx.accounts.mint.key(),
+            staker: ctx.accounts.staker.key(),
+            amount,
+            penalty,
+            burned: pool.burn_penalty,
+        });
+        checkpoint_balance(ctx.accounts.voting_checkpoints.as_mut(), &mut ctx.accounts.staker_token_account)?;
+
+        // The request is closed by its `close` constraint.
+        ctx.accounts.position.open_requests = ctx.accounts.position.open_requests.checked_sub(1).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 115: Slash up to `amount` of a staker's earning stake into
+    // the treasury, behind the timelock. Rewards earned up to now are kept;
+    // the slashed tokens stop earning from this point on. Tokens already
+    // cooling in an UnstakeRequest aren't touched, but queue_slash_stake has
+    // kept the staker from requesting any since the slash was queued. A
+    // position slashed to zero with no requests open is closed, its unclaimed
+    // rewards paid out first.
+    pub fn slash_stake(ctx: Context<SlashStake>, staker: Pubkey, amount: u64, reason: u8) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let mint_key = ctx.accounts.reward_pool.mint;
+        let now = now(ctx.remaining_accounts)?;
+        ctx.accounts
+            .admin_action
+            .require_ready(slash_action_id(&mint_key, &staker, amount, reason), now)?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let position = &mut ctx.accounts.position;
+        position.settle(pool.reward_per_token)?;
+        let slashed = amount.min(position.amount);
+        require!(slashed > 0, CustomError::InsufficientStake);
+        position.amount -= slashed;
+        pool.total_staked = pool.total_staked.checked_sub(slashed).ok_or(CustomError::MathOverflow)?;
+
+        let seeds = ctx.accounts.reward_pool.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: ctx.accounts.reward_pool.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), slashed)?;
+
+        let position = &ctx.accounts.position;
+        let closed = position.amount == 0 && position.open_requests == 0;
+        let rewards_paid = if closed { position.pending_rewards } else { 0 };
+        if rewards_paid > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.staker_token_account.to_account_info(),
+                authority: ctx.accounts.reward_pool.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), rewards_paid)?;
+        }
+
+        emit!(StakeSlashed {
+            mint: mint_key,
+            staker,
+            requested: amount,
+            slashed,
+            remaining: position.amount,
+            reason,
+            rewards_paid,
+            closed,
+            actor: ctx.accounts.super_admin.key(),
+            slashed_at: now,
+        });
+
+        if closed {
+            ctx.accounts.position.close(ctx.accounts.staker_wallet.to_account_info())?;
+        }
+        Ok(())
+    }
+
+    // Instruction 116: Checkpoint a holder's current balance. Transfers made
+    // outside the program don't checkpoint, so holders call this before a
+    // vote's snapshot slot. The first call picks the token account the
+    // holder's votes are counted from.
+    pub fn self_checkpoint(ctx: Context<SelfCheckpoint>) -> Result<()> {
+        let checkpoints = &mut ctx.accounts.voting_checkpoints;
+        if checkpoints.holder == Pubkey::default() {
+            checkpoints.mint = ctx.accounts.token_account.mint;
+            checkpoints.holder = ctx.accounts.holder.key();
+            checkpoints.token_account = ctx.accounts.token_account.key();
+            checkpoints.bump = ctx.bumps.voting_checkpoints;
+        }
+        require_keys_eq!(
+            checkpoints.token_account,
+            ctx.accounts.token_account.key(),
+            CustomError::CheckpointAccountMismatch
+        );
+        checkpoints.push(Clock::get()?.slot, ctx.accounts.token_account.amount);
+        Ok(())
+    }
+
+    // Instruction 117: View a holder's balance as of the end of `slot`.
+    pub fn get_votes_at_slot(ctx: Context<GetVotesAtSlot>, slot: u64) -> Result<u64> {
+        ctx.accounts.voting_checkpoints.balance_at(slot)
+    }
+
+    // Instruction 118: Close a DelegationRecord whose allowance ended outside
+    // the program, e.g. revoked directly through the token program, so it no
+    // longer counts as an active delegation. Accounts frozen or thawed outside
+    // the program are corrected by reconcile_frozen and gc_records.
+    pub fn reconcile_stats(ctx: Context<ReconcileStats>) -> Result<()> {
+        let record = &ctx.accounts.delegation_record;
+        let spl_delegate = match record.terms.mode {
+            DelegationMode::Advisory => record.delegate,
+            DelegationMode::ProgramEnforced => record.key(),
+        };
+        require!(
+            ctx.accounts.token_account.delegate != COption::Some(spl_delegate),
+            CustomError::RecordStillLive
+        );
+
+        // The record is closed by its `close` constraint.
+        ctx.accounts.mint_config.record_delegation_closed();
+        Ok(())
+    }
+
+    // Instruction 119: Rewrite a DelegationRecord created before the keys
+    // were reordered for `filters`. Permissionless: the contents don't
+    // change, only the order of mint and token_account. Records already in
+    // the current layout are left alone.
+    pub fn migrate_delegation_record(ctx: Context<MigrateDelegationRecord>) -> Result<()> {
+        let record = &ctx.accounts.delegation_record;
+        require_keys_eq!(*record.owner, crate::ID, CustomError::InvalidRecordAccount);
+        let mut data = record.try_borrow_mut_data()?;
+        require!(
+            data.get(..8) == Some(&DelegationRecord::DISCRIMINATOR[..]),
+            CustomError::InvalidRecordAccount
+        );
+        filters::upgrade_delegation_record(&record.key(), &mut data);
+        Ok(())
+    }
+
+    // Instruction 120: Offer `delegate` an allowance of `amount` that only
+    // starts once it accepts. The token program only approves with the
+    // owner's signature, so the owner approves now, but to the address of the
+    // token account's DelegationRecord, which accept_delegation creates. Until
+    // then no record exists and spend_delegated can't run, so nobody can move
+    // the tokens. The offer lapses at `expiry`. The delegation fee, if the
+    // mint charges one, is only paid once the delegate accepts.
+    pub fn offer_delegation(ctx: Context<OfferDelegation>, delegate: Pubkey, amount: u64, expiry: i64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        validation::require_within_balance(amount, ctx.accounts.token_account.amount)?;
+        validation::require_pubkey_not_default(&delegate)?;
+        require_keys_neq!(delegate, ctx.accounts.owner.key(), CustomError::SelfDelegation);
+        require!(expiry > now(ctx.remaining_accounts)?, CustomError::InvalidDeadline);
+        require!(ctx.accounts.delegation_record.data_is_empty(), CustomError::DelegationRecordExists);
+        // Approving would silently replace a delegate the owner already has.
+        // An approval to the record's address left by a lapsed offer doesn't
+        // count: with no record, nothing can spend through it.
+        let record_key = ctx.accounts.delegation_record.key();
+        require!(
+            !matches!(ctx.accounts.token_account.delegate, COption::Some(delegate) if delegate != record_key),
+            CustomError::DelegateAlreadySet
+        );
+        require_delegate_approved(&ctx.accounts.mint_config, ctx.accounts.approved_delegate.as_ref())?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.mint = ctx.accounts.token_account.mint;
+        offer.token_account = ctx.accounts.token_account.key();
+        offer.owner = ctx.accounts.owner.key();
+        offer.delegate = delegate;
+        offer.amount = amount;
+        offer.expires_at = expiry;
+        offer.bump = ctx.bumps.offer;
+
+        let cpi_accounts = token_interface::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.delegation_record.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::approve(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+        Ok(())
+    }
+
+    // Instruction 121: The named delegate accepts an offer before it expires.
+    // This creates the ProgramEnforced DelegationRecord the owner approved,
+    // so from here on the delegate spends through spend_delegated. The
+    // delegate pays the delegation fee, if the mint charges one.
+    pub fn accept_delegation(ctx: Context<AcceptDelegation>) -> Result<()> {
+        let now = now(ctx.remaining_accounts)?;
+        let offer = &ctx.accounts.offer;
+        require!(now < offer.expires_at, CustomError::DelegationOfferExpired);
+        // The owner may have revoked or re-approved directly since offering.
+        let token_account = &ctx.accounts.token_account;
+        require!(
+            token_account.delegate == COption::Some(ctx.accounts.delegation_record.key())
+                && token_account.delegated_amount == offer.amount,
+            CustomError::DelegationOfferStale
+        );
+        charge_delegation_fee(
+            &ctx.accounts.mint_config,
+            &ctx.accounts.delegate,
+            ctx.accounts.fee_recipient.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let record = &mut ctx.accounts.delegation_record;
+        record.mint = offer.mint;
+        record.token_account = offer.token_account;
+        record.owner = offer.owner;
+        record.delegate = offer.delegate;
+        record.terms = DelegationTerms {
+            allowance: offer.amount,
+            mode: DelegationMode::ProgramEnforced,
+            expires_at: None,
+            period_seconds: 0,
+            period_limit: 0,
+        };
+        record.allowed_destinations = Vec::new();
+        record.spent_total = 0;
+        record.spent_this_period = 0;
+        record.period_start_ts = now;
+        record.bump = ctx.bumps.delegation_record;
+
+        emit!(TokensDelegated {
+            token_account: offer.token_account,
+            delegate: offer.delegate,
+            amount: offer.amount,
+            referrer: None,
+            sequence: update_if_registered(&ctx.accounts.mint_config, |config| {
+                config.record_delegation(true)?;
+                config.next_sequence()
+            })?,
+        });
+
+        // The offer is closed by its `close` constraint.
+        Ok(())
+    }
+
+    // Instruction 122: The owner takes back an offer that hasn't been
+    // accepted, revoking the approval made for it.
+    pub fn withdraw_offer(ctx: Context<WithdrawOffer>) -> Result<()> {
+        if ctx.accounts.token_account.delegate == COption::Some(ctx.accounts.delegation_record.key()) {
+            let cpi_accounts = token_interface::Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token_interface::revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+        }
+
+        // The offer is closed by its `close` constraint.
+        Ok(())
+    }
+
+    // Instruction 123: Anyone can close an expired offer; the rent goes back to
+    // the owner. The approval made for it can only be revoked by the owner, so
+    // it is when the owner is the caller. Otherwise it stays, but no record
+    // exists for it to be spent through, and offer_delegation doesn't count
+    // it as a delegate already set.
+    pub fn close_expired_offer(ctx: Context<CloseExpiredOffer>) -> Result<()> {
+        require!(
+            now(ctx.remaining_accounts)? >= ctx.accounts.offer.expires_at,
+            CustomError::DelegationOfferActive
+        );
+        if ctx.accounts.caller.key() == ctx.accounts.owner.key()
+            && ctx.accounts.token_account.delegate == COption::Some(ctx.accounts.delegation_record.key())
+        {
+            let cpi_accounts = token_interface::Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.caller.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token_interface::revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+        }
+        // The offer is closed by its `close` constraint.
+        Ok(())
+    }
+
+    // Instruction 124: Issue a single-unit certificate to `recipient`. The
+    // mint is a Token-2022 mint with 0 decimals that holds its own token
+    // metadata, so wallets show the name, symbol and URI. Its one token is
+    // minted to the recipient's associated token account, then the metadata's
+    // update authority and the mint authority are removed, all in this
+    // instruction, so the metadata can't change and the supply stays at
+    // exactly 1. The freeze authority stays with our PDA, so the certificate
+    // can be revoked.
+    pub fn create_certificate_mint(
+        ctx: Context<CreateCertificateMint>,
+        recipient: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        validation::require_pubkey_not_default(&recipient)?;
+        validation::require_certificate_metadata_len(&name, &symbol, &uri)?;
+
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts
+            .mint_config
+            .init(mint_key, ctx.bumps.program_authority, ctx.bumps.mint_config);
+
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mint_authority = ctx.accounts.mint_authority.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let seeds = &[
+            b"mint_authority".as_ref(),
+            mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        // The account is sized for the mint and its metadata pointer; writing
+        // the metadata grows it. The rent covers the metadata as well, since
+        // the token program can't take more lamports for it.
+        let metadata = TokenMetadata {
+            mint: mint_key,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        let space = ExtensionType::try_calculate_account_len::<token_interface::spl_token_2022::state::Mint>(&[
+            ExtensionType::MetadataPointer,
+        ])?;
+        let cpi_accounts = system_program::CreateAccount {
+            from: ctx.accounts.super_admin.to_account_info(),
+            to: mint_info.clone(),
+        };
+        system_program::create_account(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?),
+            space as u64,
+            &token_program.key(),
+        )?;
+
+        // With no authority, the pointer can't be moved off the mint either.
+        let ix = metadata_pointer::instruction::initialize(&token_program.key(), &mint_key, None, Some(mint_key))?;
+        invoke(&ix, std::slice::from_ref(&mint_info))?;
+        let cpi_accounts = token_interface::InitializeMint2 { mint: mint_info.clone() };
+        token_interface::initialize_mint2(
+            CpiContext::new(token_program.clone(), cpi_accounts),
+            0,
+            &mint_authority.key(),
+            Some(&ctx.accounts.program_authority.key()),
+        )?;
+
+        let ix = spl_token_metadata_interface::instruction::initialize(
+            &token_program.key(),
+            &mint_key,
+            &mint_authority.key(),
+            &mint_key,
+            &mint_authority.key(),
+            name,
+            symbol,
+            uri,
+        );
+        invoke_signed(&ix, &[mint_info.clone(), mint_authority.clone()], signer)?;
+        // An update authority of None: nobody can sign to change the metadata.
+        let ix = spl_token_metadata_interface::instruction::update_authority(
+            &token_program.key(),
+            &mint_key,
+            &mint_authority.key(),
+            Default::default(),
+        );
+        invoke_signed(&ix, &[mint_info.clone(), mint_authority.clone()], signer)?;
+
+        let cpi_accounts = associated_token::Create {
+            payer: ctx.accounts.super_admin.to_account_info(),
+            associated_token: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: ctx.accounts.recipient_wallet.to_account_info(),
+            mint: mint_info.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: token_program.clone(),
+        };
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            cpi_accounts,
+        ))?;
+
+        let cpi_accounts = token_interface::MintTo {
+            mint: mint_info.clone(),
+            to: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: mint_authority.clone(),
+        };
+        token_interface::mint_to(CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer), 1)?;
+
+        let cpi_accounts = token_interface::SetAuthority {
+            current_authority: mint_authority,
+            account_or_mint: mint_info,
+        };
+        token_interface::set_authority(
+            CpiContext::new_with_signer(token_program, cpi_accounts, signer),
+            token_interface::spl_token_2022::instruction::AuthorityType::MintTokens,
+            None,
+        )?;
+
+        let certificate = &mut ctx.accounts.certificate;
+        certificate.mint = mint_key;
+        certificate.recipient = recipient;
+        certificate.issued_at = now(ctx.remaining_accounts)?;
+        certificate.revoked_at = None;
+        certificate.bump = ctx.bumps.certificate;
+
+        emit!(CertificateIssued {
+            mint: mint_key,
+            recipient,
+            issuer: ctx.accounts.super_admin.key(),
+        });
+        Ok(())
+    }
+
+    // Instruction 125: Revoke a certificate by freezing the account that
+    // holds it. As with freeze_token_account, a FreezeRecord is kept, and the
+    // Certificate remembers when it was revoked.
+    pub fn revoke_certificate(ctx: Context<RevokeCertificate>) -> Result<()> {
+        let now = now(ctx.remaining_accounts)?;
+        let certificate = &mut ctx.accounts.certificate;
+        require!(certificate.revoked_at.is_none(), CustomError::CertificateRevoked);
+        certificate.revoked_at = Some(now);
+
+        let cpi_accounts = token_interface::FreezeAccount {
+            account: ctx.accounts.holder_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token_interface::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        let record = &mut ctx.accounts.freeze_record;
+        record.mint = mint_key;
+        record.token_account = ctx.accounts.holder_token_account.key();
+        record.owner = ctx.accounts.holder_token_account.owner;
+        record.actor = ctx.accounts.super_admin.key();
+        record.amount = 1;
+        record.frozen_at = now;
+        record.bump = ctx.bumps.freeze_record;
+
+        ctx.accounts.mint_config.record_freeze(1)?;
+
+        emit!(AccountFrozen {
+            mint: mint_key,
+            token_account: ctx.accounts.holder_token_account.key(),
+            actor: ctx.accounts.super_admin.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+        Ok(())
+    }
+
+    // Instruction 126: Create up to MAX_BATCH_MINTS mints in one go, e.g. a
+    // project's governance, reward and LP receipt tokens. remaining_accounts
+    // holds a (mint, mint_config) pair per entry of `params`, in order: the
+    // mint a fresh keypair that signs the transaction, the mint_config its
+    // `[b"mint_config", mint]` PDA. Each mint is set up as create_token_mint
+    // would set it up. One bad entry fails the transaction, so either every
+    // mint is created or none is.
+    pub fn create_token_mints_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateTokenMintsBatch<'info>>,
+        params: Vec<CreateMintParams>,
+    ) -> Result<()> {
+        require!(params.len() <= MAX_BATCH_MINTS, CustomError::BatchTooLarge);
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            !params.is_empty() && pairs.len() == params.len() && pairs.remainder().is_empty(),
+            CustomError::InvalidRemainingAccounts
+        );
+
+        let rent = Rent::get()?;
+        let payer = ctx.accounts.payer.to_account_info();
+        let system = ctx.accounts.system_program.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let freeze_authority = ctx.accounts.program_authority.key();
+
+        for (param, pair) in params.iter().zip(pairs) {
+            validation::require_valid_decimals(param.decimals)?;
+            validation::require_pubkey_not_default(&param.mint_authority)?;
+            let (mint_info, config_info) = (&pair[0], &pair[1]);
+            require!(mint_info.is_signer, ErrorCode::AccountNotSigner);
+
+            let cpi_accounts = system_program::CreateAccount {
+                from: payer.clone(),
+                to: mint_info.clone(),
+            };
+            system_program::create_account(
+                CpiContext::new(system.clone(), cpi_accounts),
+                rent.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token_program.key(),
+            )?;
+            let cpi_accounts = token::InitializeMint2 { mint: mint_info.clone() };
+            token::initialize_mint2(
+                CpiContext::new(token_program.clone(), cpi_accounts),
+                param.decimals,
+                &param.mint_authority,
+                Some(&freeze_authority),
+            )?;
+
+            let mint_key = mint_info.key();
+            let (expected, bump) = Pubkey::find_program_address(&[b"mint_config", mint_key.as_ref()], &crate::ID);
+            require_keys_eq!(config_info.key(), expected, CustomError::InvalidRemainingAccounts);
+            let space = 8 + MintConfig::INIT_SPACE;
+            let cpi_accounts = system_program::CreateAccount {
+                from: payer.clone(),
+                to: config_info.clone(),
+            };
+            let seeds = &[b"mint_config".as_ref(), mint_key.as_ref(), &[bump]];
+            system_program::create_account(
+                CpiContext::new_with_signer(system.clone(), cpi_accounts, &[&seeds[..]]),
+                rent.minimum_balance(space),
+                space as u64,
+                &crate::ID,
+            )?;
+
+            // The account is all zeroes, which reads as an empty MintConfig.
+            let mut data = config_info.try_borrow_mut_data()?;
+            let mut config = MintConfig::try_deserialize_unchecked(&mut &data[..])?;
+            config.init(mint_key, ctx.bumps.program_authority, bump);
+            config.try_serialize(&mut &mut data[..])?;
+        }
+
+        Ok(())
+    }
+
+    // Instruction 127: Set the bond freezers must hold to freeze, and the
+    // share of it a wrongly frozen owner gets, see approve_thaw_request.
+    pub fn set_freezer_bond_config(
+        ctx: Context<SetFreezerBondConfig>,
+        min_freezer_bond: u64,
+        bond_slash_bps: u16,
+    ) -> Result<()> {
+        require!(bond_slash_bps <= BPS_DENOMINATOR, CustomError::InvalidBps);
+        ctx.accounts.mint_config.min_freezer_bond = min_freezer_bond;
+        ctx.accounts.mint_config.bond_slash_bps = bond_slash_bps;
+        Ok(())
+    }
+
+    // Instruction 128: Add `lamports` to the signer's freezer bond for a mint,
+    // creating it on first use.
+    pub fn post_freezer_bond(ctx: Context<PostFreezerBond>, lamports: u64) -> Result<()> {
+        validation::require_nonzero_amount(lamports)?;
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.freezer.to_account_info(),
+            to: ctx.accounts.freezer_bond.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            lamports,
+        )?;
+
+        let bond = &mut ctx.accounts.freezer_bond;
+        bond.mint = ctx.accounts.mint.key();
+        bond.freezer = ctx.accounts.freezer.key();
+        bond.bump = ctx.bumps.freezer_bond;
+        bond.bonded = bond.bonded.checked_add(lamports).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Instruction 129: Take the whole bond back, rent included. Only once
+    // every account the freezer froze while bonded has been thawed.
+    pub fn withdraw_freezer_bond(ctx: Context<WithdrawFreezerBond>) -> Result<()> {
+        require!(ctx.accounts.freezer_bond.open_freezes == 0, CustomError::BondLocked);
+        // The bond is closed by its `close` constraint.
+        Ok(())
+    }
+
+    // Instruction 130: The owner of a frozen account asks the mint authority
+    // to thaw it.
+    pub fn request_thaw(ctx: Context<RequestThaw>) -> Result<()> {
+        let request = &mut ctx.accounts.thaw_request;
+        request.mint = ctx.accounts.token_account.mint;
+        request.token_account = ctx.accounts.token_account.key();
+        request.owner = ctx.accounts.owner.key();
+        request.freezer = ctx.accounts.freeze_record.actor;
+        request.requested_at = now(ctx.remaining_accounts)?;
+        request.bump = ctx.bumps.thaw_request;
+        Ok(())
+    }
+
+    // Instruction 131: The mint authority grants a thaw request. When the freeze
+    // was `wrongful`, the freezer's whole bond is forfeited: `bond_slash_bps`
+    // of it goes to the owner and the rest to the treasury.
+    pub fn approve_thaw_request(ctx: Context<ApproveThawRequest>, wrongful: bool) -> Result<()> {
+        ctx.accounts.mint_config.require_not_permissioned()?;
+        let now = now(ctx.remaining_accounts)?;
+
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.token_account_to_process.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.program_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = ctx.accounts.mint_config.authority_seeds(&mint_key);
+        let signer = &[&seeds[..]];
+        token::thaw_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        // The request is closed by its `close` constraint.
+        let amount = ctx.accounts.freeze_record.amount;
+        close_freeze_record(
+            &ctx.accounts.freeze_record,
+            ctx.accounts.rent_payer.as_deref(),
+            &ctx.accounts.freeze_actor.to_account_info(),
+        )?;
+        ctx.accounts.mint_config.record_thaw(amount)?;
+        if let Some(bond) = record_bond(&ctx.accounts.freezer_bond, &ctx.accounts.freeze_record)? {
+            update_bond_if_posted(bond, FreezerBond::close_freeze)?;
+        }
+
+        emit!(AccountThawed {
+            mint: mint_key,
+            token_account: ctx.accounts.token_account_to_process.key(),
+            actor: ctx.accounts.admin.key(),
+            sequence: ctx.accounts.mint_config.next_sequence()?,
+        });
+
+        ctx.accounts.account_history.append(
+            ctx.accounts.token_account_to_process.key(),
+            ctx.bumps.account_history,
+            HistoryAction::Thaw,
+            ctx.accounts.admin.key(),
+            HistoryReason::ThawAppeal,
+            now,
+        )?;
+        ctx.accounts.treasury.bump = ctx.bumps.treasury;
+
+        if !wrongful {
+            return Ok(());
+        }
+        let Some(bond) = record_bond(&ctx.accounts.freezer_bond, &ctx.accounts.freeze_record)? else {
+            return Ok(());
+        };
+        let forfeited = update_bond_if_posted(bond, |bond| Ok(std::mem::take(&mut bond.bonded)))?;
+        let Some(forfeited) = forfeited.filter(|&forfeited| forfeited > 0) else {
+            return Ok(());
+        };
+        let to_owner = staking::bps_of(forfeited, ctx.accounts.mint_config.bond_slash_bps);
+        let to_treasury = forfeited - to_owner;
+        let owner = ctx.accounts.owner.to_account_info();
+        let treasury = ctx.accounts.treasury.to_account_info();
+        **ctx.accounts.freezer_bond.try_borrow_mut_lamports()? -= forfeited;
+        **owner.try_borrow_mut_lamports()? = owner.lamports().checked_add(to_owner).ok_or(CustomError::MathOverflow)?;
+        **treasury.try_borrow_mut_lamports()? =
+            treasury.lamports().checked_add(to_treasury).ok_or(CustomError::MathOverflow)?;
+
+        emit!(FreezerBondSlashed {
+            mint: mint_key,
+            freezer: ctx.accounts.freeze_record.actor,
+            token_account: ctx.accounts.token_account_to_process.key(),
+            owner: ctx.accounts.owner.key(),
+            to_owner,
+            to_treasury,
+        });
+        Ok(())
+    }
+
+    // Instruction 132: The mint authority turns a thaw request down. The
+    // account stays frozen and the owner gets the request's rent back.
+    pub fn deny_thaw_request(_ctx: Context<DenyThawRequest>) -> Result<()> {
+        // The request is closed by its `close` constraint.
+        Ok(())
+    }
+
+    // Instruction 133: transfer_tokens, annotated with a 32-byte reference (an
+    // invoice id, or the hash of a memo) that is kept on-chain in the
+    // `[b"payment", reference]` PaymentRecord. A reference is single-use unless
+    // it was first paid with `allow_multiple` and every later payment asks for
+    // it too; those add up against the same payee.
+    pub fn transfer_with_reference(
+        ctx: Context<TransferWithReference>,
+        amount: u64,
+        reference: [u8; 32],
+        allow_multiple: bool,
+    ) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let record = &ctx.accounts.payment_record;
+        if record.payment_count > 0 {
+            require!(record.allow_multiple && allow_multiple, CustomError::ReferenceAlreadyUsed);
+            require_keys_eq!(record.mint, ctx.accounts.mint.key(), CustomError::MintMismatch);
+            require_keys_eq!(record.payee, ctx.accounts.to.key(), CustomError::PaymentPayeeMismatch);
+        }
+
+        let accounts = &mut *ctx.accounts;
+        let received = transfer_with_tax(
+            &accounts.owner,
+            &accounts.mint,
+            &mut accounts.mint_config,
+            &mut accounts.from,
+            &mut accounts.to,
+            accounts.fee_token_account.as_mut(),
+            &accounts.token_program,
+            amount,
+        )?;
+
+        let record = &mut ctx.accounts.payment_record;
+        if record.payment_count == 0 {
+            record.reference = reference;
+            record.mint = ctx.accounts.mint.key();
+            record.payee = ctx.accounts.to.key();
+            record.allow_multiple = allow_multiple;
+            record.bump = ctx.bumps.payment_record;
+        }
+        record.append(ctx.accounts.owner.key(), received, Clock::get()?.slot)?;
+
+        emit!(PaymentReferenced {
+            reference,
+            mint: record.mint,
+            payer: ctx.accounts.owner.key(),
+            payee: record.payee,
+            amount: received,
+            total_amount: record.total_amount,
+            payment_count: record.payment_count,
+        });
+        Ok(())
+    }
+
+    // Instruction 134: View the payments made against `reference`.
+    pub fn get_payment_record(ctx: Context<GetPaymentRecord>, _reference: [u8; 32]) -> Result<PaymentRecord> {
+        Ok((*ctx.accounts.payment_record).clone())
+    }
+
+    // Instruction 135: Create a pool vault for `underlying_mint`: a custody
+    // account, and a share mint only the pool can mint. See `shares`.
+    pub fn initialize_pool_vault(ctx: Context<InitializePoolVault>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool_vault;
+        pool.underlying_mint = ctx.accounts.underlying_mint.key();
+        pool.share_mint = ctx.accounts.share_mint.key();
+        pool.bump = ctx.bumps.pool_vault;
+        pool.share_mint_bump = ctx.bumps.share_mint;
+        pool.vault_bump = ctx.bumps.vault;
+        pool.dead_shares_bump = ctx.bumps.dead_shares;
+        Ok(())
+    }
+
+    // Instruction 136: Deposit underlying tokens for shares, rounded down. The
+    // first deposit into an empty pool must exceed DEAD_SHARES, which it pays
+    // for and which stay with the pool.
+    pub fn deposit_for_shares(ctx: Context<PoolShares>, amount: u64) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        let share_supply = ctx.accounts.share_mint.supply;
+        let minted = shares::shares_for_deposit(amount, ctx.accounts.vault.amount, share_supply)?;
+        let dead_shares = if share_supply == 0 {
+            require!(minted > shares::DEAD_SHARES, CustomError::DepositBelowDeadShares);
+            shares::DEAD_SHARES
+        } else {
+            0
+        };
+        require!(minted > dead_shares, CustomError::ZeroShares);
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_underlying.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(CpiContext::new(cpi_program.clone(), cpi_accounts), amount)?;
+
+        let seeds = ctx.accounts.pool_vault.signer_seeds();
+        let signer = &[&seeds[..]];
+        let recipients = [
+            (ctx.accounts.dead_shares.to_account_info(), dead_shares),
+            (ctx.accounts.user_shares.to_account_info(), minted - dead_shares),
+        ];
+        for (to, shares) in recipients {
+            if shares == 0 {
+                continue;
+            }
+            let cpi_accounts = MintTo {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                to,
+                authority: ctx.accounts.pool_vault.to_account_info(),
+            };
+            token::mint_to(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), shares)?;
+        }
+        Ok(())
+    }
+
+    // Instruction 137: Burn shares for their slice of the pool, rounded down.
+    pub fn redeem_shares(ctx: Context<PoolShares>, shares: u64) -> Result<()> {
+        validation::require_nonzero_amount(shares)?;
+        validation::require_within_balance(shares, ctx.accounts.user_shares.amount)?;
+        let assets = shares::assets_for_redeem(shares, ctx.accounts.vault.amount, ctx.accounts.share_mint.supply)?;
+        validation::require_nonzero_amount(assets)?;
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            from: ctx.accounts.user_shares.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::burn(CpiContext::new(cpi_program.clone(), cpi_accounts), shares)?;
+
+        let seeds = ctx.accounts.pool_vault.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_underlying.to_account_info(),
+            authority: ctx.accounts.pool_vault.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), assets)
+    }
+
+    // Instruction 138: Set the share of compounded rewards paid to whoever
+    // cranks compound_position, behind the timelock.
+    pub fn set_compound_bounty(ctx: Context<SetCompoundBounty>, bounty_bps: u16) -> Result<()> {
+        require!(bounty_bps <= MAX_COMPOUND_BOUNTY_BPS, CustomError::FeeAboveCap);
+
+        let mint_key = ctx.accounts.mint.key();
+        ctx.accounts.admin_action.require_ready(
+            admin_action_id(b"set_compound_bounty", &[mint_key.as_ref(), &bounty_bps.to_le_bytes()]),
+            now(ctx.remaining_accounts)?,
+        )?;
+
+        ctx.accounts.reward_pool.compound_bounty_bps = bounty_bps;
+        Ok(())
+    }
+
+    // Instruction 139: Opt a position in or out of compound_position.
+    pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+        ctx.accounts.position.auto_compound = enabled;
+        Ok(())
+    }
+
+    // Instruction 140: Restake an opted-in position's rewards into the same
+    // position, less the pool's compound bounty, which goes to the caller.
+    // Anyone may call it. The position settles first, so the restaked rewards
+    // only earn from now on.
+    pub fn compound_position(ctx: Context<CompoundPosition>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let position = &mut ctx.accounts.position;
+        require!(position.auto_compound, CustomError::AutoCompoundDisabled);
+        position.settle(pool.reward_per_token)?;
+        let rewards = std::mem::take(&mut position.pending_rewards);
+        if rewards == 0 {
+            return Ok(());
+        }
+
+        let bounty = staking::bps_of(rewards, pool.compound_bounty_bps);
+        let restaked = rewards - bounty;
+        position.amount = position.amount.checked_add(restaked).ok_or(CustomError::MathOverflow)?;
+        pool.total_staked = pool.total_staked.checked_add(restaked).ok_or(CustomError::MathOverflow)?;
+
+        let pool = &ctx.accounts.reward_pool;
+        let seeds = pool.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        for (to, amount) in [
+            (ctx.accounts.cranker_token_account.to_account_info(), bounty),
+            (ctx.accounts.stake_vault.to_account_info(), restaked),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to,
+                authority: ctx.accounts.reward_pool.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer), amount)?;
+        }
+        Ok(())
+    }
+
+    // Instruction 141: delegate_tokens to a PDA of another program, checked to
+    // be `create_program_address(seeds, target_program)` first. The delegation
+    // is recorded as an Advisory DelegationRecord naming the program, so the
+    // PDA spends through the token program as with any SPL delegate.
+    pub fn delegate_to_program(ctx: Context<DelegateToProgram>, amount: u64, seeds: Vec<Vec<u8>>) -> Result<()> {
+        validation::require_nonzero_amount(amount)?;
+        validation::require_within_balance(amount, ctx.accounts.token_account.amount)?;
+        let seed_slices: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let derived = Pubkey::create_program_address(&seed_slices, &ctx.accounts.target_program.key())
+            .map_err(|_| error!(CustomError::DelegatePdaMismatch))?;
+        require_keys_eq!(derived, ctx.accounts.delegate.key(), CustomError::DelegatePdaMismatch);
+        require_delegate_approved(&ctx.accounts.mint_config, ctx.accounts.approved_delegate.as_ref())?;
+        charge_delegation_fee(
+            &ctx.accounts.mint_config,
+            &ctx.accounts.owner,
+            ctx.accounts.fee_recipient.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let record = &mut ctx.accounts.delegation_record;
+        record.token_account = ctx.accounts.token_account.key();
+        record.mint = ctx.accounts.token_account.mint;
+        record.owner = ctx.accounts.owner.key();
+        record.delegate = ctx.accounts.delegate.key();
+        record.terms = DelegationTerms {
+            allowance: amount,
+            mode: DelegationMode::Advisory,
+            expires_at: None,
+            period_seconds: 0,
+            period_limit: 0,
+        };
+        record.period_start_ts = now(ctx.remaining_accounts)?;
+        record.bump = ctx.bumps.delegation_record;
+        record.delegate_program = Some(ctx.accounts.target_program.key());
+
+        let cpi_accounts = token_interface::Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.delegate.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::approve(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+        emit!(TokensDelegated {
+            token_account: ctx.accounts.token_account.key(),
+            delegate: ctx.accounts.delegate.key(),
+            amount,
+            referrer: None,
+            sequence: update_if_registered(&ctx.accounts.mint_config, |config| {
+                config.record_delegation(true)?;
+                config.next_sequence()
+            })?,
+        });
+        Ok(())
+    }
+
+    // Instruction 142: Grow a MintConfig created before fields were appended
+    // to it up to the current size, so `Account<MintConfig>` can load it again.
+    // The new bytes are zeroed, which every appended field reads as its
+    // default. A config already at full size is left alone.
+    pub fn upgrade_mint_config(ctx: Context<UpgradeMintConfig>) -> Result<()> {
+        let info = ctx.accounts.mint_config.to_account_info();
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == MintConfig::DISCRIMINATOR,
+            CustomError::InvalidMintConfig
+        );
+        drop(data);
+
+        grow_account(
+            &info,
+            8 + MintConfig::INIT_SPACE,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program,
+        )
+    }
+
+    // Instruction 143: Queue a slash_stake, as queue_admin_action would, and
+    // hold the position until it is executed or cancelled. request_unstake is
+    // refused while the SlashHold exists, so a staker who sees the slash
+    // coming can't move the stake out of its reach first. One slash per
+    // position can be queued at a time.
+    pub fn queue_slash_stake(ctx: Context<QueueSlashStake>, staker: Pubkey, amount: u64, reason: u8) -> Result<()> {
+        let action = &mut ctx.accounts.admin_action;
+        action.action_id = slash_action_id(&ctx.accounts.reward_pool.mint, &staker, amount, reason);
+        action.eta = now(ctx.remaining_accounts)?
+            .checked_add(ctx.accounts.program_config.timelock_delay)
+            .ok_or(CustomError::MathOverflow)?;
+        action.bump = ctx.bumps.admin_action;
+
+        let hold = &mut ctx.accounts.slash_hold;
+        hold.position = ctx.accounts.position.key();
+        hold.action_id = action.action_id;
+        hold.bump = ctx.bumps.slash_hold;
+        Ok(())
+    }
+
+    // Instruction 144: Drop a queued slash and release the position. The
+    // action is closed too, unless cancel_admin_action already closed it.
+    pub fn cancel_slash_stake(_ctx: Context<CancelSlashStake>) -> Result<()> {
+        // The `close` constraints do the work.
+        Ok(())
+    }
+
+    // Anything Anchor doesn't recognise. Test-clock builds handle set_mock_time
+    // here, since #[program] can't compile a handler in or out by feature.
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+        clock::dispatch(program_id, accounts, data)
+    }
+}
+
+// Transfer `amounts[i]` from the vault to `recipients[i]`, emitting one
+// PayrollPaid per recipient and a PayrollCompleted summary. The total is
+// checked against the vault balance up front, so a batch that can't be paid
+// in full pays nobody.
+fn pay_from_vault<'info>(
+    vault: &Account<'info, VaultState>,
+    vault_token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    recipients: &'info [AccountInfo<'info>],
+    amounts: &[u64],
+) -> Result<()> {
+    require!(amounts.len() == recipients.len(), CustomError::InvalidRemainingAccounts);
+    require!(!vault_token_account.is_frozen(), CustomError::VaultFrozen);
+
+    let total = amounts
+        .iter()
+        .try_fold(0u64, |sum, &amount| sum.checked_add(amount))
+        .ok_or(CustomError::MathOverflow)?;
+    require!(total <= vault_token_account.amount, CustomError::InsufficientVaultBalance);
+
+    let seeds = vault.signer_seeds();
+    let signer = &[&seeds[..]];
+    for (recipient_info, &amount) in recipients.iter().zip(amounts) {
+        validation::require_nonzero_amount(amount)?;
+        let recipient = Account::<TokenAccount>::try_from(recipient_info)?;
+        require_keys_eq!(recipient.mint, vault.mint, CustomError::MintMismatch);
+
+        let cpi_accounts = Transfer {
+            from: vault_token_account.to_account_info(),
+            to: recipient_info.clone(),
+            authority: vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(PayrollPaid {
+            vault: vault.key(),
+            recipient: recipient_info.key(),
+            amount,
+        });
+    }
+
+    emit!(PayrollCompleted {
+        vault: vault.key(),
+        recipients: amounts.len() as u32,
+        total,
+    });
+    Ok(())
+}
+
+// Thaw approvals are signed over `token_account || mint || slot (u64 LE)`.
+// The slot bounds replay: approvals older than `max_age_slots` are refused.
+fn check_thaw_approval(
+    message: &[u8],
+    token_account: &Pubkey,
+    mint: &Pubkey,
+    max_age_slots: u64,
+) -> Result<()> {
+    require!(message.len() == 72, CustomError::ApprovalMessageMismatch);
+    require!(
+        &message[..32] == token_account.as_ref() && &message[32..64] == mint.as_ref(),
+        CustomError::ApprovalMessageMismatch
+    );
+
+    let slot = u64::from_le_bytes(message[64..72].try_into().unwrap());
+    let current = Clock::get()?.slot;
+    require!(
+        slot <= current && current - slot <= max_age_slots,
+        CustomError::ApprovalExpired
+    );
+    Ok(())
+}
+
+// Every wrapped token must be backed by an underlying token in custody.
+// Wrap and unwrap move both sides by the same amount, so the two stay equal;
+// this is `>=` only because anyone can send underlying tokens to the vault directly.
+fn require_backed(wrapped_mint: &Mint, vault: &TokenAccount) -> Result<()> {
+    require!(vault.amount >= wrapped_mint.supply, CustomError::WrapperUnderbacked);
+    Ok(())
+}
+
+// For account constraints: whether `actual` is `expected`. Logs both keys when
+// it isn't, since a bare constraint error doesn't say which account was wrong.
+fn key_matches(name: &str, actual: Pubkey, expected: Pubkey) -> bool {
+    if actual != expected {
+        msg!("{}: expected {}, got {}", name, expected, actual);
+        return false;
+    }
+    true
+}
+
+// As `key_matches`, for an authority field that may be unset.
+fn authority_matches(name: &str, authority: COption<Pubkey>, actual: Pubkey) -> bool {
+    match authority {
+        COption::Some(expected) => key_matches(name, actual, expected),
+        COption::None => {
+            msg!("{}: no authority is set, got {}", name, actual);
+            false
+        }
+    }
+}
+
+// Charge `payer` the delegation fee of the mint behind `mint_config`. Mints
+// without a MintConfig, such as external Token-2022 mints, are free.
+fn charge_delegation_fee<'info>(
+    mint_config: &UncheckedAccount<'info>,
+    payer: &Signer<'info>,
+    fee_recipient: Option<&UncheckedAccount<'info>>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if mint_config.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*mint_config.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let config = MintConfig::try_deserialize(&mut &mint_config.try_borrow_data()?[..])?;
+    charge_lamport_fee(
+        &payer.to_account_info(),
+        fee_recipient,
+        &config.fee_recipient,
+        config.fee_tier.delegation_fee,
+        system_program,
+    )
+}
+
+// With `restrict_delegates` on, a mint's tokens may only be delegated to keys
+// holding an ApprovedDelegate entry; `approved_delegate` must be that PDA.
+// Mints without a MintConfig never restrict.
+fn require_delegate_approved(mint_config: &UncheckedAccount, approved_delegate: Option<&UncheckedAccount>) -> Result<()> {
+    if mint_config.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*mint_config.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let config = MintConfig::try_deserialize(&mut &mint_config.try_borrow_data()?[..])?;
+    if config.restrict_delegates {
+        require!(
+            approved_delegate.is_some_and(|entry| *entry.owner == crate::ID && !entry.data_is_empty()),
+            CustomError::DelegateNotApproved
+        );
+    }
+    Ok(())
+}
+
+// Checkpoint `token_account`'s balance after a program-mediated change, when
+// its holder's VotingCheckpoints is passed.
+fn checkpoint_balance(
+    checkpoints: Option<&mut Account<VotingCheckpoints>>,
+    token_account: &mut Account<TokenAccount>,
+) -> Result<()> {
+    if let Some(checkpoints) = checkpoints {
+        token_account.reload()?;
+        checkpoints.push(Clock::get()?.slot, token_account.amount);
+    }
+    Ok(())
+}
+
+// Apply `update` to the MintConfig behind an optional `mint_config` account
+// and write it back. Mints without one are left alone.
+fn update_if_registered<T>(
+    mint_config: &UncheckedAccount,
+    update: impl FnOnce(&mut MintConfig) -> Result<T>,
+) -> Result<Option<T>> {
+    if mint_config.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*mint_config.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = mint_config.try_borrow_mut_data()?;
+    let mut config = MintConfig::try_deserialize(&mut &data[..])?;
+    let result = update(&mut config)?;
+    config.try_serialize(&mut &mut data[..])?;
+    Ok(Some(result))
+}
+
+// The token account's AccountHistory at `history`, created at its canonical
+// address on the first logged freeze or thaw. An existing one is checked with
+// the bump it stored then, so only that first call pays for
+// find_program_address. Write changes back with `store_account_history`.
+fn load_account_history<'info>(
+    history: &UncheckedAccount<'info>,
+    token_account: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<AccountHistory> {
+    if !history.data_is_empty() {
+        require_keys_eq!(*history.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let loaded = AccountHistory::try_deserialize(&mut &history.try_borrow_data()?[..])?;
+        let address = Pubkey::create_program_address(
+            &[b"account_history", token_account.as_ref(), &[loaded.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+        require_keys_eq!(history.key(), address, ErrorCode::ConstraintSeeds);
+        return Ok(loaded);
+    }
+
+    let (address, bump) = Pubkey::find_program_address(&[b"account_history", token_account.as_ref()], &crate::ID);
+    require_keys_eq!(history.key(), address, ErrorCode::ConstraintSeeds);
+    create_pda_account(
+        history,
+        payer,
+        system_program,
+        8 + AccountHistory::INIT_SPACE,
+        &[b"account_history", token_account.as_ref(), &[bump]],
+    )?;
+    Ok(AccountHistory {
+        token_account: *token_account,
+        bump,
+        total_actions: 0,
+        last_state_change_ts: 0,
+        entries: Vec::new(),
+        freeze_exempt_bump: None,
+    })
+}
+
+// Carry on a re-registered mint's sequence from its RetiredMint, if
+// deregister_mint left one.
+fn resume_sequence(mint_config: &mut MintConfig, retired_mint: &UncheckedAccount) -> Result<()> {
+    if retired_mint.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*retired_mint.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let retired = RetiredMint::try_deserialize(&mut &retired_mint.try_borrow_data()?[..])?;
+    mint_config.sequence = retired.sequence;
+    Ok(())
+}
+
+fn store_account_history(info: &AccountInfo, history: &AccountHistory) -> Result<()> {
+    history.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+// Create `account` at the PDA `seeds` sign for, owned by this program, with
+// `payer` covering the rent. Lamports already sent to the address stay, as
+// with Anchor's `init`, so funding it beforehand can't block the creation.
+fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer = &[seeds];
+    let program = system_program.to_account_info();
+    if account.lamports() == 0 {
+        let cpi_accounts = system_program::CreateAccount {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        return system_program::create_account(
+            CpiContext::new_with_signer(program, cpi_accounts, signer),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(program.clone(), cpi_accounts), top_up)?;
+    }
+    let cpi_accounts = system_program::Allocate { account_to_allocate: account.clone() };
+    system_program::allocate(CpiContext::new_with_signer(program.clone(), cpi_accounts, signer), space as u64)?;
+    let cpi_accounts = system_program::Assign { account_to_assign: account.clone() };
+    system_program::assign(CpiContext::new_with_signer(program, cpi_accounts, signer), &crate::ID)
+}
+
+// The `[b"freezer_bond", mint, freezer]` PDA for a bump known to be canonical.
+fn freezer_bond_address(mint: &Pubkey, freezer: &Pubkey, bump: u8) -> Result<Pubkey> {
+    Pubkey::create_program_address(&[b"freezer_bond", mint.as_ref(), freezer.as_ref(), &[bump]], &crate::ID)
+        .map_err(|_| error!(ErrorCode::ConstraintSeeds))
+}
+
+// `freezer_bond`, checked to be the bond the freeze behind `record` was
+// counted against. None if it wasn't counted against one.
+fn record_bond<'a, 'info>(
+    freezer_bond: &'a UncheckedAccount<'info>,
+    record: &FreezeRecord,
+) -> Result<Option<&'a UncheckedAccount<'info>>> {
+    let Some(bump) = record.bond_bump else {
+        return Ok(None);
+    };
+    require_keys_eq!(
+        freezer_bond.key(),
+        freezer_bond_address(&record.mint, &record.actor, bump)?,
+        ErrorCode::ConstraintSeeds
+    );
+    Ok(Some(freezer_bond))
+}
+
+// The FreezerBond at `freezer_bond`, if its freezer has posted one, read
+// without writing it back.
+fn posted_bond(freezer_bond: &UncheckedAccount) -> Result<Option<FreezerBond>> {
+    if freezer_bond.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*freezer_bond.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let data = freezer_bond.try_borrow_data()?;
+    Ok(Some(FreezerBond::try_deserialize(&mut &data[..])?))
+}
+
+// Apply `update` to the FreezerBond at `freezer_bond`, if its freezer has
+// posted one. Freezers without a bond are left alone.
+fn update_bond_if_posted<T>(
+    freezer_bond: &UncheckedAccount,
+    update: impl FnOnce(&mut FreezerBond) -> Result<T>,
+) -> Result<Option<T>> {
+    if freezer_bond.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*freezer_bond.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = freezer_bond.try_borrow_mut_data()?;
+    let mut bond = FreezerBond::try_deserialize(&mut &data[..])?;
+    let result = update(&mut bond)?;
+    bond.try_serialize(&mut &mut data[..])?;
+    Ok(Some(result))
+}
+
+// The stats account to credit for `referrer`, if there is one. The account is
+// passed only alongside a referrer, and nobody may refer themselves.
+fn referral_stats<'a, 'info>(
+    referrer: Option<Pubkey>,
+    owner: &Pubkey,
+    stats: Option<&'a mut Account<'info, ReferralStats>>,
+    bump: u8,
+) -> Result<Option<&'a mut Account<'info, ReferralStats>>> {
+    let (referrer, stats) = match (referrer, stats) {
+        (None, None) => return Ok(None),
+        (Some(referrer), Some(stats)) => (referrer, stats),
+        _ => return err!(CustomError::ReferralStatsMismatch),
+    };
+    require_keys_neq!(referrer, *owner, CustomError::SelfReferral);
+
+    // Freshly created by init_if_needed.
+    if stats.referrer == Pubkey::default() {
+        stats.referrer = referrer;
+        stats.bump = bump;
+    }
+    Ok(Some(stats))
+}
+
+// Pay a lamport fee from `payer` to the mint's fee recipient. Free when `fee` is zero,
+// in which case `recipient` may be left out.
+fn charge_lamport_fee<'info>(
+    payer: &AccountInfo<'info>,
+    recipient: Option<&UncheckedAccount<'info>>,
+    expected_recipient: &Pubkey,
+    fee: u64,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    let recipient = recipient.ok_or(CustomError::WrongFeeRecipient)?;
+    require_keys_eq!(recipient.key(), *expected_recipient, CustomError::WrongFeeRecipient);
+
+    let cpi_accounts = system_program::Transfer {
+        from: payer.clone(),
+        to: recipient.to_account_info(),
+    };
+    system_program::transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), fee)
+}
+
+// sighash of "global:on_freeze_event", the hook instruction's discriminator.
+pub const ON_FREEZE_EVENT_DISCRIMINATOR: [u8; 8] = [52, 9, 199, 148, 210, 86, 137, 42];
+
+// Tell the mint's notification hook, if it has one, that `token_account` was
+// frozen or thawed: `on_freeze_event(token_account, mint, frozen)`, signed by
+// the notifier PDA and followed by `hook_accounts` as passed to us.
+//
+// The runtime aborts the whole transaction when a CPI fails, so a broken hook
+// can't be caught and logged here. Instead the hook account is optional: the
+// notification is skipped when it is left out, so a failing hook never stops
+// a freeze or thaw.
+fn notify_hook<'info>(
+    configured: Option<Pubkey>,
+    hook_program: Option<&UncheckedAccount<'info>>,
+    notifier: Option<(&UncheckedAccount<'info>, u8)>,
+    token_account: &Pubkey,
+    mint: &Pubkey,
+    frozen: bool,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let Some(configured) = configured else {
+        return Ok(());
+    };
+    let Some(hook_program) = hook_program else {
+        msg!("notification hook {} not supplied, skipping", configured);
+        return Ok(());
+    };
+    require_keys_eq!(hook_program.key(), configured, CustomError::NotificationHookMismatch);
+    let (notifier, bump) = notifier.ok_or(ErrorCode::AccountNotEnoughKeys)?;
+
+    let mut data = ON_FREEZE_EVENT_DISCRIMINATOR.to_vec();
+    (token_account, mint, frozen).serialize(&mut data)?;
+    let mut accounts = vec![AccountMeta::new_readonly(notifier.key(), true)];
+    let mut infos = vec![notifier.to_account_info()];
+    for info in hook_accounts {
+        accounts.push(if info.is_writable {
+            AccountMeta::new(info.key(), info.is_signer)
+        } else {
+            AccountMeta::new_readonly(info.key(), info.is_signer)
+        });
+        infos.push(info.clone());
+    }
+    infos.push(hook_program.to_account_info());
+
+    let ix = Instruction {
+        program_id: configured,
+        accounts,
+        data,
+    };
+    let seeds = &[b"notifier".as_ref(), mint.as_ref(), std::slice::from_ref(&bump)];
+    invoke_signed(&ix, &infos, &[&seeds[..]])?;
+    Ok(())
+}
+
+// sighash of "global:validate_thaw", the validator instruction's discriminator.
+pub const VALIDATE_THAW_DISCRIMINATOR: [u8; 8] = [148, 197, 66, 99, 215, 222, 135, 181];
+
+// Ask `validator` whether `token_account` may be thawed:
+// `validate_thaw(owner, token_account) -> bool`, with `validator_accounts`
+// as its accounts. Anything but a `true` it returned itself is a rejection.
+// A validator that errors aborts the transaction outright, since the runtime
+// doesn't let a failed CPI be caught.
+fn validate_thaw<'info>(
+    validator: &UncheckedAccount<'info>,
+    owner: &Pubkey,
+    token_account: &Pubkey,
+    validator_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let mut data = VALIDATE_THAW_DISCRIMINATOR.to_vec();
+    (owner, token_account).serialize(&mut data)?;
+    let accounts = validator_accounts
+        .iter()
+        .map(|info| {
+            if info.is_writable {
+                AccountMeta::new(info.key(), info.is_signer)
+            } else {
+                AccountMeta::new_readonly(info.key(), info.is_signer)
+            }
+        })
+        .collect();
+    let mut infos = validator_accounts.to_vec();
+    infos.push(validator.to_account_info());
+
+    let ix = Instruction {
+        program_id: validator.key(),
+        accounts,
+        data,
+    };
+    invoke(&ix, &infos)?;
+
+    let approved = match get_return_data() {
+        Some((program_id, data)) if program_id == validator.key() => bool::try_from_slice(&data).unwrap_or(false),
+        _ => false,
+    };
+    require!(approved, CustomError::ValidatorRejected);
+    Ok(())
+}
+
+// When the RentPayer is passed and can afford it, pay `payer` back the
+// rent it just put into `record` and note on the record that the rent is the
+// RentPayer's, see `close_freeze_record`. The RentPayer is program-owned, so
+// its lamports are debited directly. Otherwise the payer keeps paying, as
+// without it.
+fn reimburse_record_rent<'info>(
+    rent_payer: Option<&mut Account<'info, RentPayer>>,
+    payer: &AccountInfo<'info>,
+    record: &mut Account<'info, FreezeRecord>,
+) -> Result<()> {
+    let Some(rent_payer) = rent_payer else {
+        return Ok(());
+    };
+    let rent = record.to_account_info().lamports();
+    let info = rent_payer.to_account_info();
+    let available = info.lamports().saturating_sub(Rent::get()?.minimum_balance(info.data_len()));
+    if available < rent {
+        msg!("rent payer holds {} spare lamports, {} needed; caller pays", available, rent);
+        return Ok(());
+    }
+
+    **info.try_borrow_mut_lamports()? -= rent;
+    **payer.try_borrow_mut_lamports()? = payer.lamports().checked_add(rent).ok_or(CustomError::MathOverflow)?;
+    rent_payer.total_spent = rent_payer.total_spent.checked_add(rent).ok_or(CustomError::MathOverflow)?;
+    record.rent_payer = Some(rent_payer.key());
+    Ok(())
+}
+
+// Grow `account` to `space` bytes, zero-filled, with `payer` topping up its
+// rent. Accounts already that large are left as they are.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if top_up > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), top_up)?;
+    }
+    account.realloc(space, true)?;
+    Ok(())
+}
+
+// Close a freeze record on thaw. Its rent goes back to the RentPayer if that
+// paid it, which must then be passed, and to `otherwise` if not.
+fn close_freeze_record<'info>(
+    record: &Account<'info, FreezeRecord>,
+    rent_payer: Option<&Account<'info, RentPayer>>,
+    otherwise: &AccountInfo<'info>,
+) -> Result<()> {
+    let destination = match record.rent_payer {
+        Some(_) => rent_payer.ok_or(CustomError::RentPayerRequired)?.to_account_info(),
+        None => otherwise.clone(),
+    };
+    record.close(destination)
+}
+
+// Mint `amount` to `destination`, signed by the mint authority, counting it
+// as a holder if it becomes one. Callers check it isn't frozen.
+fn mint_to_account<'info>(
+    mint: &Account<'info, Mint>,
+    destination: &Account<'info, TokenAccount>,
+    mint_authority: &Signer<'info>,
+    mint_config: &mut MintConfig,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let balance = destination.amount;
+    mint_config.track_holder(balance, balance.checked_add(amount).ok_or(CustomError::MathOverflow)?)?;
+
+    let cpi_accounts = MintTo {
+        mint: mint.to_account_info(),
+        to: destination.to_account_info(),
+        authority: mint_authority.to_account_info(),
+    };
+    token::mint_to(CpiContext::new(token_program.to_account_info(), cpi_accounts), amount)
+}
+
+// The program-mediated transfer of transfer_tokens: `amount` leaves `from`,
+// the mint's transfer tax (rounded down) goes to `fee_token_account`, and the
+// rest reaches `to`. Returns what reached `to`.
+#[allow(clippy::too_many_arguments)]
+fn transfer_with_tax<'info>(
+    owner: &Signer<'info>,
+    mint: &Account<'info, Mint>,
+    mint_config: &mut MintConfig,
+    from: &mut Account<'info, TokenAccount>,
+    to: &mut Account<'info, TokenAccount>,
+    mut fee_token_account: Option<&mut Account<'info, TokenAccount>>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<u64> {
+    let tax = (amount as u128 * mint_config.fee_tier.transfer_tax_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let decimals = mint.decimals;
+    let cpi_program = token_program.to_account_info();
+    let mut balances = vec![(from.key(), from.amount), (to.key(), to.amount)];
+    if let Some(fee_account) = fee_token_account.as_ref() {
+        balances.push((fee_account.key(), fee_account.amount));
+    }
+
+    if tax > 0 {
+        let fee_account = fee_token_account.as_ref().ok_or(CustomError::WrongFeeRecipient)?;
+        require_keys_eq!(fee_account.owner, mint_config.fee_recipient, CustomError::WrongFeeRecipient);
+        let cpi_accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: fee_account.to_account_info(),
+            authority: owner.to_account_info(),
+        };
+        token::transfer_checked(CpiContext::new(cpi_program.clone(), cpi_accounts), tax, decimals)?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: owner.to_account_info(),
+    };
+    token::transfer_checked(CpiContext::new(cpi_program, cpi_accounts), amount - tax, decimals)?;
+
+    // The sender goes first, so a holder handing over its whole balance
+    // frees its place before the recipient takes one.
+    from.reload()?;
+    to.reload()?;
+    let mut after = vec![from.amount, to.amount];
+    if let Some(fee_account) = fee_token_account.as_mut() {
+        fee_account.reload()?;
+        after.push(fee_account.amount);
+    }
+    for (i, (&(key, before), &after)) in balances.iter().zip(after.iter()).enumerate() {
+        // The same account can be passed twice; count it once.
+        if balances[..i].iter().all(|&(seen, _)| seen != key) {
+            mint_config.track_holder(before, after)?;
+        }
+    }
+    mint_config.record_transfer(amount)?;
+    Ok(amount - tax)
+}
+
+// Whitelist leaves are just the owner's pubkey.
+pub fn whitelist_leaf(owner: &Pubkey) -> [u8; 32] {
+    merkle::hash_leaf(&[owner.as_ref()])
+}
+
+// Airdrop leaves commit to the claimant and the amount they may claim.
+pub fn airdrop_leaf(claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    merkle::hash_leaf(&[claimant.as_ref(), &amount.to_le_bytes()])
+}
+
+// Close a program-owned account, paying `reward_bps` of its rent to `caller`
+// and the rest to `payer`.
+fn close_with_reward<'info>(
+    record: &AccountInfo<'info>,
+    caller: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    reward_bps: u64,
+) -> Result<()> {
+    let lamports = record.lamports();
+    let reward = (lamports as u128 * reward_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+    **caller.try_borrow_mut_lamports()? = caller
+        .lamports()
+        .checked_add(reward)
+        .ok_or(CustomError::MathOverflow)?;
+    **payer.try_borrow_mut_lamports()? = payer
+        .lamports()
+        .checked_add(lamports - reward)
+        .ok_or(CustomError::MathOverflow)?;
+    **record.try_borrow_mut_lamports()? = 0;
+
+    record.assign(&System::id());
+    record.realloc(0, false)?;
+    Ok(())
+}
+
+// The allowance `delegate` currently holds on `token_account`: zero if no
+// delegate is set, an error if a different one is.
+fn current_allowance(
+    token_account: &token_interface::TokenAccount,
+    delegate: &Pubkey,
+) -> Result<u64> {
+    match token_account.delegate {
+        COption::Some(current) => {
+            require_keys_eq!(current, *delegate, CustomError::DelegateMismatch);
+            Ok(token_account.delegated_amount)
+        }
+        COption::None => Ok(0),
+    }
+}
+
+// ====== Account Structs ======
+
+#[derive(Accounts)]
+pub struct RegisterExistingMint<'info> {
+    // The mint's current freeze authority. It must also be the mint authority,
+    // which is what the program treats as the mint's admin.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.freeze_authority.is_some() @ CustomError::NoFreezeAuthority,
+        constraint = authority_matches("freeze authority", mint.freeze_authority, admin.key()) @ CustomError::Unauthorized,
+        constraint = authority_matches("mint authority", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the same PDA from our CreateTokenMint instruction.
+    #[account(
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    // `init` refuses a mint that is already registered.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + MintConfig::INIT_SPACE,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The `[b"retired_mint", mint]` account deregister_mint leaves,
+    /// which needn't exist. See `resume_sequence`.
+    #[account(
+        seeds = [b"retired_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub retired_mint: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetNotificationHook<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `set_notification_hook` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterMint<'info> {
+    // The mint authority. Receives the MintConfig's rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `deregister_mint` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    #[account(
+        mut,
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // Keeps the sequence for if the mint is registered again.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + RetiredMint::INIT_SPACE,
+        seeds = [b"retired_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub retired_mint: Account<'info, RetiredMint>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(decimals: u8, mint_authority: Pubkey)]
+pub struct CreateTokenMint<'info> {
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = decimals,
+        mint::authority = mint_authority,
+        // CRITICAL: This sets our program's PDA as the freeze authority.
+        mint::freeze_authority = program_authority.key()
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is our program's authority, a PDA. It doesn't need to be checked because we are defining it here.
+    #[account(
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    // Per-mint settings, including the cached PDA bumps.
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MintConfig::INIT_SPACE,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, referrer: Option<Pubkey>)]
+pub struct DelegateTokens<'info> {
+    // Either an SPL Token or a Token-2022 account (extensions included).
+    // It must belong to the token program passed below, so the two can't be mixed.
+    // The signer must own it; checked here rather than left to the token program.
+    #[account(
+        mut,
+        constraint = *token_account.to_account_info().owner == token_program.key() @ CustomError::TokenProgramMismatch,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    /// CHECK: The account being delegated to. It can be any account except the owner.
+    #[account(
+        constraint = delegate.key() != owner.key() @ CustomError::SelfDelegation
+    )]
+    pub delegate: UncheckedAccount<'info>,
+    
+    // Pays the delegation fee, if the mint charges one.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: The mint's MintConfig. Token-2022 or external mints may not have
+    /// one, in which case no fee is charged; if it exists it must be used.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    /// CHECK: Must be `mint_config.fee_recipient` when a fee is due.
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The `[b"approved_delegate", mint, delegate]` PDA. Only needed
+    /// when the mint restricts delegates; see `require_delegate_approved`.
+    #[account(
+        seeds = [b"approved_delegate", token_account.mint.as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub approved_delegate: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Only with a `referrer`; created on its first referral.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ReferralStats::INIT_SPACE,
+        seeds = [b"referral", referrer.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// increase_delegation and decrease_delegation: DelegateTokens without referrals.
+#[derive(Accounts)]
+pub struct AdjustDelegation<'info> {
+    // Either an SPL Token or a Token-2022 account (extensions included).
+    // It must belong to the token program passed below, so the two can't be mixed.
+    // The signer must own it; checked here rather than left to the token program.
+    #[account(
+        mut,
+        constraint = *token_account.to_account_info().owner == token_program.key() @ CustomError::TokenProgramMismatch,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    /// CHECK: The account being delegated to. It can be any account except the owner.
+    #[account(
+        constraint = delegate.key() != owner.key() @ CustomError::SelfDelegation
+    )]
+    pub delegate: UncheckedAccount<'info>,
+    
+    // Pays the delegation fee on increases, if the mint charges one.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: The mint's MintConfig. Token-2022 or external mints may not have
+    /// one, in which case no fee is charged; if it exists it must be used.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    /// CHECK: Must be `mint_config.fee_recipient` when a fee is due.
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The ApprovedDelegate PDA, see DelegateTokens.
+    #[account(
+        seeds = [b"approved_delegate", token_account.mint.as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub approved_delegate: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    // The mint authority, or a key holding the freezer role for this mint.
+    // Pays the rent for the freeze record.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = key_matches("token program", *token_account_to_process.to_account_info().owner, token_program.key())
+            @ CustomError::TokenProgramMismatch
+    )]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+    
+    // We need the mint to verify that the admin is the mint_authority.
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account_to_process.mint) @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: The FreezeExempt PDA for this token account. Always required, so the
+    /// exemption can't be dodged by leaving it out; freezing is refused if it exists.
+    /// The handler checks the address, see `AccountHistory::freeze_exempt_address`.
+    pub freeze_exempt: UncheckedAccount<'info>,
+
+    // Required when `admin` is not the mint authority: the signer's freezer role.
+    #[account(
+        mut,
+        seeds = [b"freezer", mint.key().as_ref(), admin.key().as_ref()],
+        bump = freezer_stats.bump
+    )]
+    pub freezer_stats: Option<Account<'info, FreezerStats>>,
+
+    /// CHECK: The signer's FreezerBond, which may not exist; see `update_bond_if_posted`.
+    /// Only a freezer's is used, checked in the handler with the bump its
+    /// FreezerStats keeps. The mint authority isn't held to a bond.
+    #[account(mut)]
+    pub freezer_bond: UncheckedAccount<'info>,
+
+    /// CHECK: The token account's AccountHistory, created on its first logged
+    /// freeze or thaw; see `load_account_history`.
+    #[account(mut)]
+    pub account_history: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority: the v1 or v2 PDA depending on
+    /// `mint_config.authority_version`. The CPI signs with the matching cached seeds,
+    /// so any other account fails there without a find_program_address search here.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    // Only to override the toggle cooldown or a freezer's daily quota, in an
+    // emergency: the super_admin co-signs, with the ProgramConfig naming it.
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Option<Box<Account<'info, ProgramConfig>>>,
+
+    #[account(
+        constraint = program_config.as_ref().is_some_and(|config| config.super_admin == super_admin.key())
+            @ CustomError::NotSuperAdmin
+    )]
+    pub super_admin: Option<Signer<'info>>,
+
+    /// CHECK: The mint's notification hook program, if it has one. Leaving it
+    /// out skips the notification; see `notify_hook`.
+    #[account(executable)]
+    pub notification_hook: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The `[b"notifier", mint]` PDA, which signs hook notifications.
+    /// It holds no authority anywhere else.
+    #[account(
+        seeds = [b"notifier", mint.key().as_ref()],
+        bump = mint_config.notifier_bump()
+    )]
+    pub notifier: Option<UncheckedAccount<'info>>,
+
+    // Pays for the freeze record instead of the caller when it can, see
+    // `reimburse_record_rent`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+}
+
+#[derive(Accounts)]
+pub struct ThawTokenAccount<'info> {
+    // The mint authority. Receives the freeze record's rent, unless the
+    // RentPayer paid it.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = key_matches("token program", *token_account_to_process.to_account_info().owner, token_program.key())
+            @ CustomError::TokenProgramMismatch
+    )]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account_to_process.mint) @ CustomError::MintMismatch,
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    // Gets the freeze record's rent back if it paid it, see `close_freeze_record`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+
+    /// CHECK: The FreezerBond of whoever froze the account, which may not
+    /// exist; see `record_bond`.
+    #[account(mut)]
+    pub freezer_bond: UncheckedAccount<'info>,
+
+    /// CHECK: The token account's AccountHistory, created on its first logged
+    /// freeze or thaw; see `load_account_history`.
+    #[account(mut)]
+    pub account_history: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The Instructions sysvar. Only needed if the mint requires thaw approval.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    // Only to override the toggle cooldown, see FreezeTokenAccount.
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Option<Box<Account<'info, ProgramConfig>>>,
+
+    #[account(
+        constraint = program_config.as_ref().is_some_and(|config| config.super_admin == super_admin.key())
+            @ CustomError::NotSuperAdmin
+    )]
+    pub super_admin: Option<Signer<'info>>,
+
+    /// CHECK: The mint's notification hook program, if it has one. Leaving it
+    /// out skips the notification; see `notify_hook`.
+    #[account(executable)]
+    pub notification_hook: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The `[b"notifier", mint]` PDA, which signs hook notifications.
+    /// It holds no authority anywhere else.
+    #[account(
+        seeds = [b"notifier", mint.key().as_ref()],
+        bump = mint_config.notifier_bump()
+    )]
+    pub notifier: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeWhileFrozen<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner,
+        constraint = token_account.is_frozen() @ CustomError::AccountNotFrozen
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account.mint) @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The account's RecoveryRequest PDA; it must not exist.
+    #[account(
+        seeds = [b"recovery", token_account.key().as_ref()],
+        bump
+    )]
+    pub recovery_request: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDelegation<'info> {
+    // Same checks as DelegateTokens.
+    #[account(
+        mut,
+        constraint = *token_account.to_account_info().owner == token_program.key() @ CustomError::TokenProgramMismatch,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// CHECK: The account being delegated to. It can be any account except the owner.
+    #[account(
+        constraint = delegate.key() != owner.key() @ CustomError::SelfDelegation
+    )]
+    pub delegate: UncheckedAccount<'info>,
+
+    // Pays the record's rent and the delegation fee, if the mint charges one.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    // One per token account, like the SPL delegate itself.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DelegationRecord::INIT_SPACE,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+
+    /// CHECK: The mint's MintConfig, see DelegateTokens.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    /// CHECK: Must be `mint_config.fee_recipient` when a fee is due.
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The ApprovedDelegate PDA, see DelegateTokens.
+    #[account(
+        seeds = [b"approved_delegate", token_account.mint.as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub approved_delegate: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SpendDelegated<'info> {
+    pub delegate: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = delegate @ CustomError::Unauthorized,
+        has_one = token_account,
+        has_one = mint,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump = delegation_record.bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub destination: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: The mint's MintConfig, counted in if the mint has one.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDelegationDestinations<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"delegation", delegation_record.token_account.as_ref()],
+        bump = delegation_record.bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDelegation<'info> {
+    // Gets the record's rent back.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ CustomError::Unauthorized,
+        has_one = token_account,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump = delegation_record.bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: The mint's MintConfig, counted in if the mint has one.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileFrozen<'info> {
+    // The mint authority. Pays the rent for the freeze record.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = token_account.mint == mint.key(),
+        constraint = token_account.is_frozen() @ CustomError::AccountNotFrozen
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // `init` keeps an account from being counted twice.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMintConfig<'info> {
+    // The mint authority of the mint being migrated.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    // Only mints whose freeze authority is already our PDA can be migrated.
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(program_authority.key()) @ CustomError::WrongFreezeAuthority,
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the same PDA from our CreateTokenMint instruction.
+    #[account(
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + MintConfig::INIT_SPACE,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The `[b"retired_mint", mint]` account deregister_mint leaves,
+    /// which needn't exist. See `resume_sequence`.
+    #[account(
+        seeds = [b"retired_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub retired_mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramConfig<'info> {
+    // Must be the upgrade authority recorded in the ProgramData account below.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // Our own program account, used to locate its ProgramData account.
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ CustomError::InvalidProgramData
+    )]
+    pub program: Program<'info, crate::program::CustomTokenProgram>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ CustomError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    // `init` makes a second call fail because the account already exists.
+    // Declared after program_data so an intruder gets NotUpgradeAuthority first.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [b"program_config"],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(action_id: [u8; 32])]
+pub struct QueueAdminAction<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [b"admin_action", action_id.as_ref()],
+        bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+}
+
+#[derive(Accounts)]
+pub struct SetTimelockDelay<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // Only needed when shortening the delay.
+    #[account(
+        mut,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Option<Account<'info, AdminAction>>,
+}
+
+#[derive(Accounts)]
+pub struct RotateProgramAuthority<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `rotate_program_authority` action for this mint. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.authority_version == AUTHORITY_VERSION_V1 @ CustomError::AuthorityAlreadyRotated
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The v1 PDA, which is the mint's freeze authority until this instruction runs.
+    #[account(
+        seeds = [b"authority"],
+        bump = mint_config.authority_bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The v2 PDA that becomes the freeze authority. Derived here for the first time.
+    #[account(
+        seeds = [b"authority_v2", mint.key().as_ref()],
+        bump
+    )]
+    pub new_program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_account: Pubkey)]
+pub struct AddFreezeExempt<'info> {
+    // The mint authority.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FreezeExempt::INIT_SPACE,
+        seeds = [b"freeze_exempt", token_account.as_ref()],
+        bump
+    )]
+    pub freeze_exempt: Account<'info, FreezeExempt>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFreezeExempt<'info> {
+    // The mint authority.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = mint,
+        seeds = [b"freeze_exempt", freeze_exempt.token_account.as_ref()],
+        bump = freeze_exempt.bump
+    )]
+    pub freeze_exempt: Account<'info, FreezeExempt>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, referrer: Option<Pubkey>)]
+pub struct MintTokens<'info> {
+    pub mint_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    // Counts the destination if it becomes a holder.
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Only with a `referrer`; created on its first referral.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ReferralStats::INIT_SPACE,
+        seeds = [b"referral", referrer.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        constraint = voting_checkpoints.token_account == destination.key() @ CustomError::CheckpointAccountMismatch
+    )]
+    pub voting_checkpoints: Option<Account<'info, VotingCheckpoints>>,
+}
+
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    pub mint_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    // Counts destinations that become holders.
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MintToOwner<'info> {
+    pub mint_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Any wallet; only used to derive its associated token account.
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    // Counts the destination if it becomes a holder.
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeToOwners<'info> {
+    pub mint_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    // Counts destinations that become holders.
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // Pays for the associated token accounts created.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGcRewardBps<'info> {
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct GcRecords<'info> {
+    // Anyone may crank. Receives `gc_reward_bps` of the reclaimed rent.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // All records in one call belong to this mint, whose tallies are corrected.
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint_config.mint.as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(export_id: u64, page: u16)]
+pub struct ExportFrozenPage<'info> {
+    // The mint authority. Pays for the header and the pages.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // Created with page 0.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + FrozenExport::INIT_SPACE,
+        seeds = [b"export", mint.key().as_ref(), &export_id.to_le_bytes()],
+        bump
+    )]
+    pub export_header: Box<Account<'info, FrozenExport>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FrozenExportPage::INIT_SPACE,
+        seeds = [b"export", mint.key().as_ref(), &export_id.to_le_bytes(), &page.to_le_bytes()],
+        bump
+    )]
+    pub export_page: Box<Account<'info, FrozenExportPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecoveryWindow<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateRecovery<'info> {
+    // The current owner. Pays rent for the request and freeze record.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner,
+        constraint = token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // One pending recovery per token account.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RecoveryRequest::INIT_SPACE,
+        seeds = [b"recovery", token_account.key().as_ref()],
+        bump
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(program_authority.key()) @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRecovery<'info> {
+    // The mint authority. Pays for the new owner's ATA if it doesn't exist yet.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        address = recovery_request.token_account
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = new_owner,
+        has_one = mint,
+        has_one = new_owner,
+        seeds = [b"recovery", token_account.key().as_ref()],
+        bump = recovery_request.bump
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    #[account(
+        mut,
+        close = new_owner,
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: The wallet named in the request. Receives the tokens and all reclaimed rent.
+    #[account(mut)]
+    pub new_owner: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = new_owner
+    )]
+    pub new_owner_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(program_authority.key()) @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        address = recovery_request.token_account
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = mint,
+        has_one = owner,
+        seeds = [b"recovery", token_account.key().as_ref()],
+        bump = recovery_request.bump
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: The owner who opened the request and paid its rent. Signs too
+    /// if the delegate should be revoked.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(program_authority.key()) @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32])]
+pub struct CreateAirdrop<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = funder,
+        space = 8 + Airdrop::INIT_SPACE,
+        seeds = [b"airdrop", mint.key().as_ref(), funder.key().as_ref(), merkle_root.as_ref()],
+        bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    // Holds the undistributed tokens. Owned by the airdrop PDA.
+    #[account(
+        init,
+        payer = funder,
+        token::mint = mint,
+        token::authority = airdrop,
+        seeds = [b"airdrop_vault", airdrop.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop", airdrop.mint.as_ref(), airdrop.funder.as_ref(), airdrop.merkle_root.as_ref()],
+        bump = airdrop.bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_vault", airdrop.key().as_ref()],
+        bump = airdrop.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = claimant_token_account.mint == airdrop.mint @ CustomError::MintMismatch
+    )]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + ClaimStatus::INIT_SPACE,
+        seeds = [b"claim", airdrop.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub claim_status: Account<'info, ClaimStatus>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimUnclaimed<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        has_one = funder,
+        seeds = [b"airdrop", airdrop.mint.as_ref(), airdrop.funder.as_ref(), airdrop.merkle_root.as_ref()],
+        bump = airdrop.bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_vault", airdrop.key().as_ref()],
+        bump = airdrop.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == airdrop.mint @ CustomError::MintMismatch
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetWhitelistRoot<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetThawValidator<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ThawWithValidator<'info> {
+    // Anyone, typically the owner. Pays for the account history if it's new.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = key_matches("token program", *token_account_to_process.to_account_info().owner, token_program.key())
+            @ CustomError::TokenProgramMismatch
+    )]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account_to_process.mint) @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    // Gets the freeze record's rent back if it paid it, see `close_freeze_record`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+
+    /// CHECK: The FreezerBond of whoever froze the account, which may not
+    /// exist; see `record_bond`.
+    #[account(mut)]
+    pub freezer_bond: UncheckedAccount<'info>,
+
+    // Created on the token account's first logged freeze or thaw.
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + AccountHistory::INIT_SPACE,
+        seeds = [b"account_history", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub account_history: Box<Account<'info, AccountHistory>>,
+
+    /// CHECK: Whoever froze the account, and paid the freeze record's rent
+    /// unless the RentPayer did.
+    #[account(
+        mut,
+        address = freeze_record.actor
+    )]
+    pub freeze_actor: UncheckedAccount<'info>,
+
+    /// CHECK: Must be `mint_config.thaw_validator`, checked in the handler.
+    #[account(executable)]
+    pub validator_program: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ThawWithProof<'info> {
+    // Anyone can submit the proof, typically the owner. Pays the thaw fee.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.key() == token_account_to_process.mint @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    // Gets the freeze record's rent back if it paid it, see `close_freeze_record`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+
+    /// CHECK: The FreezerBond of whoever froze the account, which may not
+    /// exist; see `record_bond`.
+    #[account(mut)]
+    pub freezer_bond: UncheckedAccount<'info>,
+
+    // Created on the token account's first logged freeze or thaw.
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + AccountHistory::INIT_SPACE,
+        seeds = [b"account_history", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub account_history: Box<Account<'info, AccountHistory>>,
+
+    /// CHECK: Whoever froze the account, and paid the freeze record's rent
+    /// unless the RentPayer did.
+    #[account(
+        mut,
+        address = freeze_record.actor
+    )]
+    pub freeze_actor: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(program_authority.key()) @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Must be `mint_config.fee_recipient` when a thaw fee is due.
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetThawApproval<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetFreezeQuota<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetToggleCooldown<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetHoldingPeriod<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct MintLocked<'info> {
+    // Pays the rent for the holding lock.
+    #[account(mut)]
+    pub mint_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = mint_authority,
+        space = 8 + HoldingLock::INIT_SPACE,
+        seeds = [b"holding_lock", destination.key().as_ref()],
+        bump
+    )]
+    pub holding_lock: Account<'info, HoldingLock>,
+
+    /// CHECK: The destination's FreezeRecord PDA; it must not exist.
+    #[account(
+        seeds = [b"freeze_record", destination.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: UncheckedAccount<'info>,
+
+    /// CHECK: The destination's FreezeExempt PDA, see FreezeTokenAccount.
+    #[account(
+        seeds = [b"freeze_exempt", destination.key().as_ref()],
+        bump
+    )]
+    pub freeze_exempt: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockHolding<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = rent_payer,
+        has_one = token_account,
+        seeds = [b"holding_lock", token_account.key().as_ref()],
+        bump = holding_lock.bump
+    )]
+    pub holding_lock: Account<'info, HoldingLock>,
+
+    /// CHECK: Paid for the lock; gets its rent back.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account.mint) @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The token account's FreezeRecord PDA. If it exists the account
+    /// is under a compliance freeze and is not thawed.
+    #[account(
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxHolders<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(freezer: Pubkey)]
+pub struct AddFreezer<'info> {
+    // The mint authority. Pays the rent for the role account.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FreezerStats::INIT_SPACE,
+        seeds = [b"freezer", mint.key().as_ref(), freezer.as_ref()],
+        bump
+    )]
+    pub freezer_stats: Account<'info, FreezerStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFreezer<'info> {
+    // The mint authority. Receives the role account's rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"freezer", mint.key().as_ref(), freezer_stats.freezer.as_ref()],
+        bump = freezer_stats.bump
+    )]
+    pub freezer_stats: Account<'info, FreezerStats>,
+}
+
+#[derive(Accounts)]
+pub struct EscrowDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Only recorded as the one key allowed to spend from the escrow.
+    #[account(
+        constraint = delegate.key() != owner.key() @ CustomError::SelfDelegation
+    )]
+    pub delegate: UncheckedAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", mint.key().as_ref(), owner.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    // Holds the escrowed tokens. Owned by the escrow PDA.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        token::mint = mint,
+        token::authority = escrow,
+        seeds = [b"escrow_vault", escrow.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct EscrowSpend<'info> {
+    pub delegate: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = delegate @ CustomError::Unauthorized,
+        seeds = [b"escrow", escrow.mint.as_ref(), escrow.owner.as_ref(), escrow.delegate.as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == escrow.mint @ CustomError::MintMismatch
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct EscrowReclaim<'info> {
+    // Receives the rent of both the escrow and its vault.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"escrow", escrow.mint.as_ref(), escrow.owner.as_ref(), escrow.delegate.as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == escrow.mint @ CustomError::MintMismatch
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OpenVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + VaultState::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    // Holds the vault's tokens. Owned by the vault PDA.
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = vault,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct VaultDeposit<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump = vault.token_account_bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == vault.mint @ CustomError::MintMismatch
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VaultWithdraw<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump = vault.token_account_bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == vault.mint @ CustomError::MintMismatch
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    // Receives the rent of both vault accounts.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump = vault.token_account_bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RunPayroll<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump = vault.token_account_bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePayrollSchedule<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + PayrollSchedule::INIT_SPACE,
+        seeds = [b"payroll", vault.key().as_ref()],
+        bump
+    )]
+    pub schedule: Account<'info, PayrollSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePayroll<'info> {
+    #[account(
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump = vault.token_account_bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [b"payroll", vault.key().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, PayrollSchedule>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePayrollSchedule<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [b"payroll", vault.key().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, PayrollSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPayrollSchedule<'info> {
+    // Receives the schedule's rent.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ CustomError::Unauthorized,
+        seeds = [b"vault", vault.mint.as_ref(), vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = vault,
+        seeds = [b"payroll", vault.key().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, PayrollSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct SetInflation<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `set_inflation` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    #[account(
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::WrongMintAuthority
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The PDA that holds the mint authority so the crank can mint.
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = super_admin,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool", mint.key().as_ref()],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    // Receives the minted rewards. Owned by the reward pool PDA.
+    #[account(
+        init_if_needed,
+        payer = super_admin,
+        token::mint = mint,
+        token::authority = reward_pool,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CrankInflation<'info> {
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::WrongMintAuthority
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The mint authority PDA, signs the mint.
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = reward_pool.mint_authority_bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", mint.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, order_id: [u8; 32])]
+pub struct Redeem<'info> {
+    #[account(mut)]
+    pub redeemer: Signer<'info>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = redeemer_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub redeemer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = redeemer,
+        space = 8 + RedemptionReceipt::INIT_SPACE,
+        seeds = [b"redeem", order_id.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, RedemptionReceipt>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillRedemption<'info> {
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"redeem", receipt.order_id.as_ref()],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, RedemptionReceipt>,
+}
+
+#[derive(Accounts)]
+pub struct RefundRedemption<'info> {
+    pub redeemer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = redeemer @ CustomError::Unauthorized,
+        has_one = mint @ CustomError::MintMismatch,
+        seeds = [b"redeem", receipt.order_id.as_ref()],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, RedemptionReceipt>,
+
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(mint_authority.key()) @ CustomError::WrongMintAuthority
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The program's mint authority PDA, signs the refund.
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = redeemer_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub redeemer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetBurnReceiptRetention<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, tag: [u8; 32])]
+pub struct BurnWithReceipt<'info> {
+    #[account(mut)]
+    pub burner: Signer<'info>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        constraint = burner_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub burner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = burner,
+        space = 8 + BurnReceipt::INIT_SPACE,
+        seeds = [pda::BURN_RECEIPT_SEED, burner.key().as_ref(), tag.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, BurnReceipt>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBurnReceipt<'info> {
+    #[account(mut)]
+    pub burner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = burner,
+        has_one = burner @ CustomError::Unauthorized,
+        seeds = [pda::BURN_RECEIPT_SEED, burner.key().as_ref(), receipt.tag.as_ref()],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, BurnReceipt>,
+}
+
+#[derive(Accounts)]
+pub struct RescueTokens<'info> {
+    // Receives the stray account's rent when it is closed.
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// CHECK: The v1 program authority PDA, which owns the stray account.
+    #[account(
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = !pda::is_program_vault(&stray_account.key(), &stray_account.mint) @ CustomError::ProtectedVault,
+        constraint = stray_account.owner == program_authority.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub stray_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == stray_account.mint @ CustomError::MintMismatch
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RescueLamports<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `rescue_lamports` action for this treasury. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    /// CHECK: The v1 program authority PDA, holding the stray lamports.
+    #[account(
+        mut,
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Any account; bound by the queued action.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct FlagDelegate<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + FlaggedDelegate::INIT_SPACE,
+        seeds = [b"flagged_delegate", delegate.as_ref()],
+        bump
+    )]
+    pub flagged_delegate: Account<'info, FlaggedDelegate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnflagDelegate<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"flagged_delegate", flagged_delegate.delegate.as_ref()],
+        bump = flagged_delegate.bump
+    )]
+    pub flagged_delegate: Account<'info, FlaggedDelegate>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeIfFlaggedDelegate<'info> {
+    // Anyone. Pays the rent for the freeze record.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = key_matches("token program", *token_account_to_process.to_account_info().owner, token_program.key())
+            @ CustomError::TokenProgramMismatch
+    )]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account_to_process.mint) @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The FlaggedDelegate PDA for the token account's delegate. It
+    /// must exist; checked in the handler so a missing flag gets its own error.
+    #[account(
+        seeds = [b"flagged_delegate", token_account_to_process.delegate.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub flagged_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: The FreezeExempt PDA for this token account, see FreezeTokenAccount.
+    #[account(
+        seeds = [b"freeze_exempt", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub freeze_exempt: UncheckedAccount<'info>,
+
+    // Created on the token account's first logged freeze or thaw.
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + AccountHistory::INIT_SPACE,
+        seeds = [b"account_history", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub account_history: Box<Account<'info, AccountHistory>>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    // Pays for the freeze record instead of the caller when it can, see
+    // `reimburse_record_rent`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(ui_decimals: Option<u8>, logo_uri: String, project_url: String)]
+pub struct SetDisplayConfig<'info> {
+    // The mint authority. Pays for the DisplayConfig and any growth.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    // Sized for the first strings set; the handler grows it later.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = DisplayConfig::space(&logo_uri, &project_url),
+        seeds = [b"display_config", mint.key().as_ref()],
+        bump
+    )]
+    pub display_config: Account<'info, DisplayConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetDisplayConfig<'info> {
+    #[account(
+        seeds = [b"display_config", display_config.mint.as_ref()],
+        bump = display_config.bump
+    )]
+    pub display_config: Account<'info, DisplayConfig>,
+}
+
+#[derive(Accounts)]
+pub struct FundAuthority<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + RentPayer::INIT_SPACE,
+        seeds = [b"rent_payer"],
+        bump
+    )]
+    pub rent_payer: Account<'info, RentPayer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Donate<'info> {
+    #[account(mut)]
+    pub donor: Signer<'info>,
+
+    // Created by the first donation.
+    #[account(
+        init_if_needed,
+        payer = donor,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init_if_needed,
+        payer = donor,
+        space = 8 + DonorStats::INIT_SPACE,
+        seeds = [b"donor", donor.key().as_ref()],
+        bump
+    )]
+    pub donor_stats: Account<'info, DonorStats>,
+
+    // Numbered by the donor's donation count before this one.
+    #[account(
+        init,
+        payer = donor,
+        space = 8 + DonationReceipt::INIT_SPACE,
+        seeds = [b"donation", donor.key().as_ref(), donor_stats.donation_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, DonationReceipt>,
+
+    // Both only for token donations.
+    #[account(mut)]
+    pub donor_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Any token account of the mint owned by the treasury PDA.
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTransferPolicy<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct AddApprovedHolder<'info> {
+    // The mint authority.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ApprovedHolder::INIT_SPACE,
+        seeds = [b"approved_holder", mint.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub approved_holder: Account<'info, ApprovedHolder>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveApprovedHolder<'info> {
+    // The mint authority.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = mint,
+        seeds = [b"approved_holder", mint.key().as_ref(), approved_holder.owner.as_ref()],
+        bump = approved_holder.bump
+    )]
+    pub approved_holder: Account<'info, ApprovedHolder>,
+}
+
+#[derive(Accounts)]
+pub struct PermissionedTransfer<'info> {
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // Both accounts must be frozen, with their FreezeRecords below.
+    #[account(
+        mut,
+        constraint = from.mint == mint.key() @ CustomError::MintMismatch,
+        constraint = from.owner == owner.key() @ CustomError::NotTokenAccountOwner,
+        constraint = from.is_frozen() @ CustomError::AccountNotFrozen
+    )]
+    pub from: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = to.mint == mint.key() @ CustomError::MintMismatch,
+        constraint = to.key() != from.key() @ CustomError::SelfTransfer,
+        constraint = to.is_frozen() @ CustomError::AccountNotFrozen
+    )]
+    pub to: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"freeze_record", from.key().as_ref()],
+        bump = from_record.bump
+    )]
+    pub from_record: Box<Account<'info, FreezeRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"freeze_record", to.key().as_ref()],
+        bump = to_record.bump
+    )]
+    pub to_record: Box<Account<'info, FreezeRecord>>,
+
+    /// CHECK: The sender's ApprovedHolder PDA. Checked in the handler so an
+    /// unapproved holder gets its own error.
+    #[account(
+        seeds = [b"approved_holder", mint.key().as_ref(), from.owner.as_ref()],
+        bump
+    )]
+    pub from_holder: UncheckedAccount<'info>,
+
+    /// CHECK: The recipient's ApprovedHolder PDA, see `from_holder`.
+    #[account(
+        seeds = [b"approved_holder", mint.key().as_ref(), to.owner.as_ref()],
+        bump
+    )]
+    pub to_holder: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetRestrictDelegates<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct AddApprovedDelegate<'info> {
+    // The mint authority.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ApprovedDelegate::INIT_SPACE,
+        seeds = [b"approved_delegate", mint.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub approved_delegate: Account<'info, ApprovedDelegate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveApprovedDelegate<'info> {
+    // The mint authority.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = mint,
+        seeds = [b"approved_delegate", mint.key().as_ref(), approved_delegate.delegate.as_ref()],
+        bump = approved_delegate.bump
+    )]
+    pub approved_delegate: Account<'info, ApprovedDelegate>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct SanctionOwner<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + SanctionedOwner::INIT_SPACE,
+        seeds = [b"sanctioned_owner", owner.as_ref()],
+        bump
+    )]
+    pub sanctioned_owner: Account<'info, SanctionedOwner>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LiftSanction<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"sanctioned_owner", sanctioned_owner.owner.as_ref()],
+        bump = sanctioned_owner.bump
+    )]
+    pub sanctioned_owner: Account<'info, SanctionedOwner>,
+}
+
+// FreezeIfFlaggedDelegate, with the owner's sanction in place of the flag.
+#[derive(Accounts)]
+pub struct EnforceSanction<'info> {
+    // Anyone. Pays the rent for the freeze record.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = key_matches("token program", *token_account_to_process.to_account_info().owner, token_program.key())
+            @ CustomError::TokenProgramMismatch
+    )]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account_to_process.mint) @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    // Only mints registered with the program have one.
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// CHECK: The SanctionedOwner PDA for the token account's owner. It must
+    /// exist; checked in the handler so an unsanctioned owner gets its own error.
+    #[account(
+        seeds = [b"sanctioned_owner", token_account_to_process.owner.as_ref()],
+        bump
+    )]
+    pub sanctioned_owner: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: The FreezeExempt PDA for this token account, see FreezeTokenAccount.
+    #[account(
+        seeds = [b"freeze_exempt", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub freeze_exempt: UncheckedAccount<'info>,
+
+    // Created on the token account's first logged freeze or thaw.
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + AccountHistory::INIT_SPACE,
+        seeds = [b"account_history", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub account_history: Box<Account<'info, AccountHistory>>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    // Pays for the freeze record instead of the caller when it can, see
+    // `reimburse_record_rent`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct OpenStream<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = sender_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub sender_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = sender,
+        space = 8 + Stream::INIT_SPACE,
+        seeds = [b"stream", mint.key().as_ref(), sender.key().as_ref(), recipient.as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    // Holds the deposit. Owned by the stream PDA.
+    #[account(
+        init,
+        payer = sender,
+        token::mint = mint,
+        token::authority = stream,
+        seeds = [b"stream_vault", stream.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromStream<'info> {
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = recipient @ CustomError::Unauthorized,
+        seeds = [b"stream", stream.mint.as_ref(), stream.sender.as_ref(), stream.recipient.as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        mut,
+        seeds = [b"stream_vault", stream.key().as_ref()],
+        bump = stream.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == stream.mint @ CustomError::MintMismatch
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStream<'info> {
+    // Receives the rent of both the stream and its vault.
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        mut,
+        close = sender,
+        has_one = sender @ CustomError::Unauthorized,
+        seeds = [b"stream", stream.mint.as_ref(), stream.sender.as_ref(), stream.recipient.as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        mut,
+        seeds = [b"stream_vault", stream.key().as_ref()],
+        bump = stream.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    // The accrued part must reach the recipient, so it has to be their account.
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == stream.mint @ CustomError::MintMismatch,
+        constraint = recipient_token_account.owner == stream.recipient @ CustomError::NotTokenAccountOwner
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = sender_token_account.mint == stream.mint @ CustomError::MintMismatch
+    )]
+    pub sender_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakeConfig<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `set_stake_config` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", mint.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    // Holds the staked tokens. Owned by the reward pool PDA.
+    #[account(
+        init_if_needed,
+        payer = super_admin,
+        token::mint = mint,
+        token::authority = reward_pool,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.stake_vault_bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    // Created on the staker's first stake in this mint.
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [b"stake", reward_pool.mint.as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = staker_token_account.mint == reward_pool.mint @ CustomError::MintMismatch
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        constraint = voting_checkpoints.token_account == staker_token_account.key() @ CustomError::CheckpointAccountMismatch
+    )]
+    pub voting_checkpoints: Option<Account<'info, VotingCheckpoints>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakeRewards<'info> {
+    pub staker: Signer<'info>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = staker @ CustomError::Unauthorized,
+        seeds = [b"stake", reward_pool.mint.as_ref(), staker.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = staker_token_account.mint == reward_pool.mint @ CustomError::MintMismatch
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        has_one = staker @ CustomError::Unauthorized,
+        seeds = [b"stake", reward_pool.mint.as_ref(), staker.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    // Numbered by the position's request count.
+    #[account(
+        init,
+        payer = staker,
+        space = 8 + UnstakeRequest::INIT_SPACE,
+        seeds = [b"unstake", position.key().as_ref(), &position.request_count.to_le_bytes()],
+        bump
+    )]
+    pub unstake_request: Account<'info, UnstakeRequest>,
+
+    /// CHECK: The position's SlashHold PDA; it must not exist.
+    #[account(
+        seeds = [b"slash_hold", position.key().as_ref()],
+        bump
+    )]
+    pub slash_hold: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteUnstake<'info> {
+    // Receives the request's rent.
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        seeds = [b"reward_pool", unstake_request.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.stake_vault_bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = staker,
+        has_one = staker @ CustomError::Unauthorized,
+        seeds = [b"unstake", unstake_request.position.as_ref(), &unstake_request.index.to_le_bytes()],
+        bump = unstake_request.bump
+    )]
+    pub unstake_request: Account<'info, UnstakeRequest>,
+
+    #[account(
+        mut,
+        address = unstake_request.position
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = staker_token_account.mint == unstake_request.mint @ CustomError::MintMismatch
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(
+        mut,
+        constraint = voting_checkpoints.token_account == staker_token_account.key() @ CustomError::CheckpointAccountMismatch
+    )]
+    pub voting_checkpoints: Option<Account<'info, VotingCheckpoints>>,
+}
+
+// CompleteUnstake, plus what the penalty needs.
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    // Receives the request's rent.
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    // Burned from when the pool burns penalties.
+    #[account(
+        mut,
+        address = unstake_request.mint @ CustomError::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"reward_pool", unstake_request.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.stake_vault_bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = staker,
+        has_one = staker @ CustomError::Unauthorized,
+        seeds = [b"unstake", unstake_request.position.as_ref(), &unstake_request.index.to_le_bytes()],
+        bump = unstake_request.bump
+    )]
+    pub unstake_request: Account<'info, UnstakeRequest>,
+
+    #[account(
+        mut,
+        address = unstake_request.position
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = staker_token_account.mint == unstake_request.mint @ CustomError::MintMismatch
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The `[b"treasury"]` PDA, see Donate. Only its address is used.
+    #[account(
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    // Receives the penalty unless the pool burns it.
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == unstake_request.mint @ CustomError::MintMismatch,
+        constraint = treasury_token_account.owner == treasury.key() @ CustomError::InvalidTreasuryTokenAccount
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(
+        mut,
+        constraint = voting_checkpoints.token_account == staker_token_account.key() @ CustomError::CheckpointAccountMismatch
+    )]
+    pub voting_checkpoints: Option<Account<'info, VotingCheckpoints>>,
+}
+
+#[derive(Accounts)]
+#[instruction(staker: Pubkey)]
+pub struct SlashStake<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `slash_stake` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.stake_vault_bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", reward_pool.mint.as_ref(), staker.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    // Left by queue_slash_stake for this action. Released on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"slash_hold", position.key().as_ref()],
+        bump = slash_hold.bump,
+        constraint = slash_hold.action_id == admin_action.action_id @ CustomError::TimelockActionMismatch
+    )]
+    pub slash_hold: Account<'info, SlashHold>,
+
+    /// CHECK: The staker's wallet. Only receives the position's rent if it is closed.
+    #[account(
+        mut,
+        address = staker
+    )]
+    pub staker_wallet: UncheckedAccount<'info>,
+
+    // Receives the unclaimed rewards if the position is closed.
+    #[account(
+        mut,
+        constraint = staker_token_account.mint == reward_pool.mint @ CustomError::MintMismatch,
+        constraint = staker_token_account.owner == staker @ CustomError::Unauthorized
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The `[b"treasury"]` PDA, see Donate. Only its address is used.
+    #[account(
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    // Receives the slashed tokens.
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == reward_pool.mint @ CustomError::MintMismatch,
+        constraint = treasury_token_account.owner == treasury.key() @ CustomError::InvalidTreasuryTokenAccount
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SelfCheckpoint<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        constraint = token_account.owner == holder.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    // Created on the holder's first checkpoint in this mint.
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + VotingCheckpoints::INIT_SPACE,
+        seeds = [b"voting_checkpoints", token_account.mint.as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub voting_checkpoints: Account<'info, VotingCheckpoints>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetVotesAtSlot<'info> {
+    #[account(
+        seeds = [b"voting_checkpoints", voting_checkpoints.mint.as_ref(), voting_checkpoints.holder.as_ref()],
+        bump = voting_checkpoints.bump
+    )]
+    pub voting_checkpoints: Account<'info, VotingCheckpoints>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileStats<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        constraint = token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ CustomError::Unauthorized,
+        has_one = token_account,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump = delegation_record.bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+
+    /// CHECK: The record's owner, who gets its rent back.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateDelegationRecord<'info> {
+    /// CHECK: Any DelegationRecord; it may not deserialize until migrated.
+    /// Checked by hand in the handler.
+    #[account(mut)]
+    pub delegation_record: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct OfferDelegation<'info> {
+    // Same checks as CreateDelegation.
+    #[account(
+        mut,
+        constraint = *token_account.to_account_info().owner == token_program.key() @ CustomError::TokenProgramMismatch,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    // Pays the offer's rent.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    // One pending offer per token account, like the SPL delegate itself.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DelegationOffer::INIT_SPACE,
+        seeds = [b"delegation_offer", token_account.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, DelegationOffer>,
+
+    /// CHECK: The token account's DelegationRecord address. Approved as
+    /// delegate; it must not exist yet.
+    #[account(
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation_record: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's MintConfig, see DelegateTokens.
+    #[account(
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    /// CHECK: The ApprovedDelegate PDA, see DelegateTokens.
+    #[account(
+        seeds = [b"approved_delegate", token_account.mint.as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub approved_delegate: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptDelegation<'info> {
+    // Pays the record's rent and the delegation fee, if the mint charges one.
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    /// CHECK: The offer's owner. Gets the offer's rent back.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = delegate @ CustomError::Unauthorized,
+        has_one = owner,
+        has_one = token_account,
+        seeds = [b"delegation_offer", token_account.key().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, DelegationOffer>,
+
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    #[account(
+        init,
+        payer = delegate,
+        space = 8 + DelegationRecord::INIT_SPACE,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+
+    /// CHECK: The mint's MintConfig, see DelegateTokens.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    /// CHECK: Must be `mint_config.fee_recipient` when a fee is due.
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOffer<'info> {
+    // Gets the offer's rent back.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ CustomError::Unauthorized,
+        has_one = token_account,
+        seeds = [b"delegation_offer", token_account.key().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, DelegationOffer>,
+
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// CHECK: The address the offer approved. Only compared against.
+    #[account(
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation_record: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredOffer<'info> {
+    // Revokes the offer's approval too when it is the owner.
+    pub caller: Signer<'info>,
+
+    /// CHECK: The offer's owner. Gets the offer's rent back.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = token_account,
+        seeds = [b"delegation_offer", token_account.key().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, DelegationOffer>,
+
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// CHECK: The address the offer approved. Only compared against.
+    #[account(
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation_record: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct CreateCertificateMint<'info> {
+    // Only the super_admin issues certificates. Pays for every account.
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // A fresh keypair. Created and initialized in the handler, since its
+    // metadata extensions need setting up before the mint itself. Its mint
+    // authority is removed before the instruction returns.
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    /// CHECK: PDA that mints the single token, then gives the authority up.
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Our program's authority PDA; stays the freeze authority.
+    #[account(
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + MintConfig::INIT_SPACE,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + Certificate::INIT_SPACE,
+        seeds = [b"certificate", mint.key().as_ref()],
+        bump
+    )]
+    pub certificate: Account<'info, Certificate>,
+
+    /// CHECK: The `recipient` argument's wallet; only its address is used.
+    #[account(address = recipient)]
+    pub recipient_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: The recipient's associated token account, created in the
+    /// handler once the mint exists. The associated token program checks
+    /// the address.
+    #[account(mut)]
+    pub recipient_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeCertificate<'info> {
+    // Pays for the FreezeRecord.
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"certificate", mint.key().as_ref()],
+        bump = certificate.bump
+    )]
+    pub certificate: Account<'info, Certificate>,
+
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    // Whichever account holds the certificate now, not necessarily the
+    // recipient's.
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = holder_token_account.amount == 1 @ CustomError::CertificateNotHeld
+    )]
+    pub holder_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [b"freeze_record", holder_token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    /// CHECK: Our freeze authority PDA, whichever version
+    /// `mint_config.authority_version` says. The CPI fails on any other.
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTokenMintsBatch<'info> {
+    // Pays for every mint and MintConfig.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Our program's authority PDA, made each mint's freeze authority.
+    #[account(
+        seeds = [b"authority"],
+        bump
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetFreezerBondConfig<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct PostFreezerBond<'info> {
+    #[account(mut)]
+    pub freezer: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = freezer,
+        space = 8 + FreezerBond::INIT_SPACE,
+        seeds = [b"freezer_bond", mint.key().as_ref(), freezer.key().as_ref()],
+        bump
+    )]
+    pub freezer_bond: Account<'info, FreezerBond>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFreezerBond<'info> {
+    #[account(mut)]
+    pub freezer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = freezer,
+        has_one = freezer @ CustomError::Unauthorized,
+        seeds = [b"freezer_bond", freezer_bond.mint.as_ref(), freezer.key().as_ref()],
+        bump = freezer_bond.bump
+    )]
+    pub freezer_bond: Account<'info, FreezerBond>,
+}
+
+#[derive(Accounts)]
+pub struct RequestThaw<'info> {
+    // Pays the request's rent, and gets it back when it's decided.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    // Only accounts this program froze can be appealed.
+    #[account(
+        seeds = [b"freeze_record", token_account.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ThawRequest::INIT_SPACE,
+        seeds = [b"thaw_request", token_account.key().as_ref()],
+        bump
+    )]
+    pub thaw_request: Account<'info, ThawRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveThawRequest<'info> {
+    // The mint authority. Pays for the account history and treasury if they're new.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [b"thaw_request", token_account_to_process.key().as_ref()],
+        bump = thaw_request.bump
+    )]
+    pub thaw_request: Account<'info, ThawRequest>,
+
+    /// CHECK: The request's owner. Gets its rent back, and its share of a
+    /// slashed bond.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = key_matches("token program", *token_account_to_process.to_account_info().owner, token_program.key())
+            @ CustomError::TokenProgramMismatch
+    )]
+    pub token_account_to_process: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = key_matches("mint", mint.key(), token_account_to_process.mint) @ CustomError::MintMismatch,
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"freeze_record", token_account_to_process.key().as_ref()],
+        bump = freeze_record.bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    // Gets the freeze record's rent back if it paid it, see `close_freeze_record`.
+    #[account(
+        mut,
+        seeds = [b"rent_payer"],
+        bump = rent_payer.bump
+    )]
+    pub rent_payer: Option<Box<Account<'info, RentPayer>>>,
+
+    /// CHECK: Whoever froze the account, and paid the freeze record's rent
+    /// unless the RentPayer did.
+    #[account(
+        mut,
+        address = freeze_record.actor
+    )]
+    pub freeze_actor: UncheckedAccount<'info>,
+
+    /// CHECK: The FreezerBond of whoever froze the account, which may not
+    /// exist; see `record_bond`.
+    #[account(mut)]
+    pub freezer_bond: UncheckedAccount<'info>,
+
+    // Created on the token account's first logged freeze or thaw.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + AccountHistory::INIT_SPACE,
+        seeds = [b"account_history", token_account_to_process.key().as_ref()],
+        bump
+    )]
+    pub account_history: Box<Account<'info, AccountHistory>>,
+
+    // Gets what the owner doesn't of a slashed bond.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: The mint's current freeze authority, see FreezeTokenAccount.
+    #[account(
+        constraint = authority_matches("program authority", mint.freeze_authority, program_authority.key())
+            @ CustomError::WrongFreezeAuthority
+    )]
+    pub program_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DenyThawRequest<'info> {
+    // The mint authority.
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = mint,
+        has_one = owner,
+        seeds = [b"thaw_request", thaw_request.token_account.as_ref()],
+        bump = thaw_request.bump
+    )]
+    pub thaw_request: Account<'info, ThawRequest>,
+
+    /// CHECK: The request's owner. Gets its rent back.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, reference: [u8; 32])]
+pub struct TransferWithReference<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        constraint = from.mint == mint.key() @ CustomError::MintMismatch,
+        constraint = from.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = to.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub to: Account<'info, TokenAccount>,
+
+    // As in TransferTokens.
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub fee_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PaymentRecord::INIT_SPACE,
+        seeds = [b"payment", reference.as_ref()],
+        bump
+    )]
+    pub payment_record: Account<'info, PaymentRecord>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(reference: [u8; 32])]
+pub struct GetPaymentRecord<'info> {
+    #[account(
+        seeds = [b"payment", reference.as_ref()],
+        bump = payment_record.bump
+    )]
+    pub payment_record: Account<'info, PaymentRecord>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePoolVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub underlying_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PoolVault::INIT_SPACE,
+        seeds = [b"pool_vault", underlying_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_vault: Account<'info, PoolVault>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = underlying_mint.decimals,
+        mint::authority = pool_vault,
+        seeds = [b"pool_share_mint", pool_vault.key().as_ref()],
+        bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    // Custody for the pooled tokens. Owned by the pool PDA.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = underlying_mint,
+        token::authority = pool_vault,
+        seeds = [b"pool_vault_tokens", pool_vault.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    // Holds the first deposit's DEAD_SHARES. Nothing ever moves them out.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = share_mint,
+        token::authority = pool_vault,
+        seeds = [b"pool_dead_shares", pool_vault.key().as_ref()],
+        bump
+    )]
+    pub dead_shares: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Shared by deposit_for_shares and redeem_shares.
+#[derive(Accounts)]
+pub struct PoolShares<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        has_one = share_mint,
+        seeds = [b"pool_vault", pool_vault.underlying_mint.as_ref()],
+        bump = pool_vault.bump
+    )]
+    pub pool_vault: Account<'info, PoolVault>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_vault_tokens", pool_vault.key().as_ref()],
+        bump = pool_vault.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_dead_shares", pool_vault.key().as_ref()],
+        bump = pool_vault.dead_shares_bump
+    )]
+    pub dead_shares: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_underlying.mint == pool_vault.underlying_mint @ CustomError::MintMismatch
+    )]
+    pub user_underlying: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_shares.mint == pool_vault.share_mint @ CustomError::MintMismatch
+    )]
+    pub user_shares: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetCompoundBounty<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `set_compound_bounty` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", mint.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    pub staker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = staker @ CustomError::Unauthorized,
+        seeds = [b"stake", position.mint.as_ref(), staker.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundPosition<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.vault_bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.stake_vault_bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", reward_pool.mint.as_ref(), position.staker.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    // Receives the bounty.
+    #[account(
+        mut,
+        constraint = cranker_token_account.mint == reward_pool.mint @ CustomError::MintMismatch
+    )]
+    pub cranker_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateToProgram<'info> {
+    // Same checks as DelegateTokens.
+    #[account(
+        mut,
+        constraint = *token_account.to_account_info().owner == token_program.key() @ CustomError::TokenProgramMismatch,
+        constraint = token_account.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// CHECK: Checked in the handler to be the PDA of `target_program` for the given seeds.
+    pub delegate: UncheckedAccount<'info>,
+
+    /// CHECK: The program `delegate` belongs to. Only its executable flag is read.
+    #[account(
+        constraint = target_program.executable @ CustomError::TargetNotExecutable
+    )]
+    pub target_program: UncheckedAccount<'info>,
+
+    // Pays the record's rent and the delegation fee, if the mint charges one.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DelegationRecord::INIT_SPACE,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation_record: Account<'info, DelegationRecord>,
+
+    /// CHECK: The mint's MintConfig, see DelegateTokens.
+    #[account(
+        mut,
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    /// CHECK: Must be `mint_config.fee_recipient` when a fee is due.
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The ApprovedDelegate PDA, see DelegateTokens.
+    #[account(
+        seeds = [b"approved_delegate", token_account.mint.as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub approved_delegate: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeMintConfig<'info> {
+    // The mint authority. Pays the rent for the growth.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The mint's MintConfig, possibly too short for `Account` to
+    /// load. The seeds and owner pin it down; the handler checks the
+    /// discriminator.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(staker: Pubkey, amount: u64, reason: u8)]
+pub struct QueueSlashStake<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"stake", reward_pool.mint.as_ref(), staker.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    // The `slash_stake` action for these arguments, as queue_admin_action
+    // would create it.
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [b"admin_action", slash_action_id(&reward_pool.mint, &staker, amount, reason).as_ref()],
+        bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    #[account(
+        init,
+        payer = super_admin,
+        space = 8 + SlashHold::INIT_SPACE,
+        seeds = [b"slash_hold", position.key().as_ref()],
+        bump
+    )]
+    pub slash_hold: Account<'info, SlashHold>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSlashStake<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"slash_hold", slash_hold.position.as_ref()],
+        bump = slash_hold.bump
+    )]
+    pub slash_hold: Account<'info, SlashHold>,
+
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", slash_hold.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Option<Account<'info, AdminAction>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBridge<'info> {
+    // The mint authority. Pays for the bridge accounts.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + BridgeConfig::INIT_SPACE,
+        seeds = [b"bridge", mint.key().as_ref()],
+        bump
+    )]
+    pub bridge: Account<'info, BridgeConfig>,
+
+    // Custody for locked tokens. Owned by the bridge PDA.
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = bridge,
+        seeds = [b"bridge_vault", bridge.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct BridgeLock<'info> {
+    pub sender: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge", bridge.mint.as_ref()],
+        bump = bridge.bump
+    )]
+    pub bridge: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_vault", bridge.key().as_ref()],
+        bump = bridge.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = sender_token_account.mint == bridge.mint @ CustomError::MintMismatch
+    )]
+    pub sender_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, source_nonce: u64, recipient: Pubkey)]
+pub struct BridgeUnlock<'info> {
+    // Pays for the ProcessedNonce record.
+    #[account(mut)]
+    pub bridge_operator: Signer<'info>,
+
+    #[account(
+        has_one = bridge_operator @ CustomError::Unauthorized,
+        seeds = [b"bridge", bridge.mint.as_ref()],
+        bump = bridge.bump
+    )]
+    pub bridge: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_vault", bridge.key().as_ref()],
+        bump = bridge.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == bridge.mint @ CustomError::MintMismatch,
+        constraint = recipient_token_account.owner == recipient @ CustomError::NotTokenAccountOwner
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = bridge_operator,
+        space = 8 + ProcessedNonce::INIT_SPACE,
+        seeds = [b"processed_nonce", bridge.key().as_ref(), &source_nonce.to_le_bytes()],
+        bump
+    )]
+    pub processed_nonce: Account<'info, ProcessedNonce>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWrapper<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub underlying_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Wrapper::INIT_SPACE,
+        seeds = [b"wrapper", underlying_mint.key().as_ref()],
+        bump
+    )]
+    pub wrapper: Account<'info, Wrapper>,
+
+    // Same decimals as the underlying, so amounts map 1:1. Only the wrapper PDA can mint.
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = underlying_mint.decimals,
+        mint::authority = wrapper,
+        seeds = [b"wrapped_mint", wrapper.key().as_ref()],
+        bump
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    // Custody for the underlying tokens. Owned by the wrapper PDA.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = underlying_mint,
+        token::authority = wrapper,
+        seeds = [b"wrapper_vault", wrapper.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Shared by wrap and unwrap.
+#[derive(Accounts)]
+pub struct Wrap<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        has_one = wrapped_mint,
+        seeds = [b"wrapper", wrapper.underlying_mint.as_ref()],
+        bump = wrapper.bump
+    )]
+    pub wrapper: Account<'info, Wrapper>,
+
+    #[account(mut)]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"wrapper_vault", wrapper.key().as_ref()],
+        bump = wrapper.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_underlying.mint == wrapper.underlying_mint @ CustomError::MintMismatch
+    )]
+    pub user_underlying: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_wrapped.mint == wrapper.wrapped_mint @ CustomError::MintMismatch
+    )]
+    pub user_wrapped: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WrapperBacking<'info> {
+    #[account(
+        has_one = wrapped_mint,
+        seeds = [b"wrapper", wrapper.underlying_mint.as_ref()],
+        bump = wrapper.bump
+    )]
+    pub wrapper: Account<'info, Wrapper>,
+
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"wrapper_vault", wrapper.key().as_ref()],
+        bump = wrapper.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMigration<'info> {
+    // The old mint's authority. Pays for the migration accounts.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", old_mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub old_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub new_mint: Account<'info, Mint>,
+
+    /// CHECK: The PDA that mints new tokens for the migration.
+    #[account(
+        seeds = [b"mint_authority", new_mint.key().as_ref()],
+        bump
+    )]
+    pub new_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Migration::INIT_SPACE,
+        seeds = [b"migration", old_mint.key().as_ref(), new_mint.key().as_ref()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    // Where old tokens go when `vault_old_tokens` is set. Owned by the migration PDA.
+    #[account(
+        init,
+        payer = admin,
+        token::mint = old_mint,
+        token::authority = migration,
+        seeds = [b"migration_vault", migration.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTokens<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = old_mint,
+        has_one = new_mint,
+        seeds = [b"migration", old_mint.key().as_ref(), new_mint.key().as_ref()],
+        bump = migration.bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    #[account(mut)]
+    pub old_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub new_mint: Account<'info, Mint>,
+
+    /// CHECK: The new mint's authority PDA, signs the mint.
+    #[account(
+        seeds = [b"mint_authority", new_mint.key().as_ref()],
+        bump = migration.new_mint_authority_bump
+    )]
+    pub new_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"migration_vault", migration.key().as_ref()],
+        bump = migration.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = holder_old.mint == old_mint.key() @ CustomError::MintMismatch
+    )]
+    pub holder_old: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = holder_new.mint == new_mint.key() @ CustomError::MintMismatch
+    )]
+    pub holder_new: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeMigration<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ CustomError::Unauthorized,
+        seeds = [b"migration", migration.old_mint.as_ref(), migration.new_mint.as_ref()],
+        bump = migration.bump
+    )]
+    pub migration: Account<'info, Migration>,
+}
+
+#[derive(Accounts)]
+pub struct SetRebaseFactor<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = super_admin @ CustomError::NotSuperAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    // The queued `set_rebase_factor` action for these arguments. Consumed on success.
+    #[account(
+        mut,
+        close = super_admin,
+        seeds = [b"admin_action", admin_action.action_id.as_ref()],
+        bump = admin_action.bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct GetEffectiveBalance<'info> {
+    #[account(
+        seeds = [b"mint_config", token_account.mint.as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    pub token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        mut,
+        constraint = from.mint == mint.key() @ CustomError::MintMismatch,
+        constraint = from.owner == owner.key() @ CustomError::NotTokenAccountOwner
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = to.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub to: Account<'info, TokenAccount>,
+
+    // Receives the transfer tax. Must belong to `mint_config.fee_recipient`
+    // when a tax is due.
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == mint.key() @ CustomError::MintMismatch
+    )]
+    pub fee_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(
+        mut,
+        constraint = from_checkpoints.token_account == from.key() @ CustomError::CheckpointAccountMismatch
+    )]
+    pub from_checkpoints: Option<Account<'info, VotingCheckpoints>>,
+
+    #[account(
+        mut,
+        constraint = to_checkpoints.token_account == to.key() @ CustomError::CheckpointAccountMismatch
+    )]
+    pub to_checkpoints: Option<Account<'info, VotingCheckpoints>>,
+}
+
+#[derive(Accounts)]
+pub struct GetAccountHistory<'info> {
+    #[account(
+        seeds = [b"account_history", account_history.token_account.as_ref()],
+        bump = account_history.bump
+    )]
+    pub account_history: Account<'info, AccountHistory>,
+}
+
+#[derive(Accounts)]
+pub struct GetReferralStats<'info> {
+    #[account(
+        seeds = [b"referral", referral_stats.referrer.as_ref()],
+        bump = referral_stats.bump
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTier<'info> {
+    // The mint authority. Becomes the fee recipient.
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = authority_matches("admin", mint.mint_authority, admin.key()) @ CustomError::Unauthorized
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+// ====== State ======
+
+// Global, program-wide settings. There is exactly one of these.
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub super_admin: Pubkey,
+    pub bump: u8,
+    // Seconds a queued AdminAction must wait before it can be executed.
+    pub timelock_delay: i64,
+    // Share of reclaimed rent paid to the gc_records caller, in basis points.
+    pub gc_reward_bps: u16,
+}
+
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+// Most records gc_records will close in one call.
+pub const MAX_GC_RECORDS: usize = 10;
+
+// Freeze authority seed schemes, recorded in `MintConfig::authority_version`.
+pub const AUTHORITY_VERSION_V1: u8 = 1; // [b"authority"]
+pub const AUTHORITY_VERSION_V2: u8 = 2; // [b"authority_v2", mint]
+
+// One mint of create_token_mints_batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateMintParams {
+    pub decimals: u8,
+    pub mint_authority: Pubkey,
+}
+
+// Most mints create_token_mints_batch creates in one call. What limits it is
+// the 1232-byte legacy transaction: the fee payer's signature, the fixed
+// accounts, blockhash and instruction overhead take about 280 bytes, and each
+// mint adds about 163 (its signature and key, its MintConfig key, their
+// indices and its params). Six mints don't fit. Compute is no constraint at
+// this size.
+pub const MAX_BATCH_MINTS: usize = 5;
+
+// Per-mint settings, created alongside the mint.
+#[account]
+#[derive(InitSpace)]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    // Canonical bump of the `[b"authority"]` PDA.
+    pub authority_bump: u8,
+    // Canonical bump of this account.
+    pub bump: u8,
+    // Which PDA currently holds the freeze authority (AUTHORITY_VERSION_*).
+    pub authority_version: u8,
+    // Canonical bump of the `[b"authority_v2", mint]` PDA, set on rotation.
+    pub authority_v2_bump: u8,
+    // Number of token accounts currently frozen through this program.
+    pub frozen_account_count: u64,
+    // Sum of their balances at the time they were frozen.
+    pub frozen_supply: u64,
+    // Seconds a recovery request stays open to dispute before it can be executed.
+    pub recovery_window: i64,
+    // Merkle root of owners who may thaw their own accounts via thaw_with_proof.
+    pub whitelist_root: Option<[u8; 32]>,
+    // When set, thaw_token_account also needs an ed25519 signature from approval_signer.
+    pub require_thaw_approval: bool,
+    pub approval_signer: Pubkey,
+    // How many slots old a thaw approval may be.
+    pub max_approval_age_slots: u64,
+    // Accounts each freezer (other than the mint authority) may freeze per UTC
+    // day, unless the super_admin co-signs.
+    pub freeze_quota_per_day: u16,
+    // Display multiplier, scaled by `rebase::REBASE_SCALE`. Never zero.
+    pub rebase_factor: u64,
+    // Zero until set_fee_tier is called, including for migrated mints.
+    pub fee_tier: FeeTier,
+    pub fee_recipient: Pubkey,
+    // Seconds freeze_token_account and thaw_token_account wait after a token
+    // account last changed state, unless the super_admin overrides.
+    pub toggle_cooldown_seconds: i64,
+    // Token accounts of this mint with a non-zero balance, as far as the
+    // program has seen: mint_tokens, distribute and transfer_tokens keep it up
+    // to date. Tokens moved directly through the token program aren't counted.
+    pub holder_count: u64,
+    // When set, those paths refuse to add a holder once holder_count reaches it.
+    pub max_holders: Option<u16>,
+    // Program told about freezes and thaws, see `notify_hook`.
+    pub notification_hook: Option<Pubkey>,
+    // Seconds tokens issued by mint_locked stay frozen. Zero disables mint_locked.
+    pub holding_period_seconds: i64,
+    // Program thaw_with_validator asks before thawing, see `validate_thaw`.
+    pub thaw_validator: Option<Pubkey>,
+    // Seconds a burn receipt must be kept before its burner may close it.
+    pub burn_receipt_retention_seconds: i64,
+    // Bumped by every freeze, thaw, delegation, revoke and authority change of
+    // this mint, and carried in the matching event, so indexers can order
+    // them and spot gaps. Only ever counts up; see `next_sequence`.
+    pub sequence: u64,
+    // Set by set_transfer_policy, see permissioned_transfer.
+    pub permissioned_mode: bool,
+    pub transfers_paused: bool,
+    pub max_transfer_amount: Option<u64>,
+    // When set, delegations need an ApprovedDelegate entry, see `require_delegate_approved`.
+    pub restrict_delegates: bool,
+    pub stats: MintStats,
+    // Lamports a freezer (other than the mint authority) must have bonded to
+    // freeze. Zero means no bond is needed.
+    pub min_freezer_bond: u64,
+    // Share of a forfeited bond the wrongly frozen owner gets, see approve_thaw_request.
+    pub bond_slash_bps: u16,
+    // Canonical bump of the `[b"notifier", mint]` PDA, found by
+    // set_notification_hook. None for configs from before it was cached,
+    // which find it on every notification until the hook is next set.
+    pub notifier_bump: Option<u8>,
+}
+
+// Running totals for analytics. Each is updated by the instruction that makes
+// the change, so only token program operations made directly, outside this
+// program, can make them drift. The current frozen count and supply are
+// `MintConfig::frozen_account_count` and `frozen_supply`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct MintStats {
+    // DelegationRecords currently open.
+    pub active_delegations: u64,
+    pub lifetime_freezes: u64,
+    pub lifetime_thaws: u64,
+    // Made through delegate_tokens and create_delegation.
+    pub lifetime_delegations: u64,
+    // Revokes the program made.
+    pub lifetime_revocations: u64,
+    // Raw amount moved by transfer_tokens, permissioned_transfer and spend_delegated.
+    pub transfer_volume: u64,
+}
+
+// Fees a mint charges. Lamport fees go to `MintConfig::fee_recipient`, the
+// transfer tax to a token account it owns.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeTier {
+    // Lamports, charged by delegate_tokens and increase_delegation.
+    pub delegation_fee: u64,
+    // Lamports, charged by thaw_with_proof.
+    pub thaw_fee: u64,
+    // Share of each transfer_tokens amount.
+    pub transfer_tax_bps: u16,
+}
+
+pub const MAX_DELEGATION_FEE: u64 = 10_000_000;
+pub const MAX_THAW_FEE: u64 = 10_000_000;
+pub const MAX_TRANSFER_TAX_BPS: u16 = 1_000;
+
+// Dispute window for recovery requests on new mints: three days.
+pub const DEFAULT_RECOVERY_WINDOW: i64 = 3 * 24 * 60 * 60;
+
+impl MintConfig {
+    pub fn init(&mut self, mint: Pubkey, authority_bump: u8, bump: u8) {
+        self.mint = mint;
+        self.authority_bump = authority_bump;
+        self.bump = bump;
+        self.authority_version = AUTHORITY_VERSION_V1;
+        self.recovery_window = DEFAULT_RECOVERY_WINDOW;
+        self.rebase_factor = rebase::REBASE_SCALE;
+    }
+
+    // Signer seeds (including the bump) of the PDA that holds this mint's freeze authority.
+    pub fn authority_seeds<'a>(&'a self, mint: &'a Pubkey) -> Vec<&'a [u8]> {
+        match self.authority_version {
+            AUTHORITY_VERSION_V2 => vec![
+                b"authority_v2",
+                mint.as_ref(),
+                std::slice::from_ref(&self.authority_v2_bump),
+            ],
+            _ => vec![b"authority", std::slice::from_ref(&self.authority_bump)],
+        }
+    }
+
+    // The sequence number of the state change being made. The first one is 1.
+    // `init` leaves the counter alone. A mint deregistered and registered
+    // again gets a fresh MintConfig, which picks the counter up from its
+    // RetiredMint, so it is never reset.
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self.sequence.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        Ok(self.sequence)
+    }
+
+    // Bump of the notifier PDA, see `notifier_bump`.
+    pub fn notifier_bump(&self) -> u8 {
+        self.notifier_bump.unwrap_or_else(|| {
+            Pubkey::find_program_address(&[b"notifier", self.mint.as_ref()], &crate::ID).1
+        })
+    }
+
+    // Thaws other than permissioned_transfer's own would let a holder out of
+    // permissioned mode, so the instructions that thaw call this first.
+    pub fn require_not_permissioned(&self) -> Result<()> {
+        require!(!self.permissioned_mode, CustomError::PermissionedMode);
+        Ok(())
+    }
+
+    // Count a token account whose balance went from `before` to `after`.
+    pub fn track_holder(&mut self, before: u64, after: u64) -> Result<()> {
+        if before == 0 && after > 0 {
+            if let Some(max_holders) = self.max_holders {
+                require!(self.holder_count < max_holders as u64, CustomError::HolderLimitReached);
+            }
+            self.holder_count = self.holder_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        } else if before > 0 && after == 0 {
+            // Saturating: the account may have been funded outside the program.
+            self.holder_count = self.holder_count.saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    pub fn record_freeze(&mut self, amount: u64) -> Result<()> {
+        self.stats.lifetime_freezes = self.stats.lifetime_freezes.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        self.add_frozen(amount)
+    }
+
+    pub fn record_thaw(&mut self, amount: u64) -> Result<()> {
+        self.stats.lifetime_thaws = self.stats.lifetime_thaws.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        self.remove_frozen(amount)
+    }
+
+    // Count a frozen account in the tally without counting a freeze, for
+    // accounts found frozen (or found thawed, below) outside the program.
+    pub fn add_frozen(&mut self, amount: u64) -> Result<()> {
+        self.frozen_account_count = self
+            .frozen_account_count
+            .checked_add(1)
+            .ok_or(CustomError::MathOverflow)?;
+        self.frozen_supply = self
+            .frozen_supply
+            .checked_add(amount)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn remove_frozen(&mut self, amount: u64) -> Result<()> {
+        self.frozen_account_count = self
+            .frozen_account_count
+            .checked_sub(1)
+            .ok_or(CustomError::MathOverflow)?;
+        self.frozen_supply = self
+            .frozen_supply
+            .checked_sub(amount)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // `tracked` when the delegation has a DelegationRecord.
+    pub fn record_delegation(&mut self, tracked: bool) -> Result<()> {
+        let stats = &mut self.stats;
+        stats.lifetime_delegations = stats.lifetime_delegations.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        if tracked {
+            stats.active_delegations = stats.active_delegations.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_delegation_closed(&mut self) {
+        // Saturating: records opened before the counter existed were never counted.
+        self.stats.active_delegations = self.stats.active_delegations.saturating_sub(1);
+    }
+
+    pub fn record_revocation(&mut self) -> Result<()> {
+        self.stats.lifetime_revocations = self
+            .stats
+            .lifetime_revocations
+            .checked_add(1)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_transfer(&mut self, amount: u64) -> Result<()> {
+        self.stats.transfer_volume = self.stats.transfer_volume.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// Left behind by deregister_mint so the mint's sequence carries on from where
+// it stopped if the mint is registered again, see `resume_sequence`.
+#[account]
+#[derive(InitSpace)]
+pub struct RetiredMint {
+    pub mint: Pubkey,
+    // `MintConfig::sequence` when the mint was deregistered.
+    pub sequence: u64,
+    pub bump: u8,
+}
+
+// The `[b"holding_lock", token_account]` PDA: the account holds tokens from
+// mint_locked and stays frozen until `unlock_ts`.
+#[account]
+#[derive(InitSpace)]
+pub struct HoldingLock {
+    pub token_account: Pubkey,
+    pub rent_payer: Pubkey,
+    pub unlock_ts: i64,
+    pub bump: u8,
+}
+
+// One per token account frozen through this program. Closed on thaw.
+// The four keys come first, at fixed offsets; see `filters`.
+#[account]
+#[derive(InitSpace)]
+pub struct FreezeRecord {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    // Who froze it.
+    pub actor: Pubkey,
+    // Balance at freeze time. Frozen balances can't move, so thaw subtracts this.
+    pub amount: u64,
+    pub frozen_at: i64,
+    pub bump: u8,
+    // The RentPayer, if it paid this record's rent. Closing the record
+    // returns the rent there rather than to whoever froze the account.
+    pub rent_payer: Option<Pubkey>,
+    // Canonical bump of the FreezerBond this freeze was counted against, if any.
+    pub bond_bump: Option<u8>,
+}
+
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+// Most frozen accounts one FrozenExportPage can list.
+pub const MAX_EXPORT_PAGE_ENTRIES: usize = 20;
+
+// The `[b"export", mint, export_id]` header of an export of frozen accounts.
+// Auditors check a copy of the pages against `chain_hash`.
+#[account]
+#[derive(InitSpace)]
+pub struct FrozenExport {
+    pub mint: Pubkey,
+    pub export_id: u64,
+    // MintConfig::frozen_account_count when page 0 was written.
+    pub frozen_account_count: u64,
+    pub total_pages: u16,
+    pub total_accounts: u64,
+    // hash(previous chain_hash, page_hash) after each page, from all zeroes.
+    pub chain_hash: [u8; 32],
+    // Entries must be strictly ascending by token account across all pages.
+    pub last_token_account: Option<Pubkey>,
+    pub bump: u8,
+}
+
+// The `[b"export", mint, export_id, page]` page of an export.
+#[account]
+#[derive(InitSpace)]
+pub struct FrozenExportPage {
+    pub export: Pubkey,
+    pub page: u16,
+    #[max_len(MAX_EXPORT_PAGE_ENTRIES)]
+    pub entries: Vec<FrozenExportEntry>,
+    pub page_hash: [u8; 32],
+    // The header's chain_hash once this page was added.
+    pub chain_hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl FrozenExportPage {
+    // hash of each entry's token account followed by its amount, little-endian.
+    pub fn content_hash(entries: &[FrozenExportEntry]) -> [u8; 32] {
+        let amounts: Vec<[u8; 8]> = entries.iter().map(|entry| entry.amount.to_le_bytes()).collect();
+        let parts: Vec<&[u8]> = entries
+            .iter()
+            .zip(amounts.iter())
+            .flat_map(|(entry, amount)| [entry.token_account.as_ref(), amount.as_ref()])
+            .collect();
+        hashv(&parts).to_bytes()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct FrozenExportEntry {
+    pub token_account: Pubkey,
+    // Balance when it was frozen, from its FreezeRecord.
+    pub amount: u64,
+}
+
+// Exists while `freezer` holds the freezer role for `mint`, and counts how
+// much of today's quota it has used.
+#[account]
+#[derive(InitSpace)]
+pub struct FreezerStats {
+    pub mint: Pubkey,
+    pub freezer: Pubkey,
+    pub frozen_today: u16,
+    // Midnight UTC of the day `frozen_today` counts.
+    pub day_start_ts: i64,
+    pub bump: u8,
+    // Canonical bump of the freezer's FreezerBond PDA, posted or not.
+    pub bond_bump: u8,
+}
+
+impl FreezerStats {
+    // Use up `count` freezes of today's quota, starting a fresh count on a new day.
+    pub fn consume_quota(&mut self, count: u16, quota: u16, now: i64) -> Result<()> {
+        let day_start = now - now.rem_euclid(SECONDS_PER_DAY);
+        if day_start > self.day_start_ts {
+            self.day_start_ts = day_start;
+            self.frozen_today = 0;
+        }
+
+        let frozen_today = self
+            .frozen_today
+            .checked_add(count)
+            .ok_or(CustomError::MathOverflow)?;
+        require!(frozen_today <= quota, CustomError::FreezeQuotaExceeded);
+        self.frozen_today = frozen_today;
+        Ok(())
+    }
+}
+
+// The `[b"freezer_bond", mint, freezer]` PDA: lamports `freezer` has put up
+// against wrongful freezes, held in the account on top of its rent.
+#[account]
+#[derive(InitSpace)]
+pub struct FreezerBond {
+    pub mint: Pubkey,
+    pub freezer: Pubkey,
+    pub bonded: u64,
+    // Accounts it froze through freeze_token_account while bonded that are
+    // still frozen. The bond can't be withdrawn until this is back to zero.
+    pub open_freezes: u32,
+    pub bump: u8,
+}
+
+impl FreezerBond {
+    pub fn open_freeze(&mut self) -> Result<()> {
+        self.open_freezes = self.open_freezes.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Saturating, though only freezes open_freeze counted are closed; see
+    // `record_bond`.
+    pub fn close_freeze(&mut self) -> Result<()> {
+        self.open_freezes = self.open_freezes.saturating_sub(1);
+        Ok(())
+    }
+}
+
+// The `[b"thaw_request", token_account]` PDA: an owner's appeal against a
+// freeze, decided by approve_thaw_request or deny_thaw_request.
+#[account]
+#[derive(InitSpace)]
+pub struct ThawRequest {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    // The freeze record's actor when the request was made.
+    pub freezer: Pubkey,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+pub const MAX_REFERENCE_PAYMENTS: usize = 8;
+
+// The `[b"payment", reference]` PDA: what has been paid against one reference,
+// keeping the last MAX_REFERENCE_PAYMENTS payments, oldest first.
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentRecord {
+    pub reference: [u8; 32],
+    pub mint: Pubkey,
+    // The token account every payment went to.
+    pub payee: Pubkey,
+    pub allow_multiple: bool,
+    // Over every payment, including evicted ones. Net of transfer tax.
+    pub total_amount: u64,
+    pub payment_count: u32,
+    pub bump: u8,
+    #[max_len(MAX_REFERENCE_PAYMENTS)]
+    pub payments: Vec<Payment>,
+}
+
+impl PaymentRecord {
+    pub fn append(&mut self, payer: Pubkey, amount: u64, slot: u64) -> Result<()> {
+        if self.payments.len() == MAX_REFERENCE_PAYMENTS {
+            self.payments.remove(0);
+        }
+        self.payments.push(Payment { payer, amount, slot });
+        self.total_amount = self.total_amount.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        self.payment_count = self.payment_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Payment {
+    // The wallet that signed the transfer.
+    pub payer: Pubkey,
+    // What reached the payee.
+    pub amount: u64,
+    pub slot: u64,
+}
+
+// Tokens set aside for `delegate` by `owner`, held in the `[b"escrow_vault", escrow]` vault.
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    // What the delegate may still spend. Tokens sent straight to the vault
+    // are not counted; only the owner gets them back, on reclaim.
+    pub remaining: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+// Who can move the tokens a DelegationRecord covers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum DelegationMode {
+    // The SPL delegate is `delegate` itself. It can still spend directly
+    // through the token program, and those spends bypass the record.
+    Advisory,
+    // The SPL delegate is the record PDA, so spend_delegated is the only way
+    // to spend and the terms always hold.
+    ProgramEnforced,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct DelegationTerms {
+    // Approved with the token program, and the most spend_delegated will ever move.
+    pub allowance: u64,
+    pub mode: DelegationMode,
+    pub expires_at: Option<i64>,
+    // At most `period_limit` per `period_seconds`. A zero period means no per-period limit.
+    pub period_seconds: i64,
+    pub period_limit: u64,
+}
+
+// Most destinations a DelegationRecord can be limited to.
+pub const MAX_ALLOWED_DESTINATIONS: usize = 4;
+
+// The `[b"delegation", token_account]` PDA, open while `delegate` may spend
+// from `token_account` through spend_delegated. The four keys come first, at
+// the same offsets as FreezeRecord's; see `filters`.
+#[account]
+#[derive(InitSpace)]
+pub struct DelegationRecord {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub terms: DelegationTerms,
+    // Token accounts spend_delegated may pay. Empty means any.
+    #[max_len(MAX_ALLOWED_DESTINATIONS)]
+    pub allowed_destinations: Vec<Pubkey>,
+    pub spent_total: u64,
+    pub spent_this_period: u64,
+    // Start of the period `spent_this_period` counts. Periods follow on from
+    // the delegation's creation without gaps.
+    pub period_start_ts: i64,
+    pub bump: u8,
+    // The program `delegate` is a PDA of, when it was checked by delegate_to_program.
+    pub delegate_program: Option<Pubkey>,
+}
+
+// The `[b"delegation_offer", token_account]` PDA: an allowance offered to
+// `delegate`, closed when it accepts, the owner withdraws it, or anyone
+// cleans it up after `expires_at`. Keys first, as in DelegationRecord.
+#[account]
+#[derive(InitSpace)]
+pub struct DelegationOffer {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl DelegationRecord {
+    pub fn set_allowed_destinations(&mut self, allowed_destinations: Vec<Pubkey>) -> Result<()> {
+        require!(allowed_destinations.len() <= MAX_ALLOWED_DESTINATIONS, CustomError::BatchTooLarge);
+        allowed_destinations
+            .iter()
+            .try_for_each(validation::require_pubkey_not_default)?;
+        self.allowed_destinations = allowed_destinations;
+        Ok(())
+    }
+
+    pub fn require_destination_allowed(&self, destination: &Pubkey) -> Result<()> {
+        require!(
+            self.allowed_destinations.is_empty() || self.allowed_destinations.contains(destination),
+            CustomError::DestinationNotAllowed
+        );
+        Ok(())
+    }
+
+    // Check `amount` against the terms and count it as spent.
+    pub fn record_spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        if let Some(expires_at) = self.terms.expires_at {
+            require!(now < expires_at, CustomError::DelegationExpired);
+        }
+
+        let spent_total = self.spent_total.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        require!(spent_total <= self.terms.allowance, CustomError::DelegationAllowanceExceeded);
+
+        if self.terms.period_seconds > 0 {
+            let elapsed = now.saturating_sub(self.period_start_ts);
+            if elapsed >= self.terms.period_seconds {
+                self.period_start_ts += elapsed - elapsed % self.terms.period_seconds;
+                self.spent_this_period = 0;
+            }
+            let spent_this_period = self
+                .spent_this_period
+                .checked_add(amount)
+                .ok_or(CustomError::MathOverflow)?;
+            require!(spent_this_period <= self.terms.period_limit, CustomError::DelegationPeriodLimitExceeded);
+            self.spent_this_period = spent_this_period;
+        }
+
+        self.spent_total = spent_total;
+        Ok(())
+    }
+}
+
+// A user's program-owned vault for one mint. The PDA owns the
+// `[b"vault_tokens", vault]` token account.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultState {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub token_account_bump: u8,
+}
+
+impl VaultState {
+    pub fn signer_seeds(&self) -> [&[u8]; 4] {
+        [
+            b"vault",
+            self.mint.as_ref(),
+            self.owner.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+// Most recipients a payroll schedule can hold.
+pub const MAX_PAYROLL_RECIPIENTS: usize = 30;
+
+// A recurring payroll out of `vault`, cranked by execute_payroll.
+#[account]
+#[derive(InitSpace)]
+pub struct PayrollSchedule {
+    pub vault: Pubkey,
+    pub period_seconds: i64,
+    pub next_run_ts: i64,
+    #[max_len(MAX_PAYROLL_RECIPIENTS)]
+    pub recipients: Vec<Pubkey>,
+    #[max_len(MAX_PAYROLL_RECIPIENTS)]
+    pub amounts: Vec<u64>,
+    pub bump: u8,
+}
+
+impl PayrollSchedule {
+    // Validate and store the period and the (recipient, amount) pairs.
+    pub fn set_payments<'info>(
+        &mut self,
+        vault: &VaultState,
+        period_seconds: i64,
+        recipients: &'info [AccountInfo<'info>],
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        require!(period_seconds > 0, CustomError::InvalidDuration);
+        require!(amounts.len() <= MAX_PAYROLL_RECIPIENTS, CustomError::BatchTooLarge);
+        require!(amounts.len() == recipients.len(), CustomError::InvalidRemainingAccounts);
+        amounts.iter().try_for_each(|&amount| validation::require_nonzero_amount(amount))?;
+        for info in recipients {
+            let recipient = Account::<TokenAccount>::try_from(info)?;
+            require_keys_eq!(recipient.mint, vault.mint, CustomError::MintMismatch);
+        }
+
+        self.period_seconds = period_seconds;
+        self.recipients = recipients.iter().map(|info| info.key()).collect();
+        self.amounts = amounts;
+        Ok(())
+    }
+}
+
+pub const SECONDS_PER_YEAR: i64 = 365 * SECONDS_PER_DAY;
+
+// Upper bound on staking inflation: 10% a year.
+pub const MAX_INFLATION_RATE_BPS: u16 = 1_000;
+
+// Upper bound on the compound bounty: 5% of the rewards compounded.
+pub const MAX_COMPOUND_BOUNTY_BPS: u16 = 500;
+
+// Staking rewards for a mint, paid out of the `[b"reward_vault", pool]` vault.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub vault_bump: u8,
+    // Bump of the `[b"mint_authority", mint]` PDA.
+    pub mint_authority_bump: u8,
+    // Yearly issuance into the vault, as a share of supply.
+    pub inflation_rate_bps: u16,
+    // Least seconds between two inflation mints.
+    pub inflation_interval: i64,
+    pub last_inflation_ts: i64,
+    // Staking, set up by set_stake_config. Staked tokens sit in the
+    // `[b"stake_vault", pool]` vault; see `staking` for the accounting.
+    pub stake_vault_bump: u8,
+    // Tokens currently earning, i.e. staked and not requested for unstaking.
+    pub total_staked: u64,
+    pub reward_per_token: u128,
+    pub cooldown_seconds: i64,
+    pub penalty_bps: u16,
+    pub burn_penalty: bool,
+    // Share of the rewards compound_position pays its caller.
+    pub compound_bounty_bps: u16,
+}
+
+impl RewardPool {
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [b"reward_pool", self.mint.as_ref(), std::slice::from_ref(&self.bump)]
+    }
+}
+
+// The `[b"stake", mint, staker]` PDA: one staker's stake in a mint's pool.
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    // Earning stake. Tokens requested for unstaking are in their UnstakeRequest.
+    pub amount: u64,
+    // The pool's `reward_per_token` when this position last settled.
+    pub reward_per_token_paid: u128,
+    // Settled but not yet claimed.
+    pub pending_rewards: u64,
+    // UnstakeRequests ever made; numbers the next one.
+    pub request_count: u64,
+    pub bump: u8,
+    // UnstakeRequests not yet taken out. The position is only closed when
+    // there are none, so a new one never reuses a live request's number.
+    pub open_requests: u64,
+    // Whether compound_position may restake this position's rewards.
+    pub auto_compound: bool,
+}
+
+impl StakePosition {
+    // Move what the position earned since it last settled into `pending_rewards`.
+    // Call before anything changes `amount`.
+    pub fn settle(&mut self, reward_per_token: u128) -> Result<()> {
+        let earned = staking::earned(self.amount, reward_per_token, self.reward_per_token_paid)?;
+        self.pending_rewards = self.pending_rewards.checked_add(earned).ok_or(CustomError::MathOverflow)?;
+        self.reward_per_token_paid = reward_per_token;
+        Ok(())
+    }
+}
+
+// The `[b"unstake", position, index]` PDA: tokens cooling down after
+// request_unstake. Closed when they're taken out.
+#[account]
+#[derive(InitSpace)]
+pub struct UnstakeRequest {
+    pub position: Pubkey,
+    pub staker: Pubkey,
+    pub mint: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub unlocks_at: i64,
+    pub bump: u8,
+}
+
+// The `[b"slash_hold", position]` PDA: a slash of the position is queued, see
+// queue_slash_stake. Closed by slash_stake or cancel_slash_stake.
+#[account]
+#[derive(InitSpace)]
+pub struct SlashHold {
+    pub position: Pubkey,
+    pub action_id: [u8; 32],
+    pub bump: u8,
+}
+
+// How long a redemption may stay unfulfilled before the redeemer can get a refund.
+pub const REDEMPTION_EXPIRY: i64 = 30 * SECONDS_PER_DAY;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RedemptionStatus {
+    Pending,
+    Fulfilled,
+    Refunded,
+}
+
+// Links a burn to the off-chain order it pays for.
+#[account]
+#[derive(InitSpace)]
+pub struct RedemptionReceipt {
+    pub mint: Pubkey,
+    pub redeemer: Pubkey,
+    pub amount: u64,
+    pub order_id: [u8; 32],
+    // Slot of the burn.
+    pub slot: u64,
+    pub created_at: i64,
+    pub status: RedemptionStatus,
+    pub bump: u8,
+}
+
+// Proof that `burner` burned `amount` of `mint`, for other programs to check.
+// Never modified; see `pda` for its address.
+#[account]
+#[derive(InitSpace)]
+pub struct BurnReceipt {
+    pub burner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    // Slot of the burn.
+    pub slot: u64,
+    pub tag: [u8; 32],
+    // When the burner may close it, from the mint's retention period at the time.
+    pub closable_at: i64,
+    pub bump: u8,
+}
+
+// Bridge custody for a mint. Locked tokens sit in `[b"bridge_vault", bridge]`.
+#[account]
+#[derive(InitSpace)]
+pub struct BridgeConfig {
+    pub mint: Pubkey,
+    pub bridge_operator: Pubkey,
+    // Nonce given to the next bridge_lock.
+    pub next_nonce: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+// One per source-chain nonce already released by bridge_unlock.
+#[account]
+#[derive(InitSpace)]
+pub struct ProcessedNonce {
+    pub bridge: Pubkey,
+    pub nonce: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+// The `[b"pool_vault", underlying_mint]` PDA: a vault whose share mint it
+// alone can mint. Each share redeems an equal slice of the vault's balance.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolVault {
+    pub underlying_mint: Pubkey,
+    pub share_mint: Pubkey,
+    pub bump: u8,
+    pub share_mint_bump: u8,
+    pub vault_bump: u8,
+    pub dead_shares_bump: u8,
+}
+
+impl PoolVault {
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [b"pool_vault", self.underlying_mint.as_ref(), std::slice::from_ref(&self.bump)]
+    }
+}
+
+// Binds an underlying mint to its program-minted wrapped mint.
+#[account]
+#[derive(InitSpace)]
+pub struct Wrapper {
+    pub underlying_mint: Pubkey,
+    pub wrapped_mint: Pubkey,
+    pub bump: u8,
+    pub wrapped_mint_bump: u8,
+    pub vault_bump: u8,
+}
+
+pub const MAX_HISTORY_ENTRIES: usize = 16;
+
+// The last MAX_HISTORY_ENTRIES freezes and thaws of one token account, oldest first.
+#[account]
+#[derive(InitSpace)]
+pub struct AccountHistory {
+    pub token_account: Pubkey,
+    pub bump: u8,
+    // Every freeze and thaw ever logged, including evicted ones.
+    pub total_actions: u64,
+    // Unix time of the latest freeze or thaw.
+    pub last_state_change_ts: i64,
+    #[max_len(MAX_HISTORY_ENTRIES)]
+    pub entries: Vec<HistoryEntry>,
+    // Canonical bump of the token account's FreezeExempt PDA, once a freeze
+    // has looked for it.
+    pub freeze_exempt_bump: Option<u8>,
+}
+
+impl AccountHistory {
+    // Log an action, evicting the oldest entry when full. `bump` is only
+    // stored the first time, when init_if_needed has just created the account.
+    pub fn append(
+        &mut self,
+        token_account: Pubkey,
+        bump: u8,
+        action: HistoryAction,
+        actor: Pubkey,
+        reason: HistoryReason,
+        now: i64,
+    ) -> Result<()> {
+        if self.token_account == Pubkey::default() {
+            self.token_account = token_account;
+            self.bump = bump;
+        }
+        if self.entries.len() == MAX_HISTORY_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(HistoryEntry {
+            action,
+            actor,
+            slot: Clock::get()?.slot,
+            reason,
+        });
+        self.total_actions = self.total_actions.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        self.last_state_change_ts = now;
+        Ok(())
+    }
+
+    // The token account's FreezeExempt PDA. Its bump is searched for the first
+    // time and kept, so later freezes only pay for create_program_address.
+    pub fn freeze_exempt_address(&mut self) -> Result<Pubkey> {
+        let token_account = self.token_account;
+        match self.freeze_exempt_bump {
+            Some(bump) => Pubkey::create_program_address(
+                &[b"freeze_exempt", token_account.as_ref(), &[bump]],
+                &crate::ID,
+            )
+            .map_err(|_| error!(ErrorCode::ConstraintSeeds)),
+            None => {
+                let (address, bump) =
+                    Pubkey::find_program_address(&[b"freeze_exempt", token_account.as_ref()], &crate::ID);
+                self.freeze_exempt_bump = Some(bump);
+                Ok(address)
+            }
+        }
+    }
+
+    // Refuse a state change less than `cooldown` seconds after the last one.
+    pub fn require_cooldown_elapsed(&self, cooldown: i64, now: i64, overridden: bool) -> Result<()> {
+        if overridden || self.total_actions == 0 {
+            return Ok(());
+        }
+        let ready_at = self
+            .last_state_change_ts
+            .checked_add(cooldown)
+            .ok_or(CustomError::MathOverflow)?;
+        require!(now >= ready_at, CustomError::CooldownActive);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct HistoryEntry {
+    pub action: HistoryAction,
+    pub actor: Pubkey,
+    pub slot: u64,
+    pub reason: HistoryReason,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum HistoryAction {
+    Freeze,
+    Thaw,
+}
+
+// Which path performed the action.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum HistoryReason {
+    // freeze_token_account or thaw_token_account, signed by the mint authority.
+    MintAuthority,
+    // freeze_token_account, signed by a freezer under its daily quota.
+    Freezer,
+    // thaw_with_proof, against the mint's whitelist.
+    WhitelistProof,
+    // thaw_with_validator, approved by the mint's thaw validator.
+    Validator,
+    // freeze_if_flagged_delegate: approved to a flagged drainer.
+    DrainerProtection,
+    // enforce_sanction: the owner is sanctioned.
+    Sanction,
+    // approve_thaw_request: the mint authority upheld the owner's appeal.
+    ThawAppeal,
+}
+
+// Everything credited to one referrer.
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralStats {
+    pub referrer: Pubkey,
+    pub totals: ReferralTotals,
+    pub bump: u8,
+}
+
+// Returned by get_referral_stats.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct ReferralTotals {
+    // delegate_tokens calls and the amounts approved.
+    pub delegations: u64,
+    pub delegated_volume: u64,
+    // mint_tokens calls and the amounts minted.
+    pub mints: u64,
+    pub minted_volume: u64,
+}
+
+// Returned by wrapper_backing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Backing {
+    pub wrapped_supply: u64,
+    pub vault_balance: u64,
+}
+
+// A swap from `old_mint` to `new_mint` at a fixed ratio.
+#[account]
+#[derive(InitSpace)]
+pub struct Migration {
+    pub admin: Pubkey,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    // New tokens per old token = numerator / denominator.
+    pub numerator: u64,
+    pub denominator: u64,
+    // finalize_migration is allowed once this has passed.
+    pub deadline: i64,
+    // Keep old tokens in the migration vault instead of burning them.
+    pub vault_old_tokens: bool,
+    pub finalized: bool,
+    pub total_old_migrated: u64,
+    pub total_new_minted: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub new_mint_authority_bump: u8,
+}
+
+// A pending move of a token account's balance to a new wallet.
+// The PDA is also the account's delegate and close authority while pending.
+#[account]
+#[derive(InitSpace)]
+pub struct RecoveryRequest {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub initiated_at: i64,
+    pub bump: u8,
+}
+
+// A funded, claim-based airdrop.
+#[account]
+#[derive(InitSpace)]
+pub struct Airdrop {
+    pub mint: Pubkey,
+    pub funder: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    // Last unix timestamp at which claims are accepted.
+    pub claim_deadline: i64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+// Exists once `claimant` has claimed from `airdrop`.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimStatus {
+    pub airdrop: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+// Marks a token account that this program must never freeze.
+#[account]
+#[derive(InitSpace)]
+pub struct FreezeExempt {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub bump: u8,
+}
+
+// The `[b"certificate", mint]` PDA of a certificate mint: who it was issued
+// to and whether it has been revoked. Its name, symbol and URI are in the
+// mint's own token metadata.
+#[account]
+#[derive(InitSpace)]
+pub struct Certificate {
+    pub mint: Pubkey,
+    // Who it was issued to. The token itself may have moved since.
+    pub recipient: Pubkey,
+    pub issued_at: i64,
+    pub revoked_at: Option<i64>,
+    pub bump: u8,
+}
+
+// Program-specific display settings for a mint, beyond its Metaplex metadata.
+// Sized to its strings rather than their maximum, see `space`.
+#[account]
+pub struct DisplayConfig {
+    pub mint: Pubkey,
+    // Decimals wallets should show instead of the mint's.
+    pub ui_decimals: Option<u8>,
+    pub logo_uri: String,
+    pub project_url: String,
+    // Accent color, RGB.
+    pub color: [u8; 3],
+    pub bump: u8,
+}
+
+impl DisplayConfig {
+    // Account size, discriminator included, for these strings.
+    pub fn space(logo_uri: &str, project_url: &str) -> usize {
+        8 + 32 + (1 + 1) + (4 + logo_uri.len()) + (4 + project_url.len()) + 3 + 1
+    }
+}
+
+// The `[b"rent_payer"]` PDA: program-owned lamports that pay for freeze
+// records, and running totals of what went in and out.
+#[account]
+#[derive(InitSpace)]
+pub struct RentPayer {
+    pub total_funded: u64,
+    pub total_spent: u64,
+    pub bump: u8,
+}
+
+// The `[b"treasury"]` PDA donations go to. Holds the donated lamports;
+// donated tokens sit in token accounts it owns.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub donations: u64,
+    pub lamports_donated: u64,
+    pub bump: u8,
+}
+
+// Numbers a donor's DonationReceipts.
+#[account]
+#[derive(InitSpace)]
+pub struct DonorStats {
+    pub donor: Pubkey,
+    pub donation_count: u64,
+    pub bump: u8,
+}
+
+// One donation, at `[b"donation", donor, index (u64 LE)]`.
+#[account]
+#[derive(InitSpace)]
+pub struct DonationReceipt {
+    pub donor: Pubkey,
+    pub index: u64,
+    // None for a SOL-only donation.
+    pub mint: Option<Pubkey>,
+    pub token_amount: u64,
+    pub lamports: u64,
+    // Hash of an off-chain message from the donor.
+    pub memo_hash: [u8; 32],
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+// An owner a permissioned mint lets send and receive, see permissioned_transfer.
+#[account]
+#[derive(InitSpace)]
+pub struct ApprovedHolder {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+// A key a mint with `restrict_delegates` lets its holders delegate to.
+#[account]
+#[derive(InitSpace)]
+pub struct ApprovedDelegate {
+    pub mint: Pubkey,
+    pub delegate: Pubkey,
+    pub bump: u8,
+}
+
+// A sanctioned wallet. Its token accounts in every registered mint can be
+// frozen by anyone, see enforce_sanction.
+#[account]
+#[derive(InitSpace)]
+pub struct SanctionedOwner {
+    pub owner: Pubkey,
+    pub sanctioned_at: i64,
+    pub bump: u8,
+}
+
+// A payment stream from `sender` to `recipient`, see `stream`.
+#[account]
+#[derive(InitSpace)]
+pub struct Stream {
+    pub mint: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub deposit: u64,
+    // Everything the recipient has taken so far. Never above `deposit`.
+    pub withdrawn: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Stream {
+    pub fn accrued(&self, now: i64) -> Result<u64> {
+        stream::accrued(self.deposit, self.start_ts, self.end_ts, now)
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 5] {
+        [
+            b"stream",
+            self.mint.as_ref(),
+            self.sender.as_ref(),
+            self.recipient.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+pub const MAX_VOTING_CHECKPOINTS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct Checkpoint {
+    pub slot: u64,
+    pub balance: u64,
+}
+
+// The `[b"voting_checkpoints", mint, holder]` PDA: the holder's balance
+// history for vote weighting. See `checkpoints`.
+#[account]
+#[derive(InitSpace)]
+pub struct VotingCheckpoints {
+    pub mint: Pubkey,
+    pub holder: Pubkey,
+    // The token account whose balance is checkpointed, set on the first
+    // self_checkpoint.
+    pub token_account: Pubkey,
+    pub checkpoints: [Checkpoint; MAX_VOTING_CHECKPOINTS],
+    // Oldest checkpoint's index, and how many there are.
+    pub head: u8,
+    pub len: u8,
+    pub bump: u8,
+}
+
+impl VotingCheckpoints {
+    pub fn push(&mut self, slot: u64, balance: u64) {
+        checkpoints::push(&mut self.checkpoints, &mut self.head, &mut self.len, slot, balance);
+    }
+
+    pub fn balance_at(&self, slot: u64) -> Result<u64> {
+        checkpoints::balance_at(&self.checkpoints, self.head, self.len, slot)
+    }
+}
+
+// A delegate address known from wallet-drainer campaigns. Token accounts
+// approved to it can be frozen by anyone, see freeze_if_flagged_delegate.
+#[account]
+#[derive(InitSpace)]
+pub struct FlaggedDelegate {
+    pub delegate: Pubkey,
+    pub flagged_at: i64,
+    pub bump: u8,
+}
+
+// A privileged action announced ahead of time. Seeded by its action id.
+#[account]
+#[derive(InitSpace)]
+pub struct AdminAction {
+    pub action_id: [u8; 32],
+    // Earliest unix timestamp at which the action may be executed.
+    pub eta: i64,
+    pub bump: u8,
+}
+
+impl AdminAction {
+    // Check that this is the expected action and that its delay has elapsed.
+    pub fn require_ready(&self, expected_id: [u8; 32], now: i64) -> Result<()> {
+        require!(self.action_id == expected_id, CustomError::TimelockActionMismatch);
+        require!(now >= self.eta, CustomError::TimelockNotElapsed);
+        Ok(())
+    }
+}
+
+// sha256(tag || args...). Clients compute the same hash to queue an action.
+pub fn admin_action_id(tag: &[u8], args: &[&[u8]]) -> [u8; 32] {
+    let mut parts = Vec::with_capacity(args.len() + 1);
+    parts.push(tag);
+    parts.extend_from_slice(args);
+    hashv(&parts).to_bytes()
+}
+
+// The action id slash_stake checks for these arguments.
+pub fn slash_action_id(mint: &Pubkey, staker: &Pubkey, amount: u64, reason: u8) -> [u8; 32] {
+    admin_action_id(
+        b"slash_stake",
+        &[mint.as_ref(), staker.as_ref(), &amount.to_le_bytes(), &[reason]],
+    )
+}
+
+// ====== Events ======
+
+#[event]
+pub struct TokensDelegated {
+    pub token_account: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    pub referrer: Option<Pubkey>,
+    // None if the mint has no MintConfig.
+    pub sequence: Option<u64>,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub token_account: Pubkey,
+    pub delegate: Pubkey,
+    // None if the mint has no MintConfig.
+    pub sequence: Option<u64>,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub referrer: Option<Pubkey>,
+}
+
+#[event]
+pub struct MintDeregistered {
+    pub mint: Pubkey,
+    pub new_freeze_authority: Pubkey,
+    pub sequence: u64,
+}
+
+// `delegate` is None if the account had none to begin with.
+#[event]
+pub struct FrozenDelegateRevoked {
+    pub token_account: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct DelegatedSpend {
+    pub token_account: Pubkey,
+    pub delegate: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub spent_total: u64,
+}
+
+#[event]
+pub struct DistributionCompleted {
+    pub mint: Pubkey,
+    // Destinations that actually received tokens.
+    pub recipients: u32,
+    pub total_minted: u64,
+    // Frozen destinations passed over because `allow_skip` was set.
+    pub skipped: Vec<Pubkey>,
+}
+
+#[event]
+pub struct PayrollPaid {
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PayrollCompleted {
+    pub vault: Pubkey,
+    pub recipients: u32,
+    pub total: u64,
+}
+
+#[event]
+pub struct BridgeLocked {
+    pub mint: Pubkey,
+    pub sender: Pubkey,
+    pub amount: u64,
+    pub destination_chain: u16,
+    // Recipient address on the destination chain.
+    pub recipient: [u8; 32],
+    pub nonce: u64,
+}
+
+#[event]
+pub struct BridgeUnlocked {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub source_nonce: u64,
+}
+
+// `closed` if the stray account was drained and closed.
+#[event]
+pub struct TokensRescued {
+    pub stray_account: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub closed: bool,
+}
+
+// `amount` is zero if there was nothing above the rent-exempt minimum.
+#[event]
+pub struct LamportsRescued {
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DonationReceived {
+    pub donor: Pubkey,
+    // The receipt's index among the donor's donations.
+    pub index: u64,
+    pub mint: Option<Pubkey>,
+    pub token_amount: u64,
+    pub lamports: u64,
+    pub memo_hash: [u8; 32],
+}
+
+// `sanction` is the SanctionedOwner entry that triggered the freeze.
+#[event]
+pub struct SanctionEnforced {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub sanction: Pubkey,
+    pub sanctioned_at: i64,
+    pub actor: Pubkey,
+    pub sequence: u64,
+}
+
+// `rate_per_second` is rounded down; payouts follow `stream::accrued`.
+#[event]
+pub struct StreamOpened {
+    pub stream: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub deposit: u64,
+    pub rate_per_second: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+// `penalty` was burned if `burned`, else paid to the treasury.
+#[event]
+pub struct EmergencyUnstaked {
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub burned: bool,
+}
+
+// `requested` is what the slash asked for, `slashed` what was taken after
+// clamping to the earning stake. `rewards_paid` went to the staker if the
+// position was `closed`.
+#[event]
+pub struct StakeSlashed {
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    pub requested: u64,
+    pub slashed: u64,
+    pub remaining: u64,
+    pub reason: u8,
+    pub rewards_paid: u64,
+    pub closed: bool,
+    pub actor: Pubkey,
+    pub slashed_at: i64,
+}
+
+#[event]
+pub struct CertificateIssued {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub issuer: Pubkey,
+}
+
+#[event]
+pub struct FreezerBondSlashed {
+    pub mint: Pubkey,
+    pub freezer: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub to_owner: u64,
+    pub to_treasury: u64,
+}
+
+#[event]
+pub struct PaymentReferenced {
+    pub reference: [u8; 32],
+    pub mint: Pubkey,
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub amount: u64,
+    pub total_amount: u64,
+    pub payment_count: u32,
+}
+
+#[event]
+pub struct PermissionedTransferred {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub sequence: u64,
+}
+
+// `sequence` is the mint's `MintConfig::sequence` after the change.
+#[event]
+pub struct AccountFrozen {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub actor: Pubkey,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct AccountThawed {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub actor: Pubkey,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct ProgramAuthorityRotated {
+    pub mint: Pubkey,
+    pub program_authority: Pubkey,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct TransferPolicySet {
+    pub mint: Pubkey,
+    pub permissioned_mode: bool,
+    pub transfers_paused: bool,
+    pub max_transfer_amount: Option<u64>,
+    pub sequence: u64,
 }
 
 // ====== Custom Error ======
@@ -159,4 +11551,248 @@ pub struct FreezeOrThawAccount<'info> {
 pub enum CustomError {
     #[msg("Unauthorized: The signer is not the mint authority.")]
     Unauthorized,
+    #[msg("NotUpgradeAuthority: The signer is not the program's upgrade authority.")]
+    NotUpgradeAuthority,
+    #[msg("InvalidProgramData: The ProgramData account does not belong to this program.")]
+    InvalidProgramData,
+    #[msg("WrongFreezeAuthority: The mint's freeze authority is not the program authority PDA.")]
+    WrongFreezeAuthority,
+    #[msg("NotSuperAdmin: The signer is not the super_admin.")]
+    NotSuperAdmin,
+    #[msg("TimelockNotElapsed: The queued action's delay has not elapsed yet.")]
+    TimelockNotElapsed,
+    #[msg("TimelockActionMismatch: The queued action does not match this instruction.")]
+    TimelockActionMismatch,
+    #[msg("TimelockRequired: This change must be queued behind the timelock.")]
+    TimelockRequired,
+    #[msg("InvalidTimelockDelay: The timelock delay cannot be negative.")]
+    InvalidTimelockDelay,
+    #[msg("AuthorityAlreadyRotated: This mint already uses the v2 program authority.")]
+    AuthorityAlreadyRotated,
+    #[msg("MathOverflow: Arithmetic overflow.")]
+    MathOverflow,
+    #[msg("TokenProgramMismatch: The token account is not owned by the given token program.")]
+    TokenProgramMismatch,
+    #[msg("NotTokenAccountOwner: The signer does not own the token account.")]
+    NotTokenAccountOwner,
+    #[msg("SelfDelegation: The owner cannot delegate to themselves.")]
+    SelfDelegation,
+    #[msg("ZeroAmount: The amount must be greater than zero.")]
+    ZeroAmount,
+    #[msg("AccountNotFrozen: The token account is not frozen.")]
+    AccountNotFrozen,
+    #[msg("AccountExempt: The token account is exempt from freezing.")]
+    AccountExempt,
+    #[msg("DestinationFrozen: The destination token account is frozen.")]
+    DestinationFrozen,
+    #[msg("MintMismatch: The token account belongs to a different mint.")]
+    MintMismatch,
+    #[msg("InvalidRemainingAccounts: The remaining accounts don't match the instruction arguments.")]
+    InvalidRemainingAccounts,
+    #[msg("DelegateMismatch: The token account is delegated to a different account.")]
+    DelegateMismatch,
+    #[msg("InvalidBps: Basis points must be at most 10000.")]
+    InvalidBps,
+    #[msg("BatchTooLarge: Too many entries for a single instruction.")]
+    BatchTooLarge,
+    #[msg("InvalidRecordAccount: The account is not a collectable record, or its companion accounts are wrong.")]
+    InvalidRecordAccount,
+    #[msg("RecordStillLive: The record still describes live state and can't be closed.")]
+    RecordStillLive,
+    #[msg("InvalidDuration: Durations cannot be negative.")]
+    InvalidDuration,
+    #[msg("InvalidRecoveryTarget: The new owner must differ from the current owner.")]
+    InvalidRecoveryTarget,
+    #[msg("RecoveryWindowActive: The recovery dispute window has not elapsed yet.")]
+    RecoveryWindowActive,
+    #[msg("RecoveryWindowElapsed: The recovery dispute window has already elapsed.")]
+    RecoveryWindowElapsed,
+    #[msg("InvalidDeadline: The deadline must be in the future.")]
+    InvalidDeadline,
+    #[msg("InvalidProof: The merkle proof does not match the root.")]
+    InvalidProof,
+    #[msg("ClaimWindowClosed: The claim deadline has passed.")]
+    ClaimWindowClosed,
+    #[msg("ClaimWindowOpen: The claim deadline has not passed yet.")]
+    ClaimWindowOpen,
+    #[msg("WhitelistNotSet: This mint has no whitelist root.")]
+    WhitelistNotSet,
+    #[msg("MissingApprovalSignature: Expected an ed25519 approval instruction before this one.")]
+    MissingApprovalSignature,
+    #[msg("InvalidApprovalInstruction: The ed25519 approval instruction is malformed.")]
+    InvalidApprovalInstruction,
+    #[msg("WrongApprovalSigner: The approval was not signed by the configured approval signer.")]
+    WrongApprovalSigner,
+    #[msg("ApprovalMessageMismatch: The approval was signed for a different account or mint.")]
+    ApprovalMessageMismatch,
+    #[msg("ApprovalExpired: The approval's slot is too old or in the future.")]
+    ApprovalExpired,
+    #[msg("FreezeQuotaExceeded: This freezer has used up today's freeze quota.")]
+    FreezeQuotaExceeded,
+    #[msg("InsufficientEscrow: The escrow holds less than the requested amount.")]
+    InsufficientEscrow,
+    #[msg("VaultFrozen: The vault's token account is frozen.")]
+    VaultFrozen,
+    #[msg("VaultNotEmpty: The vault still holds tokens.")]
+    VaultNotEmpty,
+    #[msg("InsufficientVaultBalance: The vault holds less than the batch total.")]
+    InsufficientVaultBalance,
+    #[msg("PayrollNotDue: The next payroll run is not due yet.")]
+    PayrollNotDue,
+    #[msg("InvalidInflationRate: Inflation may not exceed 10% a year.")]
+    InvalidInflationRate,
+    #[msg("WrongMintAuthority: The mint authority is not this program's mint authority PDA.")]
+    WrongMintAuthority,
+    #[msg("RedemptionNotPending: The redemption was already fulfilled or refunded.")]
+    RedemptionNotPending,
+    #[msg("RedemptionNotExpired: The redemption can't be refunded yet.")]
+    RedemptionNotExpired,
+    #[msg("WrapperUnderbacked: The wrapped supply exceeds the underlying held in custody.")]
+    WrapperUnderbacked,
+    #[msg("InvalidRatio: Both sides of the ratio must be non-zero.")]
+    InvalidRatio,
+    #[msg("MigrationFinalized: This migration no longer accepts swaps.")]
+    MigrationFinalized,
+    #[msg("MigrationStillOpen: The migration deadline has not passed yet.")]
+    MigrationStillOpen,
+    #[msg("InvalidRebaseFactor: The rebase factor must be non-zero.")]
+    InvalidRebaseFactor,
+    #[msg("FeeAboveCap: A fee in the tier exceeds its hard cap.")]
+    FeeAboveCap,
+    #[msg("WrongFeeRecipient: The fee must be paid to the mint's fee recipient.")]
+    WrongFeeRecipient,
+    #[msg("SelfReferral: An owner cannot refer themselves.")]
+    SelfReferral,
+    #[msg("ReferralStatsMismatch: Referral stats must be passed exactly when there is a referrer.")]
+    ReferralStatsMismatch,
+    #[msg("NoFreezeAuthority: The mint has no freeze authority to hand over.")]
+    NoFreezeAuthority,
+    #[msg("FrozenAccountsOutstanding: Thaw every frozen account of the mint first.")]
+    FrozenAccountsOutstanding,
+    #[msg("AmountExceedsBalance: The amount is more than the account holds.")]
+    AmountExceedsBalance,
+    #[msg("InvalidDecimals: Decimals must be at most 18.")]
+    InvalidDecimals,
+    #[msg("DefaultPubkey: The all-zero pubkey is not a valid target.")]
+    DefaultPubkey,
+    #[msg("DelegationExpired: The delegation has expired.")]
+    DelegationExpired,
+    #[msg("DelegationAllowanceExceeded: The spend would exceed the delegation's allowance.")]
+    DelegationAllowanceExceeded,
+    #[msg("DelegationPeriodLimitExceeded: The spend would exceed this period's limit.")]
+    DelegationPeriodLimitExceeded,
+    #[msg("DestinationNotAllowed: The delegation may not pay this destination.")]
+    DestinationNotAllowed,
+    #[msg("CooldownActive: The account changed state too recently.")]
+    CooldownActive,
+    #[msg("ExportPageOutOfOrder: Export pages must be written in order, starting at 0.")]
+    ExportPageOutOfOrder,
+    #[msg("ExportOutOfOrder: Exported accounts must be sorted and appear only once.")]
+    ExportOutOfOrder,
+    #[msg("StaleFreezeRecord: The record's token account is no longer frozen.")]
+    StaleFreezeRecord,
+    #[msg("HolderLimitReached: The mint already has its maximum number of holders.")]
+    HolderLimitReached,
+    #[msg("HoldingPeriodNotSet: The mint has no holding period.")]
+    HoldingPeriodNotSet,
+    #[msg("NotNewHolder: Only an empty or still-locked account can receive locked tokens.")]
+    NotNewHolder,
+    #[msg("HoldingPeriodActive: The holding period has not ended yet.")]
+    HoldingPeriodActive,
+    #[msg("NotificationHookMismatch: The hook program is not the mint's configured hook.")]
+    NotificationHookMismatch,
+    #[msg("ThawValidatorNotSet: This mint has no thaw validator.")]
+    ThawValidatorNotSet,
+    #[msg("ThawValidatorMismatch: The validator program is not the mint's configured thaw validator.")]
+    ThawValidatorMismatch,
+    #[msg("ValidatorRejected: The thaw validator did not approve this thaw.")]
+    ValidatorRejected,
+    #[msg("BurnReceiptRetained: The burn receipt's retention period has not ended yet.")]
+    BurnReceiptRetained,
+    #[msg("ProtectedVault: This token account is a program vault and cannot be rescued.")]
+    ProtectedVault,
+    #[msg("DelegateNotFlagged: The token account's delegate is not a flagged delegate.")]
+    DelegateNotFlagged,
+    #[msg("LogoUriTooLong: The logo URI is longer than MAX_LOGO_URI_LEN bytes.")]
+    LogoUriTooLong,
+    #[msg("ProjectUrlTooLong: The project URL is longer than MAX_PROJECT_URL_LEN bytes.")]
+    ProjectUrlTooLong,
+    #[msg("InvalidAssociatedTokenAccount: The account is not the owner's associated token account for this mint.")]
+    InvalidAssociatedTokenAccount,
+    #[msg("EmptyDonation: A donation needs a token amount, lamports, or both.")]
+    EmptyDonation,
+    #[msg("InvalidTreasuryTokenAccount: Token donations need the donor's token account and one owned by the treasury.")]
+    InvalidTreasuryTokenAccount,
+    #[msg("PermissionedMode: The mint is in permissioned mode; tokens only move through permissioned_transfer.")]
+    PermissionedMode,
+    #[msg("NotPermissioned: The mint is not in permissioned mode.")]
+    NotPermissioned,
+    #[msg("TransfersPaused: The mint's transfers are paused.")]
+    TransfersPaused,
+    #[msg("TransferCapExceeded: The amount is above the mint's per-transfer cap.")]
+    TransferCapExceeded,
+    #[msg("HolderNotApproved: The sender or recipient is not an approved holder of the mint.")]
+    HolderNotApproved,
+    #[msg("SelfTransfer: The sender and recipient are the same token account.")]
+    SelfTransfer,
+    #[msg("DelegateNotApproved: The mint only allows delegating to approved delegates.")]
+    DelegateNotApproved,
+    #[msg("OwnerNotSanctioned: The token account's owner is not sanctioned.")]
+    OwnerNotSanctioned,
+    #[msg("InvalidStreamWindow: A stream must end after it starts.")]
+    InvalidStreamWindow,
+    #[msg("NothingToWithdraw: Nothing has accrued since the last withdrawal.")]
+    NothingToWithdraw,
+    #[msg("InvalidPenalty: The penalty cannot exceed 10000 basis points.")]
+    InvalidPenalty,
+    #[msg("InsufficientStake: The position has less staked than requested.")]
+    InsufficientStake,
+    #[msg("UnstakeCooldownActive: The unstake cooldown is not over yet.")]
+    UnstakeCooldownActive,
+    #[msg("CheckpointAccountMismatch: The checkpoints are kept for a different token account.")]
+    CheckpointAccountMismatch,
+    #[msg("CheckpointExpired: The checkpoints for that slot have been overwritten.")]
+    CheckpointExpired,
+    #[msg("DelegationRecordExists: The token account already has a delegation record.")]
+    DelegationRecordExists,
+    #[msg("DelegationOfferExpired: The delegation offer has expired.")]
+    DelegationOfferExpired,
+    #[msg("DelegationOfferActive: The delegation offer has not expired yet.")]
+    DelegationOfferActive,
+    #[msg("DelegationOfferStale: The owner has changed the approval since making the offer.")]
+    DelegationOfferStale,
+    #[msg("CertificateMetadataTooLong: The certificate's name, symbol or URI is too long.")]
+    CertificateMetadataTooLong,
+    #[msg("CertificateRevoked: The certificate has already been revoked.")]
+    CertificateRevoked,
+    #[msg("CertificateNotHeld: The token account doesn't hold the certificate.")]
+    CertificateNotHeld,
+    #[msg("BondTooLow: The freezer's bond is below the mint's minimum.")]
+    BondTooLow,
+    #[msg("BondLocked: Accounts the freezer froze while bonded are still frozen.")]
+    BondLocked,
+    #[msg("ReferenceAlreadyUsed: A payment has already been made against this reference.")]
+    ReferenceAlreadyUsed,
+    #[msg("PaymentPayeeMismatch: Payments against this reference go to a different account.")]
+    PaymentPayeeMismatch,
+    #[msg("DepositBelowDeadShares: The first deposit into a pool must exceed its dead shares.")]
+    DepositBelowDeadShares,
+    #[msg("ZeroShares: The deposit is too small to be worth a share.")]
+    ZeroShares,
+    #[msg("AutoCompoundDisabled: The position hasn't opted in to auto-compounding.")]
+    AutoCompoundDisabled,
+    #[msg("DelegatePdaMismatch: The delegate isn't the target program's PDA for these seeds.")]
+    DelegatePdaMismatch,
+    #[msg("TargetNotExecutable: The target program account isn't executable.")]
+    TargetNotExecutable,
+    #[msg("RentPayerRequired: The rent payer paid this record's rent and must be passed to get it back.")]
+    RentPayerRequired,
+    #[msg("InvalidMintConfig: The account isn't a MintConfig.")]
+    InvalidMintConfig,
+    #[msg("DelegateAlreadySet: The token account already has a delegate; revoke it first.")]
+    DelegateAlreadySet,
+    #[msg("RecoveryPending: A recovery of this token account is pending.")]
+    RecoveryPending,
+    #[msg("SlashPending: A slash of this stake position is queued.")]
+    SlashPending,
 }
@@ -0,0 +1,119 @@
+// Merkle proofs over sha256, used by airdrop claims and the thaw whitelist.
+//
+// Leaves and inner nodes are domain-separated (0x00 / 0x01 prefix) so an
+// inner node can never be passed off as a leaf. Pairs are hashed in sorted
+// order, so proofs don't need to carry left/right flags.
+
+use anchor_lang::solana_program::hash::hashv;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+// Hash the concatenation of `parts` into a leaf.
+pub fn hash_leaf(parts: &[&[u8]]) -> [u8; 32] {
+    let mut input = Vec::with_capacity(parts.len() + 1);
+    input.push(LEAF_PREFIX);
+    input.extend_from_slice(parts);
+    hashv(&input).to_bytes()
+}
+
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+// True if `leaf` is in the tree with the given `root`.
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| hash_leaf(&[&[i]])).collect()
+    }
+
+    // Build every level of the tree; an odd node out is carried up unchanged.
+    fn levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+
+    fn proof(levels: &[Vec<[u8; 32]>], mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for level in &levels[..levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+
+    #[test]
+    fn single_leaf_tree_has_empty_proof() {
+        let leaf = hash_leaf(&[b"only"]);
+        assert!(verify(&[], &leaf, leaf));
+    }
+
+    #[test]
+    fn every_leaf_verifies() {
+        for n in 1..=9 {
+            let levels = levels(leaves(n));
+            let root = levels.last().unwrap()[0];
+            for (i, leaf) in levels[0].iter().enumerate() {
+                assert!(verify(&proof(&levels, i), &root, *leaf), "n={n} i={i}");
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_leaf_not_in_tree() {
+        let levels = levels(leaves(4));
+        let root = levels.last().unwrap()[0];
+        let outsider = hash_leaf(&[&[99]]);
+        assert!(!verify(&proof(&levels, 0), &root, outsider));
+    }
+
+    #[test]
+    fn rejects_tampered_proof() {
+        let levels = levels(leaves(4));
+        let root = levels.last().unwrap()[0];
+        let mut bad = proof(&levels, 2);
+        bad[0][0] ^= 1;
+        assert!(!verify(&bad, &root, levels[0][2]));
+    }
+
+    #[test]
+    fn leaf_and_node_hashes_are_domain_separated() {
+        let (a, b) = (hash_leaf(&[b"a"]), hash_leaf(&[b"b"]));
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        assert_ne!(hash_leaf(&[&first, &second]), hash_pair(&a, &b));
+    }
+
+    #[test]
+    fn proof_against_other_root_fails() {
+        let a = levels(leaves(4));
+        let b = levels(leaves(5));
+        let other_root = b.last().unwrap()[0];
+        assert!(!verify(&proof(&a, 1), &other_root, a[0][1]));
+    }
+}
@@ -0,0 +1,66 @@
+// Seeds and addresses of the program's PDAs that other programs read.
+//
+// A partner program can check a BurnReceipt with an account constraint:
+//
+//     #[account(
+//         seeds = [pda::BURN_RECEIPT_SEED, burner.key().as_ref(), tag.as_ref()],
+//         bump = receipt.bump,
+//         seeds::program = custom_token_program::ID
+//     )]
+//     pub receipt: Account<'info, BurnReceipt>,
+//
+// or derive the address off-chain with `burn_receipt_address`.
+//
+// It also lists the token vaults the program keeps per mint, which
+// rescue_tokens must never touch.
+
+use anchor_lang::prelude::*;
+
+pub const BURN_RECEIPT_SEED: &[u8] = b"burn";
+
+// The `[b"burn", burner, tag]` receipt burn_with_receipt creates.
+pub fn burn_receipt_address(burner: &Pubkey, tag: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BURN_RECEIPT_SEED, burner.as_ref(), tag.as_ref()], &crate::ID)
+}
+
+// Token vaults derived from the mint alone: the staking reward and stake
+// vaults and the bridge vault. Every other vault (escrow, vault, airdrop, wrapper,
+// migration) is also keyed by accounts only its owner knows, and is owned by
+// its own PDA rather than the program authority.
+pub fn mint_vaults(mint: &Pubkey) -> [Pubkey; 3] {
+    let (reward_pool, _) = Pubkey::find_program_address(&[b"reward_pool", mint.as_ref()], &crate::ID);
+    let (bridge, _) = Pubkey::find_program_address(&[b"bridge", mint.as_ref()], &crate::ID);
+    [
+        Pubkey::find_program_address(&[b"reward_vault", reward_pool.as_ref()], &crate::ID).0,
+        Pubkey::find_program_address(&[b"stake_vault", reward_pool.as_ref()], &crate::ID).0,
+        Pubkey::find_program_address(&[b"bridge_vault", bridge.as_ref()], &crate::ID).0,
+    ]
+}
+
+pub fn is_program_vault(token_account: &Pubkey, mint: &Pubkey) -> bool {
+    mint_vaults(mint).contains(token_account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receipts_are_per_burner_and_tag() {
+        let burner = Pubkey::new_unique();
+        let (address, _) = burn_receipt_address(&burner, &[1; 32]);
+        assert_eq!(burn_receipt_address(&burner, &[1; 32]).0, address);
+        assert_ne!(burn_receipt_address(&burner, &[2; 32]).0, address);
+        assert_ne!(burn_receipt_address(&Pubkey::new_unique(), &[1; 32]).0, address);
+    }
+
+    #[test]
+    fn vaults_are_recognised_for_their_own_mint_only() {
+        let mint = Pubkey::new_unique();
+        for vault in mint_vaults(&mint) {
+            assert!(is_program_vault(&vault, &mint));
+            assert!(!is_program_vault(&vault, &Pubkey::new_unique()));
+        }
+        assert!(!is_program_vault(&Pubkey::new_unique(), &mint));
+    }
+}
@@ -0,0 +1,88 @@
+// Conversions between raw SPL amounts and rebased ("effective") amounts.
+//
+// effective = raw * factor / REBASE_SCALE. The rebase is display and
+// accounting only: SPL balances always stay in raw units, so anything the
+// program moves must be converted back to raw first.
+
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+// A factor of REBASE_SCALE means balances are shown as they are.
+pub const REBASE_SCALE: u64 = 1_000_000_000;
+
+pub fn validate_factor(factor: u64) -> Result<()> {
+    require!(factor > 0, CustomError::InvalidRebaseFactor);
+    Ok(())
+}
+
+// Raw to effective, rounded down: a holder is never shown more than they have.
+pub fn to_effective(raw: u64, factor: u64) -> Result<u64> {
+    validate_factor(factor)?;
+    let effective = raw as u128 * factor as u128 / REBASE_SCALE as u128;
+    u64::try_from(effective).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+// Effective to raw, rounded down: a transfer asked for in effective units
+// never moves more than was asked for.
+pub fn to_raw(effective: u64, factor: u64) -> Result<u64> {
+    validate_factor(factor)?;
+    let raw = effective as u128 * REBASE_SCALE as u128 / factor as u128;
+    u64::try_from(raw).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_factor_is_identity() {
+        for amount in [0, 1, 7, 1_000_000, u64::MAX] {
+            assert_eq!(to_effective(amount, REBASE_SCALE).unwrap(), amount);
+            assert_eq!(to_raw(amount, REBASE_SCALE).unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn zero_factor_is_rejected() {
+        assert!(validate_factor(0).is_err());
+        assert!(to_effective(100, 0).is_err());
+        assert!(to_raw(100, 0).is_err());
+    }
+
+    #[test]
+    fn scales_and_rounds_down() {
+        // 1.5x
+        let factor = REBASE_SCALE * 3 / 2;
+        assert_eq!(to_effective(3, factor).unwrap(), 4);
+        assert_eq!(to_raw(4, factor).unwrap(), 2);
+        // 0.333333333x
+        let factor = REBASE_SCALE / 3;
+        assert_eq!(to_effective(10, factor).unwrap(), 3);
+        assert_eq!(to_raw(3, factor).unwrap(), 9);
+    }
+
+    #[test]
+    fn round_trip_never_gains() {
+        for factor in [1, 3, REBASE_SCALE / 7, REBASE_SCALE + 1, 5 * REBASE_SCALE] {
+            for raw in [0, 1, 2, 999, 123_456_789] {
+                let back = to_raw(to_effective(raw, factor).unwrap(), factor).unwrap();
+                assert!(back <= raw, "factor={factor} raw={raw}");
+            }
+        }
+    }
+
+    #[test]
+    fn overflow_near_u64_max() {
+        assert!(to_effective(u64::MAX, 2 * REBASE_SCALE).is_err());
+        assert_eq!(to_effective(u64::MAX, REBASE_SCALE / 2).unwrap(), u64::MAX / 2);
+        assert!(to_raw(u64::MAX, REBASE_SCALE / 2).is_err());
+        assert_eq!(to_raw(u64::MAX / 2, REBASE_SCALE / 2).unwrap(), u64::MAX - 1);
+    }
+
+    #[test]
+    fn tiny_factor_shows_nothing() {
+        assert_eq!(to_effective(REBASE_SCALE - 1, 1).unwrap(), 0);
+        assert_eq!(to_effective(REBASE_SCALE, 1).unwrap(), 1);
+    }
+}
@@ -0,0 +1,86 @@
+// Share accounting for pool vaults.
+//
+// A share is a claim on an equal slice of whatever the vault holds, so tokens
+// paid into the vault from outside raise every share's value at once. Both
+// conversions round in the vault's favour: deposits get shares rounded down,
+// redemptions get tokens rounded down.
+//
+// An empty vault prices shares 1:1 and its first deposit leaves DEAD_SHARES
+// of them with the vault for good. Without them the first depositor could hold
+// a single share, pay tokens straight into the vault to make that share worth
+// more than the next deposit, and have the next depositor's shares round to
+// zero. With them, most of what such a donation adds goes to shares nobody
+// can redeem.
+
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+pub const DEAD_SHARES: u64 = 1_000;
+
+// Shares for depositing `amount` into a vault holding `vault_balance` against
+// `share_supply` shares, rounded down. 1:1 while no shares exist.
+pub fn shares_for_deposit(amount: u64, vault_balance: u64, share_supply: u64) -> Result<u64> {
+    if share_supply == 0 {
+        return Ok(amount);
+    }
+    let shares = (amount as u128 * share_supply as u128)
+        .checked_div(vault_balance as u128)
+        .ok_or(CustomError::MathOverflow)?;
+    u64::try_from(shares).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+// Tokens for redeeming `shares` from a vault holding `vault_balance` against
+// `share_supply` shares, rounded down.
+pub fn assets_for_redeem(shares: u64, vault_balance: u64, share_supply: u64) -> Result<u64> {
+    let assets = (shares as u128 * vault_balance as u128)
+        .checked_div(share_supply as u128)
+        .ok_or(CustomError::MathOverflow)?;
+    u64::try_from(assets).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_vault_prices_one_to_one() {
+        assert_eq!(shares_for_deposit(5_000, 0, 0).unwrap(), 5_000);
+        // Tokens sitting in a vault with no shares go to the first depositor.
+        assert_eq!(shares_for_deposit(5_000, 700, 0).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn yield_raises_the_share_price() {
+        // 1_000 shares over 2_000 tokens: each share is worth 2.
+        assert_eq!(shares_for_deposit(500, 2_000, 1_000).unwrap(), 250);
+        assert_eq!(assets_for_redeem(250, 2_500, 1_250).unwrap(), 500);
+    }
+
+    #[test]
+    fn rounds_in_the_vaults_favour() {
+        assert_eq!(shares_for_deposit(2, 3, 1).unwrap(), 0);
+        assert_eq!(assets_for_redeem(1, 2, 3).unwrap(), 0);
+        // Redeeming right after depositing never pays out more than went in.
+        let shares = shares_for_deposit(1_000, 3_001, 1_000).unwrap();
+        assert!(assets_for_redeem(shares, 4_001, 1_000 + shares).unwrap() <= 1_000);
+    }
+
+    #[test]
+    fn dead_shares_blunt_a_donation() {
+        // The attacker's first deposit of DEAD_SHARES + 1 leaves it one share.
+        let supply = DEAD_SHARES + 1;
+        let donated = 1_000_000;
+        let balance = DEAD_SHARES + 1 + donated;
+        // The next depositor still gets shares, and the attacker's one share
+        // is worth about a thousandth of what it paid in.
+        assert_eq!(shares_for_deposit(10_000, balance, supply).unwrap(), 9);
+        assert_eq!(assets_for_redeem(1, balance, supply).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn refuses_to_price_against_nothing() {
+        assert!(shares_for_deposit(1, 0, 10).is_err());
+        assert!(assets_for_redeem(1, 10, 0).is_err());
+    }
+}
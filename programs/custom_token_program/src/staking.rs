@@ -0,0 +1,77 @@
+// Reward accounting for staking.
+//
+// The reward pool keeps a running `reward_per_token`: rewards paid in so far
+// per staked token, scaled by REWARD_PRECISION. A position remembers the value
+// it last settled at, so what it has earned since is
+// staked * (reward_per_token - paid) / REWARD_PRECISION. Settling before every
+// change to a position's stake keeps each token earning only while staked.
+
+use anchor_lang::prelude::*;
+
+use crate::{CustomError, BPS_DENOMINATOR};
+
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// How much `reward_per_token` grows when `rewards` are shared out over
+// `total_staked`, rounded down. The remainder stays in the reward vault.
+pub fn reward_per_token_increase(rewards: u64, total_staked: u64) -> u128 {
+    if total_staked == 0 {
+        return 0;
+    }
+    rewards as u128 * REWARD_PRECISION / total_staked as u128
+}
+
+// Rewards earned by `staked` tokens since the position settled at `paid`, rounded down.
+pub fn earned(staked: u64, reward_per_token: u128, paid: u128) -> Result<u64> {
+    let growth = reward_per_token.checked_sub(paid).ok_or(CustomError::MathOverflow)?;
+    let earned = (staked as u128)
+        .checked_mul(growth)
+        .ok_or(CustomError::MathOverflow)?
+        / REWARD_PRECISION;
+    u64::try_from(earned).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+// `bps` of `amount`, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_shared_over_an_empty_pool() {
+        assert_eq!(reward_per_token_increase(1_000, 0), 0);
+    }
+
+    #[test]
+    fn rewards_split_by_stake() {
+        // 300 and 100 staked; 1_000 paid in.
+        let rpt = reward_per_token_increase(1_000, 400);
+        assert_eq!(earned(300, rpt, 0).unwrap(), 750);
+        assert_eq!(earned(100, rpt, 0).unwrap(), 250);
+        // Nothing more until the next payment.
+        assert_eq!(earned(300, rpt, rpt).unwrap(), 0);
+    }
+
+    #[test]
+    fn rounds_down_and_never_pays_out_more_than_paid_in() {
+        let rpt = reward_per_token_increase(10, 3);
+        let total: u64 = (0..3).map(|_| earned(1, rpt, 0).unwrap()).sum();
+        assert!(total <= 10);
+        assert_eq!(total, 9);
+    }
+
+    #[test]
+    fn refuses_a_stale_accumulator() {
+        assert!(earned(1, 1, 2).is_err());
+    }
+
+    #[test]
+    fn takes_basis_points() {
+        assert_eq!(bps_of(1_000, 250), 25);
+        assert_eq!(bps_of(999, 1), 0);
+        assert_eq!(bps_of(u64::MAX, 10_000), u64::MAX);
+    }
+}
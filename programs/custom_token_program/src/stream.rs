@@ -0,0 +1,81 @@
+// Accrual of payment streams.
+//
+// A stream pays out `deposit` linearly between `start_ts` and `end_ts`, i.e.
+// deposit / (end_ts - start_ts) tokens per second. The rate is never rounded
+// on its own: what has accrued is computed from the deposit each time, so
+// withdrawals add up to exactly the deposit by the end.
+
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+pub fn validate_window(start_ts: i64, end_ts: i64) -> Result<()> {
+    require!(end_ts > start_ts, CustomError::InvalidStreamWindow);
+    Ok(())
+}
+
+// Tokens per second, rounded down. For display only; see `accrued`.
+pub fn rate_per_second(deposit: u64, start_ts: i64, end_ts: i64) -> Result<u64> {
+    validate_window(start_ts, end_ts)?;
+    let duration = (end_ts as i128 - start_ts as i128) as u128;
+    Ok((deposit as u128 / duration) as u64)
+}
+
+// How much of `deposit` has accrued at `now`, rounded down. Nothing before
+// the start, everything from the end on.
+pub fn accrued(deposit: u64, start_ts: i64, end_ts: i64, now: i64) -> Result<u64> {
+    validate_window(start_ts, end_ts)?;
+    if now <= start_ts {
+        return Ok(0);
+    }
+    if now >= end_ts {
+        return Ok(deposit);
+    }
+    let elapsed = (now as i128 - start_ts as i128) as u128;
+    let duration = (end_ts as i128 - start_ts as i128) as u128;
+    // elapsed < duration, so this stays below `deposit`.
+    Ok((deposit as u128 * elapsed / duration) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_an_empty_window() {
+        assert!(validate_window(10, 10).is_err());
+        assert!(validate_window(10, 9).is_err());
+        assert!(accrued(100, 10, 10, 10).is_err());
+    }
+
+    #[test]
+    fn accrues_linearly_within_the_window() {
+        assert_eq!(accrued(1_000, 100, 200, 50).unwrap(), 0);
+        assert_eq!(accrued(1_000, 100, 200, 100).unwrap(), 0);
+        assert_eq!(accrued(1_000, 100, 200, 125).unwrap(), 250);
+        assert_eq!(accrued(1_000, 100, 200, 200).unwrap(), 1_000);
+        assert_eq!(accrued(1_000, 100, 200, i64::MAX).unwrap(), 1_000);
+        assert_eq!(rate_per_second(1_000, 100, 200).unwrap(), 10);
+    }
+
+    #[test]
+    fn withdrawals_sum_to_the_deposit() {
+        // 7 tokens over 3 seconds doesn't divide evenly: the rounding of each
+        // withdrawal is made up by the next, never lost.
+        let mut withdrawn = 0;
+        let mut payouts = vec![];
+        for now in 1..=3 {
+            let payout = accrued(7, 0, 3, now).unwrap() - withdrawn;
+            withdrawn += payout;
+            payouts.push(payout);
+        }
+        assert_eq!(payouts, vec![2, 2, 3]);
+        assert_eq!(withdrawn, 7);
+    }
+
+    #[test]
+    fn handles_extreme_values() {
+        assert_eq!(accrued(u64::MAX, i64::MIN, i64::MAX, 0).unwrap(), 1 << 63);
+        assert_eq!(accrued(u64::MAX, i64::MIN, i64::MAX, i64::MAX).unwrap(), u64::MAX);
+    }
+}
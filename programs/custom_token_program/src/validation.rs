@@ -0,0 +1,127 @@
+// Input checks shared by the handlers, each backed by a dedicated error so
+// clients can map failures without parsing logs.
+
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+// SPL mints store decimals as a u8; anything above this is a client mistake.
+pub const MAX_DECIMALS: u8 = 18;
+
+// DisplayConfig strings, in bytes. Keeps get_display_config's return data
+// well under the runtime's 1024-byte limit.
+pub const MAX_LOGO_URI_LEN: usize = 200;
+pub const MAX_PROJECT_URL_LEN: usize = 200;
+
+// Certificate metadata, in bytes. The same limits as Metaplex metadata.
+pub const MAX_CERTIFICATE_NAME_LEN: usize = 32;
+pub const MAX_CERTIFICATE_SYMBOL_LEN: usize = 10;
+pub const MAX_CERTIFICATE_URI_LEN: usize = 200;
+
+pub fn require_nonzero_amount(amount: u64) -> Result<()> {
+    require!(amount > 0, CustomError::ZeroAmount);
+    Ok(())
+}
+
+pub fn require_within_balance(amount: u64, balance: u64) -> Result<()> {
+    require!(amount <= balance, CustomError::AmountExceedsBalance);
+    Ok(())
+}
+
+pub fn require_valid_decimals(decimals: u8) -> Result<()> {
+    require!(decimals <= MAX_DECIMALS, CustomError::InvalidDecimals);
+    Ok(())
+}
+
+// The all-zero key is what an unset field deserializes to, never a real target.
+pub fn require_pubkey_not_default(key: &Pubkey) -> Result<()> {
+    require_keys_neq!(*key, Pubkey::default(), CustomError::DefaultPubkey);
+    Ok(())
+}
+
+pub fn require_logo_uri_len(uri: &str) -> Result<()> {
+    require!(uri.len() <= MAX_LOGO_URI_LEN, CustomError::LogoUriTooLong);
+    Ok(())
+}
+
+pub fn require_project_url_len(url: &str) -> Result<()> {
+    require!(url.len() <= MAX_PROJECT_URL_LEN, CustomError::ProjectUrlTooLong);
+    Ok(())
+}
+
+pub fn require_certificate_metadata_len(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(
+        name.len() <= MAX_CERTIFICATE_NAME_LEN
+            && symbol.len() <= MAX_CERTIFICATE_SYMBOL_LEN
+            && uri.len() <= MAX_CERTIFICATE_URI_LEN,
+        CustomError::CertificateMetadataTooLong
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(result: Result<()>) -> u32 {
+        match result.unwrap_err() {
+            Error::AnchorError(e) => e.error_code_number,
+            Error::ProgramError(e) => panic!("unexpected program error {e:?}"),
+        }
+    }
+
+    fn expected(err: CustomError) -> u32 {
+        anchor_lang::error::ERROR_CODE_OFFSET + err as u32
+    }
+
+    #[test]
+    fn nonzero_amount() {
+        assert!(require_nonzero_amount(1).is_ok());
+        assert!(require_nonzero_amount(u64::MAX).is_ok());
+        assert_eq!(code(require_nonzero_amount(0)), expected(CustomError::ZeroAmount));
+    }
+
+    #[test]
+    fn within_balance() {
+        assert!(require_within_balance(0, 0).is_ok());
+        assert!(require_within_balance(10, 10).is_ok());
+        assert_eq!(code(require_within_balance(11, 10)), expected(CustomError::AmountExceedsBalance));
+    }
+
+    #[test]
+    fn valid_decimals() {
+        assert!(require_valid_decimals(0).is_ok());
+        assert!(require_valid_decimals(MAX_DECIMALS).is_ok());
+        assert_eq!(code(require_valid_decimals(MAX_DECIMALS + 1)), expected(CustomError::InvalidDecimals));
+    }
+
+    #[test]
+    fn pubkey_not_default() {
+        assert!(require_pubkey_not_default(&Pubkey::new_unique()).is_ok());
+        assert_eq!(code(require_pubkey_not_default(&Pubkey::default())), expected(CustomError::DefaultPubkey));
+    }
+
+    #[test]
+    fn display_string_lengths() {
+        assert!(require_logo_uri_len("").is_ok());
+        assert!(require_logo_uri_len(&"a".repeat(MAX_LOGO_URI_LEN)).is_ok());
+        assert_eq!(code(require_logo_uri_len(&"a".repeat(MAX_LOGO_URI_LEN + 1))), expected(CustomError::LogoUriTooLong));
+        assert!(require_project_url_len(&"a".repeat(MAX_PROJECT_URL_LEN)).is_ok());
+        assert_eq!(
+            code(require_project_url_len(&"a".repeat(MAX_PROJECT_URL_LEN + 1))),
+            expected(CustomError::ProjectUrlTooLong)
+        );
+    }
+
+    #[test]
+    fn certificate_metadata_lengths() {
+        let name = "a".repeat(MAX_CERTIFICATE_NAME_LEN);
+        let symbol = "a".repeat(MAX_CERTIFICATE_SYMBOL_LEN);
+        let uri = "a".repeat(MAX_CERTIFICATE_URI_LEN);
+        assert!(require_certificate_metadata_len(&name, &symbol, &uri).is_ok());
+        let too_long = expected(CustomError::CertificateMetadataTooLong);
+        assert_eq!(code(require_certificate_metadata_len(&(name.clone() + "a"), &symbol, &uri)), too_long);
+        assert_eq!(code(require_certificate_metadata_len(&name, &(symbol.clone() + "a"), &uri)), too_long);
+        assert_eq!(code(require_certificate_metadata_len(&name, &symbol, &(uri + "a"))), too_long);
+    }
+}
@@ -0,0 +1,106 @@
+// A minimal receiver for custom_token_program's freeze/thaw notifications,
+// of the kind a lending market holding the token as collateral would run.
+//
+// custom_token_program calls `on_freeze_event(token_account, mint, frozen)`
+// with its `[b"notifier", mint]` PDA as the first account, signing. That
+// signature is what proves the call came from custom_token_program: anyone
+// can send the same instruction data, but only it can sign for the PDA.
+
+use anchor_lang::prelude::*;
+
+declare_id!("GaC7wxLjKihvejV22UVgJnfXvdcRcXvnnsV5TwoXbFNJ");
+
+// custom_token_program's ID, which derives the notifier PDA.
+pub mod notifying_program {
+    anchor_lang::declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+}
+
+#[program]
+pub mod freeze_hook_example {
+    use super::*;
+
+    // Create the account notifications are written to.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.hook_state.bump = ctx.bumps.hook_state;
+        Ok(())
+    }
+
+    // Make every later notification panic, to show what a broken hook does.
+    pub fn set_fail(ctx: Context<SetFail>, fail: bool) -> Result<()> {
+        ctx.accounts.hook_state.fail = fail;
+        Ok(())
+    }
+
+    pub fn on_freeze_event(
+        ctx: Context<OnFreezeEvent>,
+        token_account: Pubkey,
+        _mint: Pubkey,
+        frozen: bool,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.hook_state;
+        if state.fail {
+            panic!("freeze hook configured to fail");
+        }
+        state.notifications += 1;
+        state.last_token_account = token_account;
+        state.last_frozen = frozen;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HookState::INIT_SPACE,
+        seeds = [b"hook_state"],
+        bump
+    )]
+    pub hook_state: Account<'info, HookState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFail<'info> {
+    pub anyone: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_state"],
+        bump = hook_state.bump
+    )]
+    pub hook_state: Account<'info, HookState>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_account: Pubkey, mint: Pubkey)]
+pub struct OnFreezeEvent<'info> {
+    #[account(
+        seeds = [b"notifier", mint.as_ref()],
+        bump,
+        seeds::program = notifying_program::ID
+    )]
+    pub notifier: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"hook_state"],
+        bump = hook_state.bump
+    )]
+    pub hook_state: Account<'info, HookState>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct HookState {
+    pub notifications: u64,
+    pub last_token_account: Pubkey,
+    pub last_frozen: bool,
+    pub fail: bool,
+    pub bump: u8,
+}
@@ -0,0 +1,106 @@
+// A minimal thaw validator for custom_token_program's thaw_with_validator,
+// standing in for something like a KYC oracle.
+//
+// custom_token_program calls `validate_thaw(owner, token_account)` and
+// thaws only if it returns `true`. Here that means the owner has an
+// Attestation, written by the validator's authority. Returning `false`
+// lets the caller report the rejection; erroring would abort the whole
+// transaction instead.
+
+use anchor_lang::prelude::*;
+
+declare_id!("4syYrGeb3tUPFLiB69zLBVasQVs9Z9Xn4KYncquhFjkM");
+
+#[program]
+pub mod thaw_validator_example {
+    use super::*;
+
+    // Make the payer the authority that writes attestations.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let config = &mut ctx.accounts.validator_config;
+        config.authority = ctx.accounts.payer.key();
+        config.bump = ctx.bumps.validator_config;
+        Ok(())
+    }
+
+    // Record that `owner` passed whatever check this validator stands for.
+    pub fn attest(ctx: Context<Attest>, owner: Pubkey) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.owner = owner;
+        attestation.bump = ctx.bumps.attestation;
+        Ok(())
+    }
+
+    pub fn validate_thaw(ctx: Context<ValidateThaw>, _owner: Pubkey, _token_account: Pubkey) -> Result<bool> {
+        let attestation = &ctx.accounts.attestation;
+        Ok(*attestation.owner == crate::ID && !attestation.data_is_empty())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ValidatorConfig::INIT_SPACE,
+        seeds = [b"validator_config"],
+        bump
+    )]
+    pub validator_config: Account<'info, ValidatorConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct Attest<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"validator_config"],
+        bump = validator_config.bump,
+        has_one = authority
+    )]
+    pub validator_config: Account<'info, ValidatorConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Attestation::INIT_SPACE,
+        seeds = [b"attestation", owner.as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct ValidateThaw<'info> {
+    /// CHECK: The owner's Attestation, which may not exist. The address is
+    /// checked here, whether it has been written in the handler.
+    #[account(
+        seeds = [b"attestation", owner.as_ref()],
+        bump
+    )]
+    pub attestation: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ValidatorConfig {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Attestation {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
@@ -0,0 +1,96 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  accountHistoryPda,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+const MAX_ENTRIES = 16;
+
+describe("account history", () => {
+  const admin = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    tokenAccount = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+  });
+
+  const freeze = () => program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+  const thaw = () => program.methods.thawTokenAccount().accounts(thawAccounts(mint, tokenAccount)).rpc();
+  const entries = () =>
+    program.methods.getAccountHistory().accounts({ accountHistory: accountHistoryPda(tokenAccount) }).view();
+
+  it("logs five freeze/thaw rounds in order", async () => {
+    for (let i = 0; i < 5; i++) {
+      await freeze();
+      await thaw();
+    }
+
+    const log = await entries();
+    assert.lengthOf(log, 10);
+    log.forEach((entry, i) => {
+      assert.deepEqual(entry.action, i % 2 === 0 ? { freeze: {} } : { thaw: {} });
+      assert.deepEqual(entry.reason, { mintAuthority: {} });
+      assert.ok(entry.actor.equals(admin));
+      if (i > 0) assert.isAtLeast(entry.slot.toNumber(), log[i - 1].slot.toNumber());
+    });
+
+    const history = await program.account.accountHistory.fetch(accountHistoryPda(tokenAccount));
+    assert.equal(history.totalActions.toNumber(), 10);
+    assert.ok(history.tokenAccount.equals(tokenAccount));
+  });
+
+  it("evicts the oldest entries past 16 but keeps counting", async () => {
+    // 10 logged so far; 5 more rounds makes 20.
+    for (let i = 0; i < 5; i++) {
+      await freeze();
+      await thaw();
+    }
+
+    const log = await entries();
+    assert.lengthOf(log, MAX_ENTRIES);
+    // The four oldest entries (two rounds) are gone, so the log still starts on a freeze.
+    assert.deepEqual(log[0].action, { freeze: {} });
+    assert.deepEqual(log[MAX_ENTRIES - 1].action, { thaw: {} });
+
+    const history = await program.account.accountHistory.fetch(accountHistoryPda(tokenAccount));
+    assert.equal(history.totalActions.toNumber(), 20);
+  });
+
+  it("creates the history even if someone funded its address first", async () => {
+    const fresh = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: admin,
+          toPubkey: accountHistoryPda(fresh),
+          lamports: 1_000_000,
+        })
+      )
+    );
+
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, fresh)).rpc();
+    const history = await program.account.accountHistory.fetch(accountHistoryPda(fresh));
+    assert.ok(history.tokenAccount.equals(fresh));
+    assert.equal(history.totalActions.toNumber(), 1);
+  });
+
+  it("refuses a history account other than the token account's own", async () => {
+    const other = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await expectError(
+      program.methods
+        .freezeTokenAccount()
+        .accounts({ ...freezeAccounts(mint, other), accountHistory: accountHistoryPda(tokenAccount) })
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+});
@@ -0,0 +1,109 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop as fundSol,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  payer,
+  pda,
+  program,
+  provider,
+  sleep,
+} from "./helpers";
+import { MerkleTree, airdropLeaf } from "./merkle";
+
+describe("merkle airdrop", () => {
+  const funder = provider.wallet.publicKey;
+  const claimants = [0, 1, 2, 3, 4].map(() => anchor.web3.Keypair.generate());
+  const amounts = [100, 200, 300, 400, 500];
+  const tree = new MerkleTree(claimants.map((c, i) => airdropLeaf(c.publicKey, amounts[i])));
+
+  let mint: anchor.web3.PublicKey;
+  let funderTokenAccount: anchor.web3.PublicKey;
+  let airdrop: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    funderTokenAccount = await createFundedTokenAccount(mint, payer.publicKey, 10_000);
+    airdrop = pda([Buffer.from("airdrop"), mint.toBuffer(), funder.toBuffer(), Buffer.from(tree.root())]);
+    vault = pda([Buffer.from("airdrop_vault"), airdrop.toBuffer()]);
+
+    const deadline = Math.floor(Date.now() / 1000) + 8;
+    await program.methods
+      .createAirdrop(tree.root(), new anchor.BN(1_500), new anchor.BN(deadline))
+      .accounts({
+        funder,
+        mint,
+        funderTokenAccount,
+        airdrop,
+        vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    for (const c of claimants) await fundSol(c.publicKey);
+  });
+
+  async function claim(index: number, amount = amounts[index], proof = tree.proof(index)) {
+    const claimant = claimants[index];
+    const claimantTokenAccount = await createFundedTokenAccount(mint, claimant.publicKey, 0);
+    await program.methods
+      .claimAirdrop(new anchor.BN(amount), proof)
+      .accounts({
+        claimant: claimant.publicKey,
+        airdrop,
+        vault,
+        claimantTokenAccount,
+        claimStatus: pda([Buffer.from("claim"), airdrop.toBuffer(), claimant.publicKey.toBuffer()]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([claimant])
+      .rpc();
+    return claimantTokenAccount;
+  }
+
+  it("pays a claimant with a valid proof", async () => {
+    const account = await claim(2);
+    assert.equal((await getAccount(provider.connection, account)).amount, BigInt(300));
+    const state = await program.account.airdrop.fetch(airdrop);
+    assert.equal(state.claimedAmount.toNumber(), 300);
+  });
+
+  it("rejects a second claim by the same wallet", async () => {
+    try {
+      await claim(2);
+      assert.fail("expected the ClaimStatus init to fail");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+  });
+
+  it("rejects an inflated amount and a proof for someone else", async () => {
+    await expectError(claim(0, 1_000), "InvalidProof");
+    await expectError(claim(1, amounts[1], tree.proof(3)), "InvalidProof");
+  });
+
+  it("refuses to reclaim before the deadline, then returns the remainder", async () => {
+    const reclaim = () =>
+      program.methods
+        .reclaimUnclaimed()
+        .accounts({ funder, airdrop, vault, funderTokenAccount, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+    await expectError(reclaim(), "ClaimWindowOpen");
+
+    await sleep(9_000);
+    await expectError(claim(4), "ClaimWindowClosed");
+
+    const before = (await getAccount(provider.connection, funderTokenAccount)).amount;
+    await reclaim();
+    const after = (await getAccount(provider.connection, funderTokenAccount)).amount;
+    assert.equal(after - before, BigInt(1_200));
+    assert.isNull(await provider.connection.getAccountInfo(vault));
+  });
+});
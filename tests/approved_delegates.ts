@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("approved delegates", () => {
+  const admin = provider.wallet.publicKey;
+  const owner = anchor.web3.Keypair.generate();
+  const escrow = anchor.web3.Keypair.generate().publicKey;
+  const drainer = anchor.web3.Keypair.generate().publicKey;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+
+  const approvedDelegatePda = (delegate: anchor.web3.PublicKey) =>
+    pda([Buffer.from("approved_delegate"), mint.toBuffer(), delegate.toBuffer()]);
+
+  before(async () => {
+    await airdrop(owner.publicKey);
+    mint = await createProgramMint();
+    tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    await program.methods
+      .addApprovedDelegate(escrow)
+      .accounts({
+        admin,
+        mint,
+        approvedDelegate: approvedDelegatePda(escrow),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  function setRestrict(restrict: boolean) {
+    return program.methods
+      .setRestrictDelegates(restrict)
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  }
+
+  function delegateTo(delegate: anchor.web3.PublicKey, approvedDelegate: anchor.web3.PublicKey | null) {
+    return program.methods
+      .delegateTokens(new anchor.BN(10), null)
+      .accounts({
+        tokenAccount,
+        delegate,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate,
+        payer: payer.publicKey,
+        referralStats: null,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  const currentDelegate = async () => (await getAccount(provider.connection, tokenAccount)).delegate;
+
+  it("lets anyone be delegated to while the flag is off", async () => {
+    await delegateTo(drainer, null);
+    assert.ok((await currentDelegate()).equals(drainer));
+  });
+
+  describe("with the flag on", () => {
+    before(async () => {
+      await setRestrict(true);
+    });
+
+    it("accepts an approved delegate", async () => {
+      await delegateTo(escrow, approvedDelegatePda(escrow));
+      assert.ok((await currentDelegate()).equals(escrow));
+    });
+
+    it("rejects an unapproved delegate, with or without its PDA", async () => {
+      await expectError(delegateTo(drainer, approvedDelegatePda(drainer)), "DelegateNotApproved");
+      await expectError(delegateTo(drainer, null), "DelegateNotApproved");
+      assert.ok((await currentDelegate()).equals(escrow));
+    });
+
+    it("rejects a delegate once it is taken off the list", async () => {
+      await program.methods
+        .removeApprovedDelegate()
+        .accounts({ admin, mint, approvedDelegate: approvedDelegatePda(escrow) })
+        .rpc();
+      await expectError(delegateTo(escrow, approvedDelegatePda(escrow)), "DelegateNotApproved");
+    });
+  });
+});
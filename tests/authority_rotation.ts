@@ -0,0 +1,135 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount, getMint } from "@solana/spl-token";
+import {
+  adminActionId,
+  adminActionPda,
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  expectError,
+  freezeAccounts,
+  i64Le,
+  mintConfigPda,
+  pda,
+  program,
+  programAuthority,
+  programConfig,
+  provider,
+  queueAndWait,
+} from "./helpers";
+
+describe("timelock and authority rotation", () => {
+  const superAdmin = provider.wallet.publicKey;
+
+  before(async () => {
+    await ensureProgramConfig();
+  });
+
+  function rotate(mint: anchor.web3.PublicKey, adminAction: anchor.web3.PublicKey) {
+    return program.methods
+      .rotateProgramAuthority()
+      .accounts({
+        superAdmin,
+        programConfig,
+        adminAction,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        programAuthority,
+        newProgramAuthority: pda([Buffer.from("authority_v2"), mint.toBuffer()]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  it("blocks a queued action until the delay has elapsed", async () => {
+    await program.methods
+      .setTimelockDelay(new anchor.BN(2))
+      .accounts({ superAdmin, programConfig, adminAction: null })
+      .rpc();
+
+    const mint = await createProgramMint();
+    const actionId = adminActionId("rotate_program_authority", mint.toBuffer());
+    const adminAction = adminActionPda(actionId);
+    await program.methods
+      .queueAdminAction(actionId)
+      .accounts({
+        superAdmin,
+        programConfig,
+        adminAction,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await expectError(rotate(mint, adminAction), "TimelockNotElapsed");
+
+    await program.methods
+      .cancelAdminAction()
+      .accounts({ superAdmin, programConfig, adminAction })
+      .rpc();
+  });
+
+  it("only shortens the delay through the timelock", async () => {
+    await expectError(
+      program.methods
+        .setTimelockDelay(new anchor.BN(0))
+        .accounts({ superAdmin, programConfig, adminAction: null })
+        .rpc(),
+      "TimelockRequired"
+    );
+
+    const adminAction = await queueAndWait(adminActionId("set_timelock_delay", i64Le(0)));
+    await program.methods
+      .setTimelockDelay(new anchor.BN(0))
+      .accounts({ superAdmin, programConfig, adminAction })
+      .rpc();
+
+    const config = await program.account.programConfig.fetch(programConfig);
+    assert.equal(config.timelockDelay.toNumber(), 0);
+  });
+
+  it("rotates one mint to the v2 PDA and keeps an unrotated mint on v1", async () => {
+    const rotated = await createProgramMint();
+    const untouched = await createProgramMint();
+    const v2Authority = pda([Buffer.from("authority_v2"), rotated.toBuffer()]);
+
+    await rotate(rotated, await queueAndWait(adminActionId("rotate_program_authority", rotated.toBuffer())));
+
+    const mintInfo = await getMint(provider.connection, rotated);
+    assert.ok(mintInfo.freezeAuthority.equals(v2Authority));
+    const config = await program.account.mintConfig.fetch(mintConfigPda(rotated));
+    assert.equal(config.authorityVersion, 2);
+
+    // Freezes on the rotated mint are signed by the v2 PDA...
+    const holder = anchor.web3.Keypair.generate();
+    const rotatedAccount = await createFundedTokenAccount(rotated, holder.publicKey, 5);
+    await expectError(
+      program.methods
+        .freezeTokenAccount()
+        .accounts(freezeAccounts(rotated, rotatedAccount))
+        .rpc(),
+      "WrongFreezeAuthority"
+    );
+    await program.methods
+      .freezeTokenAccount()
+      .accounts(freezeAccounts(rotated, rotatedAccount, v2Authority))
+      .rpc();
+    assert.isTrue((await getAccount(provider.connection, rotatedAccount)).isFrozen);
+
+    // ...while the unrotated mint still freezes with the v1 PDA.
+    const untouchedAccount = await createFundedTokenAccount(untouched, holder.publicKey, 5);
+    await program.methods
+      .freezeTokenAccount()
+      .accounts(freezeAccounts(untouched, untouchedAccount))
+      .rpc();
+    assert.isTrue((await getAccount(provider.connection, untouchedAccount)).isFrozen);
+  });
+
+  it("refuses to rotate a mint twice", async () => {
+    const mint = await createProgramMint();
+    const actionId = adminActionId("rotate_program_authority", mint.toBuffer());
+    await rotate(mint, await queueAndWait(actionId));
+
+    await expectError(rotate(mint, await queueAndWait(actionId)), "AuthorityAlreadyRotated");
+  });
+});
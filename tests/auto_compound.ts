@@ -0,0 +1,199 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { adminActionId, expectError, i64Le, pda, programConfig } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  handMintAuthorityToProgram,
+  queueBankAction,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const DAY = 24 * 60 * 60;
+const BOUNTY_BPS = 100;
+// Mirrors staking::REWARD_PRECISION.
+const REWARD_PRECISION = new anchor.BN("1000000000000");
+
+function u16Le(value: number): Buffer {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(value);
+  return buf;
+}
+
+describe("auto-compounding", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let mintAuthority: anchor.web3.PublicKey;
+  let rewardPool: anchor.web3.PublicKey;
+  let rewardVault: anchor.web3.PublicKey;
+  let stakeVault: anchor.web3.PublicKey;
+  let stakerTokenAccount: anchor.web3.PublicKey;
+  let crankerTokenAccount: anchor.web3.PublicKey;
+  const cranker = anchor.web3.Keypair.generate();
+
+  const staker = () => bank.provider.wallet.publicKey;
+  const position = () => pda([Buffer.from("stake"), mint.toBuffer(), staker().toBuffer()]);
+  const balance = async (account: anchor.web3.PublicKey) =>
+    Number((await getAccount(bank.provider.connection, account)).amount);
+  const fetchPosition = () => bank.program.account.stakePosition.fetch(position());
+
+  async function superAdminCall(tag: string, args: Buffer[]) {
+    return queueBankAction(bank, adminActionId(tag, mint.toBuffer(), ...args));
+  }
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+
+    mint = await createBankMint(bank);
+    stakerTokenAccount = await createBankTokenAccount(bank, mint, staker(), 10_000);
+    crankerTokenAccount = await createBankTokenAccount(bank, mint, cranker.publicKey, 0);
+    mintAuthority = await handMintAuthorityToProgram(bank, mint);
+    rewardPool = pda([Buffer.from("reward_pool"), mint.toBuffer()]);
+    rewardVault = pda([Buffer.from("reward_vault"), rewardPool.toBuffer()]);
+    stakeVault = pda([Buffer.from("stake_vault"), rewardPool.toBuffer()]);
+
+    const rateBps = 1_000;
+    const interval = 60 * 60;
+    await bank.program.methods
+      .setInflation(rateBps, new anchor.BN(interval))
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction: await superAdminCall("set_inflation", [u16Le(rateBps), i64Le(interval)]),
+        mint,
+        mintAuthority,
+        rewardPool,
+        rewardVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await bank.program.methods
+      .setStakeConfig(new anchor.BN(0), 0, false)
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction: await superAdminCall("set_stake_config", [i64Le(0), u16Le(0), Buffer.from([0])]),
+        mint,
+        rewardPool,
+        stakeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await bank.program.methods
+      .setCompoundBounty(BOUNTY_BPS)
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction: await superAdminCall("set_compound_bounty", [u16Le(BOUNTY_BPS)]),
+        mint,
+        rewardPool,
+      })
+      .rpc();
+
+    await bank.program.methods
+      .stake(new anchor.BN(4_000))
+      .accounts({
+        staker: staker(),
+        rewardPool,
+        stakeVault,
+        position: position(),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  });
+
+  function compound() {
+    return bank.program.methods
+      .compoundPosition()
+      .accounts({
+        cranker: cranker.publicKey,
+        rewardPool,
+        rewardVault,
+        stakeVault,
+        position: position(),
+        crankerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([cranker])
+      .rpc();
+  }
+
+  function setAutoCompound(enabled: boolean) {
+    return bank.program.methods.setAutoCompound(enabled).accounts({ staker: staker(), position: position() }).rpc();
+  }
+
+  it("only compounds positions that opted in", async () => {
+    await expectError(compound(), "AutoCompoundDisabled");
+    await setAutoCompound(true);
+    assert.isTrue((await fetchPosition()).autoCompound);
+  });
+
+  it("does nothing while there is nothing to compound", async () => {
+    // A slot on, so the bank doesn't take this for the failed call above.
+    await warpTo(bank, await unixTimestamp(bank));
+    await compound();
+    assert.equal((await fetchPosition()).amount.toNumber(), 4_000);
+    assert.equal(await balance(crankerTokenAccount), 0);
+  });
+
+  it("restakes the rewards less the bounty, which goes to the cranker", async () => {
+    await warpTo(bank, (await unixTimestamp(bank)) + DAY);
+    await bank.program.methods
+      .crankInflation()
+      .accounts({ mint, mintAuthority, rewardPool, rewardVault, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+    const pool = await bank.program.account.rewardPool.fetch(rewardPool);
+    const before = await fetchPosition();
+    const rewards = before.amount
+      .mul(pool.rewardPerToken.sub(before.rewardPerTokenPaid))
+      .div(REWARD_PRECISION)
+      .toNumber();
+    assert.isAbove(rewards, 0);
+    const bounty = Math.floor((rewards * BOUNTY_BPS) / 10_000);
+
+    await compound();
+    const after = await fetchPosition();
+    assert.equal(after.amount.toNumber(), 4_000 + rewards - bounty);
+    assert.equal(after.pendingRewards.toNumber(), 0);
+    assert.equal(await balance(crankerTokenAccount), bounty);
+    assert.equal(await balance(stakeVault), 4_000 + rewards - bounty);
+    const poolAfter = await bank.program.account.rewardPool.fetch(rewardPool);
+    assert.equal(poolAfter.totalStaked.toNumber(), 4_000 + rewards - bounty);
+  });
+
+  it("leaves nothing more to claim", async () => {
+    const before = await balance(stakerTokenAccount);
+    await bank.program.methods
+      .claimStakeRewards()
+      .accounts({
+        staker: staker(),
+        rewardPool,
+        rewardVault,
+        position: position(),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    assert.equal(await balance(stakerTokenAccount), before);
+  });
+
+  it("stops once the staker opts out", async () => {
+    await setAutoCompound(false);
+    await warpTo(bank, await unixTimestamp(bank));
+    await expectError(compound(), "AutoCompoundDisabled");
+  });
+});
@@ -0,0 +1,162 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import {
+  ACCOUNT_SIZE,
+  AuthorityType,
+  TOKEN_PROGRAM_ID,
+  createInitializeAccountInstruction,
+  createMintToInstruction,
+  createSetAuthorityInstruction,
+} from "@solana/spl-token";
+import { CustomTokenProgram } from "../target/types/custom_token_program";
+import IDL from "../target/idl/custom_token_program.json";
+import { adminActionPda, mintConfigPda, pda, programAuthority, programConfig } from "./helpers";
+
+// An in-process bank for tests that need to move the clock. Each call starts
+// from a clean ledger with the program loaded from target/deploy.
+export interface Bank {
+  context: ProgramTestContext;
+  provider: BankrunProvider;
+  program: Program<CustomTokenProgram>;
+}
+
+export async function startBank(): Promise<Bank> {
+  const context = await startAnchor("", [], []);
+  const provider = new BankrunProvider(context);
+  const program = new Program<CustomTokenProgram>(IDL as CustomTokenProgram, provider);
+  return { context, provider, program };
+}
+
+export async function unixTimestamp(bank: Bank): Promise<number> {
+  const clock = await bank.context.banksClient.getClock();
+  return Number(clock.unixTimestamp);
+}
+
+// Set the bank's clock to `timestamp`. Also moves on one slot, so the same
+// transaction can be sent again afterwards without being deduplicated.
+export async function warpTo(bank: Bank, timestamp: number): Promise<void> {
+  const clock = await bank.context.banksClient.getClock();
+  const slot = clock.slot + BigInt(1);
+  bank.context.warpToSlot(slot);
+  bank.context.setClock(
+    new Clock(
+      slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(timestamp)
+    )
+  );
+}
+
+export async function fund(bank: Bank, to: anchor.web3.PublicKey, sol = 1): Promise<void> {
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.transfer({
+      fromPubkey: bank.provider.wallet.publicKey,
+      toPubkey: to,
+      lamports: sol * anchor.web3.LAMPORTS_PER_SOL,
+    })
+  );
+  await bank.provider.sendAndConfirm(tx);
+}
+
+// Same as helpers.createProgramMint, inside the bank.
+export async function createBankMint(bank: Bank, decimals = 6): Promise<anchor.web3.PublicKey> {
+  const mint = anchor.web3.Keypair.generate();
+  await bank.program.methods
+    .createTokenMint(decimals, bank.provider.wallet.publicKey)
+    .accounts({
+      mint: mint.publicKey,
+      programAuthority,
+      mintConfig: mintConfigPda(mint.publicKey),
+      payer: bank.provider.wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    })
+    .signers([mint])
+    .rpc();
+  return mint.publicKey;
+}
+
+// Same as helpers.createFundedTokenAccount, inside the bank.
+export async function createBankTokenAccount(
+  bank: Bank,
+  mint: anchor.web3.PublicKey,
+  owner: anchor.web3.PublicKey,
+  amount: number | bigint
+): Promise<anchor.web3.PublicKey> {
+  const account = anchor.web3.Keypair.generate();
+  const rent = await bank.context.banksClient.getRent();
+  const payer = bank.provider.wallet.publicKey;
+
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: payer,
+      newAccountPubkey: account.publicKey,
+      space: ACCOUNT_SIZE,
+      lamports: Number(rent.minimumBalance(BigInt(ACCOUNT_SIZE))),
+      programId: TOKEN_PROGRAM_ID,
+    }),
+    createInitializeAccountInstruction(account.publicKey, mint, owner)
+  );
+  if (BigInt(amount) > BigInt(0)) {
+    tx.add(createMintToInstruction(mint, account.publicKey, payer, amount));
+  }
+  await bank.provider.sendAndConfirm(tx, [account]);
+  return account.publicKey;
+}
+
+// Hand the mint authority to the program's `[b"mint_authority", mint]` PDA,
+// for instructions that mint on their own. Returns the PDA.
+export async function handMintAuthorityToProgram(
+  bank: Bank,
+  mint: anchor.web3.PublicKey
+): Promise<anchor.web3.PublicKey> {
+  const mintAuthority = pda([Buffer.from("mint_authority"), mint.toBuffer()]);
+  await bank.provider.sendAndConfirm(
+    new anchor.web3.Transaction().add(
+      createSetAuthorityInstruction(mint, bank.provider.wallet.publicKey, AuthorityType.MintTokens, mintAuthority)
+    )
+  );
+  return mintAuthority;
+}
+
+// Programs loaded by bankrun aren't upgradeable, so initialize_program_config
+// can't run. Write the ProgramConfig directly instead, with the bank's payer
+// as super_admin.
+export async function setProgramConfig(bank: Bank, timelockDelay = 0): Promise<void> {
+  const [address, bump] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("program_config")],
+    bank.program.programId
+  );
+  const data = await bank.program.coder.accounts.encode("programConfig", {
+    superAdmin: bank.provider.wallet.publicKey,
+    bump,
+    timelockDelay: new anchor.BN(timelockDelay),
+    gcRewardBps: 0,
+  });
+  bank.context.setAccount(address, {
+    lamports: anchor.web3.LAMPORTS_PER_SOL,
+    data,
+    owner: bank.program.programId,
+    executable: false,
+  });
+}
+
+// Queue an admin action; with a zero delay it is executable right away.
+export async function queueBankAction(bank: Bank, actionId: number[]): Promise<anchor.web3.PublicKey> {
+  const adminAction = adminActionPda(actionId);
+  await bank.program.methods
+    .queueAdminAction(actionId)
+    .accounts({
+      superAdmin: bank.provider.wallet.publicKey,
+      programConfig,
+      adminAction,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+  return adminAction;
+}
@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getMint } from "@solana/spl-token";
+import { expectError, mintConfigPda, programAuthority } from "./helpers";
+import { Bank, startBank } from "./bankrun";
+
+// Mirrors MAX_BATCH_MINTS.
+const MAX_BATCH_MINTS = 5;
+const PACKET_DATA_SIZE = 1232;
+
+describe("batch mint creation", () => {
+  let bank: Bank;
+  const authority = anchor.web3.Keypair.generate().publicKey;
+
+  before(async () => {
+    bank = await startBank();
+  });
+
+  const exists = async (address: anchor.web3.PublicKey) =>
+    (await bank.context.banksClient.getAccount(address)) !== null;
+
+  function batch(mints: anchor.web3.Keypair[], params = mints.map((_, i) => ({ decimals: i, mintAuthority: authority }))) {
+    return bank.program.methods
+      .createTokenMintsBatch(params)
+      .accounts({
+        payer: bank.provider.wallet.publicKey,
+        programAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        mints.flatMap((mint) => [
+          { pubkey: mint.publicKey, isSigner: true, isWritable: true },
+          { pubkey: mintConfigPda(mint.publicKey), isSigner: false, isWritable: true },
+        ])
+      )
+      .signers(mints);
+  }
+
+  // Bytes the signed transaction takes on the wire.
+  async function wireSize(mints: anchor.web3.Keypair[]) {
+    const tx = await batch(mints).transaction();
+    tx.feePayer = bank.provider.wallet.publicKey;
+    tx.recentBlockhash = bank.context.lastBlockhash;
+    const message = tx.compileMessage();
+    return 1 + 64 * message.header.numRequiredSignatures + message.serialize().length;
+  }
+
+  const keypairs = (n: number) => Array.from({ length: n }, () => anchor.web3.Keypair.generate());
+
+  it("creates MAX_BATCH_MINTS mints in one transaction", async () => {
+    const mints = keypairs(MAX_BATCH_MINTS);
+    assert.isAtMost(await wireSize(mints), PACKET_DATA_SIZE);
+    await batch(mints).rpc();
+
+    for (const [i, mint] of mints.entries()) {
+      const info = await getMint(bank.provider.connection, mint.publicKey);
+      assert.equal(info.decimals, i);
+      assert.ok(info.mintAuthority.equals(authority));
+      assert.ok(info.freezeAuthority.equals(programAuthority));
+      const config = await bank.program.account.mintConfig.fetch(mintConfigPda(mint.publicKey));
+      assert.ok(config.mint.equals(mint.publicKey));
+    }
+  });
+
+  it("couldn't fit one more mint in a transaction", async () => {
+    assert.isAbove(await wireSize(keypairs(MAX_BATCH_MINTS + 1)), PACKET_DATA_SIZE);
+  });
+
+  it("rejects more params than MAX_BATCH_MINTS", async () => {
+    const params = Array.from({ length: MAX_BATCH_MINTS + 1 }, () => ({ decimals: 0, mintAuthority: authority }));
+    await expectError(batch([], params).rpc(), "BatchTooLarge");
+  });
+
+  it("rejects params without a matching pair of accounts", async () => {
+    const mints = keypairs(2);
+    await expectError(batch(mints, [{ decimals: 0, mintAuthority: authority }]).rpc(), "InvalidRemainingAccounts");
+  });
+
+  it("creates nothing when one entry fails", async () => {
+    const mints = keypairs(3);
+    const params = mints.map((_, i) => ({ decimals: i === 2 ? 19 : 6, mintAuthority: authority }));
+    await expectError(batch(mints, params).rpc(), "InvalidDecimals");
+    for (const mint of mints) {
+      assert.isFalse(await exists(mint.publicKey));
+      assert.isFalse(await exists(mintConfigPda(mint.publicKey)));
+    }
+  });
+});
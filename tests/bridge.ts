@@ -0,0 +1,109 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  eventsFromTx,
+  eventsNamed,
+  expectError,
+  payer,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+import { u64Le } from "./merkle";
+
+describe("bridge lock / unlock", () => {
+  const operator = anchor.web3.Keypair.generate();
+  const recipientOwner = anchor.web3.Keypair.generate().publicKey;
+  let mint: anchor.web3.PublicKey;
+  let bridge: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+  let senderTokenAccount: anchor.web3.PublicKey;
+  let recipientTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    bridge = pda([Buffer.from("bridge"), mint.toBuffer()]);
+    vault = pda([Buffer.from("bridge_vault"), bridge.toBuffer()]);
+    senderTokenAccount = await createFundedTokenAccount(mint, payer.publicKey, 1_000);
+    recipientTokenAccount = await createFundedTokenAccount(mint, recipientOwner, 0);
+    await airdrop(operator.publicKey);
+
+    await program.methods
+      .initializeBridge(operator.publicKey)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        mint,
+        bridge,
+        vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  function lock(amount: number) {
+    return program.methods
+      .bridgeLock(new anchor.BN(amount), 2, [...Buffer.alloc(32, 7)])
+      .accounts({
+        sender: payer.publicKey,
+        bridge,
+        vault,
+        senderTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  function unlock(amount: number, sourceNonce: number, signer = operator) {
+    return program.methods
+      .bridgeUnlock(new anchor.BN(amount), new anchor.BN(sourceNonce), recipientOwner)
+      .accounts({
+        bridgeOperator: signer.publicKey,
+        bridge,
+        vault,
+        recipientTokenAccount,
+        processedNonce: pda([Buffer.from("processed_nonce"), bridge.toBuffer(), u64Le(sourceNonce)]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  it("locks tokens and numbers each lock", async () => {
+    const first = eventsNamed(await eventsFromTx(await lock(300)), "BridgeLocked");
+    const second = eventsNamed(await eventsFromTx(await lock(200)), "BridgeLocked");
+
+    assert.equal(first[0].data.nonce.toNumber(), 0);
+    assert.equal(second[0].data.nonce.toNumber(), 1);
+    assert.equal(second[0].data.destinationChain, 2);
+    assert.equal((await getAccount(provider.connection, vault)).amount, BigInt(500));
+  });
+
+  it("releases tokens for a message from the other chain", async () => {
+    await unlock(150, 42);
+    assert.equal((await getAccount(provider.connection, recipientTokenAccount)).amount, BigInt(150));
+    assert.equal((await getAccount(provider.connection, vault)).amount, BigInt(350));
+  });
+
+  it("refuses to replay a processed nonce", async () => {
+    try {
+      await unlock(150, 42);
+      assert.fail("expected the replay to fail");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+    assert.equal((await getAccount(provider.connection, recipientTokenAccount)).amount, BigInt(150));
+  });
+
+  it("only lets the bridge operator unlock", async () => {
+    const intruder = anchor.web3.Keypair.generate();
+    await airdrop(intruder.publicKey);
+    await expectError(unlock(10, 43, intruder), "Unauthorized");
+  });
+});
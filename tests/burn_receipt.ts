@@ -0,0 +1,86 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { randomBytes } from "crypto";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { expectError, mintConfigPda, pda } from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, startBank, unixTimestamp, warpTo } from "./bankrun";
+
+const RETENTION = 7 * 24 * 60 * 60;
+
+describe("burn receipts", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let burner: anchor.web3.PublicKey;
+  let burnerTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    bank = await startBank();
+    burner = bank.provider.wallet.publicKey;
+    mint = await createBankMint(bank);
+    burnerTokenAccount = await createBankTokenAccount(bank, mint, burner, 1_000);
+    await bank.program.methods
+      .setBurnReceiptRetention(new anchor.BN(RETENTION))
+      .accounts({ admin: burner, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  });
+
+  const receiptPda = (tag: Buffer) => pda([Buffer.from("burn"), burner.toBuffer(), tag]);
+  const balance = async () => (await getAccount(bank.provider.connection, burnerTokenAccount)).amount;
+
+  function burn(amount: number, tag: Buffer) {
+    return bank.program.methods
+      .burnWithReceipt(new anchor.BN(amount), [...tag])
+      .accounts({
+        burner,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        burnerTokenAccount,
+        receipt: receiptPda(tag),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function close(tag: Buffer) {
+    return bank.program.methods.closeBurnReceipt().accounts({ burner, receipt: receiptPda(tag) }).rpc();
+  }
+
+  const tag = randomBytes(32);
+  let burnedAt: number;
+
+  it("burns the tokens and records the receipt", async () => {
+    await burn(100, tag);
+    burnedAt = await unixTimestamp(bank);
+
+    assert.equal(await balance(), BigInt(900));
+    const receipt = await bank.program.account.burnReceipt.fetch(receiptPda(tag));
+    assert.ok(receipt.burner.equals(burner));
+    assert.ok(receipt.mint.equals(mint));
+    assert.equal(receipt.amount.toNumber(), 100);
+    assert.deepEqual(receipt.tag, [...tag]);
+    assert.isAbove(receipt.slot.toNumber(), 0);
+    assert.equal(receipt.closableAt.toNumber(), burnedAt + RETENTION);
+  });
+
+  it("rejects a second burn with the same tag", async () => {
+    try {
+      await burn(10, tag);
+      assert.fail("expected the duplicate tag to fail");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+    assert.equal(await balance(), BigInt(900));
+  });
+
+  it("refuses to close the receipt within the retention period", async () => {
+    await warpTo(bank, burnedAt + RETENTION - 1);
+    await expectError(close(tag), "BurnReceiptRetained");
+  });
+
+  it("closes it afterwards, returning the rent", async () => {
+    await warpTo(bank, burnedAt + RETENTION);
+    await close(tag);
+    assert.isNull(await bank.context.banksClient.getAccount(receiptPda(tag)));
+  });
+});
@@ -0,0 +1,159 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createAssociatedTokenAccountInstruction,
+  createMintToInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMetadataPointerState,
+  getMint,
+  getTokenMetadata,
+} from "@solana/spl-token";
+import { expectError, mintConfigPda, pda, programAuthority, programConfig } from "./helpers";
+import { Bank, setProgramConfig, startBank } from "./bankrun";
+
+describe("certificates", () => {
+  let bank: Bank;
+  const recipient = anchor.web3.Keypair.generate().publicKey;
+  const mint = anchor.web3.Keypair.generate();
+  const recipientTokenAccount = getAssociatedTokenAddressSync(mint.publicKey, recipient, false, TOKEN_2022_PROGRAM_ID);
+  const certificatePda = pda([Buffer.from("certificate"), mint.publicKey.toBuffer()]);
+
+  const superAdmin = () => bank.provider.wallet.publicKey;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    await bank.program.methods
+      .createCertificateMint(recipient, "Course completion", "CERT", "https://example.com/cert/1.json")
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        mint: mint.publicKey,
+        mintAuthority: pda([Buffer.from("mint_authority"), mint.publicKey.toBuffer()]),
+        programAuthority,
+        mintConfig: mintConfigPda(mint.publicKey),
+        certificate: certificatePda,
+        recipientWallet: recipient,
+        recipientTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mint])
+      .rpc();
+  });
+
+  function revoke(holderTokenAccount: anchor.web3.PublicKey) {
+    return bank.program.methods
+      .revokeCertificate()
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        certificate: certificatePda,
+        mint: mint.publicKey,
+        mintConfig: mintConfigPda(mint.publicKey),
+        holderTokenAccount,
+        freezeRecord: pda([Buffer.from("freeze_record"), holderTokenAccount.toBuffer()]),
+        programAuthority,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  const fetchMint = () => getMint(bank.provider.connection, mint.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+  const fetchAccount = (account: anchor.web3.PublicKey) =>
+    getAccount(bank.provider.connection, account, undefined, TOKEN_2022_PROGRAM_ID);
+
+  it("mints exactly one indivisible token to the recipient", async () => {
+    const info = await fetchMint();
+    assert.equal(info.decimals, 0);
+    assert.equal(Number(info.supply), 1);
+    assert.isNull(info.mintAuthority);
+    assert.ok(info.freezeAuthority.equals(programAuthority));
+    assert.equal(Number((await fetchAccount(recipientTokenAccount)).amount), 1);
+  });
+
+  it("can never mint again", async () => {
+    try {
+      await bank.provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          createMintToInstruction(mint.publicKey, recipientTokenAccount, superAdmin(), 1, [], TOKEN_2022_PROGRAM_ID)
+        )
+      );
+      assert.fail("minted a second certificate token");
+    } catch (err) {
+      // The token program's FixedSupply.
+      assert.match(String(err), /fixed|0x5/i);
+    }
+    assert.equal(Number((await fetchMint()).supply), 1);
+  });
+
+  it("writes token metadata into the mint that nobody can update", async () => {
+    const metadata = await getTokenMetadata(bank.provider.connection, mint.publicKey, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(metadata.name, "Course completion");
+    assert.equal(metadata.symbol, "CERT");
+    assert.equal(metadata.uri, "https://example.com/cert/1.json");
+    assert.ok(metadata.mint.equals(mint.publicKey));
+    assert.isUndefined(metadata.updateAuthority);
+
+    // Nor can the pointer be moved to other metadata.
+    const pointer = getMetadataPointerState(await fetchMint());
+    assert.ok(pointer.metadataAddress.equals(mint.publicKey));
+    assert.isNull(pointer.authority);
+
+    const certificate = await bank.program.account.certificate.fetch(certificatePda);
+    assert.ok(certificate.recipient.equals(recipient));
+  });
+
+  // Those instructions take SPL Token mints only; the mint authority is gone anyway.
+  it("can't be used with mint authority instructions", async () => {
+    await expectError(
+      bank.program.methods
+        .setDisplayConfig(null, "https://x.io/l.png", "https://example.com", [0, 0, 0])
+        .accounts({
+          admin: superAdmin(),
+          mint: mint.publicKey,
+          displayConfig: pda([Buffer.from("display_config"), mint.publicKey.toBuffer()]),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "AccountOwnedByWrongProgram"
+    );
+    await expectError(
+      bank.program.methods
+        .setRecoveryWindow(new anchor.BN(0))
+        .accounts({ admin: superAdmin(), mint: mint.publicKey, mintConfig: mintConfigPda(mint.publicKey) })
+        .rpc(),
+      "AccountOwnedByWrongProgram"
+    );
+  });
+
+  it("refuses to revoke through an account that doesn't hold it", async () => {
+    const stranger = anchor.web3.Keypair.generate().publicKey;
+    const empty = getAssociatedTokenAddressSync(mint.publicKey, stranger, false, TOKEN_2022_PROGRAM_ID);
+    await bank.provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(superAdmin(), empty, stranger, mint.publicKey, TOKEN_2022_PROGRAM_ID)
+      )
+    );
+    await expectError(revoke(empty), "CertificateNotHeld");
+  });
+
+  it("revokes by freezing the holder's account and recording it", async () => {
+    await revoke(recipientTokenAccount);
+    assert.isTrue((await fetchAccount(recipientTokenAccount)).isFrozen);
+
+    const certificate = await bank.program.account.certificate.fetch(certificatePda);
+    assert.isNotNull(certificate.revokedAt);
+    const record = await bank.program.account.freezeRecord.fetch(
+      pda([Buffer.from("freeze_record"), recipientTokenAccount.toBuffer()])
+    );
+    assert.ok(record.owner.equals(recipient));
+    assert.equal(record.amount.toNumber(), 1);
+  });
+});
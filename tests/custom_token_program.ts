@@ -1,16 +1,174 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
-import { CustomTokenProgram } from "../target/types/custom_token_program";
+import { assert } from "chai";
+import { createMint, getAccount } from "@solana/spl-token";
+import {
+  accountHistoryPda,
+  createFundedTokenAccount,
+  createProgramMint,
+  airdrop,
+  ensureProgramConfig,
+  expectError,
+  freezeAccounts,
+  freezerBondPda,
+  mintConfigPda,
+  retiredMintPda,
+  payer,
+  program,
+  programAuthority,
+  programConfig,
+  programData,
+  provider,
+  thawAccounts,
+  unitsConsumed,
+} from "./helpers";
+import { createBankMint, createBankTokenAccount, startBank } from "./bankrun";
 
 describe("custom_token_program", () => {
-  // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  describe("program config", () => {
+    it("rejects a config initialization from a non-upgrade-authority", async () => {
+      const intruder = anchor.web3.Keypair.generate();
+      await airdrop(intruder.publicKey);
 
-  const program = anchor.workspace.customTokenProgram as Program<CustomTokenProgram>;
+      await expectError(
+        program.methods
+          .initializeProgramConfig()
+          .accounts({
+            authority: intruder.publicKey,
+            program: program.programId,
+            programData,
+            programConfig,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([intruder])
+          .rpc(),
+        "NotUpgradeAuthority"
+      );
+    });
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+    it("lets the upgrade authority initialize the config as super_admin", async () => {
+      await ensureProgramConfig();
+
+      const config = await program.account.programConfig.fetch(programConfig);
+      assert.ok(config.superAdmin.equals(provider.wallet.publicKey));
+    });
+
+    it("refuses to initialize the config a second time", async () => {
+      try {
+        await program.methods
+          .initializeProgramConfig()
+          .accounts({
+            authority: provider.wallet.publicKey,
+            program: program.programId,
+            programData,
+            programConfig,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+        assert.fail("expected the init constraint to fail");
+      } catch (err) {
+        assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+      }
+    });
+  });
+
+  describe("cached bumps", () => {
+    // Upper bound for a freeze of an account frozen before, once every bump
+    // it needs is cached. Finding one with find_program_address instead
+    // costs ~1.5k CU on top of this.
+    const FREEZE_CU_BUDGET = 12_000;
+
+    it("stores the canonical authority bump in the mint config", async () => {
+      const mint = await createProgramMint();
+      const [, expectedBump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("authority")],
+        program.programId
+      );
+
+      const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+      assert.equal(config.authorityBump, expectedBump);
+      assert.ok(config.mint.equals(mint));
+    });
+
+    it("freezes for less with the bumps cached, within the CU budget", async () => {
+      const bank = await startBank();
+      const mint = await createBankMint(bank);
+      const holder = anchor.web3.Keypair.generate();
+      const tokenAccount = await createBankTokenAccount(bank, mint, holder.publicKey, 1_000);
+      const admin = bank.provider.wallet.publicKey;
+      const accounts = (build: (mint: anchor.web3.PublicKey, tokenAccount: anchor.web3.PublicKey) => object) => ({
+        ...build(mint, tokenAccount),
+        admin,
+        freezerBond: freezerBondPda(mint, admin),
+      });
+      const isFrozen = async () => (await getAccount(bank.provider.connection, tokenAccount)).isFrozen;
+
+      // What a freeze of the thawed account would cost, without making it.
+      async function freezeCost() {
+        const tx = await bank.program.methods.freezeTokenAccount().accounts(accounts(freezeAccounts)).transaction();
+        [tx.recentBlockhash] = await bank.context.banksClient.getLatestBlockhash();
+        tx.feePayer = admin;
+        tx.sign(bank.context.payer);
+        const sim = await bank.context.banksClient.simulateTransaction(tx);
+        return unitsConsumed(sim.meta.logMessages);
+      }
+
+      // The first freeze and thaw create the account history and cache the
+      // freeze exempt bump in it.
+      await bank.program.methods.freezeTokenAccount().accounts(accounts(freezeAccounts)).rpc();
+      assert.isTrue(await isFrozen());
+      await bank.program.methods.thawTokenAccount().accounts(accounts(thawAccounts)).rpc();
+      assert.isFalse(await isFrozen());
+      const cached = await freezeCost();
+
+      // The same freeze with the bump forgotten, so it has to be found again.
+      const historyPda = accountHistoryPda(tokenAccount);
+      const stored = await bank.context.banksClient.getAccount(historyPda);
+      const history = bank.program.coder.accounts.decode("accountHistory", Buffer.from(stored.data));
+      const encoded = await bank.program.coder.accounts.encode("accountHistory", { ...history, freezeExemptBump: null });
+      const data = Buffer.alloc(stored.data.length);
+      encoded.copy(data);
+      bank.context.setAccount(historyPda, { ...stored, data });
+      const uncached = await freezeCost();
+
+      assert.isBelow(cached, uncached);
+      assert.isBelow(cached, FREEZE_CU_BUDGET);
+    });
+
+    it("migrates a mint created before mint configs existed", async () => {
+      const mint = await createMint(
+        provider.connection,
+        payer,
+        provider.wallet.publicKey,
+        programAuthority,
+        6
+      );
+
+      await program.methods
+        .migrateMintConfig()
+        .accounts({
+          admin: provider.wallet.publicKey,
+          mint,
+          programAuthority,
+          mintConfig: mintConfigPda(mint),
+          retiredMint: retiredMintPda(mint),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      const [, expectedBump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("authority")],
+        program.programId
+      );
+      const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+      assert.equal(config.authorityBump, expectedBump);
+
+      const holder = anchor.web3.Keypair.generate();
+      const tokenAccount = await createFundedTokenAccount(mint, holder.publicKey, 10);
+      await program.methods
+        .freezeTokenAccount()
+        .accounts(freezeAccounts(mint, tokenAccount))
+        .rpc();
+      assert.isTrue((await getAccount(provider.connection, tokenAccount)).isFrozen);
+    });
   });
 });
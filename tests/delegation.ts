@@ -0,0 +1,265 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  getAccount,
+  getMintLen,
+  mintTo,
+} from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  mintConfigPda,
+  payer,
+  program,
+  provider,
+} from "./helpers";
+
+// A Token-2022 mint with the transfer-fee extension. Accounts for it carry a
+// TransferFeeAmount extension, so they are larger than a classic token account.
+async function createTransferFeeMint(): Promise<anchor.web3.PublicKey> {
+  const mint = anchor.web3.Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+  const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: mintLen,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mint.publicKey,
+      payer.publicKey,
+      payer.publicKey,
+      50,
+      BigInt(1_000),
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(
+      mint.publicKey,
+      6,
+      payer.publicKey,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    )
+  );
+  await provider.sendAndConfirm(tx, [mint]);
+  return mint.publicKey;
+}
+
+describe("delegation", () => {
+  it("delegates on a Token-2022 account with the transfer-fee extension", async () => {
+    const mint = await createTransferFeeMint();
+    const owner = anchor.web3.Keypair.generate();
+    const tokenAccount = await createAccount(
+      provider.connection,
+      payer,
+      mint,
+      owner.publicKey,
+      anchor.web3.Keypair.generate(),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(provider.connection, payer, mint, tokenAccount, payer, 1_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    const delegate = anchor.web3.Keypair.generate().publicKey;
+
+    await program.methods
+      .delegateTokens(new anchor.BN(250), null)
+      .accounts({
+        tokenAccount,
+        delegate,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        payer: payer.publicKey,
+        referralStats: null,
+      })
+      .signers([owner])
+      .rpc();
+
+    const account = await getAccount(provider.connection, tokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.ok(account.delegate.equals(delegate));
+    assert.equal(account.delegatedAmount, BigInt(250));
+  });
+
+  it("still delegates on a classic SPL Token account", async () => {
+    const mint = await createProgramMint();
+    const owner = anchor.web3.Keypair.generate();
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    const delegate = anchor.web3.Keypair.generate().publicKey;
+
+    await program.methods
+      .delegateTokens(new anchor.BN(40), null)
+      .accounts({
+        tokenAccount,
+        delegate,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        payer: payer.publicKey,
+        referralStats: null,
+      })
+      .signers([owner])
+      .rpc();
+
+    const account = await getAccount(provider.connection, tokenAccount);
+    assert.equal(account.delegatedAmount, BigInt(40));
+  });
+
+  it("rejects a token account paired with the other token program", async () => {
+    const mint = await createProgramMint();
+    const owner = anchor.web3.Keypair.generate();
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+
+    await expectError(
+      program.methods
+        .delegateTokens(new anchor.BN(40), null)
+        .accounts({
+          tokenAccount,
+          delegate: anchor.web3.Keypair.generate().publicKey,
+          owner: owner.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          mintConfig: mintConfigPda(mint),
+          feeRecipient: null,
+          approvedDelegate: null,
+          payer: payer.publicKey,
+          referralStats: null,
+        })
+        .signers([owner])
+        .rpc(),
+      "TokenProgramMismatch"
+    );
+  });
+
+  describe("validation", () => {
+    let mint: anchor.web3.PublicKey;
+    let owner: anchor.web3.Keypair;
+    let tokenAccount: anchor.web3.PublicKey;
+
+    before(async () => {
+      mint = await createProgramMint();
+      owner = anchor.web3.Keypair.generate();
+      tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    });
+
+    function delegate(amount: number, delegate: anchor.web3.PublicKey, signer = owner) {
+      return program.methods
+        .delegateTokens(new anchor.BN(amount), null)
+        .accounts({
+          tokenAccount,
+          delegate,
+          owner: signer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintConfig: mintConfigPda(mint),
+          feeRecipient: null,
+          approvedDelegate: null,
+          payer: payer.publicKey,
+          referralStats: null,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("rejects a signer that does not own the token account", async () => {
+      const stranger = anchor.web3.Keypair.generate();
+      await expectError(
+        delegate(10, anchor.web3.Keypair.generate().publicKey, stranger),
+        "NotTokenAccountOwner"
+      );
+    });
+
+    it("rejects delegating to the owner", async () => {
+      await expectError(delegate(10, owner.publicKey), "SelfDelegation");
+    });
+
+    it("rejects a zero amount", async () => {
+      await expectError(delegate(0, anchor.web3.Keypair.generate().publicKey), "ZeroAmount");
+    });
+
+    it("rejects more than the account holds", async () => {
+      await expectError(delegate(101, anchor.web3.Keypair.generate().publicKey), "AmountExceedsBalance");
+    });
+
+    it("rejects the default pubkey as a delegate", async () => {
+      await expectError(delegate(10, anchor.web3.PublicKey.default), "DefaultPubkey");
+    });
+
+    it("approves a valid delegation", async () => {
+      const target = anchor.web3.Keypair.generate().publicKey;
+      await delegate(10, target);
+      const account = await getAccount(provider.connection, tokenAccount);
+      assert.ok(account.delegate.equals(target));
+      assert.equal(account.delegatedAmount, BigInt(10));
+    });
+  });
+
+  describe("increase / decrease", () => {
+    let mint: anchor.web3.PublicKey;
+    let owner: anchor.web3.Keypair;
+    let tokenAccount: anchor.web3.PublicKey;
+    const delegate = anchor.web3.Keypair.generate().publicKey;
+
+    before(async () => {
+      mint = await createProgramMint();
+      owner = anchor.web3.Keypair.generate();
+      tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 1_000);
+    });
+
+    function adjust(method: "increaseDelegation" | "decreaseDelegation", amount: number, target = delegate) {
+      return program.methods[method](new anchor.BN(amount))
+        .accounts({
+          tokenAccount,
+          delegate: target,
+          owner: owner.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintConfig: mintConfigPda(mint),
+          feeRecipient: null,
+          approvedDelegate: null,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    it("stacks two increases on the same delegate", async () => {
+      await adjust("increaseDelegation", 100);
+      await adjust("increaseDelegation", 50);
+      const account = await getAccount(provider.connection, tokenAccount);
+      assert.ok(account.delegate.equals(delegate));
+      assert.equal(account.delegatedAmount, BigInt(150));
+    });
+
+    it("rejects an increase past the account balance", async () => {
+      await expectError(adjust("increaseDelegation", 851), "AmountExceedsBalance");
+    });
+
+    it("rejects adjusting the allowance of a different delegate", async () => {
+      const other = anchor.web3.Keypair.generate().publicKey;
+      await expectError(adjust("increaseDelegation", 10, other), "DelegateMismatch");
+      await expectError(adjust("decreaseDelegation", 10, other), "DelegateMismatch");
+    });
+
+    it("decreases, then clears the delegate when reduced past zero", async () => {
+      await adjust("decreaseDelegation", 30);
+      let account = await getAccount(provider.connection, tokenAccount);
+      assert.equal(account.delegatedAmount, BigInt(120));
+
+      await adjust("decreaseDelegation", 500);
+      account = await getAccount(provider.connection, tokenAccount);
+      assert.isNull(account.delegate);
+      assert.equal(account.delegatedAmount, BigInt(0));
+    });
+  });
+});
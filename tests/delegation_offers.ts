@@ -0,0 +1,238 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createApproveInstruction, createRevokeInstruction, getAccount } from "@solana/spl-token";
+import { expectError, mintConfigPda, pda } from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, fund, startBank, unixTimestamp, warpTo } from "./bankrun";
+
+describe("delegation offers", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  const delegate = anchor.web3.Keypair.generate();
+  const stranger = anchor.web3.Keypair.generate();
+
+  const owner = () => bank.provider.wallet.publicKey;
+  const offerPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("delegation_offer"), tokenAccount.toBuffer()]);
+  const recordPda = (tokenAccount: anchor.web3.PublicKey) => pda([Buffer.from("delegation"), tokenAccount.toBuffer()]);
+  const exists = async (address: anchor.web3.PublicKey) =>
+    (await bank.context.banksClient.getAccount(address)) !== null;
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+    await fund(bank, delegate.publicKey);
+    await fund(bank, stranger.publicKey);
+  });
+
+  function offer(tokenAccount: anchor.web3.PublicKey, amount: number, expiry: number) {
+    return bank.program.methods
+      .offerDelegation(delegate.publicKey, new anchor.BN(amount), new anchor.BN(expiry))
+      .accounts({
+        tokenAccount,
+        owner: owner(),
+        offer: offerPda(tokenAccount),
+        delegationRecord: recordPda(tokenAccount),
+        mintConfig: mintConfigPda(mint),
+        approvedDelegate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function accept(tokenAccount: anchor.web3.PublicKey, signer = delegate) {
+    return bank.program.methods
+      .acceptDelegation()
+      .accounts({
+        delegate: signer.publicKey,
+        owner: owner(),
+        offer: offerPda(tokenAccount),
+        tokenAccount,
+        delegationRecord: recordPda(tokenAccount),
+        mintConfig: mintConfigPda(mint),
+        // The mint authority, who collects delegation fees.
+        feeRecipient: owner(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  function withdraw(tokenAccount: anchor.web3.PublicKey) {
+    return bank.program.methods
+      .withdrawOffer()
+      .accounts({
+        owner: owner(),
+        offer: offerPda(tokenAccount),
+        tokenAccount,
+        delegationRecord: recordPda(tokenAccount),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  // By a stranger unless `byOwner`.
+  function closeExpired(tokenAccount: anchor.web3.PublicKey, byOwner = false) {
+    return bank.program.methods
+      .closeExpiredOffer()
+      .accounts({
+        caller: byOwner ? owner() : stranger.publicKey,
+        owner: owner(),
+        offer: offerPda(tokenAccount),
+        tokenAccount,
+        delegationRecord: recordPda(tokenAccount),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers(byOwner ? [] : [stranger])
+      .rpc();
+  }
+
+  it("hands the delegate a program-enforced allowance once it accepts", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    await offer(tokenAccount, 40, (await unixTimestamp(bank)) + 1_000);
+
+    // Approved to the record's address, which doesn't exist yet.
+    let account = await getAccount(bank.provider.connection, tokenAccount);
+    assert.ok(account.delegate.equals(recordPda(tokenAccount)));
+    assert.equal(Number(account.delegatedAmount), 40);
+    assert.isFalse(await exists(recordPda(tokenAccount)));
+
+    await accept(tokenAccount);
+    const record = await bank.program.account.delegationRecord.fetch(recordPda(tokenAccount));
+    assert.ok(record.delegate.equals(delegate.publicKey));
+    assert.ok(record.owner.equals(owner()));
+    assert.deepEqual(record.terms.mode, { programEnforced: {} });
+    assert.equal(record.terms.allowance.toNumber(), 40);
+    assert.isFalse(await exists(offerPda(tokenAccount)));
+    account = await getAccount(bank.provider.connection, tokenAccount);
+    assert.ok(account.delegate.equals(recordPda(tokenAccount)));
+  });
+
+  it("only lets the named delegate accept", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    await offer(tokenAccount, 10, (await unixTimestamp(bank)) + 1_000);
+    await expectError(accept(tokenAccount, stranger), "Unauthorized");
+    assert.isFalse(await exists(recordPda(tokenAccount)));
+  });
+
+  it("refuses to replace a delegate the owner already has", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    await bank.provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(createApproveInstruction(tokenAccount, stranger.publicKey, owner(), 5))
+    );
+    await expectError(offer(tokenAccount, 10, (await unixTimestamp(bank)) + 1_000), "DelegateAlreadySet");
+    const account = await getAccount(bank.provider.connection, tokenAccount);
+    assert.ok(account.delegate.equals(stranger.publicKey));
+    assert.isFalse(await exists(offerPda(tokenAccount)));
+  });
+
+  it("refuses an offer larger than the balance or already expired", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    const now = await unixTimestamp(bank);
+    await expectError(offer(tokenAccount, 101, now + 1_000), "AmountExceedsBalance");
+    await expectError(offer(tokenAccount, 10, now), "InvalidDeadline");
+  });
+
+  it("revokes the approval when the owner withdraws", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    await offer(tokenAccount, 10, (await unixTimestamp(bank)) + 1_000);
+    await withdraw(tokenAccount);
+
+    const account = await getAccount(bank.provider.connection, tokenAccount);
+    assert.isNull(account.delegate);
+    assert.isFalse(await exists(offerPda(tokenAccount)));
+  });
+
+  it("refuses an offer whose approval the owner has since revoked", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    await offer(tokenAccount, 10, (await unixTimestamp(bank)) + 1_000);
+    await bank.provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(createRevokeInstruction(tokenAccount, owner()))
+    );
+    await expectError(accept(tokenAccount), "DelegationOfferStale");
+    // The owner can still take the offer back.
+    await withdraw(tokenAccount);
+  });
+
+  describe("an offer that runs out", () => {
+    let tokenAccount: anchor.web3.PublicKey;
+    let expiry: number;
+
+    before(async () => {
+      tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+      expiry = (await unixTimestamp(bank)) + 100;
+      await offer(tokenAccount, 10, expiry);
+    });
+
+    it("can't be cleaned up before it expires", async () => {
+      await expectError(closeExpired(tokenAccount), "DelegationOfferActive");
+    });
+
+    it("can't be accepted after it expires", async () => {
+      await warpTo(bank, expiry);
+      await expectError(accept(tokenAccount), "DelegationOfferExpired");
+    });
+
+    it("can be closed by anyone, with the rent going to the owner", async () => {
+      const before = await bank.context.banksClient.getBalance(owner());
+      await closeExpired(tokenAccount);
+      assert.isFalse(await exists(offerPda(tokenAccount)));
+      assert.isTrue((await bank.context.banksClient.getBalance(owner())) > before);
+    });
+
+    it("leaves its approval to the record address, which doesn't stop a new offer", async () => {
+      let account = await getAccount(bank.provider.connection, tokenAccount);
+      assert.ok(account.delegate.equals(recordPda(tokenAccount)));
+      assert.isFalse(await exists(recordPda(tokenAccount)));
+
+      await offer(tokenAccount, 20, (await unixTimestamp(bank)) + 1_000);
+      account = await getAccount(bank.provider.connection, tokenAccount);
+      assert.equal(Number(account.delegatedAmount), 20);
+      await withdraw(tokenAccount);
+    });
+
+    it("revokes its approval when the owner closes it", async () => {
+      const closed = await createBankTokenAccount(bank, mint, owner(), 100);
+      const expiresAt = (await unixTimestamp(bank)) + 100;
+      await offer(closed, 10, expiresAt);
+      await warpTo(bank, expiresAt);
+      await closeExpired(closed, true);
+
+      const account = await getAccount(bank.provider.connection, closed);
+      assert.isNull(account.delegate);
+      assert.isFalse(await exists(offerPda(closed)));
+    });
+  });
+
+  describe("with a delegation fee", () => {
+    const FEE = 5_000;
+
+    before(async () => {
+      await bank.program.methods
+        .setFeeTier({ delegationFee: new anchor.BN(FEE), thawFee: new anchor.BN(0), transferTaxBps: 0 })
+        .accounts({ admin: owner(), mint, mintConfig: mintConfigPda(mint) })
+        .rpc();
+    });
+
+    const balance = async (address: anchor.web3.PublicKey) =>
+      Number(await bank.context.banksClient.getBalance(address));
+
+    it("charges the delegate when it accepts, and the owner nothing for offering", async () => {
+      const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+      // Offering takes no fee recipient at all.
+      await offer(tokenAccount, 10, (await unixTimestamp(bank)) + 1_000);
+
+      const ownerBefore = await balance(owner());
+      const delegateBefore = await balance(delegate.publicKey);
+      await accept(tokenAccount);
+
+      const record = await bank.context.banksClient.getAccount(recordPda(tokenAccount));
+      const rent = await bank.context.banksClient.getRent();
+      const recordRent = Number(rent.minimumBalance(BigInt(record.data.length)));
+      assert.equal(await balance(delegate.publicKey), delegateBefore - recordRent - FEE);
+      // The owner pays the transaction fee as the bank's payer, and gets the
+      // fee and the offer's rent.
+      assert.isAbove(await balance(owner()), ownerBefore);
+    });
+  });
+});
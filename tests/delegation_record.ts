@@ -0,0 +1,223 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount, transfer } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+
+const DAY = 24 * 60 * 60;
+
+describe("delegation records", () => {
+  let mint: anchor.web3.PublicKey;
+  let destination: anchor.web3.PublicKey;
+  const delegate = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    mint = await createProgramMint();
+    destination = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await airdrop(delegate.publicKey);
+  });
+
+  const recordPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("delegation"), tokenAccount.toBuffer()]);
+
+  async function createDelegation(
+    mode: object,
+    allowance = 100,
+    periodLimit = 30,
+    allowedDestinations: anchor.web3.PublicKey[] = []
+  ) {
+    const owner = anchor.web3.Keypair.generate();
+    await airdrop(owner.publicKey);
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 1_000);
+    await program.methods
+      .createDelegation(
+        {
+          allowance: new anchor.BN(allowance),
+          mode,
+          expiresAt: null,
+          periodSeconds: new anchor.BN(DAY),
+          periodLimit: new anchor.BN(periodLimit),
+        },
+        allowedDestinations
+      )
+      .accounts({
+        tokenAccount,
+        delegate: delegate.publicKey,
+        owner: owner.publicKey,
+        delegationRecord: recordPda(tokenAccount),
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+    return { owner, tokenAccount };
+  }
+
+  function spend(tokenAccount: anchor.web3.PublicKey, amount: number, to = destination) {
+    return program.methods
+      .spendDelegated(new anchor.BN(amount))
+      .accounts({
+        delegate: delegate.publicKey,
+        delegationRecord: recordPda(tokenAccount),
+        tokenAccount,
+        mint,
+        destination: to,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+      })
+      .signers([delegate])
+      .rpc();
+  }
+
+  describe("program-enforced", () => {
+    let tokenAccount: anchor.web3.PublicKey;
+    let owner: anchor.web3.Keypair;
+
+    before(async () => {
+      ({ owner, tokenAccount } = await createDelegation({ programEnforced: {} }));
+    });
+
+    it("makes the record PDA the SPL delegate", async () => {
+      const account = await getAccount(provider.connection, tokenAccount);
+      assert.ok(account.delegate.equals(recordPda(tokenAccount)));
+      assert.equal(account.delegatedAmount, BigInt(100));
+    });
+
+    it("stops the delegate spending directly", async () => {
+      try {
+        await transfer(provider.connection, payer, tokenAccount, destination, delegate, 10);
+        assert.fail("direct transfer by the delegate succeeded");
+      } catch (err) {
+        assert.match((err.logs ?? []).join("\n") || String(err), /owner does not match|0x4/);
+      }
+    });
+
+    it("spends through the program within the period limit", async () => {
+      await spend(tokenAccount, 20);
+      await spend(tokenAccount, 10);
+      const record = await program.account.delegationRecord.fetch(recordPda(tokenAccount));
+      assert.equal(record.spentTotal.toNumber(), 30);
+      assert.equal(record.spentThisPeriod.toNumber(), 30);
+      assert.equal((await getAccount(provider.connection, destination)).amount, BigInt(30));
+    });
+
+    it("refuses to go over the period limit", async () => {
+      await expectError(spend(tokenAccount, 1), "DelegationPeriodLimitExceeded");
+    });
+
+    it("refuses anyone but the delegate", async () => {
+      const stranger = anchor.web3.Keypair.generate();
+      await expectError(
+        program.methods
+          .spendDelegated(new anchor.BN(1))
+          .accounts({
+            delegate: stranger.publicKey,
+            delegationRecord: recordPda(tokenAccount),
+            tokenAccount,
+            mint,
+            destination,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            mintConfig: mintConfigPda(mint),
+          })
+          .signers([stranger])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+
+    it("revokes and closes on close_delegation", async () => {
+      await program.methods
+        .closeDelegation()
+        .accounts({
+          owner: owner.publicKey,
+          delegationRecord: recordPda(tokenAccount),
+          tokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintConfig: mintConfigPda(mint),
+        })
+        .signers([owner])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(recordPda(tokenAccount)));
+      assert.isNull((await getAccount(provider.connection, tokenAccount)).delegate);
+    });
+  });
+
+  describe("advisory", () => {
+    it("leaves the delegate able to spend directly, outside the record", async () => {
+      const { tokenAccount } = await createDelegation({ advisory: {} }, 50, 50);
+      await transfer(provider.connection, payer, tokenAccount, destination, delegate, 10);
+      await spend(tokenAccount, 20);
+
+      const record = await program.account.delegationRecord.fetch(recordPda(tokenAccount));
+      assert.equal(record.spentTotal.toNumber(), 20);
+      assert.equal((await getAccount(provider.connection, tokenAccount)).delegatedAmount, BigInt(20));
+      await expectError(spend(tokenAccount, 31), "DelegationAllowanceExceeded");
+    });
+  });
+
+  describe("allowed destinations", () => {
+    let owner: anchor.web3.Keypair;
+    let tokenAccount: anchor.web3.PublicKey;
+    let settlement: anchor.web3.PublicKey;
+
+    before(async () => {
+      settlement = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+      ({ owner, tokenAccount } = await createDelegation({ programEnforced: {} }, 100, 100, [settlement]));
+    });
+
+    function updateDestinations(allowed: anchor.web3.PublicKey[]) {
+      return program.methods
+        .updateDelegationDestinations(allowed)
+        .accounts({ owner: owner.publicKey, delegationRecord: recordPda(tokenAccount) })
+        .signers([owner])
+        .rpc();
+    }
+
+    it("pays an allowed destination", async () => {
+      await spend(tokenAccount, 10, settlement);
+      assert.equal((await getAccount(provider.connection, settlement)).amount, BigInt(10));
+    });
+
+    it("refuses any other destination", async () => {
+      await expectError(spend(tokenAccount, 10), "DestinationNotAllowed");
+    });
+
+    it("pays the other destination once the owner adds it", async () => {
+      await updateDestinations([settlement, destination]);
+      await spend(tokenAccount, 10);
+      await updateDestinations([]);
+      await spend(tokenAccount, 10);
+      const record = await program.account.delegationRecord.fetch(recordPda(tokenAccount));
+      assert.equal(record.spentTotal.toNumber(), 30);
+    });
+
+    it("caps the list at four entries", async () => {
+      const five = Array.from({ length: 5 }, () => anchor.web3.Keypair.generate().publicKey);
+      await expectError(updateDestinations(five), "BatchTooLarge");
+    });
+
+    it("only lets the owner amend it", async () => {
+      const stranger = anchor.web3.Keypair.generate();
+      await expectError(
+        program.methods
+          .updateDelegationDestinations([])
+          .accounts({ owner: stranger.publicKey, delegationRecord: recordPda(tokenAccount) })
+          .signers([stranger])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+  });
+});
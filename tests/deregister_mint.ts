@@ -0,0 +1,131 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getMint } from "@solana/spl-token";
+import {
+  adminActionId,
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  eventsFromTx,
+  eventsNamed,
+  expectError,
+  freezeAccounts,
+  mintConfigPda,
+  retiredMintPda,
+  program,
+  programAuthority,
+  programConfig,
+  provider,
+  queueAndWait,
+  thawAccounts,
+} from "./helpers";
+
+describe("deregister_mint", () => {
+  const admin = provider.wallet.publicKey;
+  const newFreezeAuthority = anchor.web3.Keypair.generate().publicKey;
+  let mint: anchor.web3.PublicKey;
+  let frozen: anchor.web3.PublicKey;
+  let adminAction: anchor.web3.PublicKey;
+
+  before(async () => {
+    await ensureProgramConfig();
+    mint = await createProgramMint();
+    frozen = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, frozen)).rpc();
+    adminAction = await queueAndWait(adminActionId("deregister_mint", mint.toBuffer(), newFreezeAuthority.toBuffer()));
+  });
+
+  function deregister() {
+    return program.methods
+      .deregisterMint(newFreezeAuthority)
+      .accounts({
+        admin,
+        superAdmin: admin,
+        programConfig,
+        adminAction,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        retiredMint: retiredMintPda(mint),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  it("refuses while an account of the mint is frozen", async () => {
+    await expectError(deregister(), "FrozenAccountsOutstanding");
+  });
+
+  it("hands the freeze authority over and closes the MintConfig once all are thawed", async () => {
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, frozen)).rpc();
+    const sig = await deregister();
+
+    const mintInfo = await getMint(provider.connection, mint);
+    assert.ok(mintInfo.freezeAuthority.equals(newFreezeAuthority));
+    assert.isNull(await provider.connection.getAccountInfo(mintConfigPda(mint)));
+    assert.isNull(await provider.connection.getAccountInfo(adminAction));
+
+    const [event] = eventsNamed(await eventsFromTx(sig), "MintDeregistered");
+    assert.ok(event.data.mint.equals(mint));
+    assert.ok(event.data.newFreezeAuthority.equals(newFreezeAuthority));
+
+    const retired = await program.account.retiredMint.fetch(retiredMintPda(mint));
+    assert.equal(retired.sequence.toNumber(), event.data.sequence.toNumber());
+  });
+});
+
+describe("re-registering a deregistered mint", () => {
+  const admin = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+  let lastSequence: number;
+
+  before(async () => {
+    await ensureProgramConfig();
+    mint = await createProgramMint();
+    tokenAccount = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, tokenAccount)).rpc();
+
+    // Hand the freeze authority back to the admin, who can then register the mint again.
+    const sig = await program.methods
+      .deregisterMint(admin)
+      .accounts({
+        admin,
+        superAdmin: admin,
+        programConfig,
+        adminAction: await queueAndWait(adminActionId("deregister_mint", mint.toBuffer(), admin.toBuffer())),
+        mint,
+        mintConfig: mintConfigPda(mint),
+        retiredMint: retiredMintPda(mint),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const [event] = eventsNamed(await eventsFromTx(sig), "MintDeregistered");
+    lastSequence = event.data.sequence.toNumber();
+  });
+
+  it("carries the sequence on instead of starting it over", async () => {
+    await program.methods
+      .registerExistingMint()
+      .accounts({
+        admin,
+        mint,
+        programAuthority,
+        mintConfig: mintConfigPda(mint),
+        retiredMint: retiredMintPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.sequence.toNumber(), lastSequence);
+
+    const sig = await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+    const [frozen] = eventsNamed(await eventsFromTx(sig), "AccountFrozen");
+    assert.equal(frozen.data.sequence.toNumber(), lastSequence + 1);
+  });
+});
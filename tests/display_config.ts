@@ -0,0 +1,63 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createProgramMint, expectError, pda, program, provider } from "./helpers";
+
+describe("display config", () => {
+  const admin = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  let displayConfig: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    displayConfig = pda([Buffer.from("display_config"), mint.toBuffer()]);
+  });
+
+  function setDisplay(logoUri: string, projectUrl: string, uiDecimals: number | null = 2) {
+    return program.methods
+      .setDisplayConfig(uiDecimals, logoUri, projectUrl, [0x12, 0x34, 0x56])
+      .accounts({ admin, mint, displayConfig, systemProgram: anchor.web3.SystemProgram.programId })
+      .rpc();
+  }
+
+  const dataLength = async () => (await provider.connection.getAccountInfo(displayConfig)).data.length;
+  const view = () => program.methods.getDisplayConfig().accounts({ displayConfig }).view();
+
+  const shortLogo = "https://x.io/l.png";
+  const longLogo = `https://cdn.example.com/${"a".repeat(150)}/logo.png`;
+  const projectUrl = "https://example.com";
+
+  it("creates the DisplayConfig sized to its strings", async () => {
+    await setDisplay(shortLogo, projectUrl);
+    const display = await view();
+    assert.equal(display.logoUri, shortLogo);
+    assert.equal(display.projectUrl, projectUrl);
+    assert.equal(display.uiDecimals, 2);
+    assert.deepEqual(display.color, [0x12, 0x34, 0x56]);
+  });
+
+  it("grows when a longer URI comes in", async () => {
+    const before = await dataLength();
+    await setDisplay(longLogo, projectUrl, null);
+    assert.equal(await dataLength(), before + longLogo.length - shortLogo.length);
+
+    const display = await program.account.displayConfig.fetch(displayConfig);
+    assert.equal(display.logoUri, longLogo);
+    assert.isNull(display.uiDecimals);
+  });
+
+  it("keeps its size, and reads back cleanly, when the URI gets shorter again", async () => {
+    const before = await dataLength();
+    await setDisplay(shortLogo, projectUrl);
+    assert.equal(await dataLength(), before);
+
+    const display = await view();
+    assert.equal(display.logoUri, shortLogo);
+    assert.equal(display.projectUrl, projectUrl);
+    assert.deepEqual(display.color, [0x12, 0x34, 0x56]);
+  });
+
+  it("refuses strings over their limits", async () => {
+    await expectError(setDisplay("a".repeat(201), projectUrl), "LogoUriTooLong");
+    await expectError(setDisplay(shortLogo, "a".repeat(201)), "ProjectUrlTooLong");
+  });
+});
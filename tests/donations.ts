@@ -0,0 +1,101 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { randomBytes } from "crypto";
+import { TOKEN_PROGRAM_ID, getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  eventsFromTx,
+  eventsNamed,
+  expectError,
+  payer,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("donations", () => {
+  const donor = anchor.web3.Keypair.generate();
+  const treasury = pda([Buffer.from("treasury")]);
+  const donorStats = pda([Buffer.from("donor"), donor.publicKey.toBuffer()]);
+  let donorTokenAccount: anchor.web3.PublicKey;
+  let treasuryTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    await airdrop(donor.publicKey, 2);
+    const mint = await createProgramMint();
+    donorTokenAccount = await createFundedTokenAccount(mint, donor.publicKey, 1_000);
+    treasuryTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, treasury, true))
+      .address;
+  });
+
+  function receiptPda(index: number) {
+    const le = Buffer.alloc(8);
+    le.writeBigUInt64LE(BigInt(index));
+    return pda([Buffer.from("donation"), donor.publicKey.toBuffer(), le]);
+  }
+
+  async function donate(tokenAmount: number, lamports: number, memoHash = randomBytes(32)) {
+    const count = (await program.account.donorStats.fetchNullable(donorStats))?.donationCount.toNumber() ?? 0;
+    const withTokens = tokenAmount > 0;
+    return program.methods
+      .donate(new anchor.BN(tokenAmount), new anchor.BN(lamports), [...memoHash])
+      .accounts({
+        donor: donor.publicKey,
+        treasury,
+        donorStats,
+        receipt: receiptPda(count),
+        donorTokenAccount: withTokens ? donorTokenAccount : null,
+        treasuryTokenAccount: withTokens ? treasuryTokenAccount : null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([donor])
+      .rpc();
+  }
+
+  const tokens = async (account: anchor.web3.PublicKey) => (await getAccount(provider.connection, account)).amount;
+
+  it("rejects a donation of nothing", async () => {
+    await expectError(donate(0, 0), "EmptyDonation");
+  });
+
+  it("takes a token-only donation", async () => {
+    const memoHash = randomBytes(32);
+    const sig = await donate(100, 0, memoHash);
+    assert.equal(await tokens(treasuryTokenAccount), BigInt(100));
+
+    const receipt = await program.account.donationReceipt.fetch(receiptPda(0));
+    assert.equal(receipt.index.toNumber(), 0);
+    assert.equal(receipt.tokenAmount.toNumber(), 100);
+    assert.equal(receipt.lamports.toNumber(), 0);
+    assert.deepEqual(receipt.memoHash, [...memoHash]);
+
+    const [event] = eventsNamed(await eventsFromTx(sig), "DonationReceived");
+    assert.ok(event.data.donor.equals(donor.publicKey));
+    assert.equal(event.data.tokenAmount.toNumber(), 100);
+  });
+
+  it("takes a SOL-only donation", async () => {
+    const before = await provider.connection.getBalance(treasury);
+    await donate(0, 50_000);
+    assert.equal(await provider.connection.getBalance(treasury), before + 50_000);
+
+    const receipt = await program.account.donationReceipt.fetch(receiptPda(1));
+    assert.isNull(receipt.mint);
+    assert.equal(receipt.lamports.toNumber(), 50_000);
+  });
+
+  it("takes both at once, numbering receipts per donor", async () => {
+    await donate(25, 10_000);
+    const receipt = await program.account.donationReceipt.fetch(receiptPda(2));
+    assert.equal(receipt.index.toNumber(), 2);
+    assert.equal(receipt.tokenAmount.toNumber(), 25);
+    assert.equal(receipt.lamports.toNumber(), 10_000);
+    assert.equal(await tokens(treasuryTokenAccount), BigInt(125));
+
+    const stats = await program.account.donorStats.fetch(donorStats);
+    assert.equal(stats.donationCount.toNumber(), 3);
+  });
+});
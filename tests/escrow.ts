@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop as fundSol,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  payer,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("escrowed delegation", () => {
+  const owner = payer.publicKey;
+  const delegate = anchor.web3.Keypair.generate();
+
+  let mint: anchor.web3.PublicKey;
+  let ownerTokenAccount: anchor.web3.PublicKey;
+  let thirdParty: anchor.web3.PublicKey;
+  let escrow: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    ownerTokenAccount = await createFundedTokenAccount(mint, owner, 1_000);
+    thirdParty = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    escrow = pda([Buffer.from("escrow"), mint.toBuffer(), owner.toBuffer(), delegate.publicKey.toBuffer()]);
+    vault = pda([Buffer.from("escrow_vault"), escrow.toBuffer()]);
+    await fundSol(delegate.publicKey);
+  });
+
+  function spend(amount: number, signer = delegate) {
+    return program.methods
+      .escrowSpend(new anchor.BN(amount))
+      .accounts({
+        delegate: signer.publicKey,
+        escrow,
+        vault,
+        destination: thirdParty,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  it("moves the escrowed amount out of the owner's account", async () => {
+    await program.methods
+      .escrowDelegate(new anchor.BN(100))
+      .accounts({
+        owner,
+        delegate: delegate.publicKey,
+        mint,
+        ownerTokenAccount,
+        escrow,
+        vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    assert.equal((await getAccount(provider.connection, ownerTokenAccount)).amount, BigInt(900));
+    assert.equal((await getAccount(provider.connection, vault)).amount, BigInt(100));
+    const state = await program.account.escrow.fetch(escrow);
+    assert.equal(state.remaining.toNumber(), 100);
+  });
+
+  it("only lets the named delegate spend", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await fundSol(stranger.publicKey);
+    await expectError(spend(10, stranger), "Unauthorized");
+  });
+
+  it("lets the delegate spend to a third party", async () => {
+    await spend(60);
+    assert.equal((await getAccount(provider.connection, thirdParty)).amount, BigInt(60));
+    const state = await program.account.escrow.fetch(escrow);
+    assert.equal(state.remaining.toNumber(), 40);
+  });
+
+  it("refuses to spend more than remains", async () => {
+    await expectError(spend(41), "InsufficientEscrow");
+  });
+
+  it("returns the rest to the owner, after which nothing can be spent", async () => {
+    await program.methods
+      .escrowReclaim()
+      .accounts({
+        owner,
+        escrow,
+        vault,
+        ownerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    assert.equal((await getAccount(provider.connection, ownerTokenAccount)).amount, BigInt(940));
+    assert.isNull(await provider.connection.getAccountInfo(escrow));
+    assert.isNull(await provider.connection.getAccountInfo(vault));
+    await expectError(spend(1), "AccountNotInitialized");
+  });
+});
@@ -0,0 +1,155 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  mintConfigPda,
+  payer,
+  program,
+  provider,
+} from "./helpers";
+
+interface Tier {
+  delegationFee: number;
+  thawFee: number;
+  transferTaxBps: number;
+}
+
+function setFeeTier(mint: anchor.web3.PublicKey, tier: Tier, admin = payer) {
+  return program.methods
+    .setFeeTier({
+      delegationFee: new anchor.BN(tier.delegationFee),
+      thawFee: new anchor.BN(tier.thawFee),
+      transferTaxBps: tier.transferTaxBps,
+    })
+    .accounts({ admin: admin.publicKey, mint, mintConfig: mintConfigPda(mint) })
+    .signers([admin])
+    .rpc();
+}
+
+describe("fee tiers", () => {
+  // The provider wallet is the mint authority, so it collects every fee.
+  const feeRecipient = payer.publicKey;
+  const cheap: Tier = { delegationFee: 0, thawFee: 0, transferTaxBps: 500 };
+  const pricey: Tier = { delegationFee: 5_000, thawFee: 0, transferTaxBps: 100 };
+  let cheapMint: anchor.web3.PublicKey;
+  let priceyMint: anchor.web3.PublicKey;
+
+  before(async () => {
+    cheapMint = await createProgramMint();
+    priceyMint = await createProgramMint();
+  });
+
+  it("starts every mint at the zero tier", async () => {
+    const config = await program.account.mintConfig.fetch(mintConfigPda(cheapMint));
+    assert.equal(config.feeTier.delegationFee.toNumber(), 0);
+    assert.equal(config.feeTier.thawFee.toNumber(), 0);
+    assert.equal(config.feeTier.transferTaxBps, 0);
+  });
+
+  it("rejects tiers above the caps and callers other than the mint authority", async () => {
+    await expectError(setFeeTier(cheapMint, { ...cheap, transferTaxBps: 1_001 }), "FeeAboveCap");
+    await expectError(setFeeTier(cheapMint, { ...cheap, delegationFee: 10_000_001 }), "FeeAboveCap");
+    await expectError(setFeeTier(cheapMint, { ...cheap, thawFee: 10_000_001 }), "FeeAboveCap");
+
+    const stranger = anchor.web3.Keypair.generate();
+    await airdrop(stranger.publicKey);
+    await expectError(setFeeTier(cheapMint, cheap, stranger), "Unauthorized");
+  });
+
+  it("stores a separate tier per mint", async () => {
+    await setFeeTier(cheapMint, cheap);
+    await setFeeTier(priceyMint, pricey);
+
+    const a = await program.account.mintConfig.fetch(mintConfigPda(cheapMint));
+    const b = await program.account.mintConfig.fetch(mintConfigPda(priceyMint));
+    assert.equal(a.feeTier.transferTaxBps, 500);
+    assert.equal(b.feeTier.transferTaxBps, 100);
+    assert.equal(b.feeTier.delegationFee.toNumber(), 5_000);
+    assert.ok(b.feeRecipient.equals(feeRecipient));
+  });
+
+  describe("delegation fee", () => {
+    const owner = anchor.web3.Keypair.generate();
+
+    before(async () => {
+      await airdrop(owner.publicKey);
+    });
+
+    async function delegate(mint: anchor.web3.PublicKey, recipient: anchor.web3.PublicKey | null) {
+      const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+      return program.methods
+        .delegateTokens(new anchor.BN(10), null)
+        .accounts({
+          tokenAccount,
+          delegate: anchor.web3.Keypair.generate().publicKey,
+          owner: owner.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintConfig: mintConfigPda(mint),
+          feeRecipient: recipient,
+          approvedDelegate: null,
+          payer: payer.publicKey,
+          referralStats: null,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    const ownerLamports = () => provider.connection.getBalance(owner.publicKey);
+
+    it("charges the owner the mint's delegation fee", async () => {
+      const before = await ownerLamports();
+      await delegate(priceyMint, feeRecipient);
+      assert.equal(before - (await ownerLamports()), 5_000);
+    });
+
+    it("charges nothing on a mint without a delegation fee", async () => {
+      const before = await ownerLamports();
+      await delegate(cheapMint, null);
+      assert.equal(await ownerLamports(), before);
+    });
+
+    it("rejects a missing or wrong fee recipient when a fee is due", async () => {
+      await expectError(delegate(priceyMint, null), "WrongFeeRecipient");
+      await expectError(delegate(priceyMint, anchor.web3.Keypair.generate().publicKey), "WrongFeeRecipient");
+    });
+  });
+
+  describe("transfer tax", () => {
+    async function transfer(mint: anchor.web3.PublicKey, amount: number) {
+      const from = await createFundedTokenAccount(mint, payer.publicKey, amount);
+      const to = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+      const feeTokenAccount = await createFundedTokenAccount(mint, feeRecipient, 0);
+      await program.methods
+        .transferTokens(new anchor.BN(amount), false)
+        .accounts({
+          owner: payer.publicKey,
+          mint,
+          mintConfig: mintConfigPda(mint),
+          from,
+          to,
+          feeTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          fromCheckpoints: null,
+          toCheckpoints: null,
+        })
+        .rpc();
+      return {
+        received: Number((await getAccount(provider.connection, to)).amount),
+        tax: Number((await getAccount(provider.connection, feeTokenAccount)).amount),
+      };
+    }
+
+    it("takes each mint's own rate", async () => {
+      assert.deepEqual(await transfer(cheapMint, 1_000), { received: 950, tax: 50 });
+      assert.deepEqual(await transfer(priceyMint, 1_000), { received: 990, tax: 10 });
+    });
+
+    it("rounds the tax down", async () => {
+      assert.deepEqual(await transfer(priceyMint, 99), { received: 99, tax: 0 });
+    });
+  });
+});
@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, approve, getAccount } from "@solana/spl-token";
+import {
+  accountHistoryPda,
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  expectError,
+  freezeExemptPda,
+  freezeRecordPda,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  programAuthority,
+  programConfig,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+describe("flagged delegates", () => {
+  const superAdmin = provider.wallet.publicKey;
+  const drainer = anchor.web3.Keypair.generate().publicKey;
+  const bystander = anchor.web3.Keypair.generate();
+  let mint: anchor.web3.PublicKey;
+
+  const flaggedDelegatePda = (delegate: anchor.web3.PublicKey) =>
+    pda([Buffer.from("flagged_delegate"), delegate.toBuffer()]);
+
+  before(async () => {
+    await ensureProgramConfig();
+    await airdrop(bystander.publicKey);
+    mint = await createProgramMint();
+    await program.methods
+      .flagDelegate(drainer)
+      .accounts({
+        superAdmin,
+        programConfig,
+        flaggedDelegate: flaggedDelegatePda(drainer),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  // A victim's token account holding an approve to `delegate`.
+  async function approvedAccount(delegate: anchor.web3.PublicKey) {
+    const owner = anchor.web3.Keypair.generate();
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    await approve(provider.connection, payer, tokenAccount, delegate, owner, 100);
+    return tokenAccount;
+  }
+
+  function freezeIfFlagged(tokenAccount: anchor.web3.PublicKey, delegate: anchor.web3.PublicKey) {
+    return program.methods
+      .freezeIfFlaggedDelegate()
+      .accounts({
+        caller: bystander.publicKey,
+        tokenAccountToProcess: tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        flaggedDelegate: flaggedDelegatePda(delegate),
+        freezeRecord: freezeRecordPda(tokenAccount),
+        freezeExempt: freezeExemptPda(tokenAccount),
+        accountHistory: accountHistoryPda(tokenAccount),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rentPayer: null,
+      })
+      .signers([bystander])
+      .rpc();
+  }
+
+  it("lets a third party freeze an account approved to a flagged delegate", async () => {
+    const victim = await approvedAccount(drainer);
+    await freezeIfFlagged(victim, drainer);
+
+    assert.isTrue((await getAccount(provider.connection, victim)).isFrozen);
+    const record = await program.account.freezeRecord.fetch(freezeRecordPda(victim));
+    assert.ok(record.actor.equals(bystander.publicKey));
+    const history = await program.account.accountHistory.fetch(accountHistoryPda(victim));
+    const last = history.entries[history.entries.length - 1];
+    assert.deepEqual(last.reason, { drainerProtection: {} });
+  });
+
+  it("rejects an account whose delegate isn't flagged", async () => {
+    const other = anchor.web3.Keypair.generate().publicKey;
+    const tokenAccount = await approvedAccount(other);
+    await expectError(freezeIfFlagged(tokenAccount, other), "DelegateNotFlagged");
+  });
+
+  it("leaves the victim to the usual thaw once the flag is lifted", async () => {
+    const victim = await approvedAccount(drainer);
+    await freezeIfFlagged(victim, drainer);
+    await program.methods
+      .unflagDelegate()
+      .accounts({ superAdmin, programConfig, flaggedDelegate: flaggedDelegatePda(drainer) })
+      .rpc();
+
+    const fresh = await approvedAccount(drainer);
+    await expectError(freezeIfFlagged(fresh, drainer), "DelegateNotFlagged");
+
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, victim)).rpc();
+    assert.isFalse((await getAccount(provider.connection, victim)).isFrozen);
+  });
+});
@@ -0,0 +1,79 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_2022_PROGRAM_ID, createAccount, createMint } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezerBondPda,
+  payer,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+// Each malformed freeze/thaw input maps to one stable error code.
+describe("freeze and thaw account errors", () => {
+  let mint: anchor.web3.PublicKey;
+  let otherMint: anchor.web3.PublicKey;
+  let open: anchor.web3.PublicKey;
+  let frozen: anchor.web3.PublicKey;
+  let foreign: anchor.web3.PublicKey;
+  const stranger = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    mint = await createProgramMint();
+    otherMint = await createProgramMint();
+    const owner = anchor.web3.Keypair.generate().publicKey;
+    open = await createFundedTokenAccount(mint, owner, 10);
+    frozen = await createFundedTokenAccount(mint, owner, 10);
+    foreign = await createFundedTokenAccount(otherMint, owner, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, frozen)).rpc();
+    await airdrop(stranger.publicKey);
+  });
+
+  const freeze = (accounts: ReturnType<typeof freezeAccounts>, signers: anchor.web3.Keypair[] = []) =>
+    program.methods.freezeTokenAccount().accounts(accounts).signers(signers).rpc();
+  const thaw = (accounts: ReturnType<typeof thawAccounts>, signers: anchor.web3.Keypair[] = []) =>
+    program.methods.thawTokenAccount().accounts(accounts).signers(signers).rpc();
+
+  it("MintMismatch for a token account of another mint", async () => {
+    await expectError(freeze({ ...freezeAccounts(otherMint, open), mint: otherMint }), "MintMismatch");
+    await expectError(freeze({ ...freezeAccounts(mint, foreign) }), "MintMismatch");
+    await expectError(thaw({ ...thawAccounts(otherMint, frozen), mint: otherMint }), "MintMismatch");
+  });
+
+  it("WrongFreezeAuthority for a program authority that isn't the mint's", async () => {
+    const impostor = anchor.web3.Keypair.generate().publicKey;
+    await expectError(freeze(freezeAccounts(mint, open, impostor)), "WrongFreezeAuthority");
+    await expectError(thaw(thawAccounts(mint, frozen, impostor)), "WrongFreezeAuthority");
+  });
+
+  it("Unauthorized for an admin that isn't the mint authority", async () => {
+    await expectError(
+      freeze(
+        { ...freezeAccounts(mint, open), admin: stranger.publicKey, freezerBond: freezerBondPda(mint, stranger.publicKey) },
+        [stranger]
+      ),
+      "Unauthorized"
+    );
+    await expectError(thaw({ ...thawAccounts(mint, frozen), admin: stranger.publicKey }, [stranger]), "Unauthorized");
+  });
+
+  it("rejects a token account owned by another token program", async () => {
+    // Anchor's own owner check on the token account runs before our constraint.
+    const mint2022 = await createMint(provider.connection, payer, payer.publicKey, payer.publicKey, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    const account2022 = await createAccount(
+      provider.connection,
+      payer,
+      mint2022,
+      payer.publicKey,
+      anchor.web3.Keypair.generate(),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await expectError(freeze(freezeAccounts(mint, account2022)), "AccountOwnedByWrongProgram");
+    await expectError(thaw(thawAccounts(mint, account2022)), "AccountOwnedByWrongProgram");
+  });
+});
@@ -0,0 +1,90 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { getAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezeExemptPda,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+describe("freeze exemptions", () => {
+  it("blocks every freeze path for an exempt account but still allows thaw", async () => {
+    const mint = await createProgramMint();
+    const pool = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 500);
+
+    // Frozen before it was exempted, so we can check thaw stays available.
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, pool)).rpc();
+
+    await program.methods
+      .addFreezeExempt(pool)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        mint,
+        freezeExempt: freezeExemptPda(pool),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, pool)).rpc();
+    assert.isFalse((await getAccount(provider.connection, pool)).isFrozen);
+
+    await expectError(
+      program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, pool)).rpc(),
+      "AccountExempt"
+    );
+
+    await program.methods
+      .removeFreezeExempt()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        mint,
+        freezeExempt: freezeExemptPda(pool),
+      })
+      .rpc();
+
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, pool)).rpc();
+    assert.isTrue((await getAccount(provider.connection, pool)).isFrozen);
+  });
+
+  it("refuses to look for the exemption anywhere but its own address", async () => {
+    const mint = await createProgramMint();
+    const pool = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 1);
+    const elsewhere = anchor.web3.Keypair.generate().publicKey;
+
+    await expectError(
+      program.methods
+        .freezeTokenAccount()
+        .accounts({ ...freezeAccounts(mint, pool), freezeExempt: elsewhere })
+        .rpc(),
+      "ConstraintSeeds"
+    );
+    assert.isFalse((await getAccount(provider.connection, pool)).isFrozen);
+  });
+
+  it("only lets the mint authority manage exemptions", async () => {
+    const mint = await createProgramMint();
+    const pool = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 1);
+    const stranger = anchor.web3.Keypair.generate();
+    await airdrop(stranger.publicKey);
+
+    await expectError(
+      program.methods
+        .addFreezeExempt(pool)
+        .accounts({
+          admin: stranger.publicKey,
+          mint,
+          freezeExempt: freezeExemptPda(pool),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+});
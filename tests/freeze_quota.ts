@@ -0,0 +1,165 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  expectError,
+  freezeAccounts,
+  freezerBondPda,
+  freezerStatsPda,
+  mintConfigPda,
+  programConfig,
+} from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  fund,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const DAY = 24 * 60 * 60;
+
+describe("daily freeze quota", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  const freezer = anchor.web3.Keypair.generate();
+  let today: number;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    mint = await createBankMint(bank);
+    await fund(bank, freezer.publicKey);
+
+    await bank.program.methods
+      .setFreezeQuota(2)
+      .accounts({
+        admin: bank.provider.wallet.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+      })
+      .rpc();
+    await bank.program.methods
+      .addFreezer(freezer.publicKey)
+      .accounts({
+        admin: bank.provider.wallet.publicKey,
+        mint,
+        freezerStats: freezerStatsPda(mint, freezer.publicKey),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // Start an hour into a UTC day, so "a day later" is unambiguous.
+    const now = await unixTimestamp(bank);
+    today = now - (now % DAY) + 60 * 60;
+    await warpTo(bank, today);
+  });
+
+  // `superAdmin` co-signs as the super_admin would, the bank's payer by default.
+  async function freezeAs(signer: anchor.web3.Keypair | null, superAdmin?: anchor.web3.Keypair | "payer") {
+    const tokenAccount = await createBankTokenAccount(
+      bank,
+      mint,
+      anchor.web3.Keypair.generate().publicKey,
+      10
+    );
+    // freezeAccounts defaults to the env wallet; the bank has its own payer.
+    const accounts = {
+      ...freezeAccounts(mint, tokenAccount),
+      admin: bank.provider.wallet.publicKey,
+      freezerBond: freezerBondPda(mint, bank.provider.wallet.publicKey),
+    };
+    const call = bank.program.methods.freezeTokenAccount();
+    if (!signer) return call.accounts(accounts).rpc();
+    const coSigner = superAdmin === "payer" ? null : superAdmin;
+    return call
+      .accounts({
+        ...accounts,
+        admin: signer.publicKey,
+        freezerStats: freezerStatsPda(mint, signer.publicKey),
+        freezerBond: freezerBondPda(mint, signer.publicKey),
+        ...(superAdmin
+          ? { programConfig, superAdmin: coSigner?.publicKey ?? bank.provider.wallet.publicKey }
+          : {}),
+      })
+      .signers(coSigner ? [signer, coSigner] : [signer])
+      .rpc();
+  }
+
+  it("stops a freezer once today's quota is used up", async () => {
+    await freezeAs(freezer);
+    await freezeAs(freezer);
+    await expectError(freezeAs(freezer), "FreezeQuotaExceeded");
+
+    const stats = await bank.program.account.freezerStats.fetch(
+      freezerStatsPda(mint, freezer.publicKey)
+    );
+    assert.equal(stats.frozenToday, 2);
+  });
+
+  it("gives the freezer a fresh quota on the next UTC day", async () => {
+    await warpTo(bank, today + DAY);
+    await freezeAs(freezer);
+
+    const stats = await bank.program.account.freezerStats.fetch(
+      freezerStatsPda(mint, freezer.publicKey)
+    );
+    assert.equal(stats.frozenToday, 1);
+    assert.equal(stats.dayStartTs.toNumber(), today + DAY - 60 * 60);
+  });
+
+  it("lets a freezer past the quota when the super_admin co-signs", async () => {
+    await freezeAs(freezer);
+    await expectError(freezeAs(freezer), "FreezeQuotaExceeded");
+
+    const impostor = anchor.web3.Keypair.generate();
+    await expectError(freezeAs(freezer, impostor), "NotSuperAdmin");
+    await freezeAs(freezer, "payer");
+
+    // Overridden freezes don't count against the quota.
+    const stats = await bank.program.account.freezerStats.fetch(
+      freezerStatsPda(mint, freezer.publicKey)
+    );
+    assert.equal(stats.frozenToday, 2);
+  });
+
+  it("does not apply the quota to the mint authority", async () => {
+    for (let i = 0; i < 4; i++) {
+      await freezeAs(null);
+    }
+  });
+
+  it("rejects a key without the freezer role", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await fund(bank, stranger.publicKey);
+    const tokenAccount = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 10);
+
+    await expectError(
+      bank.program.methods
+        .freezeTokenAccount()
+        .accounts({
+          ...freezeAccounts(mint, tokenAccount),
+          admin: stranger.publicKey,
+          freezerBond: freezerBondPda(mint, stranger.publicKey),
+        })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("revokes the role", async () => {
+    await bank.program.methods
+      .removeFreezer()
+      .accounts({
+        admin: bank.provider.wallet.publicKey,
+        mint,
+        freezerStats: freezerStatsPda(mint, freezer.publicKey),
+      })
+      .rpc();
+    await warpTo(bank, today + 2 * DAY);
+    await expectError(freezeAs(freezer), "AccountNotInitialized");
+  });
+});
@@ -0,0 +1,252 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  accountHistoryPda,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  freezerBondPda,
+  freezerStatsPda,
+  mintConfigPda,
+  pda,
+  programAuthority,
+  thawAccounts,
+} from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, fund, startBank } from "./bankrun";
+
+const LAMPORTS = anchor.web3.LAMPORTS_PER_SOL;
+const MIN_BOND = LAMPORTS / 2;
+
+describe("freezer bonds", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  const freezer = anchor.web3.Keypair.generate();
+  const owner = anchor.web3.Keypair.generate();
+  const treasury = pda([Buffer.from("treasury")]);
+  // Accounts left frozen along the way.
+  const stillFrozen: anchor.web3.PublicKey[] = [];
+
+  const admin = () => bank.provider.wallet.publicKey;
+  const bond = () => freezerBondPda(mint, freezer.publicKey);
+  const thawRequestPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("thaw_request"), tokenAccount.toBuffer()]);
+  const lamports = async (address: anchor.web3.PublicKey) =>
+    Number(await bank.context.banksClient.getBalance(address));
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+    await fund(bank, freezer.publicKey, 5);
+    await fund(bank, owner.publicKey);
+
+    await bank.program.methods.setFreezeQuota(10).accounts({ admin: admin(), mint, mintConfig: mintConfigPda(mint) }).rpc();
+    await bank.program.methods
+      .addFreezer(freezer.publicKey)
+      .accounts({
+        admin: admin(),
+        mint,
+        freezerStats: freezerStatsPda(mint, freezer.publicKey),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    // A quarter of a forfeited bond goes to the owner.
+    await bank.program.methods
+      .setFreezerBondConfig(new anchor.BN(MIN_BOND), 2_500)
+      .accounts({ admin: admin(), mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  });
+
+  function postBond(amount: number) {
+    return bank.program.methods
+      .postFreezerBond(new anchor.BN(amount))
+      .accounts({
+        freezer: freezer.publicKey,
+        mint,
+        freezerBond: bond(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([freezer])
+      .rpc();
+  }
+
+  function withdrawBond() {
+    return bank.program.methods
+      .withdrawFreezerBond()
+      .accounts({ freezer: freezer.publicKey, freezerBond: bond() })
+      .signers([freezer])
+      .rpc();
+  }
+
+  async function freezeNew() {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner.publicKey, 10);
+    await bank.program.methods
+      .freezeTokenAccount()
+      .accounts({
+        ...freezeAccounts(mint, tokenAccount),
+        admin: freezer.publicKey,
+        freezerStats: freezerStatsPda(mint, freezer.publicKey),
+        freezerBond: bond(),
+      })
+      .signers([freezer])
+      .rpc();
+    return tokenAccount;
+  }
+
+  async function appeal(tokenAccount: anchor.web3.PublicKey) {
+    await bank.program.methods
+      .requestThaw()
+      .accounts({
+        owner: owner.publicKey,
+        tokenAccount,
+        freezeRecord: freezeRecordPda(tokenAccount),
+        thawRequest: thawRequestPda(tokenAccount),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  function approve(tokenAccount: anchor.web3.PublicKey, wrongful: boolean) {
+    return bank.program.methods
+      .approveThawRequest(wrongful)
+      .accounts({
+        admin: admin(),
+        thawRequest: thawRequestPda(tokenAccount),
+        owner: owner.publicKey,
+        tokenAccountToProcess: tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        freezeRecord: freezeRecordPda(tokenAccount),
+        freezeActor: freezer.publicKey,
+        rentPayer: null,
+        freezerBond: bond(),
+        accountHistory: accountHistoryPda(tokenAccount),
+        treasury,
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  const isFrozen = async (tokenAccount: anchor.web3.PublicKey) =>
+    (await getAccount(bank.provider.connection, tokenAccount)).isFrozen;
+
+  it("only lets the mint authority set the bond terms", async () => {
+    await expectError(
+      bank.program.methods
+        .setFreezerBondConfig(new anchor.BN(0), 0)
+        .accounts({ admin: freezer.publicKey, mint, mintConfig: mintConfigPda(mint) })
+        .signers([freezer])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("won't let a freezer freeze without the minimum bond", async () => {
+    await expectError(freezeNew(), "BondTooLow");
+    await postBond(MIN_BOND / 2);
+    await expectError(freezeNew(), "BondTooLow");
+  });
+
+  it("lets a bonded freezer freeze, and counts the freeze against the bond", async () => {
+    await postBond(MIN_BOND / 2);
+    const tokenAccount = await freezeNew();
+    stillFrozen.push(tokenAccount);
+    assert.isTrue(await isFrozen(tokenAccount));
+    const state = await bank.program.account.freezerBond.fetch(bond());
+    assert.equal(state.bonded.toNumber(), MIN_BOND);
+    assert.equal(state.openFreezes, 1);
+  });
+
+  it("won't look for the freezer's bond anywhere but its own address", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner.publicKey, 10);
+    await expectError(
+      bank.program.methods
+        .freezeTokenAccount()
+        .accounts({
+          ...freezeAccounts(mint, tokenAccount),
+          admin: freezer.publicKey,
+          freezerStats: freezerStatsPda(mint, freezer.publicKey),
+          freezerBond: freezerBondPda(mint, admin()),
+        })
+        .signers([freezer])
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("won't release the bond while an account it froze is still frozen", async () => {
+    await expectError(withdrawBond(), "BondLocked");
+  });
+
+  it("thaws a justified freeze on appeal without touching the bond", async () => {
+    const tokenAccount = await freezeNew();
+    await appeal(tokenAccount);
+    await approve(tokenAccount, false);
+    assert.isFalse(await isFrozen(tokenAccount));
+    assert.isNull(await bank.context.banksClient.getAccount(thawRequestPda(tokenAccount)));
+    const state = await bank.program.account.freezerBond.fetch(bond());
+    assert.equal(state.bonded.toNumber(), MIN_BOND);
+    assert.equal(state.openFreezes, 1);
+  });
+
+  it("keeps the account frozen when the appeal is denied", async () => {
+    const tokenAccount = await freezeNew();
+    await appeal(tokenAccount);
+    const deny = (signer?: anchor.web3.Keypair) =>
+      bank.program.methods
+        .denyThawRequest()
+        .accounts({
+          admin: signer?.publicKey ?? admin(),
+          thawRequest: thawRequestPda(tokenAccount),
+          owner: owner.publicKey,
+          mint,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+    // Only the mint authority may deny; the freezer can't.
+    await expectError(deny(freezer), "Unauthorized");
+    await deny();
+    assert.isTrue(await isFrozen(tokenAccount));
+    assert.isNull(await bank.context.banksClient.getAccount(thawRequestPda(tokenAccount)));
+    stillFrozen.push(tokenAccount);
+  });
+
+  it("slashes the bond to the owner and the treasury for a wrongful freeze", async () => {
+    const tokenAccount = await freezeNew();
+    await appeal(tokenAccount);
+    const ownerBefore = await lamports(owner.publicKey);
+    const bondBefore = await lamports(bond());
+    const treasuryBefore = await lamports(treasury);
+
+    await approve(tokenAccount, true);
+    assert.isFalse(await isFrozen(tokenAccount));
+
+    const requestRent = (await bank.context.banksClient.getRent()).minimumBalance(BigInt(8 + 32 * 4 + 8 + 1));
+    assert.equal(await lamports(owner.publicKey), ownerBefore + MIN_BOND / 4 + Number(requestRent));
+    assert.equal(await lamports(bond()), bondBefore - MIN_BOND);
+    assert.equal(await lamports(treasury), treasuryBefore + (MIN_BOND * 3) / 4);
+
+    const state = await bank.program.account.freezerBond.fetch(bond());
+    assert.equal(state.bonded.toNumber(), 0);
+    await expectError(freezeNew(), "BondTooLow");
+  });
+
+  it("releases the bond once everything it froze is thawed", async () => {
+    assert.equal((await bank.program.account.freezerBond.fetch(bond())).openFreezes, 2);
+    for (const tokenAccount of stillFrozen) {
+      await bank.program.methods
+        .thawTokenAccount()
+        .accounts({ ...thawAccounts(mint, tokenAccount), admin: admin(), freezerBond: bond() })
+        .rpc();
+    }
+
+    const before = await lamports(freezer.publicKey);
+    const held = await lamports(bond());
+    await withdrawBond();
+    assert.isNull(await bank.context.banksClient.getAccount(bond()));
+    assert.equal(await lamports(freezer.publicKey), before + held);
+  });
+});
@@ -0,0 +1,114 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  mintConfigPda,
+  pda,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+function u16Le(value: number): Buffer {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(value);
+  return buf;
+}
+
+function u64Le(value: number | bigint): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(value));
+  return buf;
+}
+
+const sha256 = (...parts: Buffer[]) => createHash("sha256").update(Buffer.concat(parts)).digest();
+
+describe("frozen account export", () => {
+  const admin = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  // Sorted by pubkey, as the export requires.
+  let frozen: { account: anchor.web3.PublicKey; amount: number }[];
+
+  before(async () => {
+    mint = await createProgramMint();
+    frozen = [];
+    for (let i = 1; i <= 5; i++) {
+      const account = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, i * 10);
+      await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+      frozen.push({ account, amount: i * 10 });
+    }
+    frozen.sort((a, b) => Buffer.compare(a.account.toBuffer(), b.account.toBuffer()));
+  });
+
+  const headerPda = (exportId: number) => pda([Buffer.from("export"), mint.toBuffer(), u64Le(exportId)]);
+  const pagePda = (exportId: number, page: number) =>
+    pda([Buffer.from("export"), mint.toBuffer(), u64Le(exportId), u16Le(page)]);
+
+  function exportPage(exportId: number, page: number, accounts: anchor.web3.PublicKey[]) {
+    return program.methods
+      .exportFrozenPage(new anchor.BN(exportId), page)
+      .accounts({
+        admin,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        exportHeader: headerPda(exportId),
+        exportPage: pagePda(exportId, page),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        accounts.flatMap((account) => [
+          { pubkey: freezeRecordPda(account), isWritable: false, isSigner: false },
+          { pubkey: account, isWritable: false, isSigner: false },
+        ])
+      )
+      .rpc();
+  }
+
+  it("builds a three-page export whose hashes chain", async () => {
+    const pages = [frozen.slice(0, 2), frozen.slice(2, 4), frozen.slice(4)];
+    for (const [i, entries] of pages.entries()) {
+      await exportPage(1, i, entries.map((e) => e.account));
+    }
+
+    let chain = Buffer.alloc(32);
+    for (const [i, entries] of pages.entries()) {
+      const page = await program.account.frozenExportPage.fetch(pagePda(1, i));
+      assert.deepEqual(
+        page.entries.map((e) => e.tokenAccount.toBase58()),
+        entries.map((e) => e.account.toBase58())
+      );
+      const pageHash = sha256(...entries.flatMap((e) => [e.account.toBuffer(), u64Le(e.amount)]));
+      assert.deepEqual(Buffer.from(page.pageHash), pageHash);
+      chain = sha256(chain, pageHash);
+      assert.deepEqual(Buffer.from(page.chainHash), chain);
+    }
+
+    const header = await program.account.frozenExport.fetch(headerPda(1));
+    assert.equal(header.totalPages, 3);
+    assert.equal(header.totalAccounts.toNumber(), 5);
+    assert.equal(header.frozenAccountCount.toNumber(), 5);
+    assert.deepEqual(Buffer.from(header.chainHash), chain);
+  });
+
+  it("refuses pages out of order", async () => {
+    await expectError(exportPage(2, 1, [frozen[0].account]), "ExportPageOutOfOrder");
+  });
+
+  it("refuses repeated or unsorted accounts", async () => {
+    await expectError(exportPage(3, 0, [frozen[0].account, frozen[0].account]), "ExportOutOfOrder");
+    await exportPage(4, 0, [frozen[1].account]);
+    await expectError(exportPage(4, 1, [frozen[0].account]), "ExportOutOfOrder");
+  });
+
+  it("refuses an account that has since been thawed", async () => {
+    const thawed = frozen[0].account;
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, thawed)).rpc();
+    // Thawing closes the record, so it is no longer a program account.
+    await expectError(exportPage(5, 0, [thawed]), "InvalidRecordAccount");
+  });
+});
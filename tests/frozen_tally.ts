@@ -0,0 +1,125 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  AuthorityType,
+  createMint,
+  freezeAccount,
+  setAuthority,
+} from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  mintConfigPda,
+  retiredMintPda,
+  payer,
+  program,
+  programAuthority,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+describe("frozen tallies", () => {
+  it("tracks frozen account count and supply across freezes and thaws", async () => {
+    const mint = await createProgramMint();
+    const balances = [100, 250, 4_000];
+    const accounts: anchor.web3.PublicKey[] = [];
+    for (const balance of balances) {
+      const holder = anchor.web3.Keypair.generate();
+      accounts.push(await createFundedTokenAccount(mint, holder.publicKey, balance));
+    }
+
+    for (const account of accounts) {
+      await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+    }
+
+    let config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.frozenAccountCount.toNumber(), 3);
+    assert.equal(config.frozenSupply.toNumber(), 4_350);
+
+    const record = await program.account.freezeRecord.fetch(freezeRecordPda(accounts[1]));
+    assert.equal(record.amount.toNumber(), 250);
+    assert.ok(record.actor.equals(provider.wallet.publicKey));
+
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, accounts[1])).rpc();
+
+    config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.frozenAccountCount.toNumber(), 2);
+    assert.equal(config.frozenSupply.toNumber(), 4_100);
+    assert.isNull(await provider.connection.getAccountInfo(freezeRecordPda(accounts[1])));
+  });
+
+  it("reconciles an account that was frozen without a record", async () => {
+    // The wallet is the freeze authority while the account is frozen out-of-band...
+    const mint = await createMint(provider.connection, payer, payer.publicKey, payer.publicKey, 6);
+    const holder = anchor.web3.Keypair.generate();
+    const account = await createFundedTokenAccount(mint, holder.publicKey, 75);
+    await freezeAccount(provider.connection, payer, account, mint, payer);
+
+    // ...then hands the mint over to the program.
+    await setAuthority(provider.connection, payer, mint, payer, AuthorityType.FreezeAccount, programAuthority);
+    await program.methods
+      .migrateMintConfig()
+      .accounts({
+        admin: payer.publicKey,
+        mint,
+        programAuthority,
+        mintConfig: mintConfigPda(mint),
+        retiredMint: retiredMintPda(mint),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const reconcile = () =>
+      program.methods
+        .reconcileFrozen()
+        .accounts({
+          admin: payer.publicKey,
+          tokenAccount: account,
+          mint,
+          mintConfig: mintConfigPda(mint),
+          freezeRecord: freezeRecordPda(account),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    await reconcile();
+
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.frozenAccountCount.toNumber(), 1);
+    assert.equal(config.frozenSupply.toNumber(), 75);
+
+    // A second reconcile would double count, so `init` rejects it.
+    try {
+      await reconcile();
+      assert.fail("expected reconcile to fail the second time");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, account)).rpc();
+    const after = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(after.frozenAccountCount.toNumber(), 0);
+    assert.equal(after.frozenSupply.toNumber(), 0);
+  });
+
+  it("refuses to reconcile an account that is not frozen", async () => {
+    const mint = await createProgramMint();
+    const account = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 1);
+    await expectError(
+      program.methods
+        .reconcileFrozen()
+        .accounts({
+          admin: payer.publicKey,
+          tokenAccount: account,
+          mint,
+          mintConfig: mintConfigPda(mint),
+          freezeRecord: freezeRecordPda(account),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "AccountNotFrozen"
+    );
+  });
+});
@@ -0,0 +1,259 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  expectError,
+  freezeAccounts,
+  freezeExemptPda,
+  freezerBondPda,
+  freezeRecordPda,
+  mintConfigPda,
+  pda,
+  program,
+  programConfig,
+  provider,
+  thawAccounts,
+} from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  fund,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const DAY = 24 * 60 * 60;
+
+describe("gc_records", () => {
+  const caller = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  let frozen: anchor.web3.PublicKey;
+
+  before(async () => {
+    await ensureProgramConfig();
+    mint = await createProgramMint();
+    frozen = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, frozen)).rpc();
+  });
+
+  function gc(remaining: anchor.web3.PublicKey[], mintConfig = mintConfigPda(mint)) {
+    return program.methods
+      .gcRecords()
+      .accounts({ caller, programConfig, mintConfig })
+      .remainingAccounts(remaining.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+      .rpc();
+  }
+
+  it("caps the reward share at 100%", async () => {
+    await expectError(
+      program.methods
+        .setGcRewardBps(10_001)
+        .accounts({ superAdmin: caller, programConfig })
+        .rpc(),
+      "InvalidBps"
+    );
+    await program.methods.setGcRewardBps(1_000).accounts({ superAdmin: caller, programConfig }).rpc();
+  });
+
+  it("refuses to close a freeze record whose account is still frozen", async () => {
+    await expectError(gc([freezeRecordPda(frozen), frozen, caller]), "RecordStillLive");
+  });
+
+  it("refuses a freeze record paired with the wrong token account", async () => {
+    const other = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await expectError(gc([freezeRecordPda(frozen), other, caller]), "InvalidRecordAccount");
+  });
+
+  it("refuses to send the payer's share to someone else", async () => {
+    const thief = anchor.web3.Keypair.generate().publicKey;
+    await expectError(gc([freezeRecordPda(frozen), frozen, thief]), "InvalidRecordAccount");
+  });
+
+  it("refuses a record from another mint", async () => {
+    const otherMint = await createProgramMint();
+    await expectError(
+      gc([freezeRecordPda(frozen), frozen, caller], mintConfigPda(otherMint)),
+      "MintMismatch"
+    );
+  });
+
+  it("refuses program accounts that are not collectable records", async () => {
+    // A FreezeExempt entry is program-owned but never garbage.
+    const pool = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await program.methods
+      .addFreezeExempt(pool)
+      .accounts({
+        admin: caller,
+        mint,
+        freezeExempt: freezeExemptPda(pool),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await expectError(gc([freezeExemptPda(pool), pool, caller]), "InvalidRecordAccount");
+    await expectError(gc([mintConfigPda(mint), pool, caller]), "InvalidRecordAccount");
+  });
+
+  it("rejects malformed and oversized batches", async () => {
+    await expectError(gc([freezeRecordPda(frozen), frozen]), "InvalidRemainingAccounts");
+    const triple = [freezeRecordPda(frozen), frozen, caller];
+    await expectError(
+      program.methods
+        .gcRecords()
+        .accounts({ caller, programConfig, mintConfig: mintConfigPda(mint) })
+        .remainingAccounts(
+          Array(11)
+            .fill(triple)
+            .flat()
+            .map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }))
+        )
+        .rpc(),
+      "BatchTooLarge"
+    );
+  });
+});
+
+describe("gc_records of delegations and thaw requests", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  const cranker = anchor.web3.Keypair.generate();
+  const delegate = anchor.web3.Keypair.generate().publicKey;
+
+  const owner = () => bank.provider.wallet.publicKey;
+  const recordPda = (tokenAccount: anchor.web3.PublicKey) => pda([Buffer.from("delegation"), tokenAccount.toBuffer()]);
+  const offerPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("delegation_offer"), tokenAccount.toBuffer()]);
+  const thawRequestPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("thaw_request"), tokenAccount.toBuffer()]);
+  const exists = async (address: anchor.web3.PublicKey) =>
+    (await bank.context.banksClient.getAccount(address)) !== null;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    mint = await createBankMint(bank);
+    await fund(bank, cranker.publicKey);
+  });
+
+  // A slot on first, so a call repeated after a refusal isn't taken for the same transaction.
+  async function gc(remaining: anchor.web3.PublicKey[]) {
+    await warpTo(bank, await unixTimestamp(bank));
+    return bank.program.methods
+      .gcRecords()
+      .accounts({ caller: cranker.publicKey, programConfig, mintConfig: mintConfigPda(mint) })
+      .remainingAccounts(remaining.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+      .signers([cranker])
+      .rpc();
+  }
+
+  async function delegation(expiresAt: number | null) {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    await bank.program.methods
+      .createDelegation(
+        {
+          allowance: new anchor.BN(50),
+          mode: { programEnforced: {} },
+          expiresAt: expiresAt === null ? null : new anchor.BN(expiresAt),
+          periodSeconds: new anchor.BN(0),
+          periodLimit: new anchor.BN(0),
+        },
+        []
+      )
+      .accounts({
+        tokenAccount,
+        delegate,
+        owner: owner(),
+        delegationRecord: recordPda(tokenAccount),
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    return tokenAccount;
+  }
+
+  it("keeps a delegation record until its terms expire", async () => {
+    const expiry = (await unixTimestamp(bank)) + DAY;
+    const expiring = await delegation(expiry);
+    const open = await delegation(null);
+
+    await expectError(gc([recordPda(expiring), expiring, owner()]), "RecordStillLive");
+    await warpTo(bank, expiry);
+    // One with no expiry is never collected while its token account is there.
+    await expectError(gc([recordPda(open), open, owner()]), "RecordStillLive");
+    // Nor can an expired one's rent go to anyone but its owner.
+    await expectError(gc([recordPda(expiring), expiring, cranker.publicKey]), "InvalidRecordAccount");
+
+    const before = (await bank.program.account.mintConfig.fetch(mintConfigPda(mint))).stats.activeDelegations;
+    await gc([recordPda(expiring), expiring, owner()]);
+    assert.isFalse(await exists(recordPda(expiring)));
+    assert.isTrue(await exists(recordPda(open)));
+    const config = await bank.program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.stats.activeDelegations.toNumber(), before.toNumber() - 1);
+  });
+
+  it("keeps a delegation offer until it expires", async () => {
+    const tokenAccount = await createBankTokenAccount(bank, mint, owner(), 100);
+    const expiry = (await unixTimestamp(bank)) + DAY;
+    await bank.program.methods
+      .offerDelegation(delegate, new anchor.BN(10), new anchor.BN(expiry))
+      .accounts({
+        tokenAccount,
+        owner: owner(),
+        offer: offerPda(tokenAccount),
+        delegationRecord: recordPda(tokenAccount),
+        mintConfig: mintConfigPda(mint),
+        approvedDelegate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await expectError(gc([offerPda(tokenAccount), tokenAccount, owner()]), "RecordStillLive");
+    await warpTo(bank, expiry);
+    await gc([offerPda(tokenAccount), tokenAccount, owner()]);
+    assert.isFalse(await exists(offerPda(tokenAccount)));
+  });
+
+  it("keeps a thaw request while the freeze it appeals is in place", async () => {
+    const holder = anchor.web3.Keypair.generate();
+    await fund(bank, holder.publicKey);
+    const tokenAccount = await createBankTokenAccount(bank, mint, holder.publicKey, 10);
+    const asAdmin = { admin: owner(), freezerBond: freezerBondPda(mint, owner()) };
+    await bank.program.methods
+      .freezeTokenAccount()
+      .accounts({ ...freezeAccounts(mint, tokenAccount), ...asAdmin })
+      .rpc();
+    await bank.program.methods
+      .requestThaw()
+      .accounts({
+        owner: holder.publicKey,
+        tokenAccount,
+        freezeRecord: freezeRecordPda(tokenAccount),
+        thawRequest: thawRequestPda(tokenAccount),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([holder])
+      .rpc();
+
+    const triple = [thawRequestPda(tokenAccount), freezeRecordPda(tokenAccount), holder.publicKey];
+    await expectError(gc(triple), "RecordStillLive");
+    // It is checked against the freeze record, not the token account.
+    await expectError(gc([thawRequestPda(tokenAccount), tokenAccount, holder.publicKey]), "InvalidRecordAccount");
+
+    // Thawed without the request being decided, which leaves it behind.
+    await bank.program.methods
+      .thawTokenAccount()
+      .accounts({ ...thawAccounts(mint, tokenAccount), ...asAdmin })
+      .rpc();
+    await gc(triple);
+    assert.isFalse(await exists(thawRequestPda(tokenAccount)));
+  });
+});
@@ -0,0 +1,287 @@
+import * as anchor from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+import { Program } from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  createAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { CustomTokenProgram } from "../target/types/custom_token_program";
+
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+export const program = anchor.workspace
+  .customTokenProgram as Program<CustomTokenProgram>;
+
+// The provider wallet as a Keypair, for spl-token helpers that want a signer.
+export const payer = (provider.wallet as anchor.Wallet).payer;
+
+export const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new anchor.web3.PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+export function pda(seeds: (Buffer | Uint8Array)[]): anchor.web3.PublicKey {
+  return anchor.web3.PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+}
+
+export const programAuthority = pda([Buffer.from("authority")]);
+export const programConfig = pda([Buffer.from("program_config")]);
+
+export const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+  [program.programId.toBuffer()],
+  BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+);
+
+export function mintConfigPda(mint: anchor.web3.PublicKey): anchor.web3.PublicKey {
+  return pda([Buffer.from("mint_config"), mint.toBuffer()]);
+}
+
+export function retiredMintPda(mint: anchor.web3.PublicKey): anchor.web3.PublicKey {
+  return pda([Buffer.from("retired_mint"), mint.toBuffer()]);
+}
+
+export async function airdrop(to: anchor.web3.PublicKey, sol = 1): Promise<void> {
+  const sig = await provider.connection.requestAirdrop(
+    to,
+    sol * anchor.web3.LAMPORTS_PER_SOL
+  );
+  await provider.connection.confirmTransaction(sig);
+}
+
+// Create a mint through the program. The provider wallet is the mint authority.
+export async function createProgramMint(decimals = 6): Promise<anchor.web3.PublicKey> {
+  const mint = anchor.web3.Keypair.generate();
+  await program.methods
+    .createTokenMint(decimals, provider.wallet.publicKey)
+    .accounts({
+      mint: mint.publicKey,
+      programAuthority,
+      mintConfig: mintConfigPda(mint.publicKey),
+      payer: provider.wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    })
+    .signers([mint])
+    .rpc();
+  return mint.publicKey;
+}
+
+// Create a fresh (non-associated) token account for `owner` holding `amount`.
+export async function createFundedTokenAccount(
+  mint: anchor.web3.PublicKey,
+  owner: anchor.web3.PublicKey,
+  amount: number | bigint
+): Promise<anchor.web3.PublicKey> {
+  const account = await createAccount(
+    provider.connection,
+    payer,
+    mint,
+    owner,
+    anchor.web3.Keypair.generate()
+  );
+  if (BigInt(amount) > BigInt(0)) {
+    await mintTo(provider.connection, payer, mint, account, payer, amount);
+  }
+  return account;
+}
+
+export function freezeRecordPda(tokenAccount: anchor.web3.PublicKey): anchor.web3.PublicKey {
+  return pda([Buffer.from("freeze_record"), tokenAccount.toBuffer()]);
+}
+
+export function freezeExemptPda(tokenAccount: anchor.web3.PublicKey): anchor.web3.PublicKey {
+  return pda([Buffer.from("freeze_exempt"), tokenAccount.toBuffer()]);
+}
+
+export function freezerBondPda(
+  mint: anchor.web3.PublicKey,
+  freezer: anchor.web3.PublicKey
+): anchor.web3.PublicKey {
+  return pda([Buffer.from("freezer_bond"), mint.toBuffer(), freezer.toBuffer()]);
+}
+
+export function freezerStatsPda(
+  mint: anchor.web3.PublicKey,
+  freezer: anchor.web3.PublicKey
+): anchor.web3.PublicKey {
+  return pda([Buffer.from("freezer"), mint.toBuffer(), freezer.toBuffer()]);
+}
+
+export function accountHistoryPda(tokenAccount: anchor.web3.PublicKey): anchor.web3.PublicKey {
+  return pda([Buffer.from("account_history"), tokenAccount.toBuffer()]);
+}
+
+export function freezeAccounts(
+  mint: anchor.web3.PublicKey,
+  tokenAccount: anchor.web3.PublicKey,
+  authority: anchor.web3.PublicKey = programAuthority
+) {
+  return {
+    admin: provider.wallet.publicKey,
+    tokenAccountToProcess: tokenAccount,
+    mint,
+    mintConfig: mintConfigPda(mint),
+    freezeRecord: freezeRecordPda(tokenAccount),
+    freezeExempt: freezeExemptPda(tokenAccount),
+    freezerStats: null,
+    freezerBond: freezerBondPda(mint, provider.wallet.publicKey),
+    accountHistory: accountHistoryPda(tokenAccount),
+    programAuthority: authority,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    programConfig: null,
+    superAdmin: null,
+    notificationHook: null,
+    notifier: null,
+    rentPayer: null,
+  };
+}
+
+export function thawAccounts(
+  mint: anchor.web3.PublicKey,
+  tokenAccount: anchor.web3.PublicKey,
+  authority: anchor.web3.PublicKey = programAuthority
+) {
+  return {
+    admin: provider.wallet.publicKey,
+    tokenAccountToProcess: tokenAccount,
+    mint,
+    mintConfig: mintConfigPda(mint),
+    freezeRecord: freezeRecordPda(tokenAccount),
+    // Of whoever froze it, by default the provider wallet.
+    freezerBond: freezerBondPda(mint, provider.wallet.publicKey),
+    accountHistory: accountHistoryPda(tokenAccount),
+    programAuthority: authority,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    instructions: null,
+    programConfig: null,
+    superAdmin: null,
+    notificationHook: null,
+    notifier: null,
+    rentPayer: null,
+  };
+}
+
+// The global config is a singleton shared by every test file, so whichever
+// file runs first creates it. The provider wallet is the upgrade authority.
+export async function ensureProgramConfig(): Promise<void> {
+  if (await provider.connection.getAccountInfo(programConfig)) return;
+  await program.methods
+    .initializeProgramConfig()
+    .accounts({
+      authority: provider.wallet.publicKey,
+      program: program.programId,
+      programData,
+      programConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+}
+
+// Mirrors `admin_action_id` on-chain: sha256(tag || args...).
+export function adminActionId(tag: string, ...args: Buffer[]): number[] {
+  const hash = createHash("sha256");
+  hash.update(Buffer.from(tag));
+  args.forEach((a) => hash.update(a));
+  return [...hash.digest()];
+}
+
+export function adminActionPda(actionId: number[]): anchor.web3.PublicKey {
+  return pda([Buffer.from("admin_action"), Buffer.from(actionId)]);
+}
+
+export function i64Le(value: number): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigInt64LE(BigInt(value));
+  return buf;
+}
+
+export const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+// Only test-clock builds (`anchor build -- --features test-clock`) have a mock
+// clock. Pass it as the last remaining account to override the sysvar.
+export const mockClock = pda([Buffer.from("mock_clock")]);
+export const mockClockAccounts = [{ pubkey: mockClock, isWritable: false, isSigner: false }];
+
+// set_mock_time isn't in the IDL, so the instruction is built by hand.
+export async function setMockTime(unixTimestamp: number): Promise<void> {
+  const discriminator = createHash("sha256").update("global:set_mock_time").digest().subarray(0, 8);
+  const ix = new anchor.web3.TransactionInstruction({
+    programId: program.programId,
+    keys: [
+      { pubkey: payer.publicKey, isWritable: true, isSigner: true },
+      { pubkey: mockClock, isWritable: true, isSigner: false },
+      { pubkey: anchor.web3.SystemProgram.programId, isWritable: false, isSigner: false },
+    ],
+    data: Buffer.concat([discriminator, i64Le(unixTimestamp)]),
+  });
+  await provider.sendAndConfirm(new anchor.web3.Transaction().add(ix));
+}
+
+export async function hasMockClock(): Promise<boolean> {
+  try {
+    await setMockTime(0);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+// Queue an action under the timelock and return its PDA once it is executable.
+export async function queueAndWait(actionId: number[]): Promise<anchor.web3.PublicKey> {
+  const adminAction = adminActionPda(actionId);
+  await program.methods
+    .queueAdminAction(actionId)
+    .accounts({
+      superAdmin: provider.wallet.publicKey,
+      programConfig,
+      adminAction,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+  const config = await program.account.programConfig.fetch(programConfig);
+  await sleep((config.timelockDelay.toNumber() + 1) * 1000);
+  return adminAction;
+}
+
+// Pull the compute units our program consumed out of a simulation's logs.
+export function unitsConsumed(logs: string[]): number {
+  const line = logs.find(
+    (l) =>
+      l.startsWith(`Program ${program.programId.toBase58()} consumed`)
+  );
+  if (!line) throw new Error("no compute-unit log line for program");
+  return Number(line.split(" ")[3]);
+}
+
+export async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (err) {
+    const actual = err?.error?.errorCode?.code;
+    if (actual !== code) {
+      throw new Error(`expected ${code}, got ${actual ?? err}`);
+    }
+    return;
+  }
+  throw new Error(`expected ${code}, but the transaction succeeded`);
+}
+
+// Decode the Anchor events our program emitted in a confirmed transaction.
+export async function eventsFromTx(signature: string): Promise<anchor.Event[]> {
+  await provider.connection.confirmTransaction(signature, "confirmed");
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return [...parser.parseLogs(tx.meta.logMessages)];
+}
+
+// Event names come back camelCase or PascalCase depending on the IDL version.
+export function eventsNamed(events: anchor.Event[], name: string): anchor.Event[] {
+  return events.filter((e) => e.name.toLowerCase() === name.toLowerCase());
+}
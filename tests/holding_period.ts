@@ -0,0 +1,124 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createTransferInstruction, getAccount } from "@solana/spl-token";
+import { expectError, freezeExemptPda, freezeRecordPda, mintConfigPda, pda, programAuthority } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  fund,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const HOLDING_PERIOD = 7 * 24 * 60 * 60;
+
+describe("holding period", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  const holder = anchor.web3.Keypair.generate();
+  let holderAccount: anchor.web3.PublicKey;
+  let otherAccount: anchor.web3.PublicKey;
+  let mintedAt: number;
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+    await fund(bank, holder.publicKey);
+    holderAccount = await createBankTokenAccount(bank, mint, holder.publicKey, 0);
+    otherAccount = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await bank.program.methods
+      .setHoldingPeriod(new anchor.BN(HOLDING_PERIOD))
+      .accounts({ admin: bank.provider.wallet.publicKey, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  });
+
+  const lockPda = (account: anchor.web3.PublicKey) => pda([Buffer.from("holding_lock"), account.toBuffer()]);
+
+  function mintLocked(destination: anchor.web3.PublicKey, amount: number) {
+    return bank.program.methods
+      .mintLocked(new anchor.BN(amount))
+      .accounts({
+        mintAuthority: bank.provider.wallet.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        destination,
+        holdingLock: lockPda(destination),
+        freezeRecord: freezeRecordPda(destination),
+        freezeExempt: freezeExemptPda(destination),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function unlock(account: anchor.web3.PublicKey) {
+    return bank.program.methods
+      .unlockHolding()
+      .accounts({
+        holdingLock: lockPda(account),
+        rentPayer: bank.provider.wallet.publicKey,
+        tokenAccount: account,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        freezeRecord: freezeRecordPda(account),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  function transferOut(amount: number) {
+    const tx = new anchor.web3.Transaction().add(
+      createTransferInstruction(holderAccount, otherAccount, holder.publicKey, amount)
+    );
+    return bank.provider.sendAndConfirm(tx, [holder]);
+  }
+
+  const isFrozen = async () => (await getAccount(bank.provider.connection, holderAccount)).isFrozen;
+
+  it("mints into a new account and freezes it", async () => {
+    await mintLocked(holderAccount, 100);
+    mintedAt = await unixTimestamp(bank);
+    assert.isTrue(await isFrozen());
+    const lock = await bank.program.account.holdingLock.fetch(lockPda(holderAccount));
+    assert.equal(lock.unlockTs.toNumber(), mintedAt + HOLDING_PERIOD);
+  });
+
+  it("blocks transfers during the holding period", async () => {
+    try {
+      await transferOut(10);
+      assert.fail("transferred out of a locked account");
+    } catch (err) {
+      assert.match(String(err), /frozen|0x11/i);
+    }
+  });
+
+  it("refuses to unlock early", async () => {
+    await warpTo(bank, mintedAt + HOLDING_PERIOD - 1);
+    await expectError(unlock(holderAccount), "HoldingPeriodActive");
+  });
+
+  it("pushes the unlock back when more is minted to a locked account", async () => {
+    await mintLocked(holderAccount, 50);
+    const lock = await bank.program.account.holdingLock.fetch(lockPda(holderAccount));
+    assert.equal(lock.unlockTs.toNumber(), mintedAt + 2 * HOLDING_PERIOD - 1);
+    assert.equal((await getAccount(bank.provider.connection, holderAccount)).amount, BigInt(150));
+  });
+
+  it("unlocks after the period and then transfers work", async () => {
+    await warpTo(bank, mintedAt + 2 * HOLDING_PERIOD - 1);
+    await unlock(holderAccount);
+    assert.isFalse(await isFrozen());
+    assert.isNull(await bank.context.banksClient.getAccount(lockPda(holderAccount)));
+
+    await transferOut(10);
+    assert.equal((await getAccount(bank.provider.connection, otherAccount)).amount, BigInt(10));
+  });
+
+  it("leaves existing, unlocked holders out", async () => {
+    await expectError(mintLocked(holderAccount, 1), "NotNewHolder");
+  });
+});
@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount, getMint } from "@solana/spl-token";
+import { adminActionId, expectError, i64Le, pda, programConfig } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  handMintAuthorityToProgram,
+  queueBankAction,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const DAY = 24 * 60 * 60;
+const YEAR = 365 * DAY;
+const RATE_BPS = 1_000;
+const INTERVAL = 60 * 60;
+
+function u16Le(value: number): Buffer {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(value);
+  return buf;
+}
+
+// Mirrors crank_inflation: supply * rate_bps * elapsed / (10_000 * year), rounded down.
+function expectedMint(supply: bigint, elapsed: number): bigint {
+  return (supply * BigInt(RATE_BPS) * BigInt(elapsed)) / (BigInt(10_000) * BigInt(YEAR));
+}
+
+describe("inflation crank", () => {
+  let bank: Bank;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+  });
+
+  const staker = () => bank.provider.wallet.publicKey;
+
+  // A mint with `supply` spread between a stranger and the staker, its
+  // mint authority handed to the program for inflation.
+  async function inflatingMint(supply: number, staked: number) {
+    const mint = await createBankMint(bank);
+    await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, supply - staked);
+    const stakerTokenAccount = await createBankTokenAccount(bank, mint, staker(), staked);
+    const mintAuthority = await handMintAuthorityToProgram(bank, mint);
+    const rewardPool = pda([Buffer.from("reward_pool"), mint.toBuffer()]);
+    return {
+      mint,
+      mintAuthority,
+      rewardPool,
+      rewardVault: pda([Buffer.from("reward_vault"), rewardPool.toBuffer()]),
+      stakeVault: pda([Buffer.from("stake_vault"), rewardPool.toBuffer()]),
+      stakerTokenAccount,
+      staked,
+    };
+  }
+  type Inflating = Awaited<ReturnType<typeof inflatingMint>>;
+
+  async function setInflation({ mint, mintAuthority, rewardPool, rewardVault }: Inflating, rateBps: number) {
+    const actionId = adminActionId("set_inflation", mint.toBuffer(), u16Le(rateBps), i64Le(INTERVAL));
+    const adminAction = await queueBankAction(bank, actionId);
+    return bank.program.methods
+      .setInflation(rateBps, new anchor.BN(INTERVAL))
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction,
+        mint,
+        mintAuthority,
+        rewardPool,
+        rewardVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  // Staking with no cooldown or penalty, and the staker's whole balance staked.
+  async function stakeAll({ mint, rewardPool, stakeVault, stakerTokenAccount, staked }: Inflating) {
+    const actionId = adminActionId("set_stake_config", mint.toBuffer(), i64Le(0), u16Le(0), Buffer.from([0]));
+    await bank.program.methods
+      .setStakeConfig(new anchor.BN(0), 0, false)
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction: await queueBankAction(bank, actionId),
+        mint,
+        rewardPool,
+        stakeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await bank.program.methods
+      .stake(new anchor.BN(staked))
+      .accounts({
+        staker: staker(),
+        rewardPool,
+        stakeVault,
+        position: pda([Buffer.from("stake"), mint.toBuffer(), staker().toBuffer()]),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  function crank({ mint, mintAuthority, rewardPool, rewardVault }: Inflating) {
+    return bank.program.methods
+      .crankInflation()
+      .accounts({ mint, mintAuthority, rewardPool, rewardVault, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+  }
+
+  const vaultBalance = async ({ rewardVault }: Inflating) =>
+    (await getAccount(bank.provider.connection, rewardVault)).amount;
+  const supply = async ({ mint }: Inflating) => (await getMint(bank.provider.connection, mint)).supply;
+  const lastInflationTs = async ({ rewardPool }: Inflating) =>
+    (await bank.program.account.rewardPool.fetch(rewardPool)).lastInflationTs.toNumber();
+
+  describe("a mint with a large supply", () => {
+    let inflating: Inflating;
+    let start: number;
+
+    before(async () => {
+      inflating = await inflatingMint(1_000_000_000, 1_000_000);
+    });
+
+    it("refuses a rate above 10% a year", async () => {
+      await expectError(setInflation(inflating, RATE_BPS + 1), "InvalidInflationRate");
+    });
+
+    it("configures the pool through the timelock", async () => {
+      await setInflation(inflating, RATE_BPS);
+      start = await unixTimestamp(bank);
+      const pool = await bank.program.account.rewardPool.fetch(inflating.rewardPool);
+      assert.equal(pool.inflationRateBps, RATE_BPS);
+      assert.equal(pool.lastInflationTs.toNumber(), start);
+    });
+
+    it("does nothing within an interval of the last mint", async () => {
+      await warpTo(bank, start + INTERVAL - 1);
+      await crank(inflating);
+      assert.equal(await vaultBalance(inflating), BigInt(0));
+      assert.equal(await lastInflationTs(inflating), start);
+    });
+
+    it("mints nothing while nothing is staked, and lets that time go", async () => {
+      await warpTo(bank, start + INTERVAL);
+      await crank(inflating);
+      assert.equal(await vaultBalance(inflating), BigInt(0));
+      assert.equal(await lastInflationTs(inflating), start + INTERVAL);
+      await stakeAll(inflating);
+    });
+
+    it("mints only for the time since staking began", async () => {
+      const before = await supply(inflating);
+      await warpTo(bank, start + DAY);
+      await crank(inflating);
+      // Not the first interval, when nothing was staked.
+      assert.equal(await vaultBalance(inflating), expectedMint(before, DAY - INTERVAL));
+      assert.equal(await lastInflationTs(inflating), start + DAY);
+    });
+
+    it("mints thirty days' worth after thirty more days, then no-ops", async () => {
+      const before = await supply(inflating);
+      const vaultBefore = await vaultBalance(inflating);
+      await warpTo(bank, start + 31 * DAY);
+      await crank(inflating);
+      const minted = (await vaultBalance(inflating)) - vaultBefore;
+      assert.equal(minted, expectedMint(before, 30 * DAY));
+
+      await warpTo(bank, start + 31 * DAY + 1);
+      await crank(inflating);
+      assert.equal((await vaultBalance(inflating)) - vaultBefore, minted);
+    });
+  });
+
+  describe("a mint whose hourly inflation rounds to nothing", () => {
+    // 1_000 base units at 10% a year come to one unit every ~88 hours.
+    const SUPPLY = 1_000;
+    let inflating: Inflating;
+    let start: number;
+
+    before(async () => {
+      inflating = await inflatingMint(SUPPLY, SUPPLY);
+      await setInflation(inflating, RATE_BPS);
+      await stakeAll(inflating);
+      start = await lastInflationTs(inflating);
+    });
+
+    it("doesn't lose the time to rounding when cranked every interval", async () => {
+      for (let hour = 1; hour <= 3; hour++) {
+        await warpTo(bank, start + hour * INTERVAL);
+        await crank(inflating);
+        assert.equal(await vaultBalance(inflating), BigInt(0));
+        assert.equal(await lastInflationTs(inflating), start);
+      }
+
+      const elapsed = 100 * INTERVAL;
+      await warpTo(bank, start + elapsed);
+      await crank(inflating);
+      assert.equal(await vaultBalance(inflating), expectedMint(BigInt(SUPPLY), elapsed));
+      assert.isAbove(Number(await vaultBalance(inflating)), 0);
+      assert.equal(await lastInflationTs(inflating), start + elapsed);
+    });
+  });
+});
@@ -0,0 +1,107 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  mintConfigPda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("maximum holder count", () => {
+  const admin = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  const alice = anchor.web3.Keypair.generate();
+  const bob = anchor.web3.Keypair.generate();
+  let aliceAccount: anchor.web3.PublicKey;
+  let bobAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    aliceAccount = await createFundedTokenAccount(mint, alice.publicKey, 0);
+    bobAccount = await createFundedTokenAccount(mint, bob.publicKey, 0);
+    await setMaxHolders(2);
+  });
+
+  function setMaxHolders(maxHolders: number | null) {
+    return program.methods
+      .setMaxHolders(maxHolders)
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  }
+
+  function mintTo(destination: anchor.web3.PublicKey, amount: number) {
+    return program.methods
+      .mintTokens(new anchor.BN(amount), null)
+      .accounts({
+        mintAuthority: admin,
+        mint,
+        destination,
+        mintConfig: mintConfigPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        payer: admin,
+        referralStats: null,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  function transfer(owner: anchor.web3.Keypair, from: anchor.web3.PublicKey, to: anchor.web3.PublicKey, amount: number) {
+    return program.methods
+      .transferTokens(new anchor.BN(amount), false)
+      .accounts({
+        owner: owner.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        from,
+        to,
+        feeTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        fromCheckpoints: null,
+        toCheckpoints: null,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  const holderCount = async () =>
+    (await program.account.mintConfig.fetch(mintConfigPda(mint))).holderCount.toNumber();
+  const newAccount = () => createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+
+  it("fills up to the cap", async () => {
+    await mintTo(aliceAccount, 100);
+    await mintTo(bobAccount, 100);
+    assert.equal(await holderCount(), 2);
+  });
+
+  it("refuses a new holder at the cap", async () => {
+    await expectError(mintTo(await newAccount(), 1), "HolderLimitReached");
+    await expectError(transfer(alice, aliceAccount, await newAccount(), 1), "HolderLimitReached");
+  });
+
+  it("still lets existing holders receive", async () => {
+    await mintTo(aliceAccount, 5);
+    await transfer(alice, aliceAccount, bobAccount, 10);
+    assert.equal((await getAccount(provider.connection, bobAccount)).amount, BigInt(110));
+    assert.equal(await holderCount(), 2);
+  });
+
+  it("lets a holder hand its whole balance to a newcomer", async () => {
+    const carol = await newAccount();
+    await transfer(bob, bobAccount, carol, 110);
+    assert.equal(await holderCount(), 2);
+    assert.equal((await getAccount(provider.connection, carol)).amount, BigInt(110));
+  });
+
+  it("only blocks newcomers when lowered below the count", async () => {
+    await setMaxHolders(1);
+    await mintTo(aliceAccount, 1);
+    await expectError(mintTo(bobAccount, 1), "HolderLimitReached");
+
+    await setMaxHolders(null);
+    await mintTo(bobAccount, 1);
+    assert.equal(await holderCount(), 3);
+  });
+});
@@ -0,0 +1,53 @@
+import * as anchor from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+
+// Mirrors the on-chain `merkle` module: sha256 with 0x00 / 0x01 domain
+// prefixes for leaves / inner nodes, and sorted pairs.
+export function hashLeaf(...parts: Buffer[]): Buffer {
+  const hash = createHash("sha256").update(Buffer.from([0]));
+  parts.forEach((p) => hash.update(p));
+  return hash.digest();
+}
+
+function hashPair(a: Buffer, b: Buffer): Buffer {
+  const [first, second] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+  return createHash("sha256").update(Buffer.from([1])).update(first).update(second).digest();
+}
+
+export class MerkleTree {
+  levels: Buffer[][];
+
+  constructor(leaves: Buffer[]) {
+    this.levels = [leaves];
+    while (this.levels[this.levels.length - 1].length > 1) {
+      const level = this.levels[this.levels.length - 1];
+      const next: Buffer[] = [];
+      for (let i = 0; i < level.length; i += 2) {
+        next.push(i + 1 < level.length ? hashPair(level[i], level[i + 1]) : level[i]);
+      }
+      this.levels.push(next);
+    }
+  }
+
+  root(): number[] {
+    return [...this.levels[this.levels.length - 1][0]];
+  }
+
+  proof(index: number): number[][] {
+    const proof: number[][] = [];
+    for (const level of this.levels.slice(0, -1)) {
+      const sibling = level[index ^ 1];
+      if (sibling) proof.push([...sibling]);
+      index = Math.floor(index / 2);
+    }
+    return proof;
+  }
+}
+
+export function u64Le(value: number | anchor.BN): Buffer {
+  return new anchor.BN(value).toArrayLike(Buffer, "le", 8);
+}
+
+export function airdropLeaf(claimant: anchor.web3.PublicKey, amount: number): Buffer {
+  return hashLeaf(claimant.toBuffer(), u64Le(amount));
+}
@@ -0,0 +1,124 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount, getMint } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  payer,
+  pda,
+  program,
+  provider,
+  sleep,
+} from "./helpers";
+
+interface Swap {
+  migration: anchor.web3.PublicKey;
+  oldMint: anchor.web3.PublicKey;
+  newMint: anchor.web3.PublicKey;
+  holderOld: anchor.web3.PublicKey;
+  holderNew: anchor.web3.PublicKey;
+}
+
+async function setUpMigration(
+  numerator: number,
+  denominator: number,
+  deadline: number,
+  vaultOldTokens: boolean
+): Promise<Swap> {
+  const oldMint = await createProgramMint();
+  const newMint = await createProgramMint();
+  const migration = pda([Buffer.from("migration"), oldMint.toBuffer(), newMint.toBuffer()]);
+
+  await program.methods
+    .initializeMigration(new anchor.BN(numerator), new anchor.BN(denominator), new anchor.BN(deadline), vaultOldTokens)
+    .accounts({
+      admin: provider.wallet.publicKey,
+      oldMint,
+      newMint,
+      newMintAuthority: pda([Buffer.from("mint_authority"), newMint.toBuffer()]),
+      migration,
+      vault: pda([Buffer.from("migration_vault"), migration.toBuffer()]),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    })
+    .rpc();
+
+  return {
+    migration,
+    oldMint,
+    newMint,
+    holderOld: await createFundedTokenAccount(oldMint, payer.publicKey, 1_000),
+    holderNew: await createFundedTokenAccount(newMint, payer.publicKey, 0),
+  };
+}
+
+function migrate(swap: Swap, amount: number) {
+  return program.methods
+    .migrateTokens(new anchor.BN(amount))
+    .accounts({
+      holder: payer.publicKey,
+      migration: swap.migration,
+      oldMint: swap.oldMint,
+      newMint: swap.newMint,
+      newMintAuthority: pda([Buffer.from("mint_authority"), swap.newMint.toBuffer()]),
+      vault: pda([Buffer.from("migration_vault"), swap.migration.toBuffer()]),
+      holderOld: swap.holderOld,
+      holderNew: swap.holderNew,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+const balance = async (account: anchor.web3.PublicKey) =>
+  Number((await getAccount(provider.connection, account)).amount);
+
+describe("mint migration", () => {
+  const farDeadline = () => Math.floor(Date.now() / 1000) + 3_600;
+
+  it("hands the new mint's authority to the program", async () => {
+    const swap = await setUpMigration(10, 1, farDeadline(), false);
+    const newMint = await getMint(provider.connection, swap.newMint);
+    assert.ok(newMint.mintAuthority.equals(pda([Buffer.from("mint_authority"), swap.newMint.toBuffer()])));
+  });
+
+  it("splits 1:10, burning the old tokens", async () => {
+    const swap = await setUpMigration(10, 1, farDeadline(), false);
+    await migrate(swap, 37);
+
+    assert.equal(await balance(swap.holderOld), 963);
+    assert.equal(await balance(swap.holderNew), 370);
+    assert.equal(Number((await getMint(provider.connection, swap.oldMint)).supply), 963);
+    const state = await program.account.migration.fetch(swap.migration);
+    assert.equal(state.totalOldMigrated.toNumber(), 37);
+    assert.equal(state.totalNewMinted.toNumber(), 370);
+  });
+
+  it("rounds odd amounts down on a 10:1 consolidation and vaults the old tokens", async () => {
+    const swap = await setUpMigration(1, 10, farDeadline(), true);
+    await migrate(swap, 15);
+    assert.equal(await balance(swap.holderNew), 1);
+    assert.equal(await balance(pda([Buffer.from("migration_vault"), swap.migration.toBuffer()])), 15);
+
+    // Would round down to nothing.
+    await expectError(migrate(swap, 9), "ZeroAmount");
+
+    const state = await program.account.migration.fetch(swap.migration);
+    assert.equal(state.totalOldMigrated.toNumber(), 15);
+    assert.equal(state.totalNewMinted.toNumber(), 1);
+  });
+
+  it("rejects swaps once the admin finalizes after the deadline", async () => {
+    const swap = await setUpMigration(10, 1, Math.floor(Date.now() / 1000) + 4, false);
+    const finalize = () =>
+      program.methods.finalizeMigration().accounts({ admin: provider.wallet.publicKey, migration: swap.migration }).rpc();
+
+    await expectError(finalize(), "MigrationStillOpen");
+    await migrate(swap, 1);
+
+    await sleep(6_000);
+    await finalize();
+    await expectError(migrate(swap, 1), "MigrationFinalized");
+  });
+});
@@ -0,0 +1,233 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, revoke } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+const DAY = 24 * 60 * 60;
+
+describe("mint stats", () => {
+  const owner = payer.publicKey;
+  const delegate = anchor.web3.Keypair.generate();
+  let mint: anchor.web3.PublicKey;
+  let spending: anchor.web3.PublicKey;
+  let frozen: anchor.web3.PublicKey;
+  let destination: anchor.web3.PublicKey;
+
+  const recordPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("delegation"), tokenAccount.toBuffer()]);
+  const stats = async () => {
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    return {
+      frozenAccounts: config.frozenAccountCount.toNumber(),
+      frozenSupply: config.frozenSupply.toNumber(),
+      activeDelegations: config.stats.activeDelegations.toNumber(),
+      freezes: config.stats.lifetimeFreezes.toNumber(),
+      thaws: config.stats.lifetimeThaws.toNumber(),
+      delegations: config.stats.lifetimeDelegations.toNumber(),
+      revocations: config.stats.lifetimeRevocations.toNumber(),
+      transferVolume: config.stats.transferVolume.toNumber(),
+    };
+  };
+
+  before(async () => {
+    await airdrop(delegate.publicKey);
+    mint = await createProgramMint();
+    spending = await createFundedTokenAccount(mint, owner, 1_000);
+    frozen = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 100);
+    destination = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+  });
+
+  const freeze = (tokenAccount: anchor.web3.PublicKey) =>
+    program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+  const thaw = (tokenAccount: anchor.web3.PublicKey) =>
+    program.methods.thawTokenAccount().accounts(thawAccounts(mint, tokenAccount)).rpc();
+
+  function delegateTokens(amount: number) {
+    return program.methods
+      .delegateTokens(new anchor.BN(amount), null)
+      .accounts({
+        tokenAccount: spending,
+        delegate: delegate.publicKey,
+        owner,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        payer: owner,
+        referralStats: null,
+      })
+      .rpc();
+  }
+
+  function revokeAll() {
+    return program.methods
+      .decreaseDelegation(new anchor.BN(1_000))
+      .accounts({
+        tokenAccount: spending,
+        delegate: delegate.publicKey,
+        owner,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+      })
+      .rpc();
+  }
+
+  function createDelegation() {
+    return program.methods
+      .createDelegation(
+        {
+          allowance: new anchor.BN(100),
+          mode: { advisory: {} },
+          expiresAt: null,
+          periodSeconds: new anchor.BN(DAY),
+          periodLimit: new anchor.BN(100),
+        },
+        []
+      )
+      .accounts({
+        tokenAccount: spending,
+        delegate: delegate.publicKey,
+        owner,
+        delegationRecord: recordPda(spending),
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function spendDelegated(amount: number) {
+    return program.methods
+      .spendDelegated(new anchor.BN(amount))
+      .accounts({
+        delegate: delegate.publicKey,
+        delegationRecord: recordPda(spending),
+        tokenAccount: spending,
+        mint,
+        destination,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+      })
+      .signers([delegate])
+      .rpc();
+  }
+
+  function closeDelegation() {
+    return program.methods
+      .closeDelegation()
+      .accounts({
+        owner,
+        delegationRecord: recordPda(spending),
+        tokenAccount: spending,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+      })
+      .rpc();
+  }
+
+  function transferTokens(amount: number) {
+    return program.methods
+      .transferTokens(new anchor.BN(amount), false)
+      .accounts({
+        owner,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        from: spending,
+        to: destination,
+        feeTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        fromCheckpoints: null,
+        toCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  function reconcileStats() {
+    return program.methods
+      .reconcileStats()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        tokenAccount: spending,
+        delegationRecord: recordPda(spending),
+        owner,
+      })
+      .rpc();
+  }
+
+  it("starts at zero", async () => {
+    assert.deepEqual(await stats(), {
+      frozenAccounts: 0,
+      frozenSupply: 0,
+      activeDelegations: 0,
+      freezes: 0,
+      thaws: 0,
+      delegations: 0,
+      revocations: 0,
+      transferVolume: 0,
+    });
+  });
+
+  it("adds up a scripted sequence of actions", async () => {
+    await freeze(spending);
+    await thaw(spending);
+    await freeze(frozen);
+    await delegateTokens(50);
+    await revokeAll();
+    await createDelegation();
+    await spendDelegated(10);
+    await transferTokens(20);
+    await closeDelegation();
+
+    assert.deepEqual(await stats(), {
+      frozenAccounts: 1,
+      frozenSupply: 100,
+      activeDelegations: 0,
+      freezes: 2,
+      thaws: 1,
+      delegations: 2,
+      revocations: 2,
+      transferVolume: 30,
+    });
+  });
+
+  describe("reconcile_stats", () => {
+    before(async () => {
+      await createDelegation();
+    });
+
+    it("refuses a delegation that is still live", async () => {
+      await expectError(reconcileStats(), "RecordStillLive");
+      assert.equal((await stats()).activeDelegations, 1);
+    });
+
+    it("stops counting a delegation revoked outside the program", async () => {
+      await revoke(provider.connection, payer, spending, payer);
+      await reconcileStats();
+
+      const after = await stats();
+      assert.equal(after.activeDelegations, 0);
+      assert.equal(after.delegations, 3);
+      // Not a revoke the program made.
+      assert.equal(after.revocations, 2);
+      assert.isNull(await provider.connection.getAccountInfo(recordPda(spending)));
+    });
+  });
+});
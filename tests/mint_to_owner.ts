@@ -0,0 +1,96 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { createProgramMint, expectError, mintConfigPda, payer, program, provider } from "./helpers";
+
+describe("minting to wallets", () => {
+  let mint: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+  });
+
+  const ata = (owner: anchor.web3.PublicKey) => getAssociatedTokenAddressSync(mint, owner);
+  const balance = async (owner: anchor.web3.PublicKey) => (await getAccount(provider.connection, ata(owner))).amount;
+
+  function mintToOwner(owner: anchor.web3.PublicKey, amount: number) {
+    return program.methods
+      .mintToOwner(new anchor.BN(amount))
+      .accounts({
+        mintAuthority: provider.wallet.publicKey,
+        mint,
+        owner,
+        destination: ata(owner),
+        mintConfig: mintConfigPda(mint),
+        payer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function distributeToOwners(pairs: [anchor.web3.PublicKey, anchor.web3.PublicKey][], amounts: number[]) {
+    return program.methods
+      .distributeToOwners(amounts.map((a) => new anchor.BN(a)), false)
+      .accounts({
+        mintAuthority: provider.wallet.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        payer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        pairs.flatMap(([owner, destination]) => [
+          { pubkey: owner, isWritable: false, isSigner: false },
+          { pubkey: destination, isWritable: true, isSigner: false },
+        ])
+      )
+      .rpc();
+  }
+
+  it("creates the ATA of a wallet that has none", async () => {
+    const owner = anchor.web3.Keypair.generate().publicKey;
+    assert.isNull(await provider.connection.getAccountInfo(ata(owner)));
+    await mintToOwner(owner, 25);
+    assert.equal(await balance(owner), BigInt(25));
+  });
+
+  it("mints into an ATA that already exists", async () => {
+    const owner = anchor.web3.Keypair.generate().publicKey;
+    await createAssociatedTokenAccount(provider.connection, payer, mint, owner);
+    await mintToOwner(owner, 5);
+    await mintToOwner(owner, 5);
+    assert.equal(await balance(owner), BigInt(10));
+  });
+
+  it("handles a batch mixing new and existing ATAs", async () => {
+    const fresh = anchor.web3.Keypair.generate().publicKey;
+    const existing = anchor.web3.Keypair.generate().publicKey;
+    await createAssociatedTokenAccount(provider.connection, payer, mint, existing);
+
+    await distributeToOwners(
+      [
+        [fresh, ata(fresh)],
+        [existing, ata(existing)],
+      ],
+      [7, 9]
+    );
+    assert.equal(await balance(fresh), BigInt(7));
+    assert.equal(await balance(existing), BigInt(9));
+  });
+
+  it("refuses a token account that isn't the owner's ATA", async () => {
+    const owner = anchor.web3.Keypair.generate().publicKey;
+    const someoneElse = anchor.web3.Keypair.generate().publicKey;
+    await expectError(distributeToOwners([[owner, ata(someoneElse)]], [1]), "InvalidAssociatedTokenAccount");
+  });
+});
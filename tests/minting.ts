@@ -0,0 +1,87 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  eventsFromTx,
+  eventsNamed,
+  expectError,
+  freezeAccounts,
+  mintConfigPda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("program-mediated minting", () => {
+  let mint: anchor.web3.PublicKey;
+  let open: anchor.web3.PublicKey;
+  let frozen: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    open = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    frozen = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, frozen)).rpc();
+  });
+
+  function mintTokens(destination: anchor.web3.PublicKey, amount: number) {
+    return program.methods
+      .mintTokens(new anchor.BN(amount), null)
+      .accounts({
+        mintAuthority: provider.wallet.publicKey,
+        mint,
+        destination,
+        mintConfig: mintConfigPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        payer: provider.wallet.publicKey,
+        referralStats: null,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  function distribute(destinations: anchor.web3.PublicKey[], amounts: number[], allowSkip: boolean) {
+    return program.methods
+      .distribute(amounts.map((a) => new anchor.BN(a)), allowSkip)
+      .accounts({
+        mintAuthority: provider.wallet.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        destinations.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+      )
+      .rpc();
+  }
+
+  it("mints into an open account", async () => {
+    await mintTokens(open, 10);
+    assert.equal((await getAccount(provider.connection, open)).amount, BigInt(10));
+  });
+
+  it("refuses a single mint into a frozen account", async () => {
+    await expectError(mintTokens(frozen, 10), "DestinationFrozen");
+  });
+
+  it("aborts a batch containing a frozen account without allow_skip", async () => {
+    await expectError(distribute([open, frozen], [5, 5], false), "DestinationFrozen");
+    assert.equal((await getAccount(provider.connection, open)).amount, BigInt(10));
+  });
+
+  it("skips and reports a frozen account with allow_skip", async () => {
+    const other = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    const sig = await distribute([open, frozen, other], [5, 7, 9], true);
+
+    assert.equal((await getAccount(provider.connection, open)).amount, BigInt(15));
+    assert.equal((await getAccount(provider.connection, other)).amount, BigInt(9));
+    assert.equal((await getAccount(provider.connection, frozen)).amount, BigInt(0));
+
+    const [summary] = eventsNamed(await eventsFromTx(sig), "DistributionCompleted");
+    assert.equal(summary.data.recipients, 2);
+    assert.equal(summary.data.totalMinted.toNumber(), 14);
+    assert.equal(summary.data.skipped.length, 1);
+    assert.ok(summary.data.skipped[0].equals(frozen));
+  });
+});
@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getMint } from "@solana/spl-token";
+import {
+  adminActionId,
+  adminActionPda,
+  createProgramMint,
+  ensureProgramConfig,
+  expectError,
+  hasMockClock,
+  i64Le,
+  mintConfigPda,
+  mockClockAccounts,
+  pda,
+  program,
+  programAuthority,
+  programConfig,
+  provider,
+  setMockTime,
+} from "./helpers";
+
+const HOUR = 60 * 60;
+
+// The timelock tests from authority_rotation.ts, driven by the mock clock
+// instead of sleeping, so the delay can be an hour. Skipped unless the program
+// was built with the test-clock feature.
+describe("timelock under the mock clock", () => {
+  const superAdmin = provider.wallet.publicKey;
+  const start = 1_700_000_000;
+
+  before(async function () {
+    if (!(await hasMockClock())) this.skip();
+    await ensureProgramConfig();
+    await setMockTime(start);
+    await program.methods
+      .setTimelockDelay(new anchor.BN(HOUR))
+      .accounts({ superAdmin, programConfig, adminAction: null })
+      .rpc();
+  });
+
+  function queue(actionId: number[]) {
+    return program.methods
+      .queueAdminAction(actionId)
+      .accounts({
+        superAdmin,
+        programConfig,
+        adminAction: adminActionPda(actionId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(mockClockAccounts)
+      .rpc();
+  }
+
+  function rotate(mint: anchor.web3.PublicKey, adminAction: anchor.web3.PublicKey) {
+    return program.methods
+      .rotateProgramAuthority()
+      .accounts({
+        superAdmin,
+        programConfig,
+        adminAction,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        programAuthority,
+        newProgramAuthority: pda([Buffer.from("authority_v2"), mint.toBuffer()]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(mockClockAccounts)
+      .rpc();
+  }
+
+  it("sets the eta from the mock time", async () => {
+    const actionId = adminActionId("rotate_program_authority", anchor.web3.Keypair.generate().publicKey.toBuffer());
+    await queue(actionId);
+    const action = await program.account.adminAction.fetch(adminActionPda(actionId));
+    assert.equal(action.eta.toNumber(), start + HOUR);
+  });
+
+  it("executes exactly when the delay has elapsed", async () => {
+    const mint = await createProgramMint();
+    const actionId = adminActionId("rotate_program_authority", mint.toBuffer());
+    await queue(actionId);
+
+    await setMockTime(start + HOUR - 1);
+    await expectError(rotate(mint, adminActionPda(actionId)), "TimelockNotElapsed");
+
+    await setMockTime(start + HOUR);
+    await rotate(mint, adminActionPda(actionId));
+    const mintInfo = await getMint(provider.connection, mint);
+    assert.ok(mintInfo.freezeAuthority.equals(pda([Buffer.from("authority_v2"), mint.toBuffer()])));
+  });
+
+  it("shortens the delay back to zero through the timelock", async () => {
+    const actionId = adminActionId("set_timelock_delay", i64Le(0));
+    await queue(actionId);
+    await setMockTime(start + 2 * HOUR);
+    await program.methods
+      .setTimelockDelay(new anchor.BN(0))
+      .accounts({ superAdmin, programConfig, adminAction: adminActionPda(actionId) })
+      .remainingAccounts(mockClockAccounts)
+      .rpc();
+
+    const config = await program.account.programConfig.fetch(programConfig);
+    assert.equal(config.timelockDelay.toNumber(), 0);
+  });
+});
@@ -0,0 +1,133 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { FreezeHookExample } from "../target/types/freeze_hook_example";
+import {
+  adminActionId,
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  mintConfigPda,
+  pda,
+  program,
+  programConfig,
+  provider,
+  queueAndWait,
+  thawAccounts,
+} from "./helpers";
+
+const hookProgram = anchor.workspace.freezeHookExample as Program<FreezeHookExample>;
+
+describe("notification hook", () => {
+  const admin = provider.wallet.publicKey;
+  const hookState = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("hook_state")],
+    hookProgram.programId
+  )[0];
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    await ensureProgramConfig();
+    mint = await createProgramMint();
+    tokenAccount = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    if (!(await provider.connection.getAccountInfo(hookState))) {
+      await hookProgram.methods
+        .initialize()
+        .accounts({ payer: admin, hookState, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    }
+
+    const hook = hookProgram.programId;
+    const adminAction = await queueAndWait(adminActionId("set_notification_hook", mint.toBuffer(), hook.toBuffer()));
+    await program.methods
+      .setNotificationHook(hook)
+      .accounts({
+        admin,
+        superAdmin: admin,
+        programConfig,
+        adminAction,
+        mint,
+        mintConfig: mintConfigPda(mint),
+      })
+      .rpc();
+  });
+
+  function withHook<T extends object>(accounts: T) {
+    return {
+      ...accounts,
+      notificationHook: hookProgram.programId,
+      notifier: pda([Buffer.from("notifier"), mint.toBuffer()]),
+    };
+  }
+  const hookAccounts = () => [{ pubkey: hookState, isWritable: true, isSigner: false }];
+
+  const setFail = (fail: boolean) =>
+    hookProgram.methods.setFail(fail).accounts({ anyone: admin, hookState }).rpc();
+
+  it("stores the hook on the MintConfig", async () => {
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.ok(config.notificationHook.equals(hookProgram.programId));
+    // Along with the notifier's bump, so freezes and thaws needn't search for it.
+    const [, bump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("notifier"), mint.toBuffer()],
+      program.programId
+    );
+    assert.equal(config.notifierBump, bump);
+  });
+
+  it("notifies the hook on freeze and thaw", async () => {
+    const before = (await hookProgram.account.hookState.fetch(hookState)).notifications.toNumber();
+
+    await program.methods
+      .freezeTokenAccount()
+      .accounts(withHook(freezeAccounts(mint, tokenAccount)))
+      .remainingAccounts(hookAccounts())
+      .rpc();
+    let state = await hookProgram.account.hookState.fetch(hookState);
+    assert.equal(state.notifications.toNumber(), before + 1);
+    assert.ok(state.lastTokenAccount.equals(tokenAccount));
+    assert.isTrue(state.lastFrozen);
+
+    await program.methods
+      .thawTokenAccount()
+      .accounts(withHook(thawAccounts(mint, tokenAccount)))
+      .remainingAccounts(hookAccounts())
+      .rpc();
+    state = await hookProgram.account.hookState.fetch(hookState);
+    assert.equal(state.notifications.toNumber(), before + 2);
+    assert.isFalse(state.lastFrozen);
+  });
+
+  it("refuses a hook program other than the configured one", async () => {
+    await expectError(
+      program.methods
+        .freezeTokenAccount()
+        .accounts({ ...withHook(freezeAccounts(mint, tokenAccount)), notificationHook: program.programId })
+        .rpc(),
+      "NotificationHookMismatch"
+    );
+  });
+
+  it("reverts with a failing hook, but freezes when the hook is left out", async () => {
+    await setFail(true);
+    try {
+      await program.methods
+        .freezeTokenAccount()
+        .accounts(withHook(freezeAccounts(mint, tokenAccount)))
+        .remainingAccounts(hookAccounts())
+        .rpc();
+      assert.fail("expected the failing hook to revert the freeze");
+    } catch (err) {
+      assert.match(String(err), /freeze hook configured to fail|Simulation failed/);
+    }
+
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+    const record = await program.account.freezeRecord.fetch(freezeRecordPda(tokenAccount));
+    assert.ok(record);
+    await setFail(false);
+  });
+});
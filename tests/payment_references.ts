@@ -0,0 +1,84 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { createFundedTokenAccount, createProgramMint, expectError, mintConfigPda, pda, program, provider } from "./helpers";
+
+describe("payment references", () => {
+  let mint: anchor.web3.PublicKey;
+  let from: anchor.web3.PublicKey;
+  let payee: anchor.web3.PublicKey;
+  const merchant = anchor.web3.Keypair.generate().publicKey;
+
+  const newReference = () => Array.from(anchor.web3.Keypair.generate().publicKey.toBytes());
+  const paymentPda = (reference: number[]) => pda([Buffer.from("payment"), Buffer.from(reference)]);
+
+  before(async () => {
+    mint = await createProgramMint();
+    from = await createFundedTokenAccount(mint, provider.wallet.publicKey, 1_000);
+    payee = await createFundedTokenAccount(mint, merchant, 0);
+  });
+
+  function pay(amount: number, reference: number[], allowMultiple: boolean, to = payee) {
+    return program.methods
+      .transferWithReference(new anchor.BN(amount), reference, allowMultiple)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        from,
+        to,
+        feeTokenAccount: null,
+        paymentRecord: paymentPda(reference),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  const view = (reference: number[]) =>
+    program.methods.getPaymentRecord(reference).accounts({ paymentRecord: paymentPda(reference) }).view();
+
+  it("records a single payment against its reference", async () => {
+    const reference = newReference();
+    const before = (await getAccount(provider.connection, payee)).amount;
+    await pay(25, reference, false);
+
+    assert.equal(Number((await getAccount(provider.connection, payee)).amount - before), 25);
+    const record = await view(reference);
+    assert.deepEqual(record.reference, reference);
+    assert.ok(record.mint.equals(mint));
+    assert.ok(record.payee.equals(payee));
+    assert.equal(record.totalAmount.toNumber(), 25);
+    assert.equal(record.paymentCount, 1);
+    assert.ok(record.payments[0].payer.equals(provider.wallet.publicKey));
+    assert.isAbove(record.payments[0].slot.toNumber(), 0);
+  });
+
+  it("refuses to reuse a reference in strict mode", async () => {
+    const reference = newReference();
+    await pay(10, reference, false);
+    await expectError(pay(10, reference, false), "ReferenceAlreadyUsed");
+    // Asking for multiple payments afterwards doesn't loosen it.
+    await expectError(pay(10, reference, true), "ReferenceAlreadyUsed");
+    assert.equal((await view(reference)).totalAmount.toNumber(), 10);
+  });
+
+  it("sums the payments against a multi-payment reference", async () => {
+    const reference = newReference();
+    await pay(30, reference, true);
+    await pay(12, reference, true);
+
+    const record = await view(reference);
+    assert.equal(record.paymentCount, 2);
+    assert.equal(record.totalAmount.toNumber(), 42);
+    assert.deepEqual(
+      record.payments.map((payment) => payment.amount.toNumber()),
+      [30, 12]
+    );
+
+    // A strict payment still refuses the reference, and so does another payee.
+    await expectError(pay(1, reference, false), "ReferenceAlreadyUsed");
+    const elsewhere = await createFundedTokenAccount(mint, merchant, 0);
+    await expectError(pay(1, reference, true, elsewhere), "PaymentPayeeMismatch");
+  });
+});
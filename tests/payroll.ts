@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  eventsFromTx,
+  eventsNamed,
+  payer,
+  pda,
+  program,
+  provider,
+  sleep,
+} from "./helpers";
+
+const EMPLOYEES = 30;
+
+// 30 recipients don't fit in a legacy transaction, so payroll goes out as a
+// v0 transaction with the employee accounts in a lookup table.
+async function createLookupTable(
+  addresses: anchor.web3.PublicKey[]
+): Promise<anchor.web3.AddressLookupTableAccount> {
+  const recentSlot = await provider.connection.getSlot();
+  const [create, table] = anchor.web3.AddressLookupTableProgram.createLookupTable({
+    authority: payer.publicKey,
+    payer: payer.publicKey,
+    recentSlot,
+  });
+  await provider.sendAndConfirm(new anchor.web3.Transaction().add(create));
+  for (let i = 0; i < addresses.length; i += 20) {
+    const extend = anchor.web3.AddressLookupTableProgram.extendLookupTable({
+      lookupTable: table,
+      authority: payer.publicKey,
+      payer: payer.publicKey,
+      addresses: addresses.slice(i, i + 20),
+    });
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(extend));
+  }
+  // A table can only be used from the slot after its last extension.
+  await sleep(1_000);
+  return (await provider.connection.getAddressLookupTable(table)).value;
+}
+
+async function sendV0(
+  instructions: anchor.web3.TransactionInstruction[],
+  table: anchor.web3.AddressLookupTableAccount
+): Promise<string> {
+  const { blockhash } = await provider.connection.getLatestBlockhash();
+  const message = new anchor.web3.TransactionMessage({
+    payerKey: payer.publicKey,
+    recentBlockhash: blockhash,
+    instructions,
+  }).compileToV0Message([table]);
+  return provider.sendAndConfirm(new anchor.web3.VersionedTransaction(message));
+}
+
+describe("payroll", () => {
+  const owner = payer.publicKey;
+  let vault: anchor.web3.PublicKey;
+  let vaultTokenAccount: anchor.web3.PublicKey;
+  let employees: anchor.web3.PublicKey[];
+  let table: anchor.web3.AddressLookupTableAccount;
+
+  before(async () => {
+    const mint = await createProgramMint();
+    const treasury = await createFundedTokenAccount(mint, owner, 4_000);
+    vault = pda([Buffer.from("vault"), mint.toBuffer(), owner.toBuffer()]);
+    vaultTokenAccount = pda([Buffer.from("vault_tokens"), vault.toBuffer()]);
+
+    await program.methods
+      .openVault()
+      .accounts({
+        owner,
+        mint,
+        vault,
+        vaultTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(4_000))
+      .accounts({ owner, vault, vaultTokenAccount, ownerTokenAccount: treasury, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+    employees = [];
+    for (let i = 0; i < EMPLOYEES; i++) {
+      employees.push(await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0));
+    }
+    table = await createLookupTable(employees);
+  });
+
+  async function payroll(amounts: number[]): Promise<string> {
+    const ix = await program.methods
+      .runPayroll(amounts.map((a) => new anchor.BN(a)))
+      .accounts({ owner, vault, vaultTokenAccount, tokenProgram: TOKEN_PROGRAM_ID })
+      .remainingAccounts(employees.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+      .instruction();
+    return sendV0(
+      [anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 }), ix],
+      table
+    );
+  }
+
+  const balances = () =>
+    Promise.all(employees.map(async (e) => Number((await getAccount(provider.connection, e)).amount)));
+
+  it("pays 30 employees in one transaction", async () => {
+    const sig = await payroll(new Array(EMPLOYEES).fill(100));
+
+    assert.deepEqual(await balances(), new Array(EMPLOYEES).fill(100));
+    assert.equal((await getAccount(provider.connection, vaultTokenAccount)).amount, BigInt(1_000));
+
+    const events = await eventsFromTx(sig);
+    assert.lengthOf(eventsNamed(events, "PayrollPaid"), EMPLOYEES);
+    const [summary] = eventsNamed(events, "PayrollCompleted");
+    assert.equal(summary.data.recipients, EMPLOYEES);
+    assert.equal(summary.data.total.toNumber(), 3_000);
+  });
+
+  it("pays nobody when the 29th amount pushes past the vault balance", async () => {
+    const amounts = new Array(EMPLOYEES).fill(30);
+    amounts[28] = 200; // 28 * 30 + 200 = 1_040 > 1_000
+
+    try {
+      await payroll(amounts);
+      assert.fail("expected the payroll to fail");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /InsufficientVaultBalance/);
+    }
+
+    assert.deepEqual(await balances(), new Array(EMPLOYEES).fill(100));
+    assert.equal((await getAccount(provider.connection, vaultTokenAccount)).amount, BigInt(1_000));
+  });
+});
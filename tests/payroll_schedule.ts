@@ -0,0 +1,138 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { expectError, pda } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const PERIOD = 30 * 24 * 60 * 60;
+
+describe("payroll schedule", () => {
+  let bank: Bank;
+  let owner: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+  let vaultTokenAccount: anchor.web3.PublicKey;
+  let schedule: anchor.web3.PublicKey;
+  let employees: anchor.web3.PublicKey[];
+  let firstRun: number;
+
+  before(async () => {
+    bank = await startBank();
+    owner = bank.provider.wallet.publicKey;
+    const mint = await createBankMint(bank);
+    const treasury = await createBankTokenAccount(bank, mint, owner, 10_000);
+    vault = pda([Buffer.from("vault"), mint.toBuffer(), owner.toBuffer()]);
+    vaultTokenAccount = pda([Buffer.from("vault_tokens"), vault.toBuffer()]);
+    schedule = pda([Buffer.from("payroll"), vault.toBuffer()]);
+
+    await bank.program.methods
+      .openVault()
+      .accounts({
+        owner,
+        mint,
+        vault,
+        vaultTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await bank.program.methods
+      .deposit(new anchor.BN(10_000))
+      .accounts({ owner, vault, vaultTokenAccount, ownerTokenAccount: treasury, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+    employees = [];
+    for (let i = 0; i < 3; i++) {
+      employees.push(await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 0));
+    }
+
+    firstRun = (await unixTimestamp(bank)) + 60 * 60;
+    await bank.program.methods
+      .createPayrollSchedule(new anchor.BN(PERIOD), [100, 200, 300].map((a) => new anchor.BN(a)), new anchor.BN(firstRun))
+      .accounts({ owner, vault, schedule, systemProgram: anchor.web3.SystemProgram.programId })
+      .remainingAccounts(recipients())
+      .rpc();
+  });
+
+  function recipients() {
+    return employees.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }));
+  }
+
+  function execute() {
+    return bank.program.methods
+      .executePayroll()
+      .accounts({ vault, vaultTokenAccount, schedule, tokenProgram: TOKEN_PROGRAM_ID })
+      .remainingAccounts(recipients())
+      .rpc();
+  }
+
+  const paid = async () =>
+    Promise.all(
+      employees.map(async (e) => Number((await getAccount(bank.provider.connection, e)).amount))
+    );
+
+  const nextRun = async () =>
+    (await bank.program.account.payrollSchedule.fetch(schedule)).nextRunTs.toNumber();
+
+  it("refuses to run early", async () => {
+    await expectError(execute(), "PayrollNotDue");
+  });
+
+  it("pays on time and advances by one period", async () => {
+    await warpTo(bank, firstRun);
+    await execute();
+
+    assert.deepEqual(await paid(), [100, 200, 300]);
+    assert.equal(await nextRun(), firstRun + PERIOD);
+    await warpTo(bank, firstRun + 1);
+    await expectError(execute(), "PayrollNotDue");
+  });
+
+  it("pays one period per crank after missed periods", async () => {
+    // Three more periods have come due.
+    await warpTo(bank, firstRun + 3 * PERIOD + 10);
+    await execute();
+    assert.deepEqual(await paid(), [200, 400, 600]);
+    assert.equal(await nextRun(), firstRun + 2 * PERIOD);
+
+    // Still behind, so the next crank pays exactly one more period.
+    await warpTo(bank, firstRun + 3 * PERIOD + 20);
+    await execute();
+    assert.deepEqual(await paid(), [300, 600, 900]);
+    assert.equal(await nextRun(), firstRun + 3 * PERIOD);
+  });
+
+  it("rejects a crank with the wrong recipients", async () => {
+    await warpTo(bank, firstRun + 3 * PERIOD + 30);
+    await expectError(
+      bank.program.methods
+        .executePayroll()
+        .accounts({ vault, vaultTokenAccount, schedule, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(recipients().reverse())
+        .rpc(),
+      "InvalidRemainingAccounts"
+    );
+  });
+
+  it("lets the owner update and then cancel the schedule", async () => {
+    await bank.program.methods
+      .updateSchedule(new anchor.BN(PERIOD / 2), [50].map((a) => new anchor.BN(a)))
+      .accounts({ owner, vault, schedule })
+      .remainingAccounts(recipients().slice(0, 1))
+      .rpc();
+    const updated = await bank.program.account.payrollSchedule.fetch(schedule);
+    assert.equal(updated.periodSeconds.toNumber(), PERIOD / 2);
+    assert.lengthOf(updated.recipients, 1);
+    assert.equal(updated.nextRunTs.toNumber(), firstRun + 3 * PERIOD);
+
+    await bank.program.methods.cancelSchedule().accounts({ owner, vault, schedule }).rpc();
+    assert.isNull(await bank.context.banksClient.getAccount(schedule));
+  });
+});
@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  mintConfigPda,
+  pda,
+  program,
+  programAuthority,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+describe("permissioned transfers", () => {
+  const admin = provider.wallet.publicKey;
+  const alice = anchor.web3.Keypair.generate();
+  const bob = anchor.web3.Keypair.generate();
+  const carol = anchor.web3.Keypair.generate();
+  let mint: anchor.web3.PublicKey;
+  let aliceAccount: anchor.web3.PublicKey;
+  let bobAccount: anchor.web3.PublicKey;
+  let carolAccount: anchor.web3.PublicKey;
+
+  const approvedHolderPda = (owner: anchor.web3.PublicKey) =>
+    pda([Buffer.from("approved_holder"), mint.toBuffer(), owner.toBuffer()]);
+
+  before(async () => {
+    await airdrop(alice.publicKey);
+    mint = await createProgramMint();
+    aliceAccount = await createFundedTokenAccount(mint, alice.publicKey, 100);
+    bobAccount = await createFundedTokenAccount(mint, bob.publicKey, 0);
+    carolAccount = await createFundedTokenAccount(mint, carol.publicKey, 0);
+
+    // Onboarding: every holder's account is frozen, and alice and bob are approved.
+    for (const account of [aliceAccount, bobAccount, carolAccount]) {
+      await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+    }
+    for (const owner of [alice.publicKey, bob.publicKey]) {
+      await program.methods
+        .addApprovedHolder(owner)
+        .accounts({
+          admin,
+          mint,
+          approvedHolder: approvedHolderPda(owner),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await setPolicy(true, false, 50);
+  });
+
+  function setPolicy(permissionedMode: boolean, paused: boolean, maxTransferAmount: number | null) {
+    return program.methods
+      .setTransferPolicy(permissionedMode, paused, maxTransferAmount === null ? null : new anchor.BN(maxTransferAmount))
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  }
+
+  function transfer(
+    from: anchor.web3.PublicKey,
+    fromOwner: anchor.web3.PublicKey,
+    to: anchor.web3.PublicKey,
+    toOwner: anchor.web3.PublicKey,
+    amount: number
+  ) {
+    return program.methods
+      .permissionedTransfer(new anchor.BN(amount))
+      .accounts({
+        owner: alice.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        from,
+        to,
+        fromRecord: freezeRecordPda(from),
+        toRecord: freezeRecordPda(to),
+        fromHolder: approvedHolderPda(fromOwner),
+        toHolder: approvedHolderPda(toOwner),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([alice])
+      .rpc();
+  }
+
+  const state = (account: anchor.web3.PublicKey) => getAccount(provider.connection, account);
+
+  it("moves tokens between approved holders and refreezes both accounts", async () => {
+    await transfer(aliceAccount, alice.publicKey, bobAccount, bob.publicKey, 30);
+
+    const [from, to] = [await state(aliceAccount), await state(bobAccount)];
+    assert.equal(from.amount, BigInt(70));
+    assert.equal(to.amount, BigInt(30));
+    assert.isTrue(from.isFrozen);
+    assert.isTrue(to.isFrozen);
+
+    const record = await program.account.freezeRecord.fetch(freezeRecordPda(bobAccount));
+    assert.equal(record.amount.toNumber(), 30);
+  });
+
+  it("rejects transfers the policy doesn't allow, leaving both accounts frozen", async () => {
+    await expectError(transfer(aliceAccount, alice.publicKey, bobAccount, bob.publicKey, 60), "TransferCapExceeded");
+    await expectError(
+      transfer(aliceAccount, alice.publicKey, carolAccount, carol.publicKey, 10),
+      "HolderNotApproved"
+    );
+    await setPolicy(true, true, 50);
+    await expectError(transfer(aliceAccount, alice.publicKey, bobAccount, bob.publicKey, 10), "TransfersPaused");
+    await setPolicy(true, false, 50);
+
+    for (const account of [aliceAccount, bobAccount, carolAccount]) {
+      assert.isTrue((await state(account)).isFrozen);
+    }
+    assert.equal((await state(aliceAccount)).amount, BigInt(70));
+    assert.equal((await state(carolAccount)).amount, BigInt(0));
+  });
+
+  it("refuses the ordinary thaw while the mode is on", async () => {
+    await expectError(
+      program.methods.thawTokenAccount().accounts(thawAccounts(mint, aliceAccount)).rpc(),
+      "PermissionedMode"
+    );
+    assert.isTrue((await state(aliceAccount)).isFrozen);
+  });
+});
@@ -0,0 +1,170 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createTransferInstruction, getAccount } from "@solana/spl-token";
+import { expectError, pda } from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, fund, startBank } from "./bankrun";
+
+// Mirrors shares::DEAD_SHARES.
+const DEAD_SHARES = 1_000;
+
+describe("pool vault", () => {
+  let bank: Bank;
+
+  before(async () => {
+    bank = await startBank();
+  });
+
+  const balance = async (tokenAccount: anchor.web3.PublicKey) =>
+    Number((await getAccount(bank.provider.connection, tokenAccount)).amount);
+
+  // A fresh mint with its pool created.
+  async function newPool() {
+    const underlyingMint = await createBankMint(bank);
+    const poolVault = pda([Buffer.from("pool_vault"), underlyingMint.toBuffer()]);
+    const pool = {
+      underlyingMint,
+      poolVault,
+      shareMint: pda([Buffer.from("pool_share_mint"), poolVault.toBuffer()]),
+      vault: pda([Buffer.from("pool_vault_tokens"), poolVault.toBuffer()]),
+      deadShares: pda([Buffer.from("pool_dead_shares"), poolVault.toBuffer()]),
+    };
+    await bank.program.methods
+      .initializePoolVault()
+      .accounts({
+        payer: bank.provider.wallet.publicKey,
+        ...pool,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    return pool;
+  }
+  type Pool = Awaited<ReturnType<typeof newPool>>;
+
+  // A funded depositor with an account for underlying tokens and one for shares.
+  async function depositor(pool: Pool, tokens: number) {
+    const keypair = anchor.web3.Keypair.generate();
+    await fund(bank, keypair.publicKey);
+    return {
+      keypair,
+      underlying: await createBankTokenAccount(bank, pool.underlyingMint, keypair.publicKey, tokens),
+      shares: await createBankTokenAccount(bank, pool.shareMint, keypair.publicKey, 0),
+    };
+  }
+  type Depositor = Awaited<ReturnType<typeof depositor>>;
+
+  function poolShares(pool: Pool, user: Depositor) {
+    return {
+      user: user.keypair.publicKey,
+      poolVault: pool.poolVault,
+      shareMint: pool.shareMint,
+      vault: pool.vault,
+      deadShares: pool.deadShares,
+      userUnderlying: user.underlying,
+      userShares: user.shares,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  const deposit = (pool: Pool, user: Depositor, amount: number) =>
+    bank.program.methods
+      .depositForShares(new anchor.BN(amount))
+      .accounts(poolShares(pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  const redeem = (pool: Pool, user: Depositor, shares: number) =>
+    bank.program.methods
+      .redeemShares(new anchor.BN(shares))
+      .accounts(poolShares(pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  // Tokens paid straight into the vault, as outside yield would be.
+  async function donate(pool: Pool, amount: number) {
+    const source = await createBankTokenAccount(bank, pool.underlyingMint, bank.provider.wallet.publicKey, amount);
+    await bank.provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createTransferInstruction(source, pool.vault, bank.provider.wallet.publicKey, amount)
+      )
+    );
+  }
+
+  describe("two depositors and outside yield", () => {
+    let pool: Pool;
+    let alice: Depositor;
+    let bob: Depositor;
+
+    before(async () => {
+      pool = await newPool();
+      alice = await depositor(pool, 10_000);
+      bob = await depositor(pool, 20_000);
+    });
+
+    it("won't open a pool with a deposit no larger than its dead shares", async () => {
+      await expectError(deposit(pool, alice, DEAD_SHARES), "DepositBelowDeadShares");
+    });
+
+    it("prices shares 1:1 until yield arrives, keeping DEAD_SHARES back", async () => {
+      await deposit(pool, alice, 10_000);
+      await deposit(pool, bob, 20_000);
+      assert.equal(await balance(alice.shares), 10_000 - DEAD_SHARES);
+      assert.equal(await balance(pool.deadShares), DEAD_SHARES);
+      assert.equal(await balance(bob.shares), 20_000);
+    });
+
+    it("shares yield paid into the vault by shares held", async () => {
+      // 33_000 tokens over 30_000 shares.
+      await donate(pool, 3_000);
+
+      await redeem(pool, bob, 20_000);
+      assert.equal(await balance(bob.underlying), 22_000);
+      await redeem(pool, alice, 10_000 - DEAD_SHARES);
+      assert.equal(await balance(alice.underlying), 9_900);
+
+      // What's left belongs to the dead shares.
+      assert.equal(await balance(pool.vault), 1_100);
+      assert.equal(await balance(alice.shares), 0);
+      assert.equal(await balance(bob.shares), 0);
+    });
+
+    it("can't redeem more shares than are held", async () => {
+      await expectError(redeem(pool, alice, 1), "AmountExceedsBalance");
+    });
+  });
+
+  describe("a first depositor inflating the share price", () => {
+    let pool: Pool;
+    let attacker: Depositor;
+    let victim: Depositor;
+    const donation = 1_000_000;
+
+    before(async () => {
+      pool = await newPool();
+      attacker = await depositor(pool, DEAD_SHARES + 1);
+      victim = await depositor(pool, 10_000);
+      // One share for the attacker, then a donation to make it expensive.
+      await deposit(pool, attacker, DEAD_SHARES + 1);
+      await donate(pool, donation);
+    });
+
+    it("refuses a deposit that would round to no shares instead of taking it", async () => {
+      await expectError(deposit(pool, victim, 500), "ZeroShares");
+      assert.equal(await balance(victim.underlying), 10_000);
+    });
+
+    it("leaves the donation mostly with the dead shares", async () => {
+      await deposit(pool, victim, 10_000);
+      assert.equal(await balance(victim.shares), 9);
+      await redeem(pool, victim, 9);
+      // Rounding costs the victim less than a tenth of its deposit...
+      assert.isAbove(await balance(victim.underlying), 9_000);
+
+      // ...while the attacker's share is worth a thousandth of what it paid.
+      assert.equal(await balance(attacker.shares), 1);
+      await redeem(pool, attacker, 1);
+      assert.isBelow(await balance(attacker.underlying), (DEAD_SHARES + 1 + donation) / 500);
+    });
+  });
+});
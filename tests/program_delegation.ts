@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  mintConfigPda,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("delegation to program PDAs", () => {
+  let mint: anchor.web3.PublicKey;
+  // Any executable program will do as the protocol; the token program is one.
+  const targetProgram = TOKEN_PROGRAM_ID;
+
+  before(async () => {
+    mint = await createProgramMint();
+  });
+
+  const recordPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("delegation"), tokenAccount.toBuffer()]);
+
+  // A fresh owner and token account, and a PDA of `targetProgram` with the seeds that derive it.
+  async function setup() {
+    const owner = anchor.web3.Keypair.generate();
+    await airdrop(owner.publicKey);
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 1_000);
+    const base = [Buffer.from("protocol_vault"), owner.publicKey.toBuffer()];
+    const [delegate, bump] = anchor.web3.PublicKey.findProgramAddressSync(base, targetProgram);
+    return { owner, tokenAccount, delegate, seeds: [...base, Buffer.from([bump])] };
+  }
+
+  function delegateToProgram(
+    { owner, tokenAccount, delegate }: Awaited<ReturnType<typeof setup>>,
+    seeds: Buffer[],
+    target = targetProgram
+  ) {
+    return program.methods
+      .delegateToProgram(new anchor.BN(250), seeds)
+      .accounts({
+        tokenAccount,
+        delegate,
+        targetProgram: target,
+        owner: owner.publicKey,
+        delegationRecord: recordPda(tokenAccount),
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  it("approves the PDA and records the program it belongs to", async () => {
+    const accounts = await setup();
+    await delegateToProgram(accounts, accounts.seeds);
+
+    const tokenAccount = await getAccount(provider.connection, accounts.tokenAccount);
+    assert.ok(tokenAccount.delegate.equals(accounts.delegate));
+    assert.equal(Number(tokenAccount.delegatedAmount), 250);
+
+    const record = await program.account.delegationRecord.fetch(recordPda(accounts.tokenAccount));
+    assert.ok(record.delegate.equals(accounts.delegate));
+    assert.ok(record.delegateProgram.equals(targetProgram));
+    assert.deepEqual(record.terms.mode, { advisory: {} });
+    assert.equal(record.terms.allowance.toNumber(), 250);
+  });
+
+  it("refuses seeds that don't derive the delegate", async () => {
+    const accounts = await setup();
+    const wrong = [Buffer.from("protocol_vault"), anchor.web3.Keypair.generate().publicKey.toBuffer(), accounts.seeds[2]];
+    await expectError(delegateToProgram(accounts, wrong), "DelegatePdaMismatch");
+    // Right seeds, but under another program.
+    await expectError(delegateToProgram(accounts, accounts.seeds, program.programId), "DelegatePdaMismatch");
+    assert.isNull(await provider.connection.getAccountInfo(recordPda(accounts.tokenAccount)));
+  });
+
+  it("refuses a target that isn't an executable program", async () => {
+    const accounts = await setup();
+    const wallet = anchor.web3.Keypair.generate().publicKey;
+    await expectError(delegateToProgram(accounts, accounts.seeds, wallet), "TargetNotExecutable");
+  });
+});
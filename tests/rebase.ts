@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  adminActionId,
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  expectError,
+  mintConfigPda,
+  payer,
+  program,
+  programConfig,
+  provider,
+  queueAndWait,
+} from "./helpers";
+import { u64Le } from "./merkle";
+
+const SCALE = 1_000_000_000;
+
+describe("rebase factor", () => {
+  const superAdmin = provider.wallet.publicKey;
+  let mint: anchor.web3.PublicKey;
+  let from: anchor.web3.PublicKey;
+  let to: anchor.web3.PublicKey;
+
+  before(async () => {
+    await ensureProgramConfig();
+    mint = await createProgramMint();
+    from = await createFundedTokenAccount(mint, payer.publicKey, 1_000);
+    to = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+  });
+
+  async function setFactor(factor: number) {
+    const adminAction = await queueAndWait(adminActionId("set_rebase_factor", mint.toBuffer(), u64Le(factor)));
+    return program.methods
+      .setRebaseFactor(new anchor.BN(factor))
+      .accounts({ superAdmin, programConfig, adminAction, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  }
+
+  const effective = async (tokenAccount: anchor.web3.PublicKey) =>
+    (
+      await program.methods
+        .getEffectiveBalance()
+        .accounts({ mintConfig: mintConfigPda(mint), tokenAccount })
+        .view()
+    ).toNumber();
+
+  function transfer(amount: number, effectiveUnits: boolean) {
+    return program.methods
+      .transferTokens(new anchor.BN(amount), effectiveUnits)
+      .accounts({
+        owner: payer.publicKey,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        from,
+        to,
+        feeTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        fromCheckpoints: null,
+        toCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  it("starts at a factor of one", async () => {
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.rebaseFactor.toNumber(), SCALE);
+    assert.equal(await effective(from), 1_000);
+  });
+
+  it("refuses a zero factor", async () => {
+    await expectError(setFactor(0), "InvalidRebaseFactor");
+  });
+
+  it("reports balances scaled by the factor, rounded down", async () => {
+    await setFactor(1.5 * SCALE);
+    assert.equal(await effective(from), 1_500);
+    // Raw balances are untouched.
+    assert.equal((await getAccount(provider.connection, from)).amount, BigInt(1_000));
+  });
+
+  it("converts effective amounts to raw before transferring", async () => {
+    // 151 effective at 1.5x is 100.67 raw, rounded down to 100.
+    await transfer(151, true);
+    assert.equal((await getAccount(provider.connection, to)).amount, BigInt(100));
+    assert.equal(await effective(to), 150);
+
+    await transfer(10, false);
+    assert.equal((await getAccount(provider.connection, to)).amount, BigInt(110));
+  });
+
+  it("refuses an effective amount that rounds to nothing", async () => {
+    await setFactor(3 * SCALE);
+    await expectError(transfer(2, true), "ZeroAmount");
+  });
+});
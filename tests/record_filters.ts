@@ -0,0 +1,57 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createFundedTokenAccount, createProgramMint, freezeAccounts, program, provider } from "./helpers";
+
+// Mirrors the `filters` module.
+const MINT_OFFSET = 8;
+const ACTOR_OFFSET = 104;
+
+describe("record filters", () => {
+  let mint: anchor.web3.PublicKey;
+  let otherMint: anchor.web3.PublicKey;
+  const frozen: anchor.web3.PublicKey[] = [];
+  let frozenElsewhere: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    otherMint = await createProgramMint();
+    for (let i = 0; i < 2; i++) {
+      const tokenAccount = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+      await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+      frozen.push(tokenAccount);
+    }
+    frozenElsewhere = await createFundedTokenAccount(otherMint, anchor.web3.Keypair.generate().publicKey, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(otherMint, frozenElsewhere)).rpc();
+  });
+
+  async function freezeRecords(offset: number, key: anchor.web3.PublicKey) {
+    const accounts = await provider.connection.getProgramAccounts(program.programId, {
+      filters: [
+        { memcmp: program.coder.accounts.memcmp("freezeRecord") },
+        { memcmp: { offset, bytes: key.toBase58() } },
+      ],
+    });
+    return accounts.map(({ account }) => program.coder.accounts.decode("freezeRecord", account.data));
+  }
+
+  it("finds a mint's freeze records by the mint offset", async () => {
+    const records = await freezeRecords(MINT_OFFSET, mint);
+    assert.lengthOf(records, 2);
+    assert.sameMembers(
+      records.map((r) => r.tokenAccount.toBase58()),
+      frozen.map((a) => a.toBase58())
+    );
+
+    const elsewhere = await freezeRecords(MINT_OFFSET, otherMint);
+    assert.lengthOf(elsewhere, 1);
+    assert.ok(elsewhere[0].tokenAccount.equals(frozenElsewhere));
+  });
+
+  it("finds freezes by the actor offset", async () => {
+    const records = await freezeRecords(ACTOR_OFFSET, provider.wallet.publicKey);
+    const tokenAccounts = records.map((r) => r.tokenAccount.toBase58());
+    for (const tokenAccount of [...frozen, frozenElsewhere]) {
+      assert.include(tokenAccounts, tokenAccount.toBase58());
+    }
+  });
+});
@@ -0,0 +1,247 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createTransferInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeRecordPda,
+  mintConfigPda,
+  pda,
+  program,
+  programAuthority,
+  provider,
+  sleep,
+} from "./helpers";
+
+describe("wallet recovery", () => {
+  const admin = provider.wallet.publicKey;
+  const WINDOW_SECONDS = 2;
+
+  const recoveryPda = (tokenAccount: anchor.web3.PublicKey) =>
+    pda([Buffer.from("recovery"), tokenAccount.toBuffer()]);
+
+  async function setup() {
+    const mint = await createProgramMint();
+    await program.methods
+      .setRecoveryWindow(new anchor.BN(WINDOW_SECONDS))
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+    const owner = anchor.web3.Keypair.generate();
+    await airdrop(owner.publicKey);
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 1_000);
+    return { mint, owner, tokenAccount };
+  }
+
+  function initiate(
+    mint: anchor.web3.PublicKey,
+    tokenAccount: anchor.web3.PublicKey,
+    owner: anchor.web3.Keypair,
+    newOwner: anchor.web3.PublicKey
+  ) {
+    return program.methods
+      .initiateRecovery(newOwner)
+      .accounts({
+        owner: owner.publicKey,
+        tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        recoveryRequest: recoveryPda(tokenAccount),
+        freezeRecord: freezeRecordPda(tokenAccount),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  function execute(
+    mint: anchor.web3.PublicKey,
+    tokenAccount: anchor.web3.PublicKey,
+    newOwner: anchor.web3.PublicKey
+  ) {
+    return program.methods
+      .executeRecovery()
+      .accounts({
+        admin,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        tokenAccount,
+        recoveryRequest: recoveryPda(tokenAccount),
+        freezeRecord: freezeRecordPda(tokenAccount),
+        newOwner,
+        newOwnerTokenAccount: getAssociatedTokenAddressSync(mint, newOwner),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  // The admin cancels, with the owner co-signing if `owner` is a keypair.
+  function cancel(
+    mint: anchor.web3.PublicKey,
+    tokenAccount: anchor.web3.PublicKey,
+    owner: anchor.web3.PublicKey | anchor.web3.Keypair,
+    signer?: anchor.web3.Keypair
+  ) {
+    const ownerSigner = owner instanceof anchor.web3.Keypair ? owner : undefined;
+    const signers = [signer, ownerSigner].filter((k): k is anchor.web3.Keypair => k !== undefined);
+    const builder = program.methods.cancelRecovery().accounts({
+      admin: signer ? signer.publicKey : admin,
+      mint,
+      mintConfig: mintConfigPda(mint),
+      tokenAccount,
+      recoveryRequest: recoveryPda(tokenAccount),
+      freezeRecord: freezeRecordPda(tokenAccount),
+      owner: ownerSigner ? ownerSigner.publicKey : owner,
+      programAuthority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+    return builder.signers(signers).rpc();
+  }
+
+  it("moves the balance to the new owner only after the dispute window", async () => {
+    const { mint, owner, tokenAccount } = await setup();
+    const newOwner = anchor.web3.Keypair.generate().publicKey;
+
+    await initiate(mint, tokenAccount, owner, newOwner);
+    assert.isTrue((await getAccount(provider.connection, tokenAccount)).isFrozen);
+
+    await expectError(execute(mint, tokenAccount, newOwner), "RecoveryWindowActive");
+
+    await sleep((WINDOW_SECONDS + 1) * 1000);
+    await execute(mint, tokenAccount, newOwner);
+
+    const ata = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, newOwner));
+    assert.equal(ata.amount, BigInt(1_000));
+    assert.isNull(await provider.connection.getAccountInfo(tokenAccount));
+    assert.isNull(await provider.connection.getAccountInfo(recoveryPda(tokenAccount)));
+  });
+
+  it("thaws and clears the delegate when the owner co-signs the admin's cancel", async () => {
+    const { mint, owner, tokenAccount } = await setup();
+    await initiate(mint, tokenAccount, owner, anchor.web3.Keypair.generate().publicKey);
+
+    await cancel(mint, tokenAccount, owner);
+
+    const account = await getAccount(provider.connection, tokenAccount);
+    assert.isFalse(account.isFrozen);
+    assert.isNull(account.delegate);
+    assert.isNull(account.closeAuthority);
+  });
+
+  it("lets the admin cancel alone after the window, leaving the delegate", async () => {
+    const { mint, owner, tokenAccount } = await setup();
+    await initiate(mint, tokenAccount, owner, anchor.web3.Keypair.generate().publicKey);
+    await sleep((WINDOW_SECONDS + 1) * 1000);
+
+    await cancel(mint, tokenAccount, owner.publicKey);
+    const account = await getAccount(provider.connection, tokenAccount);
+    assert.isFalse(account.isFrozen);
+    assert.ok(account.delegate.equals(recoveryPda(tokenAccount)));
+    assert.isNull(account.closeAuthority);
+  });
+
+  it("won't let the owner cancel without the admin", async () => {
+    const { mint, owner, tokenAccount } = await setup();
+    await initiate(mint, tokenAccount, owner, anchor.web3.Keypair.generate().publicKey);
+
+    await expectError(cancel(mint, tokenAccount, owner.publicKey, owner), "Unauthorized");
+    const stranger = anchor.web3.Keypair.generate();
+    await expectError(cancel(mint, tokenAccount, owner.publicKey, stranger), "Unauthorized");
+    assert.isTrue((await getAccount(provider.connection, tokenAccount)).isFrozen);
+  });
+
+  it("gives a leftover delegate nothing once a new request is opened", async () => {
+    const { mint, owner, tokenAccount } = await setup();
+    await initiate(mint, tokenAccount, owner, anchor.web3.Keypair.generate().publicKey);
+    await cancel(mint, tokenAccount, owner.publicKey);
+
+    // The owner spends part of the balance, then opens a new request.
+    const elsewhere = await createFundedTokenAccount(mint, owner.publicKey, 0);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(createTransferInstruction(tokenAccount, elsewhere, owner.publicKey, 400)),
+      [owner]
+    );
+    const newOwner = anchor.web3.Keypair.generate().publicKey;
+    await initiate(mint, tokenAccount, owner, newOwner);
+    const account = await getAccount(provider.connection, tokenAccount);
+    assert.equal(account.delegatedAmount, BigInt(600));
+
+    await sleep((WINDOW_SECONDS + 1) * 1000);
+    await execute(mint, tokenAccount, newOwner);
+    const ata = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, newOwner));
+    assert.equal(ata.amount, BigInt(600));
+  });
+
+  it("won't let the leaked key revoke the request's delegate while it is pending", async () => {
+    const { mint, owner, tokenAccount } = await setup();
+    const newOwner = anchor.web3.Keypair.generate().publicKey;
+    await initiate(mint, tokenAccount, owner, newOwner);
+
+    await expectError(
+      program.methods
+        .revokeWhileFrozen()
+        .accounts({
+          owner: owner.publicKey,
+          tokenAccount,
+          mint,
+          mintConfig: mintConfigPda(mint),
+          recoveryRequest: recoveryPda(tokenAccount),
+          programAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc(),
+      "RecoveryPending"
+    );
+    assert.ok((await getAccount(provider.connection, tokenAccount)).delegate.equals(recoveryPda(tokenAccount)));
+
+    await sleep((WINDOW_SECONDS + 1) * 1000);
+    await execute(mint, tokenAccount, newOwner);
+    const ata = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, newOwner));
+    assert.equal(ata.amount, BigInt(1_000));
+  });
+
+  it("lets the admin defeat an attacker racing with the leaked key", async () => {
+    const { mint, owner: leakedKey, tokenAccount } = await setup();
+    const attackerWallet = anchor.web3.Keypair.generate().publicKey;
+    const rescueWallet = anchor.web3.Keypair.generate().publicKey;
+
+    // The attacker gets in first; the real owner can't open a second request.
+    await initiate(mint, tokenAccount, leakedKey, attackerWallet);
+    try {
+      await initiate(mint, tokenAccount, leakedKey, rescueWallet);
+      assert.fail("expected a second recovery request to fail");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+
+    // The admin vets the request, rejects it, and the owner starts over.
+    await cancel(mint, tokenAccount, leakedKey.publicKey);
+    await initiate(mint, tokenAccount, leakedKey, rescueWallet);
+
+    // Executing against the attacker's wallet fails the request's has_one check.
+    await sleep((WINDOW_SECONDS + 1) * 1000);
+    try {
+      await execute(mint, tokenAccount, attackerWallet);
+      assert.fail("expected execution to the wrong wallet to fail");
+    } catch (err) {
+      assert.match(String(err), /ConstraintHasOne|ConstraintAssociated|ConstraintTokenOwner/);
+    }
+
+    await execute(mint, tokenAccount, rescueWallet);
+    const ata = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, rescueWallet));
+    assert.equal(ata.amount, BigInt(1_000));
+  });
+});
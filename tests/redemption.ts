@@ -0,0 +1,119 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { randomBytes } from "crypto";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { expectError, pda, programConfig } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  handMintAuthorityToProgram,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const REDEMPTION_EXPIRY = 30 * 24 * 60 * 60;
+
+describe("redemption receipts", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let mintAuthority: anchor.web3.PublicKey;
+  let redeemer: anchor.web3.PublicKey;
+  let redeemerTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    redeemer = bank.provider.wallet.publicKey;
+    mint = await createBankMint(bank);
+    redeemerTokenAccount = await createBankTokenAccount(bank, mint, redeemer, 1_000);
+    mintAuthority = await handMintAuthorityToProgram(bank, mint);
+  });
+
+  const receiptPda = (orderId: Buffer) => pda([Buffer.from("redeem"), orderId]);
+  const balance = async () => (await getAccount(bank.provider.connection, redeemerTokenAccount)).amount;
+
+  function redeem(amount: number, orderId: Buffer) {
+    return bank.program.methods
+      .redeem(new anchor.BN(amount), [...orderId])
+      .accounts({
+        redeemer,
+        mint,
+        redeemerTokenAccount,
+        receipt: receiptPda(orderId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function refund(orderId: Buffer) {
+    return bank.program.methods
+      .refundRedemption()
+      .accounts({
+        redeemer,
+        receipt: receiptPda(orderId),
+        mint,
+        mintAuthority,
+        redeemerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  it("burns the tokens and records the order", async () => {
+    const orderId = randomBytes(32);
+    await redeem(100, orderId);
+
+    assert.equal(await balance(), BigInt(900));
+    const receipt = await bank.program.account.redemptionReceipt.fetch(receiptPda(orderId));
+    assert.ok(receipt.redeemer.equals(redeemer));
+    assert.equal(receipt.amount.toNumber(), 100);
+    assert.deepEqual(receipt.orderId, [...orderId]);
+    assert.deepEqual(receipt.status, { pending: {} });
+  });
+
+  it("rejects a second redemption for the same order id", async () => {
+    const orderId = randomBytes(32);
+    await redeem(10, orderId);
+    try {
+      await redeem(20, orderId);
+      assert.fail("expected the duplicate order id to fail");
+    } catch (err) {
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+  });
+
+  it("lets the super_admin mark a redemption fulfilled, which rules out a refund", async () => {
+    const orderId = randomBytes(32);
+    await redeem(50, orderId);
+    await bank.program.methods
+      .fulfillRedemption()
+      .accounts({ superAdmin: bank.provider.wallet.publicKey, programConfig, receipt: receiptPda(orderId) })
+      .rpc();
+
+    const receipt = await bank.program.account.redemptionReceipt.fetch(receiptPda(orderId));
+    assert.deepEqual(receipt.status, { fulfilled: {} });
+
+    await warpTo(bank, (await unixTimestamp(bank)) + REDEMPTION_EXPIRY);
+    await expectError(refund(orderId), "RedemptionNotPending");
+  });
+
+  it("refunds an unfulfilled redemption only after it expires", async () => {
+    const orderId = randomBytes(32);
+    await redeem(200, orderId);
+    const before = await balance();
+    await expectError(refund(orderId), "RedemptionNotExpired");
+
+    await warpTo(bank, (await unixTimestamp(bank)) + REDEMPTION_EXPIRY);
+    await refund(orderId);
+    assert.equal(await balance(), before + BigInt(200));
+
+    const receipt = await bank.program.account.redemptionReceipt.fetch(receiptPda(orderId));
+    assert.deepEqual(receipt.status, { refunded: {} });
+    await warpTo(bank, (await unixTimestamp(bank)) + 1);
+    await expectError(refund(orderId), "RedemptionNotPending");
+  });
+});
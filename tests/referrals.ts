@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  eventsFromTx,
+  eventsNamed,
+  expectError,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  provider,
+} from "./helpers";
+
+describe("referrals", () => {
+  const referrer = anchor.web3.Keypair.generate().publicKey;
+  const referralStats = pda([Buffer.from("referral"), referrer.toBuffer()]);
+  let mint: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+  });
+
+  async function delegate(owner: anchor.web3.Keypair, amount: number, ref: anchor.web3.PublicKey | null) {
+    const tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 1_000);
+    return program.methods
+      .delegateTokens(new anchor.BN(amount), ref)
+      .accounts({
+        tokenAccount,
+        delegate: anchor.web3.Keypair.generate().publicKey,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        payer: payer.publicKey,
+        referralStats: ref ? pda([Buffer.from("referral"), ref.toBuffer()]) : null,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function mintTo(owner: anchor.web3.PublicKey, amount: number, ref: anchor.web3.PublicKey | null) {
+    const destination = await createFundedTokenAccount(mint, owner, 0);
+    return program.methods
+      .mintTokens(new anchor.BN(amount), ref)
+      .accounts({
+        mintAuthority: provider.wallet.publicKey,
+        mint,
+        destination,
+        mintConfig: mintConfigPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        payer: payer.publicKey,
+        referralStats: ref ? pda([Buffer.from("referral"), ref.toBuffer()]) : null,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  const totals = () => program.methods.getReferralStats().accounts({ referralStats }).view();
+
+  it("creates the stats on the first referral and credits later ones", async () => {
+    const alice = anchor.web3.Keypair.generate();
+    const bob = anchor.web3.Keypair.generate();
+    await airdrop(alice.publicKey);
+    await airdrop(bob.publicKey);
+
+    const sig = await delegate(alice, 100, referrer);
+    const [event] = eventsNamed(await eventsFromTx(sig), "TokensDelegated");
+    assert.ok(event.data.referrer.equals(referrer));
+
+    await delegate(bob, 250, referrer);
+    await mintTo(alice.publicKey, 40, referrer);
+    await mintTo(anchor.web3.Keypair.generate().publicKey, 60, referrer);
+
+    const stats = await totals();
+    assert.equal(stats.delegations.toNumber(), 2);
+    assert.equal(stats.delegatedVolume.toNumber(), 350);
+    assert.equal(stats.mints.toNumber(), 2);
+    assert.equal(stats.mintedVolume.toNumber(), 100);
+
+    const account = await program.account.referralStats.fetch(referralStats);
+    assert.ok(account.referrer.equals(referrer));
+  });
+
+  it("leaves the stats alone for unreferred actions", async () => {
+    const owner = anchor.web3.Keypair.generate();
+    await airdrop(owner.publicKey);
+    const sig = await delegate(owner, 10, null);
+    const [event] = eventsNamed(await eventsFromTx(sig), "TokensDelegated");
+    assert.isNull(event.data.referrer);
+    await mintTo(owner.publicKey, 10, null);
+
+    const stats = await totals();
+    assert.equal(stats.delegations.toNumber(), 2);
+    assert.equal(stats.mints.toNumber(), 2);
+  });
+
+  it("rejects self-referral", async () => {
+    const owner = anchor.web3.Keypair.generate();
+    await airdrop(owner.publicKey);
+    await expectError(delegate(owner, 10, owner.publicKey), "SelfReferral");
+    await expectError(mintTo(owner.publicKey, 10, owner.publicKey), "SelfReferral");
+  });
+});
@@ -0,0 +1,70 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createMint, getAccount, getMint } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  expectError,
+  freezeAccounts,
+  mintConfigPda,
+  retiredMintPda,
+  payer,
+  program,
+  programAuthority,
+  provider,
+} from "./helpers";
+
+describe("register_existing_mint", () => {
+  // A mint made with the token program directly, as the spl-token CLI would.
+  function externalMint(freezeAuthority: anchor.web3.PublicKey | null) {
+    return createMint(provider.connection, payer, payer.publicKey, freezeAuthority, 6);
+  }
+
+  function register(mint: anchor.web3.PublicKey) {
+    return program.methods
+      .registerExistingMint()
+      .accounts({
+        admin: payer.publicKey,
+        mint,
+        programAuthority,
+        mintConfig: mintConfigPda(mint),
+        retiredMint: retiredMintPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  it("takes over the freeze authority, then freezes through the program", async () => {
+    const mint = await externalMint(payer.publicKey);
+    await register(mint);
+
+    const mintInfo = await getMint(provider.connection, mint);
+    assert.ok(mintInfo.freezeAuthority.equals(programAuthority));
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.ok(config.mint.equals(mint));
+
+    const account = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+    assert.isTrue((await getAccount(provider.connection, account)).isFrozen);
+  });
+
+  it("rejects a mint without a freeze authority", async () => {
+    await expectError(register(await externalMint(null)), "NoFreezeAuthority");
+  });
+
+  it("rejects a signer that isn't the freeze authority", async () => {
+    await expectError(register(await externalMint(anchor.web3.Keypair.generate().publicKey)), "Unauthorized");
+  });
+
+  it("refuses to register a mint twice", async () => {
+    const mint = await externalMint(payer.publicKey);
+    await register(mint);
+    try {
+      await register(mint);
+      assert.fail("expected the second registration to fail");
+    } catch (err) {
+      // The MintConfig already exists, so its `init` fails.
+      assert.match((err.logs ?? []).join("\n") || String(err), /already in use/);
+    }
+  });
+});
@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { expectError, freezeAccounts, freezeRecordPda, freezerBondPda, pda, thawAccounts } from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, fund, startBank } from "./bankrun";
+
+describe("rent payer", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  const rentPayer = pda([Buffer.from("rent_payer")]);
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+  });
+
+  const admin = () => bank.provider.wallet.publicKey;
+  const lamports = async (address: anchor.web3.PublicKey) =>
+    Number((await bank.context.banksClient.getAccount(address))?.lamports ?? 0);
+
+  function fundAuthority(amount: number, funder?: anchor.web3.Keypair) {
+    return bank.program.methods
+      .fundAuthority(new anchor.BN(amount))
+      .accounts({
+        funder: funder?.publicKey ?? admin(),
+        rentPayer,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers(funder ? [funder] : [])
+      .rpc();
+  }
+
+  async function freezeNew() {
+    const tokenAccount = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await bank.program.methods
+      .freezeTokenAccount()
+      .accounts({ ...freezeAccounts(mint, tokenAccount), admin: admin(), freezerBond: freezerBondPda(mint, admin()), rentPayer })
+      .rpc();
+    return tokenAccount;
+  }
+
+  function thaw(tokenAccount: anchor.web3.PublicKey, withRentPayer = true) {
+    return bank.program.methods
+      .thawTokenAccount()
+      .accounts({
+        ...thawAccounts(mint, tokenAccount),
+        admin: admin(),
+        freezerBond: freezerBondPda(mint, admin()),
+        rentPayer: withRentPayer ? rentPayer : null,
+      })
+      .rpc();
+  }
+
+  it("falls back to the caller while the rent payer has nothing spare", async () => {
+    await fundAuthority(1);
+    const before = await lamports(rentPayer);
+    await freezeNew();
+
+    assert.equal(await lamports(rentPayer), before);
+    const state = await bank.program.account.rentPayer.fetch(rentPayer);
+    assert.equal(state.totalFunded.toNumber(), 1);
+    assert.equal(state.totalSpent.toNumber(), 0);
+  });
+
+  it("can be funded by anyone", async () => {
+    const funder = anchor.web3.Keypair.generate();
+    await fund(bank, funder.publicKey);
+    await fundAuthority(anchor.web3.LAMPORTS_PER_SOL / 10, funder);
+
+    const state = await bank.program.account.rentPayer.fetch(rentPayer);
+    assert.equal(state.totalFunded.toNumber(), 1 + anchor.web3.LAMPORTS_PER_SOL / 10);
+  });
+
+  it("pays the freeze record's rent once funded", async () => {
+    const before = await lamports(rentPayer);
+    const tokenAccount = await freezeNew();
+    const recordRent = await lamports(freezeRecordPda(tokenAccount));
+
+    assert.isAbove(recordRent, 0);
+    assert.equal(await lamports(rentPayer), before - recordRent);
+    const state = await bank.program.account.rentPayer.fetch(rentPayer);
+    assert.equal(state.totalSpent.toNumber(), recordRent);
+  });
+
+  it("takes the rent back when the record it paid for is closed", async () => {
+    const tokenAccount = await freezeNew();
+    const recordRent = await lamports(freezeRecordPda(tokenAccount));
+    const before = await lamports(rentPayer);
+
+    // The admin was reimbursed at freeze, so it can't have the rent again.
+    await expectError(thaw(tokenAccount, false), "RentPayerRequired");
+    await thaw(tokenAccount);
+
+    assert.equal(await lamports(freezeRecordPda(tokenAccount)), 0);
+    assert.equal(await lamports(rentPayer), before + recordRent);
+  });
+
+  it("still refunds the caller for a record it paid for itself", async () => {
+    // Frozen without the rent payer, so the admin paid.
+    const tokenAccount = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await bank.program.methods
+      .freezeTokenAccount()
+      .accounts({ ...freezeAccounts(mint, tokenAccount), admin: admin(), freezerBond: freezerBondPda(mint, admin()) })
+      .rpc();
+    const before = await lamports(rentPayer);
+    await thaw(tokenAccount);
+    assert.equal(await lamports(rentPayer), before);
+  });
+});
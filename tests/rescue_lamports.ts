@@ -0,0 +1,64 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { adminActionId, expectError, programAuthority, programConfig } from "./helpers";
+import { Bank, queueBankAction, setProgramConfig, startBank } from "./bankrun";
+
+describe("rescue_lamports", () => {
+  let bank: Bank;
+  let superAdmin: anchor.web3.PublicKey;
+  let rentExempt: bigint;
+  const treasury = anchor.web3.Keypair.generate().publicKey;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    superAdmin = bank.provider.wallet.publicKey;
+    rentExempt = (await bank.context.banksClient.getRent()).minimumBalance(BigInt(0));
+  });
+
+  function setAuthorityLamports(lamports: bigint) {
+    bank.context.setAccount(programAuthority, {
+      lamports: Number(lamports),
+      data: Buffer.alloc(0),
+      owner: anchor.web3.SystemProgram.programId,
+      executable: false,
+    });
+  }
+
+  async function rescue(signer?: anchor.web3.Keypair) {
+    const adminAction = await queueBankAction(bank, adminActionId("rescue_lamports", treasury.toBuffer()));
+    return bank.program.methods
+      .rescueLamports()
+      .accounts({
+        superAdmin: signer?.publicKey ?? superAdmin,
+        programConfig,
+        adminAction,
+        programAuthority,
+        treasury,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers(signer ? [signer] : [])
+      .rpc();
+  }
+
+  const lamports = async (address: anchor.web3.PublicKey) =>
+    (await bank.context.banksClient.getAccount(address))?.lamports ?? 0;
+
+  it("does nothing when the PDA holds exactly the rent-exempt minimum", async () => {
+    setAuthorityLamports(rentExempt);
+    await rescue();
+    assert.equal(await lamports(programAuthority), Number(rentExempt));
+    assert.equal(await lamports(treasury), 0);
+  });
+
+  it("moves the surplus to the treasury and leaves the minimum", async () => {
+    setAuthorityLamports(rentExempt + BigInt(5_000_000));
+    await rescue();
+    assert.equal(await lamports(programAuthority), Number(rentExempt));
+    assert.equal(await lamports(treasury), 5_000_000);
+  });
+
+  it("refuses anyone but the super_admin", async () => {
+    await expectError(rescue(anchor.web3.Keypair.generate()), "NotSuperAdmin");
+  });
+});
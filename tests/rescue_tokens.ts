@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountInstruction,
+  createMintToInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { adminActionId, expectError, i64Le, pda, programAuthority, programConfig } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  handMintAuthorityToProgram,
+  queueBankAction,
+  setProgramConfig,
+  startBank,
+} from "./bankrun";
+
+describe("rescue_tokens", () => {
+  let bank: Bank;
+  let superAdmin: anchor.web3.PublicKey;
+  let mint: anchor.web3.PublicKey;
+  let stray: anchor.web3.PublicKey;
+  let destination: anchor.web3.PublicKey;
+  let rewardVault: anchor.web3.PublicKey;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    superAdmin = bank.provider.wallet.publicKey;
+    mint = await createBankMint(bank);
+    destination = await createBankTokenAccount(bank, mint, superAdmin, 0);
+
+    // Someone sends tokens to the program authority's ATA.
+    stray = getAssociatedTokenAddressSync(mint, programAuthority, true);
+    await bank.provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(superAdmin, stray, programAuthority, mint),
+        createMintToInstruction(mint, stray, superAdmin, 500)
+      )
+    );
+
+    // A staking reward vault, set up through set_inflation.
+    const mintAuthority = await handMintAuthorityToProgram(bank, mint);
+    const rewardPool = pda([Buffer.from("reward_pool"), mint.toBuffer()]);
+    rewardVault = pda([Buffer.from("reward_vault"), rewardPool.toBuffer()]);
+    const rateBps = Buffer.alloc(2);
+    rateBps.writeUInt16LE(100);
+    const interval = 60 * 60;
+    const adminAction = await queueBankAction(
+      bank,
+      adminActionId("set_inflation", mint.toBuffer(), rateBps, i64Le(interval))
+    );
+    await bank.program.methods
+      .setInflation(100, new anchor.BN(interval))
+      .accounts({
+        superAdmin,
+        programConfig,
+        adminAction,
+        mint,
+        mintAuthority,
+        rewardPool,
+        rewardVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  function rescue(strayAccount: anchor.web3.PublicKey, amount: number, rescueAll: boolean) {
+    return bank.program.methods
+      .rescueTokens(new anchor.BN(amount), rescueAll)
+      .accounts({
+        superAdmin,
+        programConfig,
+        programAuthority,
+        strayAccount,
+        destination,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  const balance = async (account: anchor.web3.PublicKey) =>
+    (await getAccount(bank.provider.connection, account)).amount;
+
+  it("moves part of the stray balance out", async () => {
+    await rescue(stray, 200, false);
+    assert.equal(await balance(stray), BigInt(300));
+    assert.equal(await balance(destination), BigInt(200));
+  });
+
+  it("drains and closes the stray account with rescue_all", async () => {
+    await rescue(stray, 0, true);
+    assert.equal(await balance(destination), BigInt(500));
+    assert.isNull(await bank.context.banksClient.getAccount(stray));
+  });
+
+  it("refuses to touch the staking reward vault", async () => {
+    await expectError(rescue(rewardVault, 1, false), "ProtectedVault");
+  });
+
+  it("refuses token accounts the program authority doesn't own", async () => {
+    const other = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await expectError(rescue(other, 1, false), "NotTokenAccountOwner");
+  });
+});
@@ -0,0 +1,67 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, approve, getAccount } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  programAuthority,
+  provider,
+} from "./helpers";
+
+describe("revoke_while_frozen", () => {
+  const owner = anchor.web3.Keypair.generate();
+  const delegate = anchor.web3.Keypair.generate().publicKey;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    tokenAccount = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    await approve(provider.connection, payer, tokenAccount, delegate, owner, 40);
+  });
+
+  function revoke(signer: anchor.web3.Keypair) {
+    return program.methods
+      .revokeWhileFrozen()
+      .accounts({
+        owner: signer.publicKey,
+        tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        recoveryRequest: pda([Buffer.from("recovery"), tokenAccount.toBuffer()]),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  it("refuses an account that isn't frozen", async () => {
+    await expectError(revoke(owner), "AccountNotFrozen");
+  });
+
+  it("refuses a signer that doesn't own the account", async () => {
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+    await expectError(revoke(anchor.web3.Keypair.generate()), "NotTokenAccountOwner");
+    const account = await getAccount(provider.connection, tokenAccount);
+    assert.ok(account.delegate.equals(delegate));
+  });
+
+  it("lets the owner drop the delegate and leaves the account frozen", async () => {
+    const frozenBefore = (await program.account.mintConfig.fetch(mintConfigPda(mint))).frozenAccountCount;
+    await revoke(owner);
+
+    const account = await getAccount(provider.connection, tokenAccount);
+    assert.isTrue(account.isFrozen);
+    assert.isNull(account.delegate);
+    assert.equal(account.delegatedAmount, BigInt(0));
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.frozenAccountCount.toString(), frozenBefore.toString());
+  });
+});
@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  accountHistoryPda,
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  ensureProgramConfig,
+  eventsFromTx,
+  eventsNamed,
+  expectError,
+  freezeExemptPda,
+  freezeRecordPda,
+  mintConfigPda,
+  pda,
+  program,
+  programAuthority,
+  programConfig,
+  provider,
+} from "./helpers";
+
+describe("sanctions", () => {
+  const superAdmin = provider.wallet.publicKey;
+  const sanctioned = anchor.web3.Keypair.generate().publicKey;
+  const bystander = anchor.web3.Keypair.generate();
+  let mintA: anchor.web3.PublicKey;
+  let mintB: anchor.web3.PublicKey;
+
+  const sanctionPda = (owner: anchor.web3.PublicKey) => pda([Buffer.from("sanctioned_owner"), owner.toBuffer()]);
+
+  before(async () => {
+    await ensureProgramConfig();
+    await airdrop(bystander.publicKey);
+    mintA = await createProgramMint();
+    mintB = await createProgramMint();
+    await program.methods
+      .sanctionOwner(sanctioned)
+      .accounts({
+        superAdmin,
+        programConfig,
+        sanctionedOwner: sanctionPda(sanctioned),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  function enforce(mint: anchor.web3.PublicKey, tokenAccount: anchor.web3.PublicKey, owner: anchor.web3.PublicKey) {
+    return program.methods
+      .enforceSanction()
+      .accounts({
+        caller: bystander.publicKey,
+        tokenAccountToProcess: tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        sanctionedOwner: sanctionPda(owner),
+        freezeRecord: freezeRecordPda(tokenAccount),
+        freezeExempt: freezeExemptPda(tokenAccount),
+        accountHistory: accountHistoryPda(tokenAccount),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rentPayer: null,
+      })
+      .signers([bystander])
+      .rpc();
+  }
+
+  const isFrozen = async (tokenAccount: anchor.web3.PublicKey) =>
+    (await getAccount(provider.connection, tokenAccount)).isFrozen;
+
+  it("freezes every account of the owner, across mints", async () => {
+    const accounts: [anchor.web3.PublicKey, anchor.web3.PublicKey][] = [
+      [mintA, await createFundedTokenAccount(mintA, sanctioned, 10)],
+      [mintA, await createFundedTokenAccount(mintA, sanctioned, 20)],
+      [mintB, await createFundedTokenAccount(mintB, sanctioned, 30)],
+    ];
+
+    for (const [mint, tokenAccount] of accounts) {
+      const sig = await enforce(mint, tokenAccount, sanctioned);
+      assert.isTrue(await isFrozen(tokenAccount));
+
+      const [event] = eventsNamed(await eventsFromTx(sig), "SanctionEnforced");
+      assert.ok(event.data.sanction.equals(sanctionPda(sanctioned)));
+      assert.ok(event.data.mint.equals(mint));
+      const history = await program.account.accountHistory.fetch(accountHistoryPda(tokenAccount));
+      assert.deepEqual(history.entries[history.entries.length - 1].reason, { sanction: {} });
+    }
+  });
+
+  it("rejects an owner who isn't sanctioned", async () => {
+    const other = anchor.web3.Keypair.generate().publicKey;
+    const tokenAccount = await createFundedTokenAccount(mintA, other, 10);
+    await expectError(enforce(mintA, tokenAccount, other), "OwnerNotSanctioned");
+    assert.isFalse(await isFrozen(tokenAccount));
+  });
+
+  it("stops enforcing once lifted, leaving frozen accounts frozen", async () => {
+    const frozen = await createFundedTokenAccount(mintB, sanctioned, 5);
+    await enforce(mintB, frozen, sanctioned);
+    await program.methods
+      .liftSanction()
+      .accounts({ superAdmin, programConfig, sanctionedOwner: sanctionPda(sanctioned) })
+      .rpc();
+
+    const fresh = await createFundedTokenAccount(mintB, sanctioned, 5);
+    await expectError(enforce(mintB, fresh, sanctioned), "OwnerNotSanctioned");
+    assert.isTrue(await isFrozen(frozen));
+  });
+});
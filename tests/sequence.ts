@@ -0,0 +1,168 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  airdrop,
+  createFundedTokenAccount,
+  createProgramMint,
+  eventsFromTx,
+  eventsNamed,
+  freezeAccounts,
+  freezeRecordPda,
+  mintConfigPda,
+  payer,
+  pda,
+  program,
+  programAuthority,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+describe("sequence numbers", () => {
+  const owner = anchor.web3.Keypair.generate();
+  let mint: anchor.web3.PublicKey;
+  let first: anchor.web3.PublicKey;
+  let second: anchor.web3.PublicKey;
+
+  before(async () => {
+    await airdrop(owner.publicKey);
+    mint = await createProgramMint();
+    first = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    second = await createFundedTokenAccount(mint, owner.publicKey, 100);
+  });
+
+  // The `sequence` carried by every event of `sig`.
+  async function sequences(sig: string) {
+    return (await eventsFromTx(sig)).filter((e) => "sequence" in e.data).map((e) => e.data.sequence.toNumber());
+  }
+
+  const freeze = (tokenAccount: anchor.web3.PublicKey) =>
+    program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, tokenAccount)).rpc();
+  const thaw = (tokenAccount: anchor.web3.PublicKey) =>
+    program.methods.thawTokenAccount().accounts(thawAccounts(mint, tokenAccount)).rpc();
+  const delegate = (tokenAccount: anchor.web3.PublicKey) =>
+    program.methods
+      .delegateTokens(new anchor.BN(10), null)
+      .accounts({
+        tokenAccount,
+        delegate: anchor.web3.Keypair.generate().publicKey,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintConfig: mintConfigPda(mint),
+        feeRecipient: null,
+        approvedDelegate: null,
+        payer: payer.publicKey,
+        referralStats: null,
+      })
+      .signers([owner])
+      .rpc();
+  const revoke = (tokenAccount: anchor.web3.PublicKey) =>
+    program.methods
+      .revokeWhileFrozen()
+      .accounts({
+        owner: owner.publicKey,
+        tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        recoveryRequest: pda([Buffer.from("recovery"), tokenAccount.toBuffer()]),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+  it("numbers interleaved changes with strictly increasing sequences", async () => {
+    const start = (await program.account.mintConfig.fetch(mintConfigPda(mint))).sequence.toNumber();
+
+    const seen: number[] = [];
+    for (const sig of [
+      await delegate(first),
+      await freeze(first),
+      await delegate(second),
+      await revoke(first),
+      await freeze(second),
+      await thaw(first),
+      await thaw(second),
+    ]) {
+      seen.push(...(await sequences(sig)));
+    }
+
+    assert.lengthOf(seen, 7);
+    seen.forEach((sequence, i) => assert.equal(sequence, start + i + 1));
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.equal(config.sequence.toNumber(), start + 7);
+  });
+
+  it("numbers recoveries and allowance increases in the same series", async () => {
+    const adjusted = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    const recovered = await createFundedTokenAccount(mint, owner.publicKey, 100);
+    const spender = anchor.web3.Keypair.generate().publicKey;
+    const recoveryRequest = pda([Buffer.from("recovery"), recovered.toBuffer()]);
+    const recoveryAccounts = {
+      mint,
+      mintConfig: mintConfigPda(mint),
+      tokenAccount: recovered,
+      recoveryRequest,
+      freezeRecord: freezeRecordPda(recovered),
+      programAuthority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    const increase = () =>
+      program.methods
+        .increaseDelegation(new anchor.BN(10))
+        .accounts({
+          tokenAccount: adjusted,
+          delegate: spender,
+          owner: owner.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintConfig: mintConfigPda(mint),
+          feeRecipient: null,
+          approvedDelegate: null,
+        })
+        .signers([owner])
+        .rpc();
+    const initiate = () =>
+      program.methods
+        .initiateRecovery(anchor.web3.Keypair.generate().publicKey)
+        .accounts({ ...recoveryAccounts, owner: owner.publicKey, systemProgram: anchor.web3.SystemProgram.programId })
+        .signers([owner])
+        .rpc();
+    // The owner co-signs, so the request's delegate is revoked as well.
+    const cancel = () =>
+      program.methods
+        .cancelRecovery()
+        .accounts({ ...recoveryAccounts, admin: provider.wallet.publicKey, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    const start = (await program.account.mintConfig.fetch(mintConfigPda(mint))).sequence.toNumber();
+    const seen: number[] = [];
+    for (const sig of [await increase(), await initiate(), await increase(), await cancel()]) {
+      seen.push(...(await sequences(sig)));
+    }
+
+    // One for each increase, and a delegate and a freeze or thaw each for initiating and cancelling.
+    assert.lengthOf(seen, 6);
+    seen.forEach((sequence, i) => assert.equal(sequence, start + i + 1));
+  });
+
+  it("numbers transfer policy changes, such as a pause", async () => {
+    const setPolicy = (paused: boolean) =>
+      program.methods
+        .setTransferPolicy(false, paused, null)
+        .accounts({ admin: provider.wallet.publicKey, mint, mintConfig: mintConfigPda(mint) })
+        .rpc();
+
+    const start = (await program.account.mintConfig.fetch(mintConfigPda(mint))).sequence.toNumber();
+    const events = [...(await eventsFromTx(await setPolicy(true))), ...(await eventsFromTx(await setPolicy(false)))];
+    const policies = eventsNamed(events, "TransferPolicySet");
+    assert.deepEqual(
+      policies.map((e) => [e.data.transfersPaused, e.data.sequence.toNumber()]),
+      [
+        [true, start + 1],
+        [false, start + 2],
+      ]
+    );
+  });
+});
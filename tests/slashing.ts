@@ -0,0 +1,296 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { adminActionId, adminActionPda, expectError, i64Le, pda, programConfig } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  fund,
+  handMintAuthorityToProgram,
+  queueBankAction,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const DAY = 24 * 60 * 60;
+const PRECISION = BigInt(1_000_000_000_000);
+
+function u16Le(value: number): Buffer {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(value);
+  return buf;
+}
+
+function u64Le(value: number): Buffer {
+  return new anchor.BN(value).toArrayLike(Buffer, "le", 8);
+}
+
+describe("stake slashing", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let mintAuthority: anchor.web3.PublicKey;
+  let rewardPool: anchor.web3.PublicKey;
+  let rewardVault: anchor.web3.PublicKey;
+  let stakeVault: anchor.web3.PublicKey;
+  const treasury = pda([Buffer.from("treasury")]);
+  let treasuryTokenAccount: anchor.web3.PublicKey;
+
+  // `slashed` is the bank's wallet; `bystander` is never slashed.
+  let slashedTokenAccount: anchor.web3.PublicKey;
+  const bystander = anchor.web3.Keypair.generate();
+  let bystanderTokenAccount: anchor.web3.PublicKey;
+
+  const superAdmin = () => bank.provider.wallet.publicKey;
+  const slashed = () => bank.provider.wallet.publicKey;
+  const positionPda = (staker: anchor.web3.PublicKey) =>
+    pda([Buffer.from("stake"), mint.toBuffer(), staker.toBuffer()]);
+  const balance = async (account: anchor.web3.PublicKey) =>
+    (await getAccount(bank.provider.connection, account)).amount;
+  const rewardPerToken = async () =>
+    BigInt((await bank.program.account.rewardPool.fetch(rewardPool)).rewardPerToken.toString());
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    await fund(bank, bystander.publicKey);
+
+    mint = await createBankMint(bank);
+    slashedTokenAccount = await createBankTokenAccount(bank, mint, slashed(), 1_000);
+    bystanderTokenAccount = await createBankTokenAccount(bank, mint, bystander.publicKey, 1_000);
+    treasuryTokenAccount = await createBankTokenAccount(bank, mint, treasury, 0);
+    mintAuthority = await handMintAuthorityToProgram(bank, mint);
+    rewardPool = pda([Buffer.from("reward_pool"), mint.toBuffer()]);
+    rewardVault = pda([Buffer.from("reward_vault"), rewardPool.toBuffer()]);
+    stakeVault = pda([Buffer.from("stake_vault"), rewardPool.toBuffer()]);
+
+    const rateBps = 1_000;
+    const interval = 60 * 60;
+    await bank.program.methods
+      .setInflation(rateBps, new anchor.BN(interval))
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        adminAction: await queueBankAction(
+          bank,
+          adminActionId("set_inflation", mint.toBuffer(), u16Le(rateBps), i64Le(interval))
+        ),
+        mint,
+        mintAuthority,
+        rewardPool,
+        rewardVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await bank.program.methods
+      .setStakeConfig(new anchor.BN(0), 0, false)
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        adminAction: await queueBankAction(
+          bank,
+          adminActionId("set_stake_config", mint.toBuffer(), i64Le(0), u16Le(0), Buffer.from([0]))
+        ),
+        mint,
+        rewardPool,
+        stakeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await stake(slashed(), slashedTokenAccount, 1_000);
+    await stake(bystander.publicKey, bystanderTokenAccount, 1_000, [bystander]);
+  });
+
+  function stake(
+    staker: anchor.web3.PublicKey,
+    stakerTokenAccount: anchor.web3.PublicKey,
+    amount: number,
+    signers: anchor.web3.Keypair[] = []
+  ) {
+    return bank.program.methods
+      .stake(new anchor.BN(amount))
+      .accounts({
+        staker,
+        rewardPool,
+        stakeVault,
+        position: positionPda(staker),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        votingCheckpoints: null,
+      })
+      .signers(signers)
+      .rpc();
+  }
+
+  function claim(
+    staker: anchor.web3.PublicKey,
+    stakerTokenAccount: anchor.web3.PublicKey,
+    signers: anchor.web3.Keypair[] = []
+  ) {
+    return bank.program.methods
+      .claimStakeRewards()
+      .accounts({
+        staker,
+        rewardPool,
+        rewardVault,
+        position: positionPda(staker),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers(signers)
+      .rpc();
+  }
+
+  // Crank a day's inflation and return the new reward_per_token.
+  async function crankADay() {
+    await warpTo(bank, (await unixTimestamp(bank)) + DAY);
+    await bank.program.methods
+      .crankInflation()
+      .accounts({ mint, mintAuthority, rewardPool, rewardVault, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+    return rewardPerToken();
+  }
+
+  const slashHold = () => pda([Buffer.from("slash_hold"), positionPda(slashed()).toBuffer()]);
+  const slashAction = (amount: number, reason: number) =>
+    adminActionPda(adminActionId("slash_stake", mint.toBuffer(), slashed().toBuffer(), u64Le(amount), Buffer.from([reason])));
+
+  function queueSlash(amount: number, reason: number) {
+    return bank.program.methods
+      .queueSlashStake(slashed(), new anchor.BN(amount), reason)
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        rewardPool,
+        position: positionPda(slashed()),
+        adminAction: slashAction(amount, reason),
+        slashHold: slashHold(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function executeSlash(amount: number, reason: number) {
+    return bank.program.methods
+      .slashStake(slashed(), new anchor.BN(amount), reason)
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        adminAction: slashAction(amount, reason),
+        rewardPool,
+        stakeVault,
+        rewardVault,
+        position: positionPda(slashed()),
+        slashHold: slashHold(),
+        stakerWallet: slashed(),
+        stakerTokenAccount: slashedTokenAccount,
+        treasury,
+        treasuryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  async function slash(amount: number, reason: number) {
+    await queueSlash(amount, reason);
+    return executeSlash(amount, reason);
+  }
+
+  function requestUnstake(amount: number) {
+    const position = positionPda(slashed());
+    return bank.program.methods
+      .requestUnstake(new anchor.BN(amount))
+      .accounts({
+        staker: slashed(),
+        rewardPool,
+        position,
+        unstakeRequest: pda([Buffer.from("unstake"), position.toBuffer(), u64Le(0)]),
+        slashHold: slashHold(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  let beforeSlash: bigint;
+
+  it("slashes part of a position into the treasury", async () => {
+    beforeSlash = await crankADay();
+    await slash(400, 7);
+
+    assert.equal(await balance(treasuryTokenAccount), BigInt(400));
+    const position = await bank.program.account.stakePosition.fetch(positionPda(slashed()));
+    assert.equal(position.amount.toNumber(), 600);
+    // What the slashed tokens earned before the slash is kept.
+    assert.equal(BigInt(position.pendingRewards.toString()), (BigInt(1_000) * beforeSlash) / PRECISION);
+    const pool = await bank.program.account.rewardPool.fetch(rewardPool);
+    assert.equal(pool.totalStaked.toNumber(), 1_600);
+  });
+
+  it("shares later rewards over the stake left after the slash", async () => {
+    const afterSlash = await crankADay();
+    const growth = afterSlash - beforeSlash;
+
+    const bystanderBefore = await balance(bystanderTokenAccount);
+    await claim(bystander.publicKey, bystanderTokenAccount, [bystander]);
+    assert.equal(
+      (await balance(bystanderTokenAccount)) - bystanderBefore,
+      (BigInt(1_000) * afterSlash) / PRECISION
+    );
+
+    const slashedBefore = await balance(slashedTokenAccount);
+    await claim(slashed(), slashedTokenAccount);
+    assert.equal(
+      (await balance(slashedTokenAccount)) - slashedBefore,
+      (BigInt(1_000) * beforeSlash) / PRECISION + (BigInt(600) * growth) / PRECISION
+    );
+  });
+
+  it("keeps the staker from unstaking out of reach of a queued slash", async () => {
+    await queueSlash(100, 2);
+    await expectError(requestUnstake(600), "SlashPending");
+
+    await executeSlash(100, 2);
+    assert.equal(await balance(treasuryTokenAccount), BigInt(500));
+    const position = await bank.program.account.stakePosition.fetch(positionPda(slashed()));
+    assert.equal(position.amount.toNumber(), 500);
+    assert.equal(position.openRequests.toNumber(), 0);
+    assert.isNull(await bank.context.banksClient.getAccount(slashHold()));
+  });
+
+  it("releases the position when a queued slash is cancelled", async () => {
+    await queueSlash(50, 3);
+    await bank.program.methods
+      .cancelSlashStake()
+      .accounts({
+        superAdmin: superAdmin(),
+        programConfig,
+        slashHold: slashHold(),
+        adminAction: slashAction(50, 3),
+      })
+      .rpc();
+    assert.isNull(await bank.context.banksClient.getAccount(slashHold()));
+    assert.isNull(await bank.context.banksClient.getAccount(slashAction(50, 3)));
+  });
+
+  it("clamps a slash to the position and closes it, paying out its rewards", async () => {
+    const paid = await rewardPerToken();
+    const lastDay = (await crankADay()) - paid;
+    const before = await balance(slashedTokenAccount);
+    await slash(10_000, 1);
+
+    // 500 earlier plus the 500 left.
+    assert.equal(await balance(treasuryTokenAccount), BigInt(1_000));
+    assert.equal((await balance(slashedTokenAccount)) - before, (BigInt(500) * lastDay) / PRECISION);
+    assert.isNull(await bank.context.banksClient.getAccount(positionPda(slashed())));
+    const pool = await bank.program.account.rewardPool.fetch(rewardPool);
+    assert.equal(pool.totalStaked.toNumber(), 1_000);
+  });
+});
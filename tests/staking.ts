@@ -0,0 +1,264 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { adminActionId, expectError, i64Le, pda, programConfig } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  handMintAuthorityToProgram,
+  queueBankAction,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const DAY = 24 * 60 * 60;
+const COOLDOWN = 7 * DAY;
+const PENALTY_BPS = 1_000;
+
+function u16Le(value: number): Buffer {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(value);
+  return buf;
+}
+
+describe("staking", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let mintAuthority: anchor.web3.PublicKey;
+  let rewardPool: anchor.web3.PublicKey;
+  let rewardVault: anchor.web3.PublicKey;
+  let stakeVault: anchor.web3.PublicKey;
+  let stakerTokenAccount: anchor.web3.PublicKey;
+  const treasury = pda([Buffer.from("treasury")]);
+  let treasuryTokenAccount: anchor.web3.PublicKey;
+
+  const staker = () => bank.provider.wallet.publicKey;
+  const position = () => pda([Buffer.from("stake"), mint.toBuffer(), staker().toBuffer()]);
+  const requestPda = (index: number) =>
+    pda([Buffer.from("unstake"), position().toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)]);
+  const balance = async (account: anchor.web3.PublicKey) =>
+    Number((await getAccount(bank.provider.connection, account)).amount);
+  const requestCount = async () =>
+    (await bank.program.account.stakePosition.fetch(position())).requestCount.toNumber();
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+
+    mint = await createBankMint(bank);
+    stakerTokenAccount = await createBankTokenAccount(bank, mint, staker(), 10_000);
+    treasuryTokenAccount = await createBankTokenAccount(bank, mint, treasury, 0);
+    mintAuthority = await handMintAuthorityToProgram(bank, mint);
+    rewardPool = pda([Buffer.from("reward_pool"), mint.toBuffer()]);
+    rewardVault = pda([Buffer.from("reward_vault"), rewardPool.toBuffer()]);
+    stakeVault = pda([Buffer.from("stake_vault"), rewardPool.toBuffer()]);
+
+    const rateBps = 1_000;
+    const interval = 60 * 60;
+    const inflationAction = await queueBankAction(
+      bank,
+      adminActionId("set_inflation", mint.toBuffer(), u16Le(rateBps), i64Le(interval))
+    );
+    await bank.program.methods
+      .setInflation(rateBps, new anchor.BN(interval))
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction: inflationAction,
+        mint,
+        mintAuthority,
+        rewardPool,
+        rewardVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  async function setStakeConfig(cooldown: number, penaltyBps: number, burnPenalty: boolean) {
+    const actionId = adminActionId(
+      "set_stake_config",
+      mint.toBuffer(),
+      i64Le(cooldown),
+      u16Le(penaltyBps),
+      Buffer.from([burnPenalty ? 1 : 0])
+    );
+    const adminAction = await queueBankAction(bank, actionId);
+    return bank.program.methods
+      .setStakeConfig(new anchor.BN(cooldown), penaltyBps, burnPenalty)
+      .accounts({
+        superAdmin: staker(),
+        programConfig,
+        adminAction,
+        mint,
+        rewardPool,
+        stakeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  function stake(amount: number) {
+    return bank.program.methods
+      .stake(new anchor.BN(amount))
+      .accounts({
+        staker: staker(),
+        rewardPool,
+        stakeVault,
+        position: position(),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  async function requestUnstake(amount: number) {
+    const index = await requestCount();
+    await bank.program.methods
+      .requestUnstake(new anchor.BN(amount))
+      .accounts({
+        staker: staker(),
+        rewardPool,
+        position: position(),
+        unstakeRequest: requestPda(index),
+        slashHold: pda([Buffer.from("slash_hold"), position().toBuffer()]),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    return requestPda(index);
+  }
+
+  function completeUnstake(unstakeRequest: anchor.web3.PublicKey) {
+    return bank.program.methods
+      .completeUnstake()
+      .accounts({
+        staker: staker(),
+        rewardPool,
+        stakeVault,
+        unstakeRequest,
+        position: position(),
+        stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  function emergencyUnstake(unstakeRequest: anchor.web3.PublicKey) {
+    return bank.program.methods
+      .emergencyUnstake()
+      .accounts({
+        staker: staker(),
+        mint,
+        rewardPool,
+        stakeVault,
+        unstakeRequest,
+        position: position(),
+        stakerTokenAccount,
+        treasury,
+        treasuryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        votingCheckpoints: null,
+      })
+      .rpc();
+  }
+
+  it("refuses a penalty above 100%", async () => {
+    await expectError(setStakeConfig(COOLDOWN, 10_001, false), "InvalidPenalty");
+  });
+
+  describe("once configured", () => {
+    before(async () => {
+      await setStakeConfig(COOLDOWN, PENALTY_BPS, false);
+      await stake(4_000);
+    });
+
+    it("holds the stake in the vault", async () => {
+      assert.equal(await balance(stakeVault), 4_000);
+      assert.equal(await balance(stakerTokenAccount), 6_000);
+      const pool = await bank.program.account.rewardPool.fetch(rewardPool);
+      assert.equal(pool.totalStaked.toNumber(), 4_000);
+    });
+
+    it("pays out inflation to the only staker", async () => {
+      await warpTo(bank, (await unixTimestamp(bank)) + DAY);
+      await bank.program.methods
+        .crankInflation()
+        .accounts({ mint, mintAuthority, rewardPool, rewardVault, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+      const minted = await balance(rewardVault);
+      assert.isAbove(minted, 0);
+
+      const before = await balance(stakerTokenAccount);
+      await bank.program.methods
+        .claimStakeRewards()
+        .accounts({
+          staker: staker(),
+          rewardPool,
+          rewardVault,
+          position: position(),
+          stakerTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      // Rounded down per token, so at most a token's worth of dust is left.
+      const paid = (await balance(stakerTokenAccount)) - before;
+      assert.isAtMost(paid, minted);
+      assert.isAtMost(minted - paid, 1);
+    });
+
+    it("releases a request only once its cooldown is over", async () => {
+      const request = await requestUnstake(1_000);
+      const unlocksAt = (await bank.program.account.unstakeRequest.fetch(request)).unlocksAt.toNumber();
+      const pool = await bank.program.account.rewardPool.fetch(rewardPool);
+      assert.equal(pool.totalStaked.toNumber(), 3_000);
+
+      await warpTo(bank, unlocksAt - 1);
+      await expectError(completeUnstake(request), "UnstakeCooldownActive");
+
+      const before = await balance(stakerTokenAccount);
+      await warpTo(bank, unlocksAt);
+      await completeUnstake(request);
+      assert.equal(await balance(stakerTokenAccount), before + 1_000);
+      assert.isNull(await bank.context.banksClient.getAccount(request));
+    });
+
+    it("charges the penalty to the treasury on an emergency exit", async () => {
+      const request = await requestUnstake(1_005);
+      const before = await balance(stakerTokenAccount);
+      await emergencyUnstake(request);
+
+      // 10% of 1_005, rounded down, goes to the treasury.
+      assert.equal(await balance(treasuryTokenAccount), 100);
+      assert.equal(await balance(stakerTokenAccount), before + 905);
+      assert.equal(await balance(stakeVault), 1_995);
+      assert.isNull(await bank.context.banksClient.getAccount(request));
+    });
+
+    it("refuses to unstake more than is staked", async () => {
+      const index = await requestCount();
+      await expectError(
+        bank.program.methods
+          .requestUnstake(new anchor.BN(1_996))
+          .accounts({
+            staker: staker(),
+            rewardPool,
+            position: position(),
+            unstakeRequest: requestPda(index),
+            slashHold: pda([Buffer.from("slash_hold"), position().toBuffer()]),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc(),
+        "InsufficientStake"
+      );
+    });
+  });
+});
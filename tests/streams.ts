@@ -0,0 +1,144 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { expectError, pda } from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, startBank, unixTimestamp, warpTo } from "./bankrun";
+
+describe("streams", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let senderTokenAccount: anchor.web3.PublicKey;
+  const recipient = anchor.web3.Keypair.generate();
+  let recipientTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+    senderTokenAccount = await createBankTokenAccount(bank, mint, sender(), 10_000);
+    recipientTokenAccount = await createBankTokenAccount(bank, mint, recipient.publicKey, 0);
+  });
+
+  function sender() {
+    return bank.provider.wallet.publicKey;
+  }
+  const streamPda = () =>
+    pda([Buffer.from("stream"), mint.toBuffer(), sender().toBuffer(), recipient.publicKey.toBuffer()]);
+  const vaultPda = () => pda([Buffer.from("stream_vault"), streamPda().toBuffer()]);
+  const balance = async (account: anchor.web3.PublicKey) =>
+    Number((await getAccount(bank.provider.connection, account)).amount);
+
+  function open(deposit: number, startTs: number, endTs: number) {
+    return bank.program.methods
+      .openStream(recipient.publicKey, new anchor.BN(deposit), new anchor.BN(startTs), new anchor.BN(endTs))
+      .accounts({
+        sender: sender(),
+        mint,
+        senderTokenAccount,
+        stream: streamPda(),
+        vault: vaultPda(),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  function withdraw() {
+    return bank.program.methods
+      .withdrawFromStream()
+      .accounts({
+        recipient: recipient.publicKey,
+        stream: streamPda(),
+        vault: vaultPda(),
+        recipientTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([recipient])
+      .rpc();
+  }
+
+  function close() {
+    return bank.program.methods
+      .closeStream()
+      .accounts({
+        sender: sender(),
+        stream: streamPda(),
+        vault: vaultPda(),
+        recipientTokenAccount,
+        senderTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  it("refuses a stream that ends before it starts", async () => {
+    const now = await unixTimestamp(bank);
+    await expectError(open(1_000, now + 100, now + 100), "InvalidStreamWindow");
+  });
+
+  describe("a stream run to its end", () => {
+    // 1_000 tokens over 300 seconds doesn't divide evenly.
+    const deposit = 1_000;
+    let start: number;
+
+    before(async () => {
+      start = (await unixTimestamp(bank)) + 100;
+      await open(deposit, start, start + 300);
+    });
+
+    it("has nothing to withdraw before it starts", async () => {
+      await expectError(withdraw(), "NothingToWithdraw");
+      await warpTo(bank, start);
+      await expectError(withdraw(), "NothingToWithdraw");
+    });
+
+    it("pays out what has accrued, rounded down", async () => {
+      await warpTo(bank, start + 100);
+      await withdraw();
+      assert.equal(await balance(recipientTokenAccount), 333);
+
+      await warpTo(bank, start + 200);
+      await withdraw();
+      assert.equal(await balance(recipientTokenAccount), 666);
+
+      const stream = await bank.program.account.stream.fetch(streamPda());
+      assert.equal(stream.withdrawn.toNumber(), 666);
+    });
+
+    it("pays exactly the deposit once fully elapsed, with no dust left", async () => {
+      await warpTo(bank, start + 1_000);
+      await withdraw();
+      assert.equal(await balance(recipientTokenAccount), deposit);
+      assert.equal(await balance(vaultPda()), 0);
+      await warpTo(bank, start + 1_001);
+      await expectError(withdraw(), "NothingToWithdraw");
+    });
+
+    it("closes with nothing left to split", async () => {
+      const before = await balance(senderTokenAccount);
+      await close();
+      assert.equal(await balance(senderTokenAccount), before);
+      assert.isNull(await bank.context.banksClient.getAccount(streamPda()));
+      assert.isNull(await bank.context.banksClient.getAccount(vaultPda()));
+    });
+  });
+
+  describe("a stream closed early", () => {
+    it("pays the recipient what has accrued and refunds the rest", async () => {
+      const start = await unixTimestamp(bank);
+      await open(400, start, start + 400);
+      await warpTo(bank, start + 100);
+      await withdraw();
+      await warpTo(bank, start + 300);
+
+      const recipientBefore = await balance(recipientTokenAccount);
+      const senderBefore = await balance(senderTokenAccount);
+      await close();
+
+      // 100 withdrawn, 200 more accrued, 100 still to come.
+      assert.equal(await balance(recipientTokenAccount), recipientBefore + 200);
+      assert.equal(await balance(senderTokenAccount), senderBefore + 100);
+      assert.isNull(await bank.context.banksClient.getAccount(streamPda()));
+    });
+  });
+});
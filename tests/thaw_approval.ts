@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { getAccount } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  mintConfigPda,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+import { u64Le } from "./merkle";
+
+describe("ed25519 thaw approval", () => {
+  const admin = provider.wallet.publicKey;
+  const approver = anchor.web3.Keypair.generate();
+  const MAX_AGE_SLOTS = 50;
+  let mint: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    await program.methods
+      .setThawApproval(true, approver.publicKey, new anchor.BN(MAX_AGE_SLOTS))
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  });
+
+  async function frozenAccount() {
+    const account = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+    return account;
+  }
+
+  function approvalMessage(account: anchor.web3.PublicKey, forMint: anchor.web3.PublicKey, slot: number) {
+    return Buffer.concat([account.toBuffer(), forMint.toBuffer(), u64Le(slot)]);
+  }
+
+  function thaw(account: anchor.web3.PublicKey, approval?: anchor.web3.TransactionInstruction) {
+    return program.methods
+      .thawTokenAccount()
+      .accounts({ ...thawAccounts(mint, account), instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY })
+      .preInstructions(approval ? [approval] : [])
+      .rpc();
+  }
+
+  function sign(signer: anchor.web3.Keypair, message: Buffer) {
+    return anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: signer.secretKey,
+      message,
+    });
+  }
+
+  it("thaws with a fresh approval from the configured signer", async () => {
+    const account = await frozenAccount();
+    const slot = await provider.connection.getSlot();
+    await thaw(account, sign(approver, approvalMessage(account, mint, slot)));
+    assert.isFalse((await getAccount(provider.connection, account)).isFrozen);
+  });
+
+  it("refuses a thaw without an approval", async () => {
+    const account = await frozenAccount();
+    await expectError(thaw(account), "MissingApprovalSignature");
+  });
+
+  it("refuses an approval from the wrong signer", async () => {
+    const account = await frozenAccount();
+    const slot = await provider.connection.getSlot();
+    const impostor = anchor.web3.Keypair.generate();
+    await expectError(
+      thaw(account, sign(impostor, approvalMessage(account, mint, slot))),
+      "WrongApprovalSigner"
+    );
+  });
+
+  it("refuses an approval signed for a different token account", async () => {
+    const account = await frozenAccount();
+    const other = await frozenAccount();
+    const slot = await provider.connection.getSlot();
+    await expectError(
+      thaw(account, sign(approver, approvalMessage(other, mint, slot))),
+      "ApprovalMessageMismatch"
+    );
+  });
+
+  it("fails the whole transaction if the signed message was tampered with", async () => {
+    const account = await frozenAccount();
+    const slot = await provider.connection.getSlot();
+    const ix = sign(approver, approvalMessage(account, mint, slot));
+    // Flip the last byte of the message after signing; the ed25519 program rejects it.
+    ix.data[ix.data.length - 1] ^= 0xff;
+    try {
+      await thaw(account, ix);
+      assert.fail("expected the ed25519 precompile to reject the signature");
+    } catch (err) {
+      assert.notInclude(String(err), "expected the ed25519");
+    }
+    assert.isTrue((await getAccount(provider.connection, account)).isFrozen);
+  });
+
+  it("refuses a stale approval", async () => {
+    const account = await frozenAccount();
+    const slot = await provider.connection.getSlot();
+    await expectError(
+      thaw(account, sign(approver, approvalMessage(account, mint, Math.max(0, slot - MAX_AGE_SLOTS - 10)))),
+      "ApprovalExpired"
+    );
+  });
+});
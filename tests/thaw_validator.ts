@@ -0,0 +1,124 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { ThawValidatorExample } from "../target/types/thaw_validator_example";
+import {
+  accountHistoryPda,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  freezerBondPda,
+  mintConfigPda,
+  program,
+  programAuthority,
+  provider,
+} from "./helpers";
+
+const validatorProgram = anchor.workspace.thawValidatorExample as Program<ThawValidatorExample>;
+
+function validatorPda(seeds: Buffer[]): anchor.web3.PublicKey {
+  return anchor.web3.PublicKey.findProgramAddressSync(seeds, validatorProgram.programId)[0];
+}
+
+describe("thaw validator", () => {
+  const admin = provider.wallet.publicKey;
+  const validatorConfig = validatorPda([Buffer.from("validator_config")]);
+  const attestedOwner = anchor.web3.Keypair.generate().publicKey;
+  const unattestedOwner = anchor.web3.Keypair.generate().publicKey;
+  let mint: anchor.web3.PublicKey;
+  let attested: anchor.web3.PublicKey;
+  let unattested: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    attested = await createFundedTokenAccount(mint, attestedOwner, 10);
+    unattested = await createFundedTokenAccount(mint, unattestedOwner, 10);
+    for (const account of [attested, unattested]) {
+      await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+    }
+
+    if (!(await provider.connection.getAccountInfo(validatorConfig))) {
+      await validatorProgram.methods
+        .initialize()
+        .accounts({ payer: admin, validatorConfig, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    }
+    await validatorProgram.methods
+      .attest(attestedOwner)
+      .accounts({
+        authority: admin,
+        validatorConfig,
+        attestation: validatorPda([Buffer.from("attestation"), attestedOwner.toBuffer()]),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  function thaw(
+    tokenAccount: anchor.web3.PublicKey,
+    owner: anchor.web3.PublicKey,
+    validator = validatorProgram.programId
+  ) {
+    return program.methods
+      .thawWithValidator()
+      .accounts({
+        caller: admin,
+        tokenAccountToProcess: tokenAccount,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        freezeRecord: freezeRecordPda(tokenAccount),
+        accountHistory: accountHistoryPda(tokenAccount),
+        freezeActor: admin,
+        rentPayer: null,
+        freezerBond: freezerBondPda(mint, admin),
+        validatorProgram: validator,
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        {
+          pubkey: validatorPda([Buffer.from("attestation"), owner.toBuffer()]),
+          isWritable: false,
+          isSigner: false,
+        },
+      ])
+      .rpc();
+  }
+
+  function setValidator(validator: anchor.web3.PublicKey) {
+    return program.methods
+      .setThawValidator(validator)
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  }
+
+  it("refuses while no validator is set", async () => {
+    await expectError(thaw(attested, attestedOwner), "ThawValidatorNotSet");
+  });
+
+  it("refuses a validator other than the configured one", async () => {
+    await setValidator(validatorProgram.programId);
+    await expectError(thaw(attested, attestedOwner, TOKEN_PROGRAM_ID), "ThawValidatorMismatch");
+  });
+
+  it("thaws an account the validator approves", async () => {
+    await thaw(attested, attestedOwner);
+    assert.isFalse((await getAccount(provider.connection, attested)).isFrozen);
+    assert.isNull(await provider.connection.getAccountInfo(freezeRecordPda(attested)));
+  });
+
+  it("keeps an account frozen when the validator rejects it", async () => {
+    await expectError(thaw(unattested, unattestedOwner), "ValidatorRejected");
+    assert.isTrue((await getAccount(provider.connection, unattested)).isFrozen);
+  });
+
+  it("clears the validator with the default pubkey", async () => {
+    await setValidator(anchor.web3.PublicKey.default);
+    const config = await program.account.mintConfig.fetch(mintConfigPda(mint));
+    assert.isNull(config.thawValidator);
+  });
+});
@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { getAccount } from "@solana/spl-token";
+import { expectError, freezeAccounts, freezerBondPda, mintConfigPda, programConfig, thawAccounts } from "./helpers";
+import {
+  Bank,
+  createBankMint,
+  createBankTokenAccount,
+  setProgramConfig,
+  startBank,
+  unixTimestamp,
+  warpTo,
+} from "./bankrun";
+
+const COOLDOWN = 10 * 60;
+
+describe("freeze/thaw toggle cooldown", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+  let start: number;
+
+  before(async () => {
+    bank = await startBank();
+    await setProgramConfig(bank);
+    mint = await createBankMint(bank);
+    tokenAccount = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 10);
+    await bank.program.methods
+      .setToggleCooldown(new anchor.BN(COOLDOWN))
+      .accounts({ admin: bank.provider.wallet.publicKey, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+    start = await unixTimestamp(bank);
+  });
+
+  // freezeAccounts/thawAccounts default to the env wallet; the bank has its own payer.
+  const admin = () => bank.provider.wallet.publicKey;
+  const override = (on: boolean) => (on ? { programConfig, superAdmin: admin() } : {});
+  // The bank's payer both freezes and thaws, so it's also the freeze record's actor.
+  const asAdmin = () => ({ admin: admin(), freezerBond: freezerBondPda(mint, admin()) });
+
+  const freeze = (superAdmin = false) =>
+    bank.program.methods
+      .freezeTokenAccount()
+      .accounts({ ...freezeAccounts(mint, tokenAccount), ...asAdmin(), ...override(superAdmin) })
+      .rpc();
+  const thaw = (superAdmin = false) =>
+    bank.program.methods
+      .thawTokenAccount()
+      .accounts({ ...thawAccounts(mint, tokenAccount), ...asAdmin(), ...override(superAdmin) })
+      .rpc();
+
+  const isFrozen = async () => (await getAccount(bank.provider.connection, tokenAccount)).isFrozen;
+
+  it("lets the first freeze through", async () => {
+    await freeze();
+    assert.isTrue(await isFrozen());
+  });
+
+  it("blocks a thaw within the cooldown", async () => {
+    await warpTo(bank, start + COOLDOWN - 1);
+    await expectError(thaw(), "CooldownActive");
+  });
+
+  it("allows the thaw once the cooldown has passed, then blocks the refreeze", async () => {
+    await warpTo(bank, start + COOLDOWN);
+    await thaw();
+    assert.isFalse(await isFrozen());
+
+    await warpTo(bank, start + COOLDOWN + 1);
+    await expectError(freeze(), "CooldownActive");
+  });
+
+  it("lets the super_admin override the cooldown", async () => {
+    await freeze(true);
+    assert.isTrue(await isFrozen());
+    await warpTo(bank, start + COOLDOWN + 2);
+    await thaw(true);
+    assert.isFalse(await isFrozen());
+
+    const history = await bank.program.account.accountHistory.fetch(
+      freezeAccounts(mint, tokenAccount).accountHistory
+    );
+    assert.equal(history.lastStateChangeTs.toNumber(), start + COOLDOWN + 2);
+  });
+
+  it("refuses an override signer that isn't the super_admin", async () => {
+    const impostor = anchor.web3.Keypair.generate();
+    await expectError(
+      bank.program.methods
+        .freezeTokenAccount()
+        .accounts({ ...freezeAccounts(mint, tokenAccount), ...asAdmin(), programConfig, superAdmin: impostor.publicKey })
+        .signers([impostor])
+        .rpc(),
+      "NotSuperAdmin"
+    );
+  });
+});
@@ -0,0 +1,80 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { expectError, mintConfigPda } from "./helpers";
+import { Bank, createBankMint, fund, startBank, unixTimestamp, warpTo } from "./bankrun";
+
+// min_freezer_bond (u64), bond_slash_bps (u16) and notifier_bump (Option<u8>),
+// the last fields appended.
+const APPENDED_BYTES = 8 + 2 + 2;
+
+describe("upgrade mint config", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let mintConfig: anchor.web3.PublicKey;
+  let fullSize: number;
+
+  const dataLen = async () => (await bank.context.banksClient.getAccount(mintConfig)).data.length;
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+    mintConfig = mintConfigPda(mint);
+
+    // Cut the config back to the size it had before the last fields were added.
+    const account = await bank.context.banksClient.getAccount(mintConfig);
+    fullSize = account.data.length;
+    bank.context.setAccount(mintConfig, {
+      lamports: account.lamports,
+      data: Buffer.from(account.data.subarray(0, fullSize - APPENDED_BYTES)),
+      owner: account.owner,
+      executable: false,
+    });
+  });
+
+  function upgrade(admin?: anchor.web3.Keypair) {
+    return bank.program.methods
+      .upgradeMintConfig()
+      .accounts({
+        admin: admin?.publicKey ?? bank.provider.wallet.publicKey,
+        mint,
+        mintConfig,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers(admin ? [admin] : [])
+      .rpc();
+  }
+
+  it("can't load a config at the old size", async () => {
+    let failed = false;
+    try {
+      await bank.program.account.mintConfig.fetch(mintConfig);
+    } catch {
+      failed = true;
+    }
+    assert.isTrue(failed);
+  });
+
+  it("only lets the mint authority upgrade", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await fund(bank, stranger.publicKey);
+    await expectError(upgrade(stranger), "Unauthorized");
+    assert.equal(await dataLen(), fullSize - APPENDED_BYTES);
+  });
+
+  it("grows the config to the current size with the new fields zeroed", async () => {
+    await upgrade();
+    assert.equal(await dataLen(), fullSize);
+
+    const config = await bank.program.account.mintConfig.fetch(mintConfig);
+    assert.ok(config.mint.equals(mint));
+    assert.equal(config.minFreezerBond.toNumber(), 0);
+    assert.equal(config.bondSlashBps, 0);
+    assert.isNull(config.notifierBump);
+  });
+
+  it("leaves a config already at full size alone", async () => {
+    await warpTo(bank, await unixTimestamp(bank));
+    await upgrade();
+    assert.equal(await dataLen(), fullSize);
+  });
+});
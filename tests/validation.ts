@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeExemptPda,
+  freezerStatsPda,
+  mintConfigPda,
+  pda,
+  program,
+  programAuthority,
+  provider,
+} from "./helpers";
+
+// Each validation helper's error code, as seen by a client, at each call site.
+describe("input validation", () => {
+  const admin = provider.wallet.publicKey;
+  const nobody = anchor.web3.PublicKey.default;
+  let mint: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+  });
+
+  describe("create_token_mint", () => {
+    function create(decimals: number, mintAuthority: anchor.web3.PublicKey) {
+      const newMint = anchor.web3.Keypair.generate();
+      return program.methods
+        .createTokenMint(decimals, mintAuthority)
+        .accounts({
+          mint: newMint.publicKey,
+          programAuthority,
+          mintConfig: mintConfigPda(newMint.publicKey),
+          payer: admin,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([newMint])
+        .rpc();
+    }
+
+    it("InvalidDecimals above 18", async () => {
+      await create(18, admin);
+      await expectError(create(19, admin), "InvalidDecimals");
+    });
+
+    it("DefaultPubkey for the mint authority", async () => {
+      await expectError(create(6, nobody), "DefaultPubkey");
+    });
+  });
+
+  it("ZeroAmount on mint_tokens", async () => {
+    const destination = await createFundedTokenAccount(mint, anchor.web3.Keypair.generate().publicKey, 0);
+    await expectError(
+      program.methods
+        .mintTokens(new anchor.BN(0), null)
+        .accounts({
+          mintAuthority: admin,
+          mint,
+          destination,
+          mintConfig: mintConfigPda(mint),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          payer: admin,
+          referralStats: null,
+          votingCheckpoints: null,
+        })
+        .rpc(),
+      "ZeroAmount"
+    );
+  });
+
+  it("DefaultPubkey on add_freezer", async () => {
+    await expectError(
+      program.methods
+        .addFreezer(nobody)
+        .accounts({
+          admin,
+          mint,
+          freezerStats: freezerStatsPda(mint, nobody),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "DefaultPubkey"
+    );
+  });
+
+  it("DefaultPubkey on add_freeze_exempt", async () => {
+    await expectError(
+      program.methods
+        .addFreezeExempt(nobody)
+        .accounts({
+          admin,
+          mint,
+          freezeExempt: freezeExemptPda(nobody),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "DefaultPubkey"
+    );
+  });
+
+  it("DefaultPubkey on initialize_bridge", async () => {
+    const bridge = pda([Buffer.from("bridge"), mint.toBuffer()]);
+    await expectError(
+      program.methods
+        .initializeBridge(nobody)
+        .accounts({
+          admin,
+          mint,
+          bridge,
+          vault: pda([Buffer.from("bridge_vault"), bridge.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc(),
+      "DefaultPubkey"
+    );
+  });
+});
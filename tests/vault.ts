@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  payer,
+  pda,
+  program,
+  provider,
+  thawAccounts,
+} from "./helpers";
+
+describe("user vault", () => {
+  const owner = payer.publicKey;
+  let mint: anchor.web3.PublicKey;
+  let ownerTokenAccount: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+  let vaultTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    ownerTokenAccount = await createFundedTokenAccount(mint, owner, 1_000);
+    vault = pda([Buffer.from("vault"), mint.toBuffer(), owner.toBuffer()]);
+    vaultTokenAccount = pda([Buffer.from("vault_tokens"), vault.toBuffer()]);
+  });
+
+  const accounts = () => ({
+    owner,
+    vault,
+    vaultTokenAccount,
+    ownerTokenAccount,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const vaultBalance = async () => (await getAccount(provider.connection, vaultTokenAccount)).amount;
+
+  it("opens a vault and takes a deposit", async () => {
+    await program.methods
+      .openVault()
+      .accounts({
+        owner,
+        mint,
+        vault,
+        vaultTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await program.methods.deposit(new anchor.BN(500)).accounts(accounts()).rpc();
+    assert.equal(await vaultBalance(), BigInt(500));
+  });
+
+  it("withdraws part of the balance", async () => {
+    await program.methods.withdraw(new anchor.BN(200)).accounts(accounts()).rpc();
+    assert.equal(await vaultBalance(), BigInt(300));
+  });
+
+  it("blocks withdrawals while the admin has the vault frozen", async () => {
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, vaultTokenAccount)).rpc();
+    await expectError(program.methods.withdraw(new anchor.BN(1)).accounts(accounts()).rpc(), "VaultFrozen");
+  });
+
+  it("refuses to close a vault that still holds tokens", async () => {
+    await program.methods.thawTokenAccount().accounts(thawAccounts(mint, vaultTokenAccount)).rpc();
+    await expectError(
+      program.methods
+        .closeVault()
+        .accounts({ owner, vault, vaultTokenAccount, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc(),
+      "VaultNotEmpty"
+    );
+  });
+
+  it("withdraws the rest after the thaw and closes the vault", async () => {
+    await program.methods.withdraw(new anchor.BN(300)).accounts(accounts()).rpc();
+    assert.equal((await getAccount(provider.connection, ownerTokenAccount)).amount, BigInt(1_000));
+
+    await program.methods
+      .closeVault()
+      .accounts({ owner, vault, vaultTokenAccount, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(vault));
+    assert.isNull(await provider.connection.getAccountInfo(vaultTokenAccount));
+  });
+});
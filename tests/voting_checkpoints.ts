@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createMintToInstruction, createTransferInstruction } from "@solana/spl-token";
+import { mintConfigPda, pda } from "./helpers";
+import { Bank, createBankMint, createBankTokenAccount, startBank } from "./bankrun";
+
+describe("voting checkpoints", () => {
+  let bank: Bank;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+  let elsewhere: anchor.web3.PublicKey;
+
+  const holder = () => bank.provider.wallet.publicKey;
+  const votingCheckpoints = () => pda([Buffer.from("voting_checkpoints"), mint.toBuffer(), holder().toBuffer()]);
+
+  // Transfers and mints that bypass the program, so don't checkpoint.
+  const external = (ix: anchor.web3.TransactionInstruction) =>
+    bank.provider.sendAndConfirm(new anchor.web3.Transaction().add(ix));
+
+  function selfCheckpoint() {
+    return bank.program.methods
+      .selfCheckpoint()
+      .accounts({
+        holder: holder(),
+        tokenAccount,
+        votingCheckpoints: votingCheckpoints(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function votesAt(slot: number) {
+    const votes = await bank.program.methods
+      .getVotesAtSlot(new anchor.BN(slot))
+      .accounts({ votingCheckpoints: votingCheckpoints() })
+      .view();
+    return votes.toNumber();
+  }
+
+  before(async () => {
+    bank = await startBank();
+    mint = await createBankMint(bank);
+    tokenAccount = await createBankTokenAccount(bank, mint, holder(), 100);
+    elsewhere = await createBankTokenAccount(bank, mint, anchor.web3.Keypair.generate().publicKey, 0);
+
+    // Four checkpoints: 100 at slot 100, 250 at 200, 300 at 300, 120 at 400.
+    bank.context.warpToSlot(BigInt(100));
+    await selfCheckpoint();
+
+    bank.context.warpToSlot(BigInt(200));
+    await external(createMintToInstruction(mint, tokenAccount, holder(), 150));
+    await selfCheckpoint();
+
+    // Minting through the program checkpoints on its own.
+    bank.context.warpToSlot(BigInt(300));
+    await bank.program.methods
+      .mintTokens(new anchor.BN(50), null)
+      .accounts({
+        mintAuthority: holder(),
+        mint,
+        destination: tokenAccount,
+        mintConfig: mintConfigPda(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        payer: holder(),
+        referralStats: null,
+        votingCheckpoints: votingCheckpoints(),
+      })
+      .rpc();
+
+    bank.context.warpToSlot(BigInt(400));
+    await external(createTransferInstruction(tokenAccount, elsewhere, holder(), 180));
+    await selfCheckpoint();
+  });
+
+  it("keeps one checkpoint per change", async () => {
+    const checkpoints = await bank.program.account.votingCheckpoints.fetch(votingCheckpoints());
+    assert.equal(checkpoints.len, 4);
+    assert.deepEqual(
+      checkpoints.checkpoints.slice(0, 4).map((c) => [c.slot.toNumber(), c.balance.toNumber()]),
+      [
+        [100, 100],
+        [200, 250],
+        [300, 300],
+        [400, 120],
+      ]
+    );
+  });
+
+  it("has no votes before the first checkpoint", async () => {
+    assert.equal(await votesAt(99), 0);
+  });
+
+  it("returns the balance of the last checkpoint at or before a slot", async () => {
+    assert.equal(await votesAt(100), 100);
+    assert.equal(await votesAt(150), 100);
+    assert.equal(await votesAt(299), 250);
+    assert.equal(await votesAt(300), 300);
+    assert.equal(await votesAt(399), 300);
+  });
+
+  it("returns the latest balance after the last checkpoint", async () => {
+    assert.equal(await votesAt(400), 120);
+    assert.equal(await votesAt(1_000_000), 120);
+  });
+});
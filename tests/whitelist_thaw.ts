@@ -0,0 +1,83 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  accountHistoryPda,
+  createFundedTokenAccount,
+  createProgramMint,
+  expectError,
+  freezeAccounts,
+  freezeRecordPda,
+  freezerBondPda,
+  mintConfigPda,
+  program,
+  programAuthority,
+  provider,
+} from "./helpers";
+import { MerkleTree, hashLeaf } from "./merkle";
+
+describe("merkle whitelist thaw", () => {
+  const admin = provider.wallet.publicKey;
+  const members = [0, 1, 2, 3].map(() => anchor.web3.Keypair.generate().publicKey);
+  const tree = new MerkleTree(members.map((m) => hashLeaf(m.toBuffer())));
+  let mint: anchor.web3.PublicKey;
+
+  before(async () => {
+    mint = await createProgramMint();
+    await program.methods
+      .setWhitelistRoot(tree.root())
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+  });
+
+  async function frozenAccountFor(owner: anchor.web3.PublicKey) {
+    const account = await createFundedTokenAccount(mint, owner, 10);
+    await program.methods.freezeTokenAccount().accounts(freezeAccounts(mint, account)).rpc();
+    return account;
+  }
+
+  function thawWithProof(account: anchor.web3.PublicKey, proof: number[][]) {
+    return program.methods
+      .thawWithProof(proof)
+      .accounts({
+        caller: admin,
+        tokenAccountToProcess: account,
+        mint,
+        mintConfig: mintConfigPda(mint),
+        freezeRecord: freezeRecordPda(account),
+        accountHistory: accountHistoryPda(account),
+        freezeActor: admin,
+        rentPayer: null,
+        freezerBond: freezerBondPda(mint, admin),
+        programAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        feeRecipient: null,
+      })
+      .rpc();
+  }
+
+  it("thaws an account whose owner is in the tree", async () => {
+    const account = await frozenAccountFor(members[1]);
+    await thawWithProof(account, tree.proof(1));
+    assert.isFalse((await getAccount(provider.connection, account)).isFrozen);
+  });
+
+  it("rejects an owner who is not in the tree", async () => {
+    const outsider = anchor.web3.Keypair.generate().publicKey;
+    const account = await frozenAccountFor(outsider);
+    await expectError(thawWithProof(account, tree.proof(0)), "InvalidProof");
+  });
+
+  it("rejects proofs against a rotated root", async () => {
+    const account = await frozenAccountFor(members[2]);
+    const rotated = new MerkleTree(
+      [anchor.web3.Keypair.generate().publicKey, members[3]].map((m) => hashLeaf(m.toBuffer()))
+    );
+    await program.methods
+      .setWhitelistRoot(rotated.root())
+      .accounts({ admin, mint, mintConfig: mintConfigPda(mint) })
+      .rpc();
+
+    await expectError(thawWithProof(account, tree.proof(2)), "InvalidProof");
+  });
+});
@@ -0,0 +1,78 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createMint, getAccount, getMint, transfer } from "@solana/spl-token";
+import { airdrop, createFundedTokenAccount, payer, pda, program, provider } from "./helpers";
+
+describe("wrapped mint", () => {
+  const other = anchor.web3.Keypair.generate();
+  let underlyingMint: anchor.web3.PublicKey;
+  let wrapper: anchor.web3.PublicKey;
+  let wrappedMint: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+
+  before(async () => {
+    // An external mint the program has no authority over.
+    underlyingMint = await createMint(provider.connection, payer, payer.publicKey, null, 9);
+    wrapper = pda([Buffer.from("wrapper"), underlyingMint.toBuffer()]);
+    wrappedMint = pda([Buffer.from("wrapped_mint"), wrapper.toBuffer()]);
+    vault = pda([Buffer.from("wrapper_vault"), wrapper.toBuffer()]);
+    await airdrop(other.publicKey);
+
+    await program.methods
+      .initializeWrapper()
+      .accounts({
+        payer: payer.publicKey,
+        underlyingMint,
+        wrapper,
+        wrappedMint,
+        vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  function accounts(user: anchor.web3.PublicKey, userUnderlying: anchor.web3.PublicKey, userWrapped: anchor.web3.PublicKey) {
+    return { user, wrapper, wrappedMint, vault, userUnderlying, userWrapped, tokenProgram: TOKEN_PROGRAM_ID };
+  }
+
+  async function assertBacked() {
+    const backing = await program.methods.wrapperBacking().accounts({ wrapper, wrappedMint, vault }).view();
+    assert.equal(backing.wrappedSupply.toString(), backing.vaultBalance.toString());
+    return backing.wrappedSupply.toNumber();
+  }
+
+  it("creates the wrapped mint with the underlying's decimals", async () => {
+    const wrapped = await getMint(provider.connection, wrappedMint);
+    assert.equal(wrapped.decimals, 9);
+    assert.ok(wrapped.mintAuthority.equals(wrapper));
+    assert.equal(await assertBacked(), 0);
+  });
+
+  it("wraps, moves the wrapped token to another wallet and unwraps from there", async () => {
+    const payerUnderlying = await createFundedTokenAccount(underlyingMint, payer.publicKey, 1_000);
+    const payerWrapped = await createFundedTokenAccount(wrappedMint, payer.publicKey, 0);
+
+    await program.methods
+      .wrap(new anchor.BN(600))
+      .accounts(accounts(payer.publicKey, payerUnderlying, payerWrapped))
+      .rpc();
+    assert.equal((await getAccount(provider.connection, payerWrapped)).amount, BigInt(600));
+    assert.equal(await assertBacked(), 600);
+
+    const otherWrapped = await createFundedTokenAccount(wrappedMint, other.publicKey, 0);
+    const otherUnderlying = await createFundedTokenAccount(underlyingMint, other.publicKey, 0);
+    await transfer(provider.connection, payer, payerWrapped, otherWrapped, payer, 250);
+    assert.equal(await assertBacked(), 600);
+
+    await program.methods
+      .unwrap(new anchor.BN(250))
+      .accounts(accounts(other.publicKey, otherUnderlying, otherWrapped))
+      .signers([other])
+      .rpc();
+    assert.equal((await getAccount(provider.connection, otherUnderlying)).amount, BigInt(250));
+    assert.equal((await getAccount(provider.connection, otherWrapped)).amount, BigInt(0));
+    assert.equal(await assertBacked(), 350);
+  });
+});